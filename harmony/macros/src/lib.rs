@@ -0,0 +1,161 @@
+//! Provides procedural macros for `harmony`. Do not use this crate directly.
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use proc_macro_error::{abort, proc_macro_error};
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote, Data, DeriveInput, Fields, GenericArgument, Ident, Index,
+    PathArguments, Type,
+};
+
+/// Derive `harmony::Diff`. See the documentation of `harmony::Diff`.
+#[proc_macro_derive(Diff)]
+#[proc_macro_error]
+pub fn derive_diff(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    let ident = &input.ident;
+
+    let mut generics = input.generics.clone();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let elem_fields = elem_fields(&data.fields);
+
+            // `differ.field("name", &old.name, &new.name);`
+            let stmts = elem_fields.iter().map(|field| {
+                let (name, member) = (&field.name, &field.member);
+                quote! { differ.field(#name, &old.#member, &new.#member); }
+            });
+
+            add_bounds(&mut generics, &elem_fields);
+
+            quote! { #(#stmts)* }
+        }
+        Data::Enum(data) => {
+            let mut arms = Vec::new();
+
+            for variant in data.variants.iter() {
+                let elem_fields = elem_fields(&variant.fields);
+                if elem_fields.is_empty() {
+                    continue;
+                }
+
+                let var_ident = &variant.ident;
+                let old_vars: Vec<_> = (0..elem_fields.len())
+                    .map(|i| Ident::new(&format!("__old_{}", i), Span::call_site()))
+                    .collect();
+                let new_vars: Vec<_> = (0..elem_fields.len())
+                    .map(|i| Ident::new(&format!("__new_{}", i), Span::call_site()))
+                    .collect();
+                let members: Vec<_> = elem_fields.iter().map(|f| &f.member).collect();
+                let names = elem_fields.iter().map(|f| &f.name);
+
+                // `(Self::Variant { name: __old_0, .. }, Self::Variant { name: __new_0, .. }) =>
+                //      { differ.field("name", __old_0, __new_0); }`
+                arms.push(quote! {
+                    (
+                        Self::#var_ident { #(#members: #old_vars,)* .. },
+                        Self::#var_ident { #(#members: #new_vars,)* .. },
+                    ) => {
+                        #( differ.field(#names, #old_vars, #new_vars); )*
+                    }
+                });
+
+                add_bounds(&mut generics, &elem_fields);
+            }
+
+            // If the variants differ, there are no corresponding fields to
+            // compare
+            quote! {
+                match (old, new) {
+                    #(#arms)*
+                    _ => {}
+                }
+            }
+        }
+        Data::Union(data) => {
+            abort!(data.union_token, "unions are not supported");
+        }
+    };
+
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    (quote! {
+        impl #impl_generics ::harmony::Diff for #ident #ty_generics #where_clause {
+            fn diff_fields(old: &Self, new: &Self, differ: &mut ::harmony::Differ<'_>) {
+                #body
+            }
+        }
+    })
+    .into()
+}
+
+struct ElemField<'a> {
+    /// The field name reported to the visitor.
+    name: String,
+    /// The token used to access the field.
+    member: TokenStream2,
+    /// `T` of `Elem<T>`.
+    inner_ty: &'a Type,
+}
+
+/// Find the fields of type `Elem<T>`.
+fn elem_fields(fields: &Fields) -> Vec<ElemField<'_>> {
+    fields
+        .iter()
+        .enumerate()
+        .filter_map(|(i, field)| {
+            let inner_ty = elem_inner_ty(&field.ty)?;
+            let (name, member) = if let Some(ident) = &field.ident {
+                (ident.to_string(), quote! { #ident })
+            } else {
+                let index = Index::from(i);
+                (i.to_string(), quote! { #index })
+            };
+            Some(ElemField {
+                name,
+                member,
+                inner_ty,
+            })
+        })
+        .collect()
+}
+
+/// Get `T` if `ty` is `Elem<T>`. The type is identified by its name because
+/// we don't have access to semantic information.
+fn elem_inner_ty(ty: &Type) -> Option<&Type> {
+    let path = match ty {
+        Type::Path(path) if path.qself.is_none() => &path.path,
+        _ => return None,
+    };
+
+    let seg = path.segments.last()?;
+    if seg.ident != "Elem" {
+        return None;
+    }
+
+    match &seg.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Add `T: Diff` for each field of type `Elem<T>`.
+fn add_bounds(generics: &mut syn::Generics, fields: &[ElemField<'_>]) {
+    if generics.params.is_empty() {
+        return;
+    }
+
+    let where_clause = generics.make_where_clause();
+    for field in fields {
+        let inner_ty = field.inner_ty;
+        where_clause
+            .predicates
+            .push(parse_quote! { #inner_ty: ::harmony::Diff });
+    }
+}
@@ -0,0 +1,258 @@
+use super::Elem;
+
+/// Types whose `Elem` fields can be compared by [`diff`].
+///
+/// This trait is usually implemented by `#[derive(Diff)]`, which enumerates
+/// the fields of type `Elem<T>` (identified by the type name) and requires
+/// `T: Diff` for each of them. Fields of other types, including the ones
+/// containing `Elem`s indirectly (e.g., `Vec<Elem<T>>`), are ignored. For
+/// enums, only the fields of the same variant are compared.
+///
+/// Types without `Elem` fields can implement this trait with an empty `impl`
+/// block.
+pub trait Diff {
+    /// Call [`Differ::field`] for each pair of the corresponding `Elem`
+    /// fields of `old` and `new`.
+    fn diff_fields(old: &Self, new: &Self, differ: &mut Differ<'_>) {
+        let _ = (old, new, differ);
+    }
+}
+
+macro_rules! impl_leaf {
+    ($($ty:ty),* $(,)?) => {
+        $( impl Diff for $ty {} )*
+    };
+}
+
+impl_leaf!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    String,
+);
+
+impl<T> Diff for Vec<T> {}
+impl<T> Diff for Option<T> {}
+
+/// Tracks the state of [`diff`].
+pub struct Differ<'a> {
+    path: Vec<&'static str>,
+    visitor: &'a mut dyn FnMut(&[&'static str]),
+    num_reported: usize,
+}
+
+impl Differ<'_> {
+    /// Compare a pair of the corresponding `Elem` fields named `name`.
+    pub fn field<T: Diff>(&mut self, name: &'static str, old: &Elem<T>, new: &Elem<T>) {
+        if Elem::ptr_eq(old, new) {
+            return;
+        }
+
+        self.path.push(name);
+        self.visit(&**old, &**new);
+        self.path.pop();
+    }
+
+    fn visit<T: Diff>(&mut self, old: &T, new: &T) {
+        let num_reported = self.num_reported;
+
+        T::diff_fields(old, new, self);
+
+        if self.num_reported == num_reported {
+            // None of the children were reported, so this is a leaf
+            (self.visitor)(&self.path);
+            self.num_reported += 1;
+        }
+    }
+}
+
+/// Find the sub-`Elem`s that changed between `old` and `new`.
+///
+/// `visitor` is called with the path (a list of field names) of each changed
+/// leaf, i.e., an `Elem` that is not identical to the original one but whose
+/// `Elem` fields (see [`Diff`]) are all identical to the original ones. The
+/// subtrees identical to the original ones are skipped without examining
+/// them, which is very efficient when the new tree was derived from the old
+/// one by `set_field!`.
+///
+/// Note that a change to a non-`Elem` field of an `Elem` is not reported if
+/// any of its `Elem` fields are reported.
+///
+/// # Examples
+///
+///     use harmony::{diff, set_field, Diff, Elem};
+///
+///     #[derive(Clone, Diff)]
+///     struct App { sidebar: Elem<Sidebar>, main: Elem<Pane> }
+///
+///     #[derive(Clone, Diff)]
+///     struct Sidebar { pane: Elem<Pane>, visible: bool }
+///
+///     #[derive(Clone, Diff)]
+///     struct Pane { title: String }
+///
+///     let pane = || Elem::new(Pane { title: String::new() });
+///     let old = Elem::new(App {
+///         sidebar: Elem::new(Sidebar { pane: pane(), visible: true }),
+///         main: pane(),
+///     });
+///
+///     let new_pane = set_field! { title: "hello".to_owned(), ..old.sidebar.pane.clone() };
+///     let new_sidebar = set_field! { pane: new_pane, ..old.sidebar.clone() };
+///     let new = set_field! { sidebar: new_sidebar, ..old.clone() };
+///
+///     let mut changed = Vec::new();
+///     diff(&old, &new, |path| changed.push(path.join(".")));
+///     assert_eq!(changed, ["sidebar.pane"]);
+///
+pub fn diff<T: Diff>(old: &Elem<T>, new: &Elem<T>, mut visitor: impl FnMut(&[&'static str])) {
+    if Elem::ptr_eq(old, new) {
+        return;
+    }
+
+    let mut differ = Differ {
+        path: Vec::new(),
+        visitor: &mut visitor,
+        num_reported: 0,
+    };
+    differ.visit(&**old, &**new);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{set_field, Diff};
+
+    #[derive(Clone, Diff)]
+    struct Root {
+        a: Elem<Node>,
+        b: Elem<Node>,
+        count: u32,
+    }
+
+    #[derive(Clone, Diff)]
+    struct Node {
+        value: u32,
+        child: Elem<Leaf>,
+    }
+
+    #[derive(Clone, Diff)]
+    struct Leaf(Elem<String>);
+
+    #[derive(Clone, Diff)]
+    enum Either {
+        Left(Elem<Leaf>),
+        Right { node: Elem<Node> },
+        Neither,
+    }
+
+    #[derive(Clone, Diff)]
+    struct Wrapper<T> {
+        content: Elem<T>,
+    }
+
+    fn leaf() -> Elem<Leaf> {
+        Elem::new(Leaf(Elem::new(String::new())))
+    }
+
+    fn node() -> Elem<Node> {
+        Elem::new(Node {
+            value: 0,
+            child: leaf(),
+        })
+    }
+
+    fn root() -> Elem<Root> {
+        Elem::new(Root {
+            a: node(),
+            b: node(),
+            count: 0,
+        })
+    }
+
+    fn collect<T: Diff>(old: &Elem<T>, new: &Elem<T>) -> Vec<String> {
+        let mut changed = Vec::new();
+        diff(old, new, |path| changed.push(path.join(".")));
+        changed
+    }
+
+    #[test]
+    fn identical() {
+        let old = root();
+        assert!(collect(&old, &old.clone()).is_empty());
+
+        let new = set_field! { count: 0, ..old.clone() };
+        assert!(collect(&old, &new).is_empty());
+    }
+
+    #[test]
+    fn root_leaf() {
+        let old = root();
+        let new = set_field! { count: 1, ..old.clone() };
+        assert_eq!(collect(&old, &new), [""]);
+    }
+
+    #[test]
+    fn nested() {
+        let old = root();
+
+        let new_a = set_field! { value: 1, ..old.a.clone() };
+        let new_b_child = Elem::new(Leaf(Elem::new("hello".to_owned())));
+        let new_b = set_field! { child: new_b_child, ..old.b.clone() };
+        let new = set_field! { a: new_a, ..old.clone() };
+        let new = set_field! { b: new_b, ..new };
+
+        assert_eq!(collect(&old, &new), ["a", "b.child.0"]);
+    }
+
+    #[test]
+    fn changed_without_changed_children() {
+        let old = node();
+        // Replaced with a distinct `Elem` sharing the same children
+        let new_child = Elem::new(Leaf(old.child.0.clone()));
+        let new = set_field! { child: new_child, ..old.clone() };
+        assert_eq!(collect(&old, &new), ["child"]);
+    }
+
+    #[test]
+    fn enum_variants() {
+        let old = Elem::new(Either::Right { node: node() });
+
+        let new = Elem::new(Either::Right {
+            node: match &*old {
+                Either::Right { node } => set_field! { value: 1, ..node.clone() },
+                _ => unreachable!(),
+            },
+        });
+        assert_eq!(collect(&old, &new), ["node"]);
+
+        let new = Elem::new(Either::Left(leaf()));
+        assert_eq!(collect(&old, &new), [""]);
+
+        let old = Elem::new(Either::Neither);
+        let new = Elem::new(Either::Neither);
+        assert_eq!(collect(&old, &new), [""]);
+    }
+
+    #[test]
+    fn generic() {
+        let old = Elem::new(Wrapper { content: node() });
+        let new_inner = set_field! { child: leaf(), ..old.content.clone() };
+        let new = set_field! { content: new_inner, ..old.clone() };
+        assert_eq!(collect(&old, &new), ["content.child.0"]);
+    }
+}
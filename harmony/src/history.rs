@@ -0,0 +1,194 @@
+use std::collections::VecDeque;
+
+use super::Elem;
+
+/// Records snapshots of state data for undo and redo.
+///
+/// The snapshots are stored in a ring buffer with a fixed capacity. When the
+/// buffer is full, the oldest snapshot is discarded.
+///
+/// Taking a snapshot is cheap because it only clones an `Elem` (i.e., `Rc`).
+/// Furthermore, a new state produced by a reducer shares unchanged sub-`Elem`s
+/// with the old state. Therefore, the memory cost of keeping many snapshots is
+/// low as long as each of them changes only a few fields.
+///
+/// # Examples
+///
+///     use harmony::{Elem, History};
+///
+///     let mut history = History::new(2);
+///     history.push(Elem::new(1));
+///     history.push(Elem::new(2));
+///     history.push(Elem::new(3));
+///
+///     // `1` was discarded
+///     assert_eq!(history.undo().map(|e| *e), Some(2));
+///     assert_eq!(history.undo().map(|e| *e), None);
+///     assert_eq!(history.redo().map(|e| *e), Some(3));
+///
+#[derive(Debug)]
+pub struct History<S> {
+    snapshots: VecDeque<Elem<S>>,
+    /// The index of the current snapshot in `snapshots`. Meaningless if
+    /// `snapshots` is empty.
+    cursor: usize,
+    capacity: usize,
+}
+
+impl<S> History<S> {
+    /// Construct an empty `History` that can hold up to `capacity` snapshots.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be non-zero");
+        Self {
+            snapshots: VecDeque::with_capacity(capacity),
+            cursor: 0,
+            capacity,
+        }
+    }
+
+    /// Get the maximum number of snapshots this `History` can hold.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the number of snapshots, including the ones that can be restored
+    /// by `redo`.
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Check if there are no snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// Get the current snapshot.
+    pub fn current(&self) -> Option<Elem<S>> {
+        self.snapshots.get(self.cursor).cloned()
+    }
+
+    /// Record a new snapshot and make it current. The snapshots newer than
+    /// the current one are discarded.
+    pub fn push(&mut self, elem: Elem<S>) {
+        if !self.snapshots.is_empty() {
+            self.snapshots.truncate(self.cursor + 1);
+        }
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back(elem);
+        self.cursor = self.snapshots.len() - 1;
+    }
+
+    /// Check if `undo` would succeed.
+    pub fn can_undo(&self) -> bool {
+        self.cursor > 0
+    }
+
+    /// Check if `redo` would succeed.
+    pub fn can_redo(&self) -> bool {
+        self.cursor + 1 < self.snapshots.len()
+    }
+
+    /// Step backward. Returns the new current snapshot, or `None` if there
+    /// are no older snapshots.
+    pub fn undo(&mut self) -> Option<Elem<S>> {
+        if !self.can_undo() {
+            return None;
+        }
+        self.cursor -= 1;
+        self.current()
+    }
+
+    /// Step forward. Returns the new current snapshot, or `None` if there
+    /// are no newer snapshots.
+    pub fn redo(&mut self) -> Option<Elem<S>> {
+        if !self.can_redo() {
+            return None;
+        }
+        self.cursor += 1;
+        self.current()
+    }
+
+    /// Remove all snapshots.
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.cursor = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn current(history: &History<u32>) -> Option<u32> {
+        history.current().map(|e| *e)
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut history = History::new(4);
+        assert_eq!(current(&history), None);
+        assert!(!history.can_undo());
+        assert!(!history.can_redo());
+        assert!(history.undo().is_none());
+        assert!(history.redo().is_none());
+
+        for i in 0..3 {
+            history.push(Elem::new(i));
+        }
+        assert_eq!(current(&history), Some(2));
+
+        assert_eq!(history.undo().map(|e| *e), Some(1));
+        assert_eq!(history.undo().map(|e| *e), Some(0));
+        assert!(history.undo().is_none());
+        assert_eq!(current(&history), Some(0));
+
+        assert_eq!(history.redo().map(|e| *e), Some(1));
+        assert!(history.can_redo());
+
+        // Discards `2`
+        history.push(Elem::new(10));
+        assert!(!history.can_redo());
+        assert_eq!(history.len(), 3);
+        assert_eq!(history.undo().map(|e| *e), Some(1));
+        assert_eq!(history.redo().map(|e| *e), Some(10));
+    }
+
+    #[test]
+    fn capacity() {
+        let mut history = History::new(3);
+        for i in 0..10 {
+            history.push(Elem::new(i));
+            assert!(history.len() <= 3);
+        }
+        assert_eq!(current(&history), Some(9));
+        assert_eq!(history.undo().map(|e| *e), Some(8));
+        assert_eq!(history.undo().map(|e| *e), Some(7));
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn clear() {
+        let mut history = History::new(3);
+        history.push(Elem::new(1));
+        history.push(Elem::new(2));
+        history.clear();
+        assert!(history.is_empty());
+        assert_eq!(current(&history), None);
+
+        history.push(Elem::new(3));
+        assert_eq!(current(&history), Some(3));
+        assert!(!history.can_undo());
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_capacity() {
+        History::<u32>::new(0);
+    }
+}
@@ -2,6 +2,15 @@
 //! [Redux](https://redux.js.org) in Rust.
 //!
 //!  - *Elements* ([`Elem`]) store state data, updated through *reducers*.
+//!  - [`ObservableElem`] holds an `Elem` and notifies subscribers when it's
+//!    replaced.
+//!  - [`Store`] holds an `Elem` and updates it by applying a reducer to
+//!    dispatched actions, which go through a chain of middlewares.
+//!  - [`History`] records snapshots of an `Elem` for undo and redo.
+//!  - [`update!`] updates a field of a nested `Elem`.
+//!  - [`diff`] finds the sub-`Elem`s that changed between two `Elem` trees.
+//!  - [`WeakElem`] is a weak reference to an `Elem`, which can be used to
+//!    refer to an ancestor without creating a reference cycle.
 //!
 //! # Usage
 //!
@@ -60,18 +69,50 @@
 #![feature(specialization)]
 use std::{fmt, rc::Rc};
 
+// Allow the code generated by `harmony_macros` to refer to this crate in this
+// crate
+extern crate self as harmony;
+
+/// Derive [`Diff`].
+pub use harmony_macros::Diff;
+
 #[cfg(feature = "miniserde")]
 mod miniserde;
 
+#[cfg(feature = "serde")]
+mod serde;
+
+mod diff;
+mod history;
+mod observable;
+mod store;
+mod weak;
+pub use self::{
+    diff::{diff, Diff, Differ},
+    history::History,
+    observable::ObservableElem,
+    store::{Middleware, Reducer, Store},
+    weak::WeakElem,
+};
+
 /// A container type for state data.
 ///
 /// `Elem` is conceptually immutable, but may perform in-place mutation when
 /// there are no other owners.
-#[derive(Debug, Clone)]
+#[derive(Debug)]
 pub struct Elem<T: ?Sized> {
     inner: Rc<T>,
 }
 
+// `#[derive(Clone)]` would add an unnecessary `T: Clone` bound
+impl<T: ?Sized> Clone for Elem<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Rc::clone(&self.inner),
+        }
+    }
+}
+
 impl<T> Elem<T> {
     /// Construct a `Elem` with the specified inner value.
     pub fn new(x: T) -> Self {
@@ -81,6 +122,13 @@ impl<T> Elem<T> {
     pub fn ptr_eq(this: &Self, other: &Self) -> bool {
         Rc::ptr_eq(&this.inner, &other.inner)
     }
+
+    /// Create a [`WeakElem`] pointing to `this`.
+    pub fn downgrade(this: &Self) -> WeakElem<T> {
+        WeakElem {
+            inner: Rc::downgrade(&this.inner),
+        }
+    }
 }
 
 impl<T: Clone> Elem<T> {
@@ -138,6 +186,65 @@ macro_rules! set_field {
     }};
 }
 
+/// Update a field of the object contained by a nested [`Elem`], creating a
+/// new root `Elem`. It consumes the root `Elem`.
+///
+/// `update! { this.a.b.c = x }` is equivalent to the following code:
+///
+/// ```text
+/// set_field! {
+///     a: set_field! {
+///         b: set_field! { c: x, ..this.a.b.clone() },
+///         ..this.a.clone()
+///     },
+///     ..this
+/// }
+/// ```
+///
+/// Since each level is updated by [`set_field!`], the ancestors are left
+/// intact (and keep their identity) if the new value is identical to the old
+/// one. `x` is evaluated before the root `Elem` is consumed, so it can refer
+/// to the root `Elem`.
+///
+/// # Examples
+///
+///     use harmony::{Elem, update};
+///
+///     #[derive(Clone)]
+///     struct App { main_wnd: Elem<Wnd> }
+///     #[derive(Clone)]
+///     struct Wnd { count: usize }
+///
+///     let state = Elem::new(App { main_wnd: Elem::new(Wnd { count: 1 }) });
+///
+///     let new_state = update! { state.main_wnd.count = state.main_wnd.count + 1 };
+///     assert_eq!(new_state.main_wnd.count, 2);
+///
+///     // The value is unchanged, so is the root `Elem`
+///     let state = new_state;
+///     let old_state = state.clone();
+///     let new_state = update! { state.main_wnd.count = 2 };
+///     assert!(Elem::ptr_eq(&new_state, &old_state));
+///
+#[macro_export]
+macro_rules! update {
+    ($root:ident . $($path:ident).+ = $value:expr) => {{
+        let value = $value;
+        $crate::update!(@elem $root; $($path).+ = value)
+    }};
+    (@elem $in_elem:expr; $field:ident = $value:expr) => {
+        $crate::set_field! { $field: $value, ..$in_elem }
+    };
+    (@elem $in_elem:expr; $field:ident . $($path:ident).+ = $value:expr) => {{
+        let in_elem: $crate::Elem<_> = $in_elem;
+        let child = $crate::update!(
+            @elem ::std::clone::Clone::clone(&in_elem.$field);
+            $($path).+ = $value
+        );
+        $crate::set_field! { $field: child, ..in_elem }
+    }};
+}
+
 /// Similar to `PartialEq`, but may perform shallow comparison and incorrectly
 /// output "not equal" for deep structures with identical children
 /// which are logically identical, but located in different memory locations.
@@ -171,3 +278,57 @@ impl<T> ShallowEq for Elem<T> {
         !Self::ptr_eq(self, other)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Root {
+        a: Elem<Node>,
+        b: Elem<Node>,
+    }
+
+    #[derive(Clone)]
+    struct Node {
+        leaf: Elem<Leaf>,
+    }
+
+    #[derive(Clone)]
+    struct Leaf {
+        value: u32,
+    }
+
+    fn node() -> Elem<Node> {
+        Elem::new(Node {
+            leaf: Elem::new(Leaf { value: 0 }),
+        })
+    }
+
+    #[test]
+    fn update_nested() {
+        let root = Elem::new(Root {
+            a: node(),
+            b: node(),
+        });
+        let old = root.clone();
+
+        let root = update! { root.a.leaf.value = 42 };
+        assert!(!Elem::ptr_eq(&root, &old));
+        assert!(!Elem::ptr_eq(&root.a, &old.a));
+        assert!(Elem::ptr_eq(&root.b, &old.b));
+        assert_eq!(root.a.leaf.value, 42);
+        assert_eq!(old.a.leaf.value, 0);
+
+        let old = root.clone();
+        let root = update! { root.a.leaf.value = root.a.leaf.value };
+        assert!(Elem::ptr_eq(&root, &old));
+    }
+
+    #[test]
+    fn update_single_level() {
+        let leaf = Elem::new(Leaf { value: 0 });
+        let leaf = update! { leaf.value = leaf.value + 1 };
+        assert_eq!(leaf.value, 1);
+    }
+}
@@ -0,0 +1,182 @@
+use std::{cell::RefCell, fmt};
+use subscriber_list::{SubscriberList, UntypedSubscription};
+
+use super::Elem;
+
+type Handler<T> = Box<dyn Fn(&Elem<T>)>;
+
+/// Holds an [`Elem`] and notifies subscribers when it's replaced with another
+/// `Elem`.
+///
+/// Replacing the `Elem` with the one already stored (as determined by
+/// [`Elem::ptr_eq`]) is a no-op and doesn't trigger the subscribers.
+pub struct ObservableElem<T> {
+    elem: RefCell<Elem<T>>,
+    handlers: RefCell<SubscriberList<Handler<T>>>,
+}
+
+impl<T: fmt::Debug> fmt::Debug for ObservableElem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ObservableElem")
+            .field("elem", &self.elem)
+            .field("handlers", &())
+            .finish()
+    }
+}
+
+impl<T> ObservableElem<T> {
+    /// Construct an `ObservableElem` with the specified initial `Elem`.
+    pub fn new(elem: Elem<T>) -> Self {
+        Self {
+            elem: RefCell::new(elem),
+            handlers: RefCell::new(SubscriberList::new()),
+        }
+    }
+
+    /// Get the currently stored `Elem`.
+    pub fn get(&self) -> Elem<T> {
+        self.elem.borrow().clone()
+    }
+
+    /// Replace the stored `Elem` with `new_elem`. Calls the subscribers if
+    /// `new_elem` is not identical to the current one.
+    ///
+    /// Returns `true` if the stored `Elem` was replaced.
+    pub fn set(&self, new_elem: Elem<T>) -> bool {
+        {
+            let mut elem = self.elem.borrow_mut();
+            if Elem::ptr_eq(&elem, &new_elem) {
+                return false;
+            }
+            *elem = new_elem.clone();
+        }
+
+        let handlers = self.handlers.borrow();
+        for handler in handlers.iter() {
+            handler(&new_elem);
+        }
+
+        true
+    }
+
+    /// Apply a reducer for each of `actions` in order and replace the stored
+    /// `Elem` with the final result.
+    ///
+    /// Intermediate states are not observable. The subscribers are called at
+    /// most once, and only if the final `Elem` is not identical to the
+    /// original one (see [`set`](Self::set)).
+    ///
+    /// Returns `true` if the stored `Elem` was replaced.
+    pub fn dispatch_all<I, F>(&self, actions: I, reducer: F) -> bool
+    where
+        I: IntoIterator,
+        F: FnMut(Elem<T>, I::Item) -> Elem<T>,
+    {
+        let elem = actions.into_iter().fold(self.get(), reducer);
+        self.set(elem)
+    }
+
+    /// Add a function to be called when the stored `Elem` is replaced.
+    /// The function receives the new `Elem`.
+    ///
+    /// Returns a [`UntypedSubscription`], which can be used to unregister the
+    /// function.
+    ///
+    /// This method will panic if it's called from one of the subscribers.
+    pub fn subscribe(&self, cb: Handler<T>) -> UntypedSubscription
+    where
+        T: 'static,
+    {
+        self.handlers.borrow_mut().insert(cb).untype()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{cell::Cell, rc::Rc};
+
+    #[test]
+    fn fire_once_per_distinct_update() {
+        let elem = Elem::new(1);
+        let observable = ObservableElem::new(elem.clone());
+
+        let count = Rc::new(Cell::new(0));
+        let last = Rc::new(Cell::new(0));
+        let sub = {
+            let count = Rc::clone(&count);
+            let last = Rc::clone(&last);
+            observable.subscribe(Box::new(move |elem| {
+                count.set(count.get() + 1);
+                last.set(**elem);
+            }))
+        };
+
+        // No-op update
+        assert!(!observable.set(elem.clone()));
+        assert_eq!(count.get(), 0);
+
+        // A new `Elem`, even if it has an identical value
+        let elem2 = Elem::new(1);
+        assert!(observable.set(elem2.clone()));
+        assert_eq!(count.get(), 1);
+        assert!(Elem::ptr_eq(&observable.get(), &elem2));
+
+        assert!(!observable.set(elem2));
+        assert_eq!(count.get(), 1);
+
+        assert!(observable.set(Elem::new(42)));
+        assert_eq!(count.get(), 2);
+        assert_eq!(last.get(), 42);
+
+        sub.unsubscribe().unwrap();
+        assert!(observable.set(elem));
+        assert_eq!(count.get(), 2);
+    }
+
+    #[test]
+    fn dispatch_all_fires_once() {
+        use crate::set_field;
+
+        #[derive(Clone)]
+        struct State {
+            count: u32,
+        }
+
+        enum Action {
+            Increment,
+            Set(u32),
+        }
+
+        fn reduce(this: Elem<State>, act: &Action) -> Elem<State> {
+            match act {
+                Action::Increment => set_field! { count: this.count + 1, ..this },
+                Action::Set(x) => set_field! { count: *x, ..this },
+            }
+        }
+
+        let observable = ObservableElem::new(Elem::new(State { count: 0 }));
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let _sub = {
+            let log = Rc::clone(&log);
+            observable.subscribe(Box::new(move |elem| {
+                log.borrow_mut().push(elem.count);
+            }))
+        };
+
+        let actions = [Action::Increment, Action::Increment, Action::Increment];
+        assert!(observable.dispatch_all(actions.iter(), reduce));
+        assert_eq!(*log.borrow(), vec![3]);
+        assert_eq!(observable.get().count, 3);
+
+        // The net result is unchanged, so the subscribers aren't called
+        let actions = [Action::Set(3), Action::Set(3)];
+        assert!(!observable.dispatch_all(actions.iter(), reduce));
+        assert_eq!(*log.borrow(), vec![3]);
+
+        // An empty batch is a no-op
+        assert!(!observable.dispatch_all(std::iter::empty(), reduce));
+        assert_eq!(*log.borrow(), vec![3]);
+    }
+}
@@ -0,0 +1,59 @@
+//! `serde` support
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::Elem;
+
+impl<T> Serialize for Elem<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        (**self).serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Elem<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        T::deserialize(deserializer).map(Elem::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct App {
+        primitive_field: f32,
+        main_wnd: Elem<Wnd>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Wnd {
+        count: usize,
+    }
+
+    #[test]
+    fn roundtrip() {
+        let input = Elem::new(App {
+            primitive_field: 1.5,
+            main_wnd: Elem::new(Wnd { count: 42 }),
+        });
+
+        let json = serde_json::to_string(&input).unwrap();
+        assert_eq!(json, r#"{"primitive_field":1.5,"main_wnd":{"count":42}}"#);
+
+        let output: Elem<App> = serde_json::from_str(&json).unwrap();
+        assert_eq!(output.primitive_field, 1.5);
+        assert_eq!(output.main_wnd.count, 42);
+    }
+}
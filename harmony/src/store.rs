@@ -0,0 +1,403 @@
+use std::{cell::RefCell, fmt};
+use subscriber_list::UntypedSubscription;
+
+use super::{Elem, History, ObservableElem};
+
+/// A reducer function used by [`Store`].
+pub type Reducer<S, A> = fn(Elem<S>, &A) -> Elem<S>;
+
+/// A middleware function used by [`Store`].
+///
+/// A middleware receives an action and a function `next`, which passes an
+/// action to the next middleware (or the reducer if there are no more
+/// middlewares). A middleware may inspect or replace the action, and may call
+/// `next` any number of times, including zero.
+pub type Middleware<A> = Box<dyn Fn(&A, &dyn Fn(&A))>;
+
+type Handler<S> = Box<dyn Fn(&Elem<S>)>;
+
+/// Owns a state [`Elem`] and updates it by applying a reducer to dispatched
+/// actions.
+///
+/// The actions go through a chain of [middlewares](Middleware) before reaching
+/// the reducer. Subscribers are notified when the state is replaced with
+/// another `Elem`, like [`ObservableElem`].
+///
+/// # Examples
+///
+///     use harmony::{Elem, Store, set_field};
+///     use std::{cell::RefCell, rc::Rc};
+///
+///     #[derive(Clone)]
+///     struct State { count: u32 }
+///     enum Action { Increment }
+///
+///     fn reduce(this: Elem<State>, act: &Action) -> Elem<State> {
+///         match act {
+///             Action::Increment => set_field! { count: this.count + 1, ..this },
+///         }
+///     }
+///
+///     let mut store = Store::new(Elem::new(State { count: 0 }), reduce);
+///
+///     let log = Rc::new(RefCell::new(Vec::new()));
+///     let log2 = Rc::clone(&log);
+///     store.add_middleware(Box::new(move |act, next| {
+///         log2.borrow_mut().push("dispatching");
+///         next(act);
+///     }));
+///
+///     store.dispatch(&Action::Increment);
+///     assert_eq!(store.get().count, 1);
+///     assert_eq!(*log.borrow(), ["dispatching"]);
+///
+pub struct Store<S, A> {
+    elem: ObservableElem<S>,
+    reducer: Reducer<S, A>,
+    middlewares: Vec<Middleware<A>>,
+    history: Option<History<S>>,
+}
+
+impl<S: fmt::Debug, A> fmt::Debug for Store<S, A> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Store")
+            .field("elem", &self.elem)
+            .field("reducer", &())
+            .field("middlewares", &())
+            .field("history", &self.history)
+            .finish()
+    }
+}
+
+impl<S, A> Store<S, A> {
+    /// Construct a `Store` with the specified initial state and reducer.
+    pub fn new(elem: Elem<S>, reducer: Reducer<S, A>) -> Self {
+        Self {
+            elem: ObservableElem::new(elem),
+            reducer,
+            middlewares: Vec::new(),
+            history: None,
+        }
+    }
+
+    /// Get the current state.
+    pub fn get(&self) -> Elem<S> {
+        self.elem.get()
+    }
+
+    /// Append a middleware to the middleware chain. The first middleware
+    /// receives dispatched actions first.
+    pub fn add_middleware(&mut self, middleware: Middleware<A>) {
+        self.middlewares.push(middleware);
+    }
+
+    /// Dispatch an action. The action goes through the middleware chain and
+    /// then the reducer. The subscribers are called if the resulting state is
+    /// not identical to the original one (see [`ObservableElem::set`]).
+    ///
+    /// Returns `true` if the state was replaced.
+    pub fn dispatch(&mut self, action: &A) -> bool {
+        let state = RefCell::new(self.elem.get());
+        self.reduce(&state, action);
+        self.set(state.into_inner())
+    }
+
+    /// Dispatch multiple actions in order. Each action goes through the
+    /// middleware chain and then the reducer.
+    ///
+    /// Intermediate states are not observable. The subscribers are called at
+    /// most once, and only if the final state is not identical to the
+    /// original one (see [`ObservableElem::dispatch_all`]).
+    ///
+    /// Returns `true` if the state was replaced.
+    pub fn dispatch_batch(&mut self, actions: impl IntoIterator<Item = A>) -> bool {
+        let state = RefCell::new(self.elem.get());
+        for action in actions {
+            self.reduce(&state, &action);
+        }
+        self.set(state.into_inner())
+    }
+
+    /// Replace the state, recording a snapshot if it was replaced.
+    fn set(&mut self, elem: Elem<S>) -> bool {
+        if !self.elem.set(elem) {
+            return false;
+        }
+        if let Some(history) = &mut self.history {
+            history.push(self.elem.get());
+        }
+        true
+    }
+
+    fn reduce(&self, state: &RefCell<Elem<S>>, action: &A) {
+        let reducer = self.reducer;
+
+        run_middlewares(&self.middlewares, action, &|action| {
+            let mut state = state.borrow_mut();
+            *state = reducer(state.clone(), action);
+        });
+    }
+
+    /// Add a function to be called when the state is replaced.
+    /// The function receives the new state.
+    ///
+    /// See [`ObservableElem::subscribe`].
+    pub fn subscribe(&self, cb: Handler<S>) -> UntypedSubscription
+    where
+        S: 'static,
+    {
+        self.elem.subscribe(cb)
+    }
+
+    /// Start recording a snapshot of the state every time it's replaced by
+    /// `dispatch` or `dispatch_batch`. The current state is recorded as the
+    /// first snapshot. At most `capacity` snapshots are kept.
+    ///
+    /// The previously recorded snapshots are discarded.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn enable_history(&mut self, capacity: usize) {
+        let mut history = History::new(capacity);
+        history.push(self.elem.get());
+        self.history = Some(history);
+    }
+
+    /// Stop recording snapshots and discard the recorded ones.
+    pub fn disable_history(&mut self) {
+        self.history = None;
+    }
+
+    /// Get the recorded snapshots. Returns `None` if the history is disabled.
+    pub fn history(&self) -> Option<&History<S>> {
+        self.history.as_ref()
+    }
+
+    /// Restore the previous snapshot. The subscribers are called if the
+    /// state was replaced.
+    ///
+    /// Returns `false` if the history is disabled or there are no older
+    /// snapshots.
+    pub fn undo(&mut self) -> bool {
+        match self.history.as_mut().and_then(History::undo) {
+            Some(elem) => {
+                self.elem.set(elem);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Restore the next snapshot undone by `undo`. The subscribers are called
+    /// if the state was replaced.
+    ///
+    /// Returns `false` if the history is disabled or there are no newer
+    /// snapshots.
+    pub fn redo(&mut self) -> bool {
+        match self.history.as_mut().and_then(History::redo) {
+            Some(elem) => {
+                self.elem.set(elem);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+fn run_middlewares<A>(middlewares: &[Middleware<A>], action: &A, reduce: &dyn Fn(&A)) {
+    if let Some((first, rest)) = middlewares.split_first() {
+        first(action, &|action| run_middlewares(rest, action, reduce));
+    } else {
+        reduce(action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_field;
+    use std::rc::Rc;
+
+    #[derive(Clone, Debug)]
+    struct State {
+        count: u32,
+    }
+
+    #[derive(Debug)]
+    enum Action {
+        Increment,
+        Set(u32),
+    }
+
+    fn reduce(this: Elem<State>, act: &Action) -> Elem<State> {
+        match act {
+            Action::Increment => set_field! { count: this.count + 1, ..this },
+            Action::Set(x) => set_field! { count: *x, ..this },
+        }
+    }
+
+    #[test]
+    fn logging_middleware() {
+        let mut store = Store::new(Elem::new(State { count: 0 }), reduce);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        for &name in &["outer", "inner"] {
+            let log = Rc::clone(&log);
+            store.add_middleware(Box::new(move |act, next| {
+                log.borrow_mut().push(format!("{}: begin {:?}", name, act));
+                next(act);
+                log.borrow_mut().push(format!("{}: end", name));
+            }));
+        }
+
+        let _sub = {
+            let log = Rc::clone(&log);
+            store.subscribe(Box::new(move |elem| {
+                log.borrow_mut().push(format!("notify {}", elem.count));
+            }))
+        };
+
+        assert!(store.dispatch(&Action::Increment));
+        assert_eq!(store.get().count, 1);
+        assert_eq!(
+            *log.borrow(),
+            [
+                "outer: begin Increment",
+                "inner: begin Increment",
+                "inner: end",
+                "outer: end",
+                "notify 1",
+            ]
+        );
+        log.borrow_mut().clear();
+
+        // The state is unchanged, so the subscribers aren't called
+        assert!(!store.dispatch(&Action::Set(1)));
+        assert_eq!(
+            *log.borrow(),
+            [
+                "outer: begin Set(1)",
+                "inner: begin Set(1)",
+                "inner: end",
+                "outer: end",
+            ]
+        );
+    }
+
+    #[test]
+    fn middleware_replace_action() {
+        let mut store = Store::new(Elem::new(State { count: 0 }), reduce);
+
+        // Swallow `Set` and double `Increment`
+        store.add_middleware(Box::new(|act, next| match act {
+            Action::Increment => {
+                next(act);
+                next(act);
+            }
+            Action::Set(_) => {}
+        }));
+        // Clamp the value
+        store.add_middleware(Box::new(|act, next| match act {
+            Action::Set(x) => next(&Action::Set((*x).min(10))),
+            _ => next(act),
+        }));
+
+        assert!(store.dispatch(&Action::Increment));
+        assert_eq!(store.get().count, 2);
+
+        assert!(!store.dispatch(&Action::Set(100)));
+        assert_eq!(store.get().count, 2);
+    }
+
+    #[test]
+    fn dispatch_batch_fires_once() {
+        let mut store = Store::new(Elem::new(State { count: 0 }), reduce);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        {
+            let log = Rc::clone(&log);
+            store.add_middleware(Box::new(move |act, next| {
+                log.borrow_mut().push(format!("{:?}", act));
+                next(act);
+            }));
+        }
+
+        let _sub = {
+            let log = Rc::clone(&log);
+            store.subscribe(Box::new(move |elem| {
+                log.borrow_mut().push(format!("notify {}", elem.count));
+            }))
+        };
+
+        assert!(store.dispatch_batch(vec![Action::Increment, Action::Increment, Action::Set(5)]));
+        assert_eq!(store.get().count, 5);
+        assert_eq!(
+            *log.borrow(),
+            ["Increment", "Increment", "Set(5)", "notify 5"]
+        );
+        log.borrow_mut().clear();
+
+        // The net result is unchanged, so the subscribers aren't called
+        assert!(!store.dispatch_batch(vec![Action::Set(5), Action::Set(5)]));
+        assert_eq!(*log.borrow(), ["Set(5)", "Set(5)"]);
+        log.borrow_mut().clear();
+
+        // An empty batch is a no-op
+        assert!(!store.dispatch_batch(std::iter::empty()));
+        assert!(log.borrow().is_empty());
+    }
+
+    #[test]
+    fn history() {
+        let mut store = Store::new(Elem::new(State { count: 0 }), reduce);
+        assert!(!store.undo());
+        assert!(store.history().is_none());
+
+        store.enable_history(3);
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let _sub = {
+            let log = Rc::clone(&log);
+            store.subscribe(Box::new(move |elem| {
+                log.borrow_mut().push(elem.count);
+            }))
+        };
+
+        store.dispatch(&Action::Increment);
+        store.dispatch(&Action::Increment);
+        store.dispatch(&Action::Increment);
+        // The state is unchanged, so no snapshot is recorded
+        store.dispatch(&Action::Set(3));
+        assert_eq!(store.history().unwrap().len(), 3);
+
+        assert!(store.undo());
+        assert_eq!(store.get().count, 2);
+        assert!(store.undo());
+        assert_eq!(store.get().count, 1);
+        // `0` was discarded
+        assert!(!store.undo());
+        assert_eq!(store.get().count, 1);
+
+        assert!(store.redo());
+        assert_eq!(store.get().count, 2);
+
+        // Discards `3`
+        store.dispatch(&Action::Set(10));
+        assert!(!store.redo());
+        assert!(store.undo());
+        assert_eq!(store.get().count, 2);
+
+        assert_eq!(*log.borrow(), [1, 2, 3, 2, 1, 2, 10, 2]);
+
+        store.disable_history();
+        assert!(!store.undo());
+    }
+
+    #[test]
+    fn no_middlewares() {
+        let mut store = Store::new(Elem::new(State { count: 0 }), reduce);
+        assert!(store.dispatch(&Action::Set(42)));
+        assert_eq!(store.get().count, 42);
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fmt, rc::Weak};
+
+use super::{Elem, ShallowEq};
+
+/// A weak reference to the contents of [`Elem`], created by
+/// [`Elem::downgrade`].
+///
+/// `WeakElem` doesn't keep the contents alive, so it can be used to refer to
+/// an ancestor from a descendant without creating a reference cycle.
+///
+/// Note that [`set_field!`](crate::set_field) and other operations that
+/// update `Elem` produce a new `Elem`. Existing `WeakElem`s keep pointing to
+/// the old one (which may be already dropped) and never observe the update.
+pub struct WeakElem<T: ?Sized> {
+    pub(crate) inner: Weak<T>,
+}
+
+// `#[derive(Clone)]` would add an unnecessary `T: Clone` bound
+impl<T: ?Sized> Clone for WeakElem<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Weak::clone(&self.inner),
+        }
+    }
+}
+
+impl<T: ?Sized> fmt::Debug for WeakElem<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "(WeakElem)")
+    }
+}
+
+impl<T> WeakElem<T> {
+    /// Construct a `WeakElem` that doesn't point to anything. [`upgrade`]
+    /// always returns `None`.
+    ///
+    /// [`upgrade`]: Self::upgrade
+    pub fn new() -> Self {
+        Self { inner: Weak::new() }
+    }
+}
+
+impl<T> Default for WeakElem<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: ?Sized> WeakElem<T> {
+    /// Get the `Elem` pointed by `self`. Returns `None` if it has been
+    /// dropped.
+    pub fn upgrade(&self) -> Option<Elem<T>> {
+        self.inner.upgrade().map(|inner| Elem { inner })
+    }
+
+    /// Return `true` if two `WeakElem`s point to the same `Elem` (or both
+    /// point to nothing, in the case of [`WeakElem::new`]).
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        this.inner.ptr_eq(&other.inner)
+    }
+}
+
+impl<T> ShallowEq for WeakElem<T> {
+    fn shallow_eq(&self, other: &Self) -> bool {
+        Self::ptr_eq(self, other)
+    }
+    fn shallow_ne(&self, other: &Self) -> bool {
+        !Self::ptr_eq(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::set_field;
+
+    #[derive(Clone)]
+    struct Parent {
+        name: &'static str,
+    }
+
+    #[derive(Clone)]
+    struct Child {
+        parent: WeakElem<Parent>,
+        count: u32,
+    }
+
+    #[test]
+    fn upgrade() {
+        let parent = Elem::new(Parent { name: "a" });
+        let weak = Elem::downgrade(&parent);
+        assert!(Elem::ptr_eq(&weak.upgrade().unwrap(), &parent));
+        assert_eq!(weak.upgrade().unwrap().name, "a");
+
+        drop(parent);
+        assert!(weak.upgrade().is_none());
+
+        assert!(WeakElem::<Parent>::new().upgrade().is_none());
+    }
+
+    #[test]
+    fn set_field_shallow_eq() {
+        let parent = Elem::new(Parent { name: "a" });
+        let child = Elem::new(Child {
+            parent: Elem::downgrade(&parent),
+            count: 0,
+        });
+        let child2 = child.clone();
+
+        // Assigning the same `WeakElem` is a no-op
+        let child = set_field! {
+            parent: Elem::downgrade(&parent),
+            ..child
+        };
+        assert!(Elem::ptr_eq(&child, &child2));
+
+        // Assigning a different one isn't
+        let other_parent = Elem::new(Parent { name: "b" });
+        let child = set_field! {
+            parent: Elem::downgrade(&other_parent),
+            ..child
+        };
+        assert!(!Elem::ptr_eq(&child, &child2));
+        assert_eq!(child.parent.upgrade().unwrap().name, "b");
+    }
+
+    #[test]
+    fn no_resurrection() {
+        let parent = Elem::new(Parent { name: "a" });
+        let child = Elem::new(Child {
+            parent: Elem::downgrade(&parent),
+            count: 0,
+        });
+
+        drop(parent);
+        assert!(child.parent.upgrade().is_none());
+
+        // Mutating the child doesn't bring back the parent
+        let child = set_field! {
+            count: child.count + 1,
+            ..child
+        };
+        assert_eq!(child.count, 1);
+        assert!(child.parent.upgrade().is_none());
+    }
+}
@@ -0,0 +1,71 @@
+use cgmath::Point2;
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+use rgb::RGBA8;
+use stvg_io::{decompress, Cmd, CmdDecoder, CmdEncoder};
+
+struct Xorshift32(u32);
+
+impl Iterator for Xorshift32 {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        Some(self.0)
+    }
+}
+
+/// Generate an encoder containing random shapes.
+fn random_shapes(num_shapes: usize, rng: &mut Xorshift32) -> CmdEncoder {
+    let mut point = || {
+        let x = rng.next().unwrap();
+        Point2::new((x & 0x3fff) as i16, ((x >> 16) & 0x3fff) as i16)
+    };
+
+    let mut encoder = CmdEncoder::new();
+    for i in 0..num_shapes {
+        encoder.set_fill_rgb(RGBA8::new(i as u8, 0x80, 0x40, 0xff));
+        encoder.begin_path();
+        encoder.move_to(point());
+        for _ in 0..8 {
+            encoder.line_to(point());
+            encoder.cubic_bezier_to([point(), point(), point()]);
+        }
+        encoder.fill();
+    }
+    encoder
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let encoder = random_shapes(256, &mut Xorshift32(42));
+    let raw = encoder.clone().take_bytes();
+    let compressed = encoder.clone().take_bytes_compressed();
+    println!(
+        "size: {} bytes (uncompressed), {} bytes (compressed)",
+        raw.len(),
+        compressed.len()
+    );
+
+    let mut group = c.benchmark_group("decode");
+    group.throughput(Throughput::Bytes(raw.len() as u64));
+
+    group.bench_function("uncompressed", |b| {
+        b.iter(|| {
+            CmdDecoder::from_bytes(&decompress(&raw))
+                .filter(|cmd| *cmd == Cmd::Fill)
+                .count()
+        });
+    });
+
+    group.bench_function("compressed", |b| {
+        b.iter(|| {
+            CmdDecoder::from_bytes(&decompress(&compressed))
+                .filter(|cmd| *cmd == Cmd::Fill)
+                .count()
+        });
+    });
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
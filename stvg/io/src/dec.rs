@@ -1,13 +1,95 @@
 //! Decoder
 use arrayvec::ArrayVec;
-use cgmath::Point2;
+use cggeom::Box2;
+use cgmath::{prelude::*, Matrix3, Point2};
 use rgb::FromSlice;
+use std::borrow::Cow;
 
-use crate::{op, Cmd, BYTES_PER_POINT, CONTOUR_HDR_SIZE};
+use crate::{
+    ext, matrix_from_transform, op, Cmd, FillRule, BYTES_PER_POINT, COMPRESSED_HEADER,
+    CONTOUR_HDR_SIZE, FRAC_BITS,
+};
+
+/// Decompress StellaVG data produced by `CmdEncoder::take_bytes_compressed`.
+/// Uncompressed data is returned as-is, so this function can be used to
+/// accept both forms of StellaVG data.
+///
+/// The result can be passed to [`CmdDecoder::from_bytes`]. Panics if the
+/// compressed data is corrupted. See [`try_decompress`] for a non-panicking
+/// version.
+///
+/// # Examples
+///
+///     use stvg_io::{decompress, Cmd, CmdDecoder, CmdEncoder};
+///     let mut encoder = CmdEncoder::new();
+///     encoder.cmd(Cmd::Fill);
+///
+///     let compressed = encoder.clone().take_bytes_compressed();
+///     let raw = encoder.take_bytes();
+///     assert_eq!(*decompress(&compressed), *raw);
+///     assert_eq!(*decompress(&raw), *raw);
+///
+///     let cmds: Vec<_> = CmdDecoder::from_bytes(&decompress(&compressed)).collect();
+///     assert_eq!(cmds, [Cmd::Fill]);
+///
+pub fn decompress(data: &[u8]) -> Cow<'_, [u8]> {
+    try_decompress(data).expect("corrupted compressed data")
+}
+
+/// Decompress StellaVG data like [`decompress`], but returns
+/// `Err(DecodeError::CorruptedCompressedData)` instead of panicking if the
+/// compressed data is corrupted.
+pub fn try_decompress(data: &[u8]) -> Result<Cow<'_, [u8]>, DecodeError> {
+    if data.first() == Some(&COMPRESSED_HEADER) {
+        let decompressed = miniz_oxide::inflate::decompress_to_vec(&data[1..])
+            .map_err(|_| DecodeError::CorruptedCompressedData)?;
+        Ok(Cow::Owned(decompressed))
+    } else {
+        Ok(Cow::Borrowed(data))
+    }
+}
+
+/// Indicates that StellaVG data is invalid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DecodeError {
+    /// The data ends in the middle of a command.
+    Truncated,
+    /// An op flag byte is zero. This can happen if compressed data is passed
+    /// to `CmdDecoder` without decompressing it first.
+    EmptyOp,
+    /// The extended op code is unknown.
+    UnknownExtOp(u8),
+    /// The fill rule value is unknown.
+    UnknownFillRule(u8),
+    /// The on-curve flags of a contour are inconsistent with its point count.
+    MalformedContour,
+    /// The compressed data could not be decompressed.
+    CorruptedCompressedData,
+    /// `SaveTransform`/`RestoreTransform` and `BeginClip`/`EndClip` are not
+    /// properly nested.
+    UnbalancedStack,
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "unexpected end of data"),
+            DecodeError::EmptyOp => write!(f, "empty op flags"),
+            DecodeError::UnknownExtOp(code) => write!(f, "unknown extended op {}", code),
+            DecodeError::UnknownFillRule(rule) => write!(f, "unknown fill rule {}", rule),
+            DecodeError::MalformedContour => write!(f, "malformed contour"),
+            DecodeError::CorruptedCompressedData => write!(f, "corrupted compressed data"),
+            DecodeError::UnbalancedStack => write!(f, "unbalanced transform/clip stack"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
 
 /// An iterator over drawing commands in StellaVG data.
 ///
-/// It assumes the data is valid and handles invalid data by panicking.
+/// It handles invalid data by panicking. Use [`CmdDecoder::try_from_bytes`]
+/// or [`CmdDecoder::try_iter`] to handle invalid data gracefully.
 #[derive(Debug, Clone)]
 pub struct CmdDecoder<'a> {
     data: &'a [u8],
@@ -25,6 +107,7 @@ pub struct CmdDecoder<'a> {
 }
 
 impl<'a> CmdDecoder<'a> {
+    /// Construct a `CmdDecoder` without validating `data`.
     pub fn from_bytes(data: &'a [u8]) -> Self {
         Self {
             data,
@@ -37,14 +120,127 @@ impl<'a> CmdDecoder<'a> {
             curve_index: 0,
         }
     }
-}
 
-impl<'a> Iterator for CmdDecoder<'a> {
-    type Item = Cmd;
+    /// Construct a `CmdDecoder` after validating the entirety of `data`,
+    /// including the nesting of `SaveTransform`/`RestoreTransform` and
+    /// `BeginClip`/`EndClip`.
+    ///
+    /// The returned `CmdDecoder` never panics while decoding.
+    ///
+    /// # Examples
+    ///
+    ///     use stvg_io::{Cmd, CmdDecoder, CmdEncoder, DecodeError};
+    ///     let mut encoder = CmdEncoder::new();
+    ///     encoder.cmd(Cmd::SetFillRgb([1, 2, 3, 4].into()));
+    ///     let bytes = encoder.take_bytes();
+    ///
+    ///     assert!(CmdDecoder::try_from_bytes(&bytes).is_ok());
+    ///     assert_eq!(
+    ///         CmdDecoder::try_from_bytes(&bytes[..3]).unwrap_err(),
+    ///         DecodeError::Truncated,
+    ///     );
+    ///
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, DecodeError> {
+        let this = Self::from_bytes(data);
 
-    fn next(&mut self) -> Option<Self::Item> {
+        // `true` = `BeginClip`, `false` = `SaveTransform`
+        let mut stack = Vec::new();
+
+        for result in this.clone().try_iter() {
+            let cmd = result?;
+            match cmd {
+                Cmd::SaveTransform => stack.push(false),
+                Cmd::BeginClip => stack.push(true),
+                Cmd::RestoreTransform | Cmd::EndClip => {
+                    let is_clip = cmd == Cmd::EndClip;
+                    if stack.pop() != Some(is_clip) {
+                        return Err(DecodeError::UnbalancedStack);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if !stack.is_empty() {
+            return Err(DecodeError::UnbalancedStack);
+        }
+
+        Ok(this)
+    }
+
+    /// Convert `self` into a fallible iterator, which yields
+    /// `Err(DecodeError)` instead of panicking on invalid data.
+    pub fn try_iter(self) -> TryIter<'a> {
+        TryIter { inner: self }
+    }
+
+    /// Calculate the bounding box of all points (including Bézier control
+    /// points) in the remaining commands, without consuming `self`.
+    ///
+    /// The returned box is in the same coordinate space as the one used by
+    /// `CanvasStvgExt::draw_stellavg`, i.e., the fixed-point coordinates are
+    /// divided by `1 << FRAC_BITS`. The points are transformed by
+    /// [`Cmd::Transform`]. Line widths and clipping are not taken into
+    /// account. The commands after a stack underflow are ignored.
+    ///
+    /// Returns `None` if there are no points.
+    pub fn bounds(&self) -> Option<Box2<f32>> {
+        let mut points = self
+            .clone()
+            .scan(
+                (Matrix3::identity(), Vec::new()),
+                |(xform, xform_stack), cmd| {
+                    let mut points = ArrayVec::<[Point2<i16>; 3]>::new();
+                    match cmd {
+                        Cmd::MoveTo(p) | Cmd::LineTo(p) => points.push(p),
+                        Cmd::QuadBezierTo(cps) => points.extend(cps.iter().cloned()),
+                        Cmd::CubicBezierTo(cps) => points.extend(cps.iter().cloned()),
+                        Cmd::SaveTransform | Cmd::BeginClip => xform_stack.push(*xform),
+                        Cmd::RestoreTransform | Cmd::EndClip => {
+                            // Stop on underflow
+                            *xform = xform_stack.pop()?;
+                        }
+                        Cmd::Transform(m) => *xform = *xform * matrix_from_transform(m),
+                        _ => {}
+                    }
+
+                    let xform: Matrix3<f32> = *xform;
+                    Some(
+                        points
+                            .into_iter()
+                            .map(move |p| xform.transform_point(p.cast::<f32>().unwrap())),
+                    )
+                },
+            )
+            .flatten();
+
+        let first = points.next()?;
+        let (min, max) = points.fold((first, first), |(min, max), p| {
+            (
+                Point2::new(min.x.min(p.x), min.y.min(p.y)),
+                Point2::new(max.x.max(p.x), max.y.max(p.y)),
+            )
+        });
+
+        let scale = 1.0 / (1 << FRAC_BITS) as f32;
+        Some(Box2::new(min * scale, max * scale))
+    }
+
+    /// Decode the next command. On error, `self` is made empty so that
+    /// subsequent calls return `None`.
+    fn try_next(&mut self) -> Option<Result<Cmd, DecodeError>> {
+        let result = self.try_next_inner();
+        if let Some(Err(_)) = result {
+            self.data = &[];
+            self.curve_points = &[];
+        }
+        result
+    }
+
+    fn try_next_inner(&mut self) -> Option<Result<Cmd, DecodeError>> {
         if self.curve_points.len() >= 4 {
-            // Get the next control point(s)
+            // Get the next control point(s). The curve flags were validated
+            // by the `CONTOUR` handler.
             let mut cps = ArrayVec::<[Point2<i16>; 3]>::new();
 
             loop {
@@ -64,70 +260,191 @@ impl<'a> Iterator for CmdDecoder<'a> {
                 }
             }
 
-            match cps.len() {
-                1 => Some(Cmd::LineTo(cps[0])),
-                2 => Some(Cmd::QuadBezierTo([cps[0], cps[1]])),
-                3 => Some(Cmd::CubicBezierTo([cps[0], cps[1], cps[2]])),
+            Some(Ok(match cps.len() {
+                1 => Cmd::LineTo(cps[0]),
+                2 => Cmd::QuadBezierTo([cps[0], cps[1]]),
+                3 => Cmd::CubicBezierTo([cps[0], cps[1], cps[2]]),
                 _ => unreachable!(),
-            }
+            }))
         } else if !self.data.is_empty() {
+            Some(self.next_op_cmd())
+        } else {
+            None
+        }
+    }
+
+    /// Decode the command represented by the next unprocessed op flag.
+    fn next_op_cmd(&mut self) -> Result<Cmd, DecodeError> {
+        if self.op == 0 {
+            self.op = self.data[0];
+            self.param_len = 1;
             if self.op == 0 {
-                self.op = self.data[0];
-                self.param_len = 1;
-                debug_assert_ne!(self.op, 0);
+                return Err(DecodeError::EmptyOp);
             }
+        }
 
-            // Get the next unprocessed op flag
-            let next = self.op.trailing_zeros();
-            self.op &= !(1u8 << next);
+        // Get the next unprocessed op flag
+        let next = self.op.trailing_zeros();
+        self.op &= !(1u8 << next);
 
-            // Convert the op flag to `Cmd`
-            let cmd;
-            match next {
-                op::FILL_SHIFT => {
-                    cmd = Cmd::Fill;
-                }
-                op::BEGIN_PATH_SHIFT => {
-                    cmd = Cmd::BeginPath;
-                }
-                op::SET_FILL_RGB_SHIFT => {
-                    let color = &self.data[self.param_len..][..4];
-                    self.param_len += 4;
-                    cmd = Cmd::SetFillRgb(color.as_rgba()[0]);
-                }
-                op::CONTOUR_SHIFT => {
-                    let param = &self.data[self.param_len..][..6];
-                    self.param_len += CONTOUR_HDR_SIZE;
+        // Convert the op flag to `Cmd`
+        let cmd;
+        match next {
+            op::FILL_SHIFT => {
+                cmd = Cmd::Fill;
+            }
+            op::BEGIN_PATH_SHIFT => {
+                cmd = Cmd::BeginPath;
+            }
+            op::SET_FILL_RGB_SHIFT => {
+                let color = take(&self.data[self.param_len..], 4)?;
+                self.param_len += 4;
+                cmd = Cmd::SetFillRgb(color.as_rgba()[0]);
+            }
+            op::CONTOUR_SHIFT => {
+                let param = take(&self.data[self.param_len..], CONTOUR_HDR_SIZE)?;
+                self.param_len += CONTOUR_HDR_SIZE;
 
-                    let start = Point2::new(
-                        <i16>::from_le_bytes([param[0], param[1]]),
-                        <i16>::from_le_bytes([param[2], param[3]]),
-                    );
-                    let num_points = <u16>::from_le_bytes([param[4], param[5]]) as usize;
+                let start = Point2::new(
+                    <i16>::from_le_bytes([param[0], param[1]]),
+                    <i16>::from_le_bytes([param[2], param[3]]),
+                );
+                let num_points = <u16>::from_le_bytes([param[4], param[5]]) as usize;
 
-                    let flags_len = (num_points + 7) / 8;
-                    self.curve_flags = &self.data[self.param_len..][..flags_len];
-                    self.param_len += flags_len;
+                let flags_len = (num_points + 7) / 8;
+                let curve_flags = take(&self.data[self.param_len..], flags_len)?;
+                self.param_len += flags_len;
 
-                    let points_len = num_points * BYTES_PER_POINT;
-                    self.curve_points = &self.data[self.param_len..][..points_len];
-                    self.param_len += points_len;
+                let points_len = num_points * BYTES_PER_POINT;
+                let curve_points = take(&self.data[self.param_len..], points_len)?;
+                self.param_len += points_len;
 
-                    self.curve_index = 0;
+                validate_curve_flags(curve_flags, num_points)?;
 
-                    cmd = Cmd::MoveTo(start);
-                }
-                _ => panic!("unknown op"),
+                self.curve_flags = curve_flags;
+                self.curve_points = curve_points;
+                self.curve_index = 0;
+
+                cmd = Cmd::MoveTo(start);
+            }
+            op::SET_STROKE_RGB_SHIFT => {
+                let color = take(&self.data[self.param_len..], 4)?;
+                self.param_len += 4;
+                cmd = Cmd::SetStrokeRgb(color.as_rgba()[0]);
             }
+            op::SET_STROKE_WIDTH_SHIFT => {
+                let param = take(&self.data[self.param_len..], 2)?;
+                self.param_len += 2;
+                cmd = Cmd::SetStrokeWidth(<i16>::from_le_bytes([param[0], param[1]]));
+            }
+            op::STROKE_SHIFT => {
+                cmd = Cmd::Stroke;
+            }
+            op::EXT_SHIFT => {
+                let code = take(&self.data[self.param_len..], 1)?[0];
+                self.param_len += 1;
 
-            if self.op == 0 {
-                // Fetch the next command
-                self.data = &self.data[self.param_len..];
+                match code {
+                    ext::SAVE_TRANSFORM => {
+                        cmd = Cmd::SaveTransform;
+                    }
+                    ext::RESTORE_TRANSFORM => {
+                        cmd = Cmd::RestoreTransform;
+                    }
+                    ext::TRANSFORM => {
+                        let param = take(&self.data[self.param_len..], 12)?;
+                        self.param_len += 12;
+
+                        let mut m = [0i16; 6];
+                        for (x, bytes) in m.iter_mut().zip(param.chunks_exact(2)) {
+                            *x = <i16>::from_le_bytes([bytes[0], bytes[1]]);
+                        }
+                        cmd = Cmd::Transform(m);
+                    }
+                    ext::SET_FILL_RULE => {
+                        let rule = take(&self.data[self.param_len..], 1)?[0];
+                        self.param_len += 1;
+
+                        cmd = Cmd::SetFillRule(match rule {
+                            0 => FillRule::NonZero,
+                            1 => FillRule::EvenOdd,
+                            _ => return Err(DecodeError::UnknownFillRule(rule)),
+                        });
+                    }
+                    ext::BEGIN_CLIP => {
+                        cmd = Cmd::BeginClip;
+                    }
+                    ext::END_CLIP => {
+                        cmd = Cmd::EndClip;
+                    }
+                    _ => return Err(DecodeError::UnknownExtOp(code)),
+                }
             }
+            _ => unreachable!(),
+        }
 
-            Some(cmd)
-        } else {
-            None
+        if self.op == 0 {
+            // Fetch the next command
+            self.data = &self.data[self.param_len..];
         }
+
+        Ok(cmd)
     }
 }
+
+impl<'a> Iterator for CmdDecoder<'a> {
+    type Item = Cmd;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.try_next()
+            .map(|result| result.unwrap_or_else(|e| panic!("invalid StellaVG data: {}", e)))
+    }
+}
+
+/// A fallible iterator over drawing commands in StellaVG data, created by
+/// [`CmdDecoder::try_iter`].
+///
+/// After yielding an error, the iterator yields no more elements.
+#[derive(Debug, Clone)]
+pub struct TryIter<'a> {
+    inner: CmdDecoder<'a>,
+}
+
+impl<'a> Iterator for TryIter<'a> {
+    type Item = Result<Cmd, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.try_next()
+    }
+}
+
+impl std::iter::FusedIterator for TryIter<'_> {}
+
+/// Get the first `len` bytes of `data`.
+fn take(data: &[u8], len: usize) -> Result<&[u8], DecodeError> {
+    data.get(..len).ok_or(DecodeError::Truncated)
+}
+
+/// Check that the on-curve flags of a contour divide its points into
+/// segments of one to three points.
+fn validate_curve_flags(flags: &[u8], num_points: usize) -> Result<(), DecodeError> {
+    let mut run_len = 0;
+
+    for i in 0..num_points {
+        run_len += 1;
+
+        if flags[i / 8] & (1 << (i % 8) as u32) != 0 {
+            run_len = 0;
+        } else if run_len >= 3 {
+            // A cubic Bézier curve has at most two off-curve points
+            return Err(DecodeError::MalformedContour);
+        }
+    }
+
+    if run_len != 0 {
+        // The contour doesn't end with an on-curve point
+        return Err(DecodeError::MalformedContour);
+    }
+
+    Ok(())
+}
@@ -3,14 +3,14 @@ use arrayvec::ArrayVec;
 use cgmath::Point2;
 use rgb::RGBA8;
 
-use crate::{op, Cmd, BYTES_PER_POINT};
+use crate::{ext, op, Cmd, FillRule, BYTES_PER_POINT, COMPRESSED_HEADER, COMPRESSION_LEVEL};
 
 /// Encodes StellaVG data.
 ///
 /// This type exposes methods similar to what is commonly seen in
 /// immediate-mode 2D drawing APIs. However, it doesn't support some unusual
-/// usages; e.g., after `fill` or `begin_path` is called, `move_to` must be
-/// called before appending more vertices to the current path.
+/// usages; e.g., after `fill`, `stroke`, or `begin_path` is called, `move_to`
+/// must be called before appending more vertices to the current path.
 #[derive(Debug, Clone)]
 pub struct CmdEncoder {
     data: Vec<u8>,
@@ -23,6 +23,15 @@ pub struct CmdEncoder {
     // `CONTOUR`
     start_point: Point2<i16>,
     points: Vec<(bool, Point2<i16>)>,
+
+    // `SET_STROKE_RGB`
+    stroke_rgba: [u8; 4],
+
+    // `SET_STROKE_WIDTH`
+    stroke_width: i16,
+
+    // `EXT` (the extended op code and its parameters)
+    ext: ArrayVec<[u8; 13]>,
 }
 
 impl Default for CmdEncoder {
@@ -40,6 +49,9 @@ impl CmdEncoder {
             fill_rgba: [0; 4],
             start_point: Point2::new(0, 0),
             points: Vec::new(),
+            stroke_rgba: [0; 4],
+            stroke_width: 0,
+            ext: ArrayVec::new(),
         }
     }
 
@@ -49,6 +61,22 @@ impl CmdEncoder {
         std::mem::replace(&mut self.data, Vec::new())
     }
 
+    /// Take the encoded data in a compressed form, resetting `self`.
+    ///
+    /// The compressed data must be decompressed by [`crate::decompress`]
+    /// before passing it to `CmdDecoder`. Decompression is several times
+    /// slower than decoding, so the uncompressed form is preferable for the
+    /// data that is decoded frequently.
+    pub fn take_bytes_compressed(&mut self) -> Vec<u8> {
+        let data = self.take_bytes();
+        let mut compressed = vec![COMPRESSED_HEADER];
+        compressed.extend(miniz_oxide::deflate::compress_to_vec(
+            &data,
+            COMPRESSION_LEVEL,
+        ));
+        compressed
+    }
+
     fn finalize_op(&mut self) {
         if self.op == 0 {
             return;
@@ -82,12 +110,29 @@ impl CmdEncoder {
 
             self.points.clear();
         }
+        if self.op & op::SET_STROKE_RGB != 0 {
+            self.data.extend(self.stroke_rgba.iter().cloned());
+        }
+        if self.op & op::SET_STROKE_WIDTH != 0 {
+            self.data
+                .extend(self.stroke_width.to_le_bytes().iter().cloned());
+        }
+        if self.op & op::EXT != 0 {
+            self.data.extend(self.ext.drain(..));
+        }
 
         self.op = 0;
     }
 
+    /// Check if vertices can be appended to the current contour. Vertices
+    /// added after `STROKE` would be stroked prematurely. Vertices added after
+    /// `EXT` would not be affected by a transformation change.
+    fn has_active_contour(&self) -> bool {
+        self.op & (op::CONTOUR | op::STROKE | op::EXT) == op::CONTOUR
+    }
+
     pub fn move_to(&mut self, point: Point2<i16>) {
-        if self.op & op::CONTOUR != 0 {
+        if self.op & (op::CONTOUR | op::STROKE | op::EXT) != 0 {
             self.finalize_op();
         }
 
@@ -96,25 +141,25 @@ impl CmdEncoder {
     }
 
     pub fn line_to(&mut self, point: Point2<i16>) {
-        assert_ne!(self.op & op::CONTOUR, 0, "no active contour");
+        assert!(self.has_active_contour(), "no active contour");
         self.points.push((true, point));
     }
 
     pub fn quad_bezier_to(&mut self, cps: [Point2<i16>; 2]) {
-        assert_ne!(self.op & op::CONTOUR, 0, "no active contour");
+        assert!(self.has_active_contour(), "no active contour");
         self.points.push((false, cps[0]));
         self.points.push((true, cps[1]));
     }
 
     pub fn cubic_bezier_to(&mut self, cps: [Point2<i16>; 3]) {
-        assert_ne!(self.op & op::CONTOUR, 0, "no active contour");
+        assert!(self.has_active_contour(), "no active contour");
         self.points.push((false, cps[0]));
         self.points.push((false, cps[1]));
         self.points.push((true, cps[2]));
     }
 
     pub fn fill(&mut self) {
-        if self.op & (op::FILL | op::BEGIN_PATH | op::CONTOUR) != 0 {
+        if self.op & (op::FILL | op::BEGIN_PATH | op::CONTOUR | op::STROKE | op::EXT) != 0 {
             self.finalize_op();
         }
 
@@ -122,7 +167,7 @@ impl CmdEncoder {
     }
 
     pub fn begin_path(&mut self) {
-        if self.op & op::CONTOUR != 0 {
+        if self.op & (op::CONTOUR | op::STROKE | op::EXT) != 0 {
             self.finalize_op();
         }
 
@@ -130,7 +175,7 @@ impl CmdEncoder {
     }
 
     pub fn set_fill_rgb(&mut self, color: RGBA8) {
-        if self.op & op::FILL != 0 {
+        if self.op & (op::FILL | op::EXT) != 0 {
             self.finalize_op();
         }
 
@@ -138,6 +183,78 @@ impl CmdEncoder {
         self.fill_rgba = color.into();
     }
 
+    pub fn stroke(&mut self) {
+        if self.op & (op::STROKE | op::EXT) != 0 {
+            self.finalize_op();
+        }
+
+        self.op |= op::STROKE;
+    }
+
+    pub fn set_stroke_rgb(&mut self, color: RGBA8) {
+        if self.op & (op::SET_STROKE_RGB | op::STROKE | op::EXT) != 0 {
+            self.finalize_op();
+        }
+
+        self.op |= op::SET_STROKE_RGB;
+        self.stroke_rgba = color.into();
+    }
+
+    /// Set the line width, represented in the same fixed-point format as
+    /// coordinates.
+    pub fn set_stroke_width(&mut self, width: i16) {
+        if self.op & (op::SET_STROKE_WIDTH | op::STROKE | op::EXT) != 0 {
+            self.finalize_op();
+        }
+
+        self.op |= op::SET_STROKE_WIDTH;
+        self.stroke_width = width;
+    }
+
+    fn begin_ext(&mut self, code: u8) {
+        if self.op & op::EXT != 0 {
+            self.finalize_op();
+        }
+
+        self.op |= op::EXT;
+        self.ext.clear();
+        self.ext.push(code);
+    }
+
+    pub fn save_transform(&mut self) {
+        self.begin_ext(ext::SAVE_TRANSFORM);
+    }
+
+    pub fn restore_transform(&mut self) {
+        self.begin_ext(ext::RESTORE_TRANSFORM);
+    }
+
+    /// Multiply the current transformation by a matrix. See
+    /// [`Cmd::Transform`] for the format.
+    pub fn transform(&mut self, m: [i16; 6]) {
+        self.begin_ext(ext::TRANSFORM);
+        self.ext
+            .extend(m.iter().flat_map(|x| ArrayVec::from(x.to_le_bytes())));
+    }
+
+    pub fn begin_clip(&mut self) {
+        self.begin_ext(ext::BEGIN_CLIP);
+    }
+
+    pub fn end_clip(&mut self) {
+        self.begin_ext(ext::END_CLIP);
+    }
+
+    pub fn set_fill_rule(&mut self, rule: FillRule) {
+        // `EXT` is processed after `FILL` in the same op
+        if self.op & op::FILL != 0 {
+            self.finalize_op();
+        }
+
+        self.begin_ext(ext::SET_FILL_RULE);
+        self.ext.push(rule as u8);
+    }
+
     pub fn cmd(&mut self, cmd: Cmd) {
         match cmd {
             Cmd::BeginPath => self.begin_path(),
@@ -147,6 +264,15 @@ impl CmdEncoder {
             Cmd::QuadBezierTo(cps) => self.quad_bezier_to(cps),
             Cmd::CubicBezierTo(cps) => self.cubic_bezier_to(cps),
             Cmd::SetFillRgb(color) => self.set_fill_rgb(color),
+            Cmd::Stroke => self.stroke(),
+            Cmd::SetStrokeRgb(color) => self.set_stroke_rgb(color),
+            Cmd::SetStrokeWidth(width) => self.set_stroke_width(width),
+            Cmd::SaveTransform => self.save_transform(),
+            Cmd::RestoreTransform => self.restore_transform(),
+            Cmd::Transform(m) => self.transform(m),
+            Cmd::SetFillRule(rule) => self.set_fill_rule(rule),
+            Cmd::BeginClip => self.begin_clip(),
+            Cmd::EndClip => self.end_clip(),
         }
     }
 }
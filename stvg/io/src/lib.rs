@@ -1,13 +1,16 @@
 //! Implements the decoder and encoder of the StellaVG (Stella Vector Graphics)
 //! format.
-use cgmath::Point2;
+use cgmath::{Matrix3, Point2};
 use rgb::RGBA8;
 use std::mem::size_of;
 
 mod dec;
 mod enc;
 
-pub use {dec::CmdDecoder, enc::CmdEncoder};
+pub use {
+    dec::{decompress, try_decompress, CmdDecoder, DecodeError, TryIter},
+    enc::CmdEncoder,
+};
 
 /// The op flags indicates which operation to perform. When multiple flags are
 /// specified, the corresponding operations are performed from LSB to MSB.
@@ -29,11 +32,67 @@ mod op {
     /// structure-of-arrays style in hopes of efficient application of
     /// data compression on the encoded data.
     ///
-    /// This flag must be the last one.
+    /// The vertices are added before performing the operations indicated by
+    /// the subsequent flags.
     pub const CONTOUR: u8 = 1 << CONTOUR_SHIFT;
     pub const CONTOUR_SHIFT: u32 = 3;
+
+    /// Set the current stroke color. Followed by a 4-byte color value.
+    pub const SET_STROKE_RGB: u8 = 1 << SET_STROKE_RGB_SHIFT;
+    pub const SET_STROKE_RGB_SHIFT: u32 = 4;
+
+    /// Set the current line width. Followed by a 2-byte fixed-point number.
+    pub const SET_STROKE_WIDTH: u8 = 1 << SET_STROKE_WIDTH_SHIFT;
+    pub const SET_STROKE_WIDTH_SHIFT: u32 = 5;
+
+    /// Stroke the current path.
+    pub const STROKE: u8 = 1 << STROKE_SHIFT;
+    pub const STROKE_SHIFT: u32 = 6;
+
+    /// Perform an extended operation. Followed by a 1-byte extended op code
+    /// (see [`super::ext`]) and its parameters.
+    pub const EXT: u8 = 1 << EXT_SHIFT;
+    pub const EXT_SHIFT: u32 = 7;
+}
+
+/// The extended op codes used by `op::EXT`.
+mod ext {
+    /// Save the current transformation.
+    pub const SAVE_TRANSFORM: u8 = 0;
+
+    /// Restore the last saved transformation.
+    pub const RESTORE_TRANSFORM: u8 = 1;
+
+    /// Multiply the current transformation by a matrix. Followed by six
+    /// 2-byte fixed-point numbers.
+    pub const TRANSFORM: u8 = 2;
+
+    /// Set the current fill rule. Followed by a 1-byte fill rule value (see
+    /// [`super::FillRule`]).
+    pub const SET_FILL_RULE: u8 = 3;
+
+    /// Save the current transformation and clipping region, and then
+    /// intersect the clipping region with the current path.
+    pub const BEGIN_CLIP: u8 = 4;
+
+    /// Restore the transformation and clipping region saved by the matching
+    /// `BEGIN_CLIP`.
+    pub const END_CLIP: u8 = 5;
 }
 
+/// The first byte of compressed StellaVG data, followed by a raw DEFLATE
+/// stream of uncompressed StellaVG data.
+///
+/// Uncompressed StellaVG data starts with an op flag byte, which is never
+/// zero. Thus, compressed data can be distinguished without ambiguity.
+///
+/// This value is a part of the format and must not be changed. Note that any
+/// other value can appear as an op flag byte (e.g., `0x80` is `op::EXT`).
+const COMPRESSED_HEADER: u8 = 0;
+
+/// The compression level passed to `miniz_oxide`.
+const COMPRESSION_LEVEL: u8 = 10;
+
 const BYTES_PER_POINT: usize = 4;
 const CONTOUR_HDR_SIZE: usize = BYTES_PER_POINT + size_of::<u16>();
 
@@ -45,6 +104,62 @@ const CONTOUR_HDR_SIZE: usize = BYTES_PER_POINT + size_of::<u16>();
 /// representable range is circa `[-2048, 2048]`.
 pub const FRAC_BITS: u32 = 4;
 
+/// The number of fractional bits included in the fixed-point numbers
+/// representing the linear part of a transformation matrix
+/// ([`Cmd::Transform`]).
+///
+/// The maximum representable range is circa `[-8, 8]`.
+pub const MATRIX_FRAC_BITS: u32 = 12;
+
+/// Convert the parameter of [`Cmd::Transform`] to a `Matrix3`.
+///
+/// The returned matrix operates on the fixed-point coordinate space (i.e.,
+/// the coordinates are not divided by `1 << FRAC_BITS`).
+///
+/// # Examples
+///
+///     use cgmath::{Matrix3, Point2, Transform};
+///     use stvg_io::{matrix_from_transform, MATRIX_FRAC_BITS};
+///
+///     let one = 1 << MATRIX_FRAC_BITS;
+///     let m = matrix_from_transform([one * 2, 0, 0, one, 16, -32]);
+///     assert_eq!(
+///         m.transform_point(Point2::new(100.0, 100.0)),
+///         Point2::new(216.0, 68.0),
+///     );
+///
+pub fn matrix_from_transform(m: [i16; 6]) -> Matrix3<f32> {
+    let scale = 1.0 / (1 << MATRIX_FRAC_BITS) as f32;
+    Matrix3::new(
+        m[0] as f32 * scale,
+        m[1] as f32 * scale,
+        0.0,
+        m[2] as f32 * scale,
+        m[3] as f32 * scale,
+        0.0,
+        m[4] as f32,
+        m[5] as f32,
+        1.0,
+    )
+}
+
+/// Specifies how to determine the area within a path. Used by
+/// [`Cmd::SetFillRule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum FillRule {
+    /// The non-zero winding number rule. This is the initial value.
+    NonZero = 0,
+    /// The even-odd rule.
+    EvenOdd = 1,
+}
+
+impl Default for FillRule {
+    fn default() -> Self {
+        Self::NonZero
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Cmd {
     BeginPath,
@@ -54,4 +169,48 @@ pub enum Cmd {
     QuadBezierTo([Point2<i16>; 2]),
     CubicBezierTo([Point2<i16>; 3]),
     SetFillRgb(RGBA8),
+    Stroke,
+    SetStrokeRgb(RGBA8),
+    /// Set the line width, represented in the same fixed-point format as
+    /// coordinates.
+    SetStrokeWidth(i16),
+    /// Push the current transformation onto the stack.
+    ///
+    /// The decoder's user may save other graphics states as well (e.g.,
+    /// `CanvasStvgExt::draw_stellavg` uses `Canvas::save`). Thus, the fill
+    /// color, fill rule, stroke color, and line width are unspecified after
+    /// the matching `RestoreTransform`.
+    ///
+    /// `SaveTransform`/`RestoreTransform` and `BeginClip`/`EndClip` share
+    /// the same stack and must be properly nested. For example,
+    /// `[SaveTransform, BeginClip, RestoreTransform, EndClip]` is invalid.
+    SaveTransform,
+    /// Pop a transformation from the stack.
+    RestoreTransform,
+    /// Multiply the current transformation by a matrix `[a, b, c, d, e, f]`,
+    /// which maps a point `(x, y)` to `(a*x + c*y + e, b*x + d*y + f)`.
+    ///
+    /// The matrix operates on the fixed-point coordinate space. `a`–`d` have
+    /// [`MATRIX_FRAC_BITS`] fractional bits. `e` and `f` are represented in
+    /// the same fixed-point format as coordinates. See
+    /// [`matrix_from_transform`].
+    ///
+    /// The transformation applies to the vertices added after this command.
+    /// The line width is specified in the transformed coordinate space.
+    Transform([i16; 6]),
+    /// Set the fill rule used by subsequent `Fill` commands.
+    ///
+    /// The fill rule defaults to [`FillRule::NonZero`].
+    SetFillRule(FillRule),
+    /// Push the current transformation and clipping region onto the stack,
+    /// intersect the clipping region with the area within the current path
+    /// (using the non-zero winding number rule), and clear the current path.
+    ///
+    /// Like `SaveTransform`, the fill color, fill rule, stroke color, and
+    /// line width are unspecified after the matching `EndClip`.
+    BeginClip,
+    /// Pop a transformation and clipping region from the stack.
+    ///
+    /// The topmost stack entry must be the one pushed by `BeginClip`.
+    EndClip,
 }
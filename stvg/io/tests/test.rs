@@ -1,7 +1,8 @@
+use cggeom::Box2;
 use cgmath::Point2;
 use rgb::RGBA8;
 
-use stvg_io::Cmd;
+use stvg_io::{Cmd, CmdDecoder, DecodeError, FillRule};
 
 fn decode(b: &[u8]) -> Vec<Cmd> {
     stvg_io::CmdDecoder::from_bytes(b).collect()
@@ -44,3 +45,439 @@ fn roundtrip() {
 
     assert_eq!(decoded_cmds, cmds);
 }
+
+#[test]
+fn roundtrip_compressed() {
+    let cmds: Vec<_> = (0..100)
+        .flat_map(|i| {
+            vec![
+                Cmd::SetFillRgb(RGBA8::new(i, 43, 44, 45)),
+                Cmd::BeginPath,
+                Cmd::MoveTo(Point2::new(1000, 2000 + i as i16)),
+                Cmd::LineTo(Point2::new(1000, 2100)),
+                Cmd::LineTo(Point2::new(3000, 500 - i as i16)),
+                Cmd::Fill,
+            ]
+        })
+        .collect();
+
+    let raw_bytes = encode(cmds.iter().cloned());
+    let bytes = cmds
+        .iter()
+        .cloned()
+        .collect::<stvg_io::CmdEncoder>()
+        .take_bytes_compressed();
+    println!("len = {} (uncompressed: {})", bytes.len(), raw_bytes.len());
+    assert!(bytes.len() < raw_bytes.len());
+
+    // The header byte is a part of the format
+    assert_eq!(bytes[0], 0);
+
+    let decompressed = stvg_io::decompress(&bytes);
+    assert_eq!(*decompressed, *raw_bytes);
+    assert_eq!(decode(&decompressed), cmds);
+
+    // Uncompressed data is passed through
+    assert_eq!(*stvg_io::decompress(&raw_bytes), *raw_bytes);
+}
+
+#[test]
+fn bounds() {
+    let bytes = encode(vec![
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(160, 320)),
+        Cmd::LineTo(Point2::new(480, 320)),
+        Cmd::QuadBezierTo([Point2::new(-16, 40), Point2::new(480, 640)]),
+        Cmd::Fill,
+        Cmd::MoveTo(Point2::new(100, 100)),
+        Cmd::CubicBezierTo([
+            Point2::new(100, 100),
+            Point2::new(100, 800),
+            Point2::new(100, 100),
+        ]),
+        Cmd::Stroke,
+    ]);
+
+    assert_eq!(
+        stvg_io::CmdDecoder::from_bytes(&bytes).bounds(),
+        Some(Box2::new(Point2::new(-1.0, 2.5), Point2::new(30.0, 50.0)))
+    );
+}
+
+#[test]
+fn bounds_transform() {
+    let one = 1 << stvg_io::MATRIX_FRAC_BITS;
+    let bytes = encode(vec![
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(160, 160)),
+        Cmd::Fill,
+        Cmd::SaveTransform,
+        Cmd::Transform([one * 2, 0, 0, one, 320, 0]),
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(160, 160)),
+        Cmd::Fill,
+        Cmd::RestoreTransform,
+        Cmd::MoveTo(Point2::new(-16, -16)),
+        Cmd::Fill,
+    ]);
+
+    assert_eq!(
+        stvg_io::CmdDecoder::from_bytes(&bytes).bounds(),
+        Some(Box2::new(Point2::new(-1.0, -1.0), Point2::new(40.0, 10.0)))
+    );
+}
+
+#[test]
+fn bounds_underflow() {
+    let bytes = encode(vec![
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(160, 160)),
+        Cmd::Fill,
+        Cmd::RestoreTransform,
+        Cmd::MoveTo(Point2::new(-16, -16)),
+        Cmd::Fill,
+    ]);
+
+    // The commands after the underflow are ignored
+    assert_eq!(
+        stvg_io::CmdDecoder::from_bytes(&bytes).bounds(),
+        Some(Box2::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0)))
+    );
+}
+
+#[test]
+fn bounds_empty() {
+    assert_eq!(stvg_io::CmdDecoder::from_bytes(&[]).bounds(), None);
+
+    let bytes = encode(vec![Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4)), Cmd::Fill]);
+    assert_eq!(stvg_io::CmdDecoder::from_bytes(&bytes).bounds(), None);
+}
+
+#[test]
+fn roundtrip_stroke() {
+    let cmds = vec![
+        Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4)),
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(1000, 2000)),
+        Cmd::LineTo(Point2::new(1000, 2100)),
+        Cmd::SetStrokeRgb(RGBA8::new(42, 43, 44, 45)),
+        Cmd::SetStrokeWidth(16),
+        Cmd::Stroke,
+        Cmd::MoveTo(Point2::new(3000, 500)),
+        Cmd::QuadBezierTo([Point2::new(500, 200), Point2::new(800, 250)]),
+        Cmd::SetStrokeWidth(24),
+        Cmd::Stroke,
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(1000, 2000)),
+        Cmd::LineTo(Point2::new(1000, 2100)),
+        Cmd::Fill,
+        Cmd::SetStrokeRgb(RGBA8::new(5, 6, 7, 8)),
+        Cmd::Stroke,
+        Cmd::Stroke,
+    ];
+
+    let bytes = encode(cmds.iter().cloned());
+    println!("{:?}", bytes);
+    println!("len = {}", bytes.len());
+
+    let decoded_cmds = decode(&bytes);
+
+    assert_eq!(decoded_cmds, cmds);
+}
+
+#[test]
+fn roundtrip_transform() {
+    let one = 1 << stvg_io::MATRIX_FRAC_BITS;
+    let shape = [
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(100, 0)),
+        Cmd::LineTo(Point2::new(100, 100)),
+        Cmd::Fill,
+    ];
+
+    // Draw the same shape at different positions
+    let mut cmds = vec![Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4))];
+    for &(x, y) in &[(0, 0), (160, 0), (0, 160)] {
+        cmds.push(Cmd::SaveTransform);
+        cmds.push(Cmd::Transform([one, 0, 0, one, x, y]));
+        cmds.extend(shape.iter().cloned());
+        cmds.push(Cmd::RestoreTransform);
+    }
+    cmds.extend(vec![
+        Cmd::SaveTransform,
+        Cmd::Transform([0, one, -one, 0, 0, 0]),
+        Cmd::Transform([one / 2, 0, 0, one / 2, -1, 1]),
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(100, 0)),
+        Cmd::SetStrokeWidth(16),
+        Cmd::Stroke,
+        Cmd::RestoreTransform,
+        Cmd::SaveTransform,
+    ]);
+
+    let bytes = encode(cmds.iter().cloned());
+    println!("{:?}", bytes);
+    println!("len = {}", bytes.len());
+
+    let decoded_cmds = decode(&bytes);
+
+    assert_eq!(decoded_cmds, cmds);
+
+    // The compressed form is still distinguishable
+    let bytes = cmds
+        .iter()
+        .cloned()
+        .collect::<stvg_io::CmdEncoder>()
+        .take_bytes_compressed();
+    assert_eq!(decode(&stvg_io::decompress(&bytes)), cmds);
+}
+
+#[test]
+fn roundtrip_fill_rule() {
+    // A self-intersecting pentagram. The inner pentagon is filled only by the
+    // non-zero winding number rule. (The rendering result is checked by
+    // `bitmap_fill_rule` in `tcw3_pal`'s tests.)
+    let star = [
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(800, 0)),
+        Cmd::LineTo(Point2::new(1270, 1450)),
+        Cmd::LineTo(Point2::new(40, 550)),
+        Cmd::LineTo(Point2::new(1560, 550)),
+        Cmd::LineTo(Point2::new(330, 1450)),
+        Cmd::Fill,
+    ];
+
+    let mut cmds = star.to_vec();
+    cmds.push(Cmd::SetFillRule(FillRule::EvenOdd));
+    cmds.extend(star.iter().cloned());
+    cmds.push(Cmd::SetFillRule(FillRule::NonZero));
+    cmds.extend(star.iter().cloned());
+
+    let bytes = encode(cmds.iter().cloned());
+    println!("{:?}", bytes);
+    println!("len = {}", bytes.len());
+
+    let decoded_cmds = decode(&bytes);
+
+    assert_eq!(decoded_cmds, cmds);
+}
+
+#[test]
+fn roundtrip_clip() {
+    let cmds = vec![
+        Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4)),
+        // Outer clip
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(1600, 0)),
+        Cmd::LineTo(Point2::new(1600, 1600)),
+        Cmd::BeginClip,
+        Cmd::SaveTransform,
+        Cmd::Transform([4096, 0, 0, 4096, 160, 160]),
+        // Inner clip
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(800, 800)),
+        Cmd::LineTo(Point2::new(0, 800)),
+        Cmd::BeginClip,
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(-100, -100)),
+        Cmd::LineTo(Point2::new(1000, -100)),
+        Cmd::LineTo(Point2::new(1000, 1000)),
+        Cmd::Fill,
+        Cmd::EndClip,
+        Cmd::RestoreTransform,
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(-100, -100)),
+        Cmd::LineTo(Point2::new(1000, 1000)),
+        Cmd::LineTo(Point2::new(-100, 1000)),
+        Cmd::Fill,
+        Cmd::EndClip,
+    ];
+
+    let bytes = encode(cmds.iter().cloned());
+    println!("{:?}", bytes);
+    println!("len = {}", bytes.len());
+
+    let decoded_cmds = decode(&bytes);
+
+    assert_eq!(decoded_cmds, cmds);
+}
+
+#[test]
+fn bounds_clip() {
+    let one = 1 << stvg_io::MATRIX_FRAC_BITS;
+    let bytes = encode(vec![
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(160, 160)),
+        Cmd::BeginClip,
+        Cmd::Transform([one * 2, 0, 0, one * 2, 0, 0]),
+        Cmd::MoveTo(Point2::new(0, 0)),
+        Cmd::LineTo(Point2::new(160, 160)),
+        Cmd::Fill,
+        // Restores the transformation
+        Cmd::EndClip,
+        Cmd::MoveTo(Point2::new(-16, -16)),
+        Cmd::LineTo(Point2::new(160, 160)),
+        Cmd::Fill,
+    ]);
+
+    assert_eq!(
+        stvg_io::CmdDecoder::from_bytes(&bytes).bounds(),
+        Some(Box2::new(Point2::new(-1.0, -1.0), Point2::new(20.0, 20.0)))
+    );
+}
+
+#[test]
+fn try_decode_valid() {
+    let cmds = vec![
+        Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4)),
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(1000, 2000)),
+        Cmd::LineTo(Point2::new(1000, 2100)),
+        Cmd::QuadBezierTo([Point2::new(500, 200), Point2::new(800, 250)]),
+        Cmd::Fill,
+        Cmd::SetFillRule(FillRule::EvenOdd),
+        Cmd::SaveTransform,
+        Cmd::Transform([1, 2, 3, 4, 5, 6]),
+        Cmd::RestoreTransform,
+        Cmd::SetStrokeWidth(16),
+        Cmd::Stroke,
+    ];
+    let bytes = encode(cmds.iter().cloned());
+
+    let decoded_cmds: Vec<_> = CmdDecoder::try_from_bytes(&bytes).unwrap().collect();
+    assert_eq!(decoded_cmds, cmds);
+
+    let decoded_cmds: Result<Vec<_>, _> = CmdDecoder::from_bytes(&bytes).try_iter().collect();
+    assert_eq!(decoded_cmds, Ok(cmds));
+}
+
+#[test]
+fn try_decode_truncated() {
+    let cmds = vec![
+        Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4)),
+        Cmd::BeginPath,
+        Cmd::MoveTo(Point2::new(1000, 2000)),
+        Cmd::LineTo(Point2::new(1000, 2100)),
+        Cmd::CubicBezierTo([
+            Point2::new(600, 300),
+            Point2::new(900, 350),
+            Point2::new(1200, 450),
+        ]),
+        Cmd::Fill,
+        Cmd::SetFillRule(FillRule::EvenOdd),
+        Cmd::Transform([1, 2, 3, 4, 5, 6]),
+        Cmd::SetStrokeRgb(RGBA8::new(5, 6, 7, 8)),
+        Cmd::SetStrokeWidth(16),
+        Cmd::Stroke,
+    ];
+    let bytes = encode(cmds.iter().cloned());
+
+    // Every proper prefix either is a sequence of complete ops or ends in the
+    // middle of an op
+    for len in 0..bytes.len() {
+        let bytes = &bytes[..len];
+        match CmdDecoder::try_from_bytes(bytes) {
+            Ok(decoder) => {
+                let decoded_cmds: Vec<_> = decoder.collect();
+                assert_eq!(decoded_cmds[..], cmds[..decoded_cmds.len()]);
+            }
+            Err(e) => assert_eq!(e, DecodeError::Truncated),
+        }
+
+        // The fallible iterator yields valid commands up to the error
+        let mut results: Vec<_> = CmdDecoder::from_bytes(bytes).try_iter().collect();
+        if let Some(Err(e)) = results.last() {
+            assert_eq!(*e, DecodeError::Truncated);
+            results.pop();
+        }
+        let decoded_cmds: Vec<_> = results.into_iter().map(Result::unwrap).collect();
+        assert_eq!(decoded_cmds[..], cmds[..decoded_cmds.len()]);
+    }
+}
+
+#[test]
+fn try_decode_malformed_contour() {
+    let mut bytes = encode(vec![
+        Cmd::MoveTo(Point2::new(1000, 2000)),
+        Cmd::LineTo(Point2::new(1000, 2100)),
+        Cmd::Fill,
+    ]);
+
+    // The op flags (1 byte) are followed by the contour header (6 bytes) and
+    // the curve flags. Make the last point an off-curve point.
+    assert_eq!(bytes[7], 1);
+    bytes[7] = 0;
+
+    assert_eq!(
+        CmdDecoder::try_from_bytes(&bytes).unwrap_err(),
+        DecodeError::MalformedContour
+    );
+
+    let results: Vec<_> = CmdDecoder::from_bytes(&bytes).try_iter().collect();
+    assert_eq!(results, [Err(DecodeError::MalformedContour)]);
+}
+
+#[test]
+fn try_decode_bad_op() {
+    assert_eq!(
+        CmdDecoder::try_from_bytes(&[0]).unwrap_err(),
+        DecodeError::EmptyOp
+    );
+    // `EXT` with an unknown extended op code
+    assert_eq!(
+        CmdDecoder::try_from_bytes(&[0x80, 0xff]).unwrap_err(),
+        DecodeError::UnknownExtOp(0xff)
+    );
+    // `EXT` with `SET_FILL_RULE` and an unknown fill rule
+    assert_eq!(
+        CmdDecoder::try_from_bytes(&[0x80, 3, 42]).unwrap_err(),
+        DecodeError::UnknownFillRule(42)
+    );
+    // A lone `RestoreTransform`
+    assert_eq!(
+        CmdDecoder::try_from_bytes(&[0x80, 1]).unwrap_err(),
+        DecodeError::UnbalancedStack
+    );
+    // `SaveTransform` without the matching `RestoreTransform`
+    assert_eq!(
+        CmdDecoder::try_from_bytes(&[0x80, 0]).unwrap_err(),
+        DecodeError::UnbalancedStack
+    );
+    // `SaveTransform` followed by `EndClip`
+    assert_eq!(
+        CmdDecoder::try_from_bytes(&[0x80, 0, 0x80, 5]).unwrap_err(),
+        DecodeError::UnbalancedStack
+    );
+    assert!(CmdDecoder::try_from_bytes(&[0x80, 4, 0x80, 0, 0x80, 1, 0x80, 5]).is_ok());
+}
+
+#[test]
+fn try_decompress_corrupted() {
+    let bytes = encode(vec![Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4)), Cmd::Fill]);
+    assert_eq!(*stvg_io::try_decompress(&bytes).unwrap(), *bytes);
+
+    let mut bytes = vec![Cmd::SetFillRgb(RGBA8::new(1, 2, 3, 4)), Cmd::Fill]
+        .into_iter()
+        .collect::<stvg_io::CmdEncoder>()
+        .take_bytes_compressed();
+    bytes.truncate(bytes.len() - 1);
+    assert_eq!(
+        stvg_io::try_decompress(&bytes).unwrap_err(),
+        DecodeError::CorruptedCompressedData
+    );
+}
+
+#[test]
+#[should_panic]
+fn line_to_after_stroke() {
+    encode(vec![
+        Cmd::MoveTo(Point2::new(1000, 2000)),
+        Cmd::LineTo(Point2::new(1000, 2100)),
+        Cmd::Stroke,
+        Cmd::LineTo(Point2::new(3000, 500)),
+    ]);
+}
@@ -6,7 +6,7 @@ use pathfinder_geometry as pf_geo;
 use quote::quote;
 use rgb::RGBA8;
 use std::path::Path;
-use stvg_io::CmdEncoder;
+use stvg_io::{CmdEncoder, FillRule};
 use syn::{parse_macro_input, spanned::Spanned, Lit, LitByteStr};
 
 #[proc_macro]
@@ -46,7 +46,9 @@ pub fn include_stvg(params: proc_macro::TokenStream) -> proc_macro::TokenStream
     let size;
 
     let mut converter = Converter {
+        tree: &svg_tree,
         encoder: CmdEncoder::new(),
+        fill_rule: Some(FillRule::default()),
     };
 
     use usvg::NodeKind;
@@ -93,13 +95,127 @@ pub fn include_stvg(params: proc_macro::TokenStream) -> proc_macro::TokenStream
     .into()
 }
 
-struct Converter {
+struct Converter<'a> {
+    tree: &'a usvg::Tree,
     encoder: CmdEncoder,
+    /// The current fill rule of `encoder`. `None` if it's unknown (e.g., after
+    /// `EndClip`).
+    fill_rule: Option<FillRule>,
 }
 
-impl Converter {
+impl Converter<'_> {
+    fn set_fill_rule(&mut self, rule: FillRule) {
+        if self.fill_rule != Some(rule) {
+            self.encoder.set_fill_rule(rule);
+            self.fill_rule = Some(rule);
+        }
+    }
+
+    /// Emit `BeginClip` for the clip path `id` (and the clip paths applied to
+    /// it). Returns the number of emitted `BeginClip`s.
+    fn begin_clip_path(&mut self, id: &str, xform: &usvg::Transform) -> usize {
+        use usvg::{NodeExt, NodeKind};
+
+        let node = self
+            .tree
+            .defs_by_id(id)
+            .unwrap_or_else(|| panic!("clip path {:?} is missing", id));
+
+        let (units, transform, clip_path) = match &*node.borrow() {
+            NodeKind::ClipPath(clip_path) => (
+                clip_path.units,
+                clip_path.transform,
+                clip_path.clip_path.clone(),
+            ),
+            _ => panic!("{:?} is not a clip path", id),
+        };
+
+        if units != usvg::Units::UserSpaceOnUse {
+            panic!("unsupported clip path units: {:?}", units);
+        }
+
+        // A clip path can be clipped by another clip path
+        let mut depth = 0;
+        if let Some(id) = &clip_path {
+            depth += self.begin_clip_path(id, xform);
+        }
+
+        let mut clip_xform = *xform;
+        clip_xform.append(&transform);
+
+        self.encoder.begin_path();
+        for child in node.children() {
+            if let NodeKind::Path(path) = &*child.borrow() {
+                let mut path_xform = clip_xform;
+                path_xform.append(&child.transform());
+
+                self.encode_path_data(path, &path_xform);
+            }
+        }
+        self.encoder.begin_clip();
+
+        depth + 1
+    }
+
+    fn end_clip_paths(&mut self, depth: usize) {
+        for _ in 0..depth {
+            self.encoder.end_clip();
+        }
+
+        if depth > 0 {
+            // `EndClip` makes the fill rule unspecified
+            self.fill_rule = None;
+        }
+    }
+
+    /// Add the contours of `path` to the current path.
+    fn encode_path_data(&mut self, path: &usvg::Path, xform: &usvg::Transform) {
+        use usvg::PathSegment;
+
+        let segments = path
+            .data
+            .subpaths()
+            .map(|subpath| subpath.0)
+            .flatten()
+            .cloned();
+
+        for seg in segments {
+            match seg {
+                PathSegment::MoveTo { mut x, mut y } => {
+                    xform.apply_to(&mut x, &mut y);
+
+                    self.encoder.move_to(point_from(x, y));
+                }
+                PathSegment::LineTo { mut x, mut y } => {
+                    xform.apply_to(&mut x, &mut y);
+
+                    self.encoder.line_to(point_from(x, y));
+                }
+                PathSegment::CurveTo {
+                    mut x1,
+                    mut y1,
+                    mut x2,
+                    mut y2,
+                    mut x,
+                    mut y,
+                } => {
+                    xform.apply_to(&mut x1, &mut y1);
+                    xform.apply_to(&mut x2, &mut y2);
+                    xform.apply_to(&mut x, &mut y);
+
+                    self.encoder.cubic_bezier_to([
+                        point_from(x1, y1),
+                        point_from(x2, y2),
+                        point_from(x, y),
+                    ]);
+                }
+                PathSegment::ClosePath => {}
+            }
+        }
+    }
+
     fn process_node(&mut self, node: &usvg::Node, xform: &usvg::Transform, opacity: f32) {
-        use usvg::{NodeExt, NodeKind, PathSegment};
+        use usvg::{NodeExt, NodeKind};
 
         let mut node_xform = *xform;
         node_xform.append(&node.transform());
@@ -108,59 +224,32 @@ impl Converter {
             NodeKind::Group(group) => {
                 let g_opacity = group.opacity.value() as f32;
 
+                let clip_depth = if let Some(id) = &group.clip_path {
+                    self.begin_clip_path(id, &node_xform)
+                } else {
+                    0
+                };
+
                 for child in node.children() {
                     self.process_node(&child, &node_xform, opacity * g_opacity);
                 }
+
+                self.end_clip_paths(clip_depth);
             }
             NodeKind::Path(path) if path.visibility == usvg::Visibility::Visible => {
                 if let Some(fill) = &path.fill {
+                    self.set_fill_rule(match fill.rule {
+                        usvg::FillRule::NonZero => FillRule::NonZero,
+                        usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+                    });
                     set_paint_as_fill(
                         &mut self.encoder,
                         &fill.paint,
                         opacity * fill.opacity.value() as f32,
                     );
 
-                    let segments = path
-                        .data
-                        .subpaths()
-                        .map(|subpath| subpath.0)
-                        .flatten()
-                        .cloned();
-
                     self.encoder.begin_path();
-                    for seg in segments {
-                        match seg {
-                            PathSegment::MoveTo { mut x, mut y } => {
-                                node_xform.apply_to(&mut x, &mut y);
-
-                                self.encoder.move_to(point_from(x, y));
-                            }
-                            PathSegment::LineTo { mut x, mut y } => {
-                                node_xform.apply_to(&mut x, &mut y);
-
-                                self.encoder.line_to(point_from(x, y));
-                            }
-                            PathSegment::CurveTo {
-                                mut x1,
-                                mut y1,
-                                mut x2,
-                                mut y2,
-                                mut x,
-                                mut y,
-                            } => {
-                                node_xform.apply_to(&mut x1, &mut y1);
-                                node_xform.apply_to(&mut x2, &mut y2);
-                                node_xform.apply_to(&mut x, &mut y);
-
-                                self.encoder.cubic_bezier_to([
-                                    point_from(x1, y1),
-                                    point_from(x2, y2),
-                                    point_from(x, y),
-                                ]);
-                            }
-                            PathSegment::ClosePath => {}
-                        }
-                    }
+                    self.encode_path_data(path, &node_xform);
                     self.encoder.fill();
                 } // let Some(fill)
 
@@ -174,7 +263,11 @@ impl Converter {
                         opacity * stroke.opacity.value() as f32,
                     );
 
-                    // StellaVG doesn't support strokes, so convert them to fills
+                    // StellaVG's `Stroke` can't specify line caps or joins, so
+                    // convert strokes to fills to render them faithfully.
+                    // The stroke outline may self-intersect, so it must be
+                    // filled by the non-zero winding number rule.
+                    self.set_fill_rule(FillRule::NonZero);
                     let stroke_style = pf_geo::stroke::StrokeStyle {
                         line_width: stroke.width.value() as f32,
                         line_cap: pf_line_cap_from_usvg(stroke.linecap),
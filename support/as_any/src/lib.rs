@@ -17,7 +17,7 @@
 //!
 //!     assert_eq!((*x).downcast_ref(), Some(&42i32));
 //!
-use std::any::Any;
+use std::{any::Any, rc::Rc, sync::Arc};
 
 /// Allows conversion from a reference to `&dyn Any`.
 pub trait AsAny {
@@ -64,6 +64,103 @@ impl<T: Any + Send + Sync> AsAnySendSync for T {
     }
 }
 
+/// Allows conversion from `Box<Self>` to `Box<dyn Any>`.
+///
+///     use as_any::{AsAnyBox, Downcast};
+///
+///     trait MyTrait: AsAnyBox {}
+///     impl MyTrait for i32 {}
+///     impl MyTrait for &'static str {}
+///
+///     let x: Box<dyn MyTrait> = Box::new(42i32);
+///     assert_eq!(x.into_any().downcast::<i32>().ok(), Some(Box::new(42)));
+///
+///     let x: Box<dyn MyTrait> = Box::new("hello");
+///     let x = x.downcast::<i32>().unwrap_err();
+///     assert_eq!(x.downcast::<&str>().ok(), Some(Box::new("hello")));
+///
+pub trait AsAnyBox: AsAny {
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+/// Allows conversion from `Box<Self>` to `Box<dyn Any + Send>`.
+pub trait AsAnyBoxSend: AsAnyBox + Send {
+    fn into_any_send(self: Box<Self>) -> Box<dyn Any + Send>;
+}
+
+/// Allows conversion from `Box<Self>` to `Box<dyn Any + Send + Sync>`.
+pub trait AsAnyBoxSendSync: AsAnyBox + Send + Sync {
+    fn into_any_send_sync(self: Box<Self>) -> Box<dyn Any + Send + Sync>;
+}
+
+impl<T: Any> AsAnyBox for T {
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl<T: Any + Send> AsAnyBoxSend for T {
+    fn into_any_send(self: Box<Self>) -> Box<dyn Any + Send> {
+        self
+    }
+}
+
+impl<T: Any + Send + Sync> AsAnyBoxSendSync for T {
+    fn into_any_send_sync(self: Box<Self>) -> Box<dyn Any + Send + Sync> {
+        self
+    }
+}
+
+/// Allows conversion from `Rc<Self>` to `Rc<dyn Any>`, which can be
+/// downcast by `Rc::downcast`.
+///
+/// Like other traits in this crate, this trait is implemented only for
+/// `'static` types because `Any` requires `'static`.
+///
+///     use as_any::AsAnyRc;
+///     use std::rc::Rc;
+///
+///     trait MyTrait: AsAnyRc {}
+///     impl MyTrait for i32 {}
+///
+///     let x: Rc<dyn MyTrait> = Rc::new(42i32);
+///     assert_eq!(x.as_any_rc().downcast::<i32>().ok(), Some(Rc::new(42)));
+///
+pub trait AsAnyRc: AsAny {
+    fn as_any_rc(self: Rc<Self>) -> Rc<dyn Any>;
+}
+
+/// Allows conversion from `Arc<Self>` to `Arc<dyn Any + Send + Sync>`, which
+/// can be downcast by `Arc::downcast`.
+///
+/// Like other traits in this crate, this trait is implemented only for
+/// `'static` types because `Any` requires `'static`.
+///
+///     use as_any::AsAnyArc;
+///     use std::sync::Arc;
+///
+///     trait MyTrait: AsAnyArc {}
+///     impl MyTrait for i32 {}
+///
+///     let x: Arc<dyn MyTrait> = Arc::new(42i32);
+///     assert_eq!(x.as_any_arc().downcast::<i32>().ok(), Some(Arc::new(42)));
+///
+pub trait AsAnyArc: AsAny + Send + Sync {
+    fn as_any_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync>;
+}
+
+impl<T: Any> AsAnyRc for T {
+    fn as_any_rc(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+}
+
+impl<T: Any + Send + Sync> AsAnyArc for T {
+    fn as_any_arc(self: Arc<Self>) -> Arc<dyn Any + Send + Sync> {
+        self
+    }
+}
+
 /// An extension trait for [`AsAny`] that provides downcasting methods.
 pub trait Downcast: AsAny {
     /// Returns `true` if the underlying type is identical with `T`.
@@ -74,6 +171,11 @@ pub trait Downcast: AsAny {
     /// Attempt a downcast. Returns a mutable reference to a concrete type if
     /// successful.
     fn downcast_mut<T: Any>(&mut self) -> Option<&mut T>;
+    /// Attempt a downcast. Returns a box of a concrete type if successful.
+    /// Otherwise, returns the original box.
+    fn downcast<T: Any>(self: Box<Self>) -> Result<Box<T>, Box<Self>>
+    where
+        Self: AsAnyBox;
 }
 
 impl<S: AsAny + ?Sized> Downcast for S {
@@ -86,4 +188,14 @@ impl<S: AsAny + ?Sized> Downcast for S {
     fn downcast_mut<T: Any>(&mut self) -> Option<&mut T> {
         self.as_any_mut().downcast_mut()
     }
+    fn downcast<T: Any>(self: Box<Self>) -> Result<Box<T>, Box<Self>>
+    where
+        Self: AsAnyBox,
+    {
+        if (*self).is::<T>() {
+            Ok(self.into_any().downcast().unwrap())
+        } else {
+            Err(self)
+        }
+    }
 }
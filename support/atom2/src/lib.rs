@@ -1,19 +1,28 @@
 //! Reimplementation of the [atom] library with specialized and extended features.
 //!
 //! [atom]: https://crates.io/crates/atom
+//!
+//! This crate supports `no_std` environments with `alloc`. The default feature
+//! `std` must be disabled in such environments.
 #![feature(const_fn)] // `const fn` with a constrained type parameter (e.g., `T: PtrSized`)
-use std::cell::Cell;
-use std::marker::PhantomData;
-use std::sync::atomic::{AtomicPtr, Ordering};
-use std::sync::{Arc, Weak};
-use std::{
+#![cfg_attr(not(feature = "std"), no_std)]
+extern crate alloc;
+
+use alloc::{
+    boxed::Box,
+    sync::{Arc, Weak},
+};
+use core::cell::Cell;
+use core::marker::PhantomData;
+use core::sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+use core::{
     fmt,
     hint::unreachable_unchecked,
     mem,
     ptr::{self, NonNull},
 };
 
-#[cfg(all(feature = "winrt", target_os = "windows"))]
+#[cfg(all(feature = "winrt", feature = "std", target_os = "windows"))]
 mod winrt_comptr;
 
 /// Types whose value can be converted into a non-zero pointer-sized value
@@ -240,6 +249,38 @@ impl<T: TypedPtrSized> Atom<T> {
         }
     }
 
+    /// Stores a value into the storage if the current value is the same as the
+    /// `current` value.
+    ///
+    /// Unlike `compare_and_swap`, this function is allowed to spuriously fail
+    /// even when the comparison succeeds, which can result in more efficient
+    /// code on some platforms. `success` and `failure` specify the memory
+    /// orderings used for the successful and failed cases, respectively.
+    ///
+    /// Returns the previous value with `Ok(x)` if the value was updated.
+    /// `Err(new)` otherwise.
+    pub fn compare_exchange_weak<P: AsRawPtr<T::Target>>(
+        &self,
+        current: &P,
+        new: Option<T>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Option<T>, Option<T>> {
+        let new_ptr = T::option_into_raw(new);
+        let current_ptr = current.as_raw_ptr();
+        match self.ptr.compare_exchange_weak(
+            current_ptr as *mut (),
+            new_ptr as *mut (),
+            success,
+            failure,
+        ) {
+            // Successful
+            Ok(old_ptr) => Ok(unsafe { T::option_from_raw(old_ptr) }),
+            // Failure
+            Err(_) => Err(unsafe { T::option_from_raw(new_ptr) }),
+        }
+    }
+
     pub fn is_equal_to<P: AsRawPtr<T::Target>>(&self, other: &P, order: Ordering) -> bool {
         let other_ptr = other.as_raw_ptr();
         self.ptr.load(order) == other_ptr as *mut ()
@@ -256,6 +297,25 @@ impl<T: TypedPtrSized + MutPtrSized> Atom<T> {
             Some(unsafe { &mut *p })
         }
     }
+
+    /// Call a given function with a mutable reference to the dereferenced
+    /// inner object (or `None` if `self` is empty), and return its result.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom2::Atom;
+    /// let mut cell = Atom::new(Some(Box::new(1u32)));
+    /// let old = cell.map_inner(|x| std::mem::replace(x.unwrap(), 2));
+    /// assert_eq!(old, 1);
+    /// assert_eq!(cell.as_inner_ref(), Some(&2));
+    ///
+    /// let mut cell: Atom<Box<u32>> = Atom::empty();
+    /// assert_eq!(cell.map_inner(|x| x.is_none()), true);
+    /// ```
+    pub fn map_inner<R>(&mut self, f: impl FnOnce(Option<&mut T::Target>) -> R) -> R {
+        f(self.as_inner_mut())
+    }
 }
 
 impl<T: PtrSized> fmt::Debug for Atom<T> {
@@ -276,6 +336,260 @@ impl<T: PtrSized> Default for Atom<T> {
     }
 }
 
+/// An [`Atom`] that additionally supports reading the inner object through a
+/// shared reference (e.g., [`SharedAtom::load_shared`]).
+///
+/// This is achieved by tracking the readers. The methods removing a value
+/// from `self` (e.g., `swap`) wait for the readers that might have observed
+/// the removed value to leave before returning it. Use `Atom` if you don't
+/// need this capability, as it's smaller and never blocks.
+pub struct SharedAtom<T: PtrSized> {
+    inner: Atom<T>,
+    /// The number of the readers (see [`ReadGuard`]) in each slot.
+    readers: [AtomicUsize; 2],
+    /// The lowest bit indicates the slot new readers enter.
+    gen: AtomicUsize,
+}
+
+impl<T: PtrSized> SharedAtom<T> {
+    /// Construct an empty `SharedAtom`.
+    pub fn empty() -> Self {
+        Self::new(None)
+    }
+
+    /// Construct a `SharedAtom` with an initial value.
+    pub fn new(x: Option<T>) -> Self {
+        Self::from(Atom::new(x))
+    }
+
+    /// Return the inner object, consuming `self`.
+    pub fn into_inner(self) -> Option<T> {
+        self.inner.into_inner()
+    }
+
+    /// Get a mutable reference to the underlying `Atom`.
+    pub fn as_atom_mut(&mut self) -> &mut Atom<T> {
+        &mut self.inner
+    }
+
+    pub fn swap(&self, x: Option<T>, order: Ordering) -> Option<T> {
+        let old = self.inner.swap(x, order);
+        self.wait_readers();
+        old
+    }
+
+    pub fn store(&self, x: Option<T>, order: Ordering) {
+        self.swap(x, order);
+    }
+
+    pub fn take(&self, order: Ordering) -> Option<T> {
+        self.swap(None, order)
+    }
+
+    /// Register a reader, preventing the values removed after this point from
+    /// being returned to the caller (and possibly dropped) until the returned
+    /// guard is dropped.
+    ///
+    /// The guard must not be held while removing a value from `self`, or the
+    /// operation will deadlock.
+    fn read(&self) -> ReadGuard<'_> {
+        let count = &self.readers[self.gen.load(Ordering::Relaxed) & 1];
+        count.fetch_add(1, Ordering::Relaxed);
+
+        // Pairs with the fence in `wait_readers`. Either the writer observes
+        // our registration, or we observe the writer's removal.
+        fence(Ordering::SeqCst);
+
+        ReadGuard { count }
+    }
+
+    /// Wait until all readers that might have observed a value just removed
+    /// from `self` leave.
+    fn wait_readers(&self) {
+        fence(Ordering::SeqCst);
+
+        // The readers registered after this point can't observe the removed
+        // value, so it's sufficient to see each slot empty at least once.
+        let mut seen = [false; 2];
+        for (seen, count) in seen.iter_mut().zip(self.readers.iter()) {
+            *seen = count.load(Ordering::Acquire) == 0;
+        }
+
+        while !(seen[0] && seen[1]) {
+            // Direct new readers to the other slot so that this one drains
+            let slot = self.gen.fetch_add(1, Ordering::Relaxed) & 1;
+
+            while self.readers[slot].load(Ordering::Acquire) != 0 {
+                #[cfg(feature = "std")]
+                std::thread::yield_now();
+                #[cfg(not(feature = "std"))]
+                core::sync::atomic::spin_loop_hint();
+            }
+            seen[slot] = true;
+        }
+    }
+}
+
+/// An RAII guard representing a reader registered by [`SharedAtom::read`].
+struct ReadGuard<'a> {
+    count: &'a AtomicUsize,
+}
+
+impl Drop for ReadGuard<'_> {
+    fn drop(&mut self) {
+        // Pairs with the `Acquire` loads in `wait_readers`
+        self.count.fetch_sub(1, Ordering::Release);
+    }
+}
+
+impl<T: TypedPtrSized> SharedAtom<T> {
+    /// Stores a value into the storage if the current value is the same as the
+    /// `current` value.
+    ///
+    /// Returns the previous value with `Ok(x)` if the value was updated.
+    /// `Err(new)` otherwise.
+    pub fn compare_and_swap<P: AsRawPtr<T::Target>>(
+        &self,
+        current: &P,
+        new: Option<T>,
+        order: Ordering,
+    ) -> Result<Option<T>, Option<T>> {
+        let result = self.inner.compare_and_swap(current, new, order);
+        if result.is_ok() {
+            self.wait_readers();
+        }
+        result
+    }
+
+    /// Stores a value into the storage if the current value is the same as the
+    /// `current` value. See [`Atom::compare_exchange_weak`].
+    pub fn compare_exchange_weak<P: AsRawPtr<T::Target>>(
+        &self,
+        current: &P,
+        new: Option<T>,
+        success: Ordering,
+        failure: Ordering,
+    ) -> Result<Option<T>, Option<T>> {
+        let result = self
+            .inner
+            .compare_exchange_weak(current, new, success, failure);
+        if result.is_ok() {
+            self.wait_readers();
+        }
+        result
+    }
+
+    /// Replace the inner object with a value computed from the current one by
+    /// `f`, which may be called several times. Returns the previous value.
+    ///
+    /// `f` is called again if other threads modify `self` concurrently. The
+    /// values returned by `f` that couldn't be stored are dropped. Like
+    /// `AtomicPtr::fetch_update`, `set_order` and `fetch_order` specify the
+    /// memory orderings used for the successful and failed updates,
+    /// respectively.
+    ///
+    /// `f` receives a reference to the current inner object without owning
+    /// it. The calling thread is registered as a reader during each attempt
+    /// (like [`SharedAtom::load_shared`]), so the referenced object isn't
+    /// dropped until `f` returns. Consequently, `f` must not remove a value
+    /// from `self` (e.g., by calling `swap` on it), or it will deadlock.
+    pub fn update(
+        &self,
+        set_order: Ordering,
+        fetch_order: Ordering,
+        mut f: impl FnMut(Option<&T::Target>) -> Option<T>,
+    ) -> Option<T> {
+        loop {
+            let guard = self.read();
+
+            // `f` dereferences the pointer, so `Acquire` is required
+            let cur_ptr = self.inner.ptr.load(Ordering::Acquire);
+            let new = f(unsafe { (cur_ptr as *const T::Target).as_ref() });
+            let new_ptr = T::option_into_raw(new);
+
+            let result = self.inner.ptr.compare_exchange_weak(
+                cur_ptr,
+                new_ptr as *mut (),
+                set_order,
+                fetch_order,
+            );
+
+            // Leave before waiting for readers
+            drop(guard);
+
+            match result {
+                // Successful
+                Ok(old_ptr) => {
+                    self.wait_readers();
+                    return unsafe { T::option_from_raw(old_ptr) };
+                }
+                // Failure - drop the new value and try again. It was never
+                // visible to other threads.
+                Err(_) => drop(unsafe { T::option_from_raw(new_ptr) }),
+            }
+        }
+    }
+
+    pub fn is_equal_to<P: AsRawPtr<T::Target>>(&self, other: &P, order: Ordering) -> bool {
+        self.inner.is_equal_to(other, order)
+    }
+}
+
+impl<T> SharedAtom<Arc<T>> {
+    /// Clone the inner `Arc` without a unique reference to `self`.
+    ///
+    /// This method registers the calling thread as a reader while
+    /// incrementing the reference count. The methods removing a value from
+    /// `self` (e.g., `swap`) wait for such readers to leave before returning
+    /// the removed value, so the `Arc` remains alive during this operation.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use atom2::SharedAtom;
+    /// use std::sync::Arc;
+    /// let cell = SharedAtom::new(Some(Arc::new(42u32)));
+    /// let x = cell.load_shared().unwrap();
+    /// assert_eq!(*x, 42);
+    /// assert_eq!(Arc::strong_count(&x), 2);
+    /// ```
+    pub fn load_shared(&self) -> Option<Arc<T>> {
+        let _guard = self.read();
+
+        let ptr = self.inner.ptr.load(Ordering::Acquire);
+        if ptr.is_null() {
+            return None;
+        }
+
+        // Increment the reference count. The pointee is kept alive by the
+        // reference owned by `self` or the pending removal waiting for us.
+        let arc = mem::ManuallyDrop::new(unsafe { Arc::from_raw(ptr as *const T) });
+        Some(Arc::clone(&arc))
+    }
+}
+
+impl<T: PtrSized> From<Atom<T>> for SharedAtom<T> {
+    fn from(x: Atom<T>) -> Self {
+        Self {
+            inner: x,
+            readers: [AtomicUsize::new(0), AtomicUsize::new(0)],
+            gen: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl<T: PtrSized> fmt::Debug for SharedAtom<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("SharedAtom").field(&self.inner.ptr).finish()
+    }
+}
+
+impl<T: PtrSized> Default for SharedAtom<T> {
+    fn default() -> Self {
+        SharedAtom::empty()
+    }
+}
+
 pub trait AsRawPtr<T> {
     fn as_raw_ptr(&self) -> *const T;
 }
@@ -1,5 +1,11 @@
-use atom2::Atom;
-use std::sync::{atomic::Ordering, Arc};
+use atom2::{Atom, SharedAtom};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
 
 #[test]
 fn arc_into_inner_some() {
@@ -39,6 +45,25 @@ fn box_as_inner_mut_none() {
     assert!(aa.as_inner_mut().is_none());
 }
 
+#[test]
+fn box_map_inner_some() {
+    let mut aa = Atom::new(Some(Box::new(1)));
+    let ret = aa.map_inner(|x| {
+        let x = x.unwrap();
+        *x += 1;
+        *x
+    });
+    assert_eq!(ret, 2);
+    assert_eq!(*aa.into_inner().unwrap(), 2);
+}
+
+#[test]
+fn box_map_inner_none() {
+    let mut aa: Atom<Box<u32>> = Atom::empty();
+    assert!(aa.map_inner(|x| x.is_none()));
+    assert!(aa.into_inner().is_none());
+}
+
 #[test]
 fn arc_load_some() {
     let mut aa = Atom::new(Some(Arc::new(1)));
@@ -76,3 +101,195 @@ fn arc_compare_and_swap2() {
     assert_eq!(*old.unwrap_err().unwrap(), 2);
     assert_eq!(*aa.into_inner().unwrap(), 1);
 }
+
+#[test]
+fn arc_compare_exchange_weak1() {
+    let cur = Some(Arc::new(1));
+    let aa = Atom::new(cur.clone());
+    let mut new = Some(Arc::new(2));
+    let old = loop {
+        // `compare_exchange_weak` may fail spuriously
+        match aa.compare_exchange_weak(&cur, new, Ordering::AcqRel, Ordering::Relaxed) {
+            Ok(old) => break old,
+            Err(x) => new = x,
+        }
+    };
+    assert_eq!(*old.unwrap(), 1);
+    assert_eq!(*aa.into_inner().unwrap(), 2);
+}
+
+#[test]
+fn arc_compare_exchange_weak2() {
+    let cur = Some(Arc::new(114514));
+    let aa = Atom::new(Some(Arc::new(1)));
+    let new = Arc::new(2);
+    let old = aa.compare_exchange_weak(
+        &cur,
+        Some(Arc::clone(&new)),
+        Ordering::AcqRel,
+        Ordering::Relaxed,
+    );
+    assert_eq!(*old.unwrap_err().unwrap(), 2);
+    // The returned value must not be leaked
+    assert_eq!(Arc::strong_count(&new), 1);
+    assert_eq!(*aa.into_inner().unwrap(), 1);
+}
+
+#[test]
+fn atom_size() {
+    // `Atom` must not pay for the reader tracking done by `SharedAtom`
+    assert_eq!(
+        std::mem::size_of::<Atom<Arc<u32>>>(),
+        std::mem::size_of::<usize>()
+    );
+}
+
+#[test]
+fn arc_update() {
+    let aa = SharedAtom::new(Some(Arc::new(1)));
+    let old = aa.update(Ordering::AcqRel, Ordering::Acquire, |x| {
+        x.map(|x| Arc::new(x + 1))
+    });
+    assert_eq!(*old.unwrap(), 1);
+    assert_eq!(*aa.into_inner().unwrap(), 2);
+}
+
+#[test]
+fn arc_update_contended() {
+    const NUM_THREADS: usize = 8;
+    const NUM_ITERATIONS: usize = 1000;
+
+    // Used to detect leaked values
+    let token = Arc::new(());
+
+    let aa = Arc::new(SharedAtom::new(Some(Arc::new((0, Arc::clone(&token))))));
+    let threads: Vec<_> = (0..NUM_THREADS)
+        .map(|_| {
+            let aa = Arc::clone(&aa);
+            let token = Arc::clone(&token);
+            thread::spawn(move || {
+                for _ in 0..NUM_ITERATIONS {
+                    // Drop the old value immediately
+                    aa.update(Ordering::AcqRel, Ordering::Acquire, |x| {
+                        let count = x.unwrap().0;
+                        Some(Arc::new((count + 1, Arc::clone(&token))))
+                    });
+                }
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let aa = Arc::try_unwrap(aa).unwrap();
+    assert_eq!(aa.into_inner().unwrap().0, NUM_THREADS * NUM_ITERATIONS);
+
+    // All values must have been dropped
+    assert_eq!(Arc::strong_count(&token), 1);
+}
+
+#[test]
+fn arc_load_shared_some() {
+    let aa = SharedAtom::new(Some(Arc::new(1)));
+    let x = aa.load_shared().unwrap();
+    assert_eq!(*x, 1);
+    assert_eq!(Arc::strong_count(&x), 2);
+    drop(aa);
+    assert_eq!(Arc::strong_count(&x), 1);
+}
+
+#[test]
+fn arc_load_shared_none() {
+    let aa: SharedAtom<Arc<u32>> = SharedAtom::empty();
+    assert!(aa.load_shared().is_none());
+}
+
+/// A value used by `arc_load_shared_contended`. It's overwritten with an
+/// invalid state on drop so that a reader accessing a dropped value can notice
+/// that.
+struct Canary {
+    x: [usize; 2],
+    _token: Arc<()>,
+}
+
+impl Canary {
+    fn new(x: usize, token: &Arc<()>) -> Self {
+        Self {
+            x: [x, !x],
+            _token: Arc::clone(token),
+        }
+    }
+
+    fn check(&self) {
+        let x = unsafe { std::ptr::read_volatile(&self.x) };
+        assert_eq!(x[0], !x[1], "observed a dropped value");
+    }
+}
+
+impl Drop for Canary {
+    fn drop(&mut self) {
+        unsafe { std::ptr::write_volatile(&mut self.x, [0, 0]) };
+    }
+}
+
+#[test]
+fn arc_load_shared_contended() {
+    const NUM_READERS: usize = 4;
+    const NUM_WRITERS: usize = 4;
+    const NUM_ITERATIONS: usize = 10000;
+
+    // Used to detect leaked values
+    let token = Arc::new(());
+
+    let aa = Arc::new(SharedAtom::new(Some(Arc::new(Canary::new(0, &token)))));
+    let done = Arc::new(AtomicBool::new(false));
+    let num_finished_writers = Arc::new(AtomicUsize::new(0));
+
+    let readers: Vec<_> = (0..NUM_READERS)
+        .map(|_| {
+            let aa = Arc::clone(&aa);
+            let done = Arc::clone(&done);
+            thread::spawn(move || {
+                while !done.load(Ordering::Relaxed) {
+                    let x = aa.load_shared().unwrap();
+                    x.check();
+                }
+            })
+        })
+        .collect();
+
+    let writers: Vec<_> = (0..NUM_WRITERS)
+        .map(|i| {
+            let aa = Arc::clone(&aa);
+            let token = Arc::clone(&token);
+            let done = Arc::clone(&done);
+            let num_finished_writers = Arc::clone(&num_finished_writers);
+            thread::spawn(move || {
+                for k in 0..NUM_ITERATIONS {
+                    let new = Arc::new(Canary::new(i * NUM_ITERATIONS + k, &token));
+
+                    // Drop the old value immediately
+                    let old = aa.swap(Some(new), Ordering::AcqRel).unwrap();
+                    old.check();
+                }
+
+                // Stop the readers after all writers are done
+                if num_finished_writers.fetch_add(1, Ordering::Relaxed) == NUM_WRITERS - 1 {
+                    done.store(true, Ordering::Relaxed);
+                }
+            })
+        })
+        .collect();
+
+    for thread in readers.into_iter().chain(writers) {
+        thread.join().unwrap();
+    }
+
+    let aa = Arc::try_unwrap(aa).unwrap();
+    drop(aa);
+
+    // All values must have been dropped
+    assert_eq!(Arc::strong_count(&token), 1);
+}
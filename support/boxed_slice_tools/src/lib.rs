@@ -62,6 +62,23 @@ pub fn repeating_by_clone<T: Clone>(proto: &T, len: usize) -> Box<[T]> {
     from_fn(|_| proto.clone(), len)
 }
 
+/// Construct a boxed slice by calling the given generator function repeatedly.
+///
+/// Unlike [`from_fn`], the generator does not receive an element index.
+///
+/// # Examples
+///
+/// ```
+/// use boxed_slice_tools::repeating_with;
+/// let bufs: Box<[Vec<u8>]> = repeating_with(Vec::new, 3);
+/// assert_eq!(bufs.len(), 3);
+/// assert!(bufs.iter().all(|b| b.is_empty()));
+/// ```
+///
+pub fn repeating_with<T>(mut gen: impl FnMut() -> T, len: usize) -> Box<[T]> {
+    from_fn(|_| gen(), len)
+}
+
 /// Construct a boxed slice by filling it with default values.
 ///
 /// # Examples
@@ -11,6 +11,15 @@ pub trait ElementWiseOp {
     //       `alt_fp::FloatOrd`
     fn element_wise_min(&self, rhs: &Self) -> Self;
     fn element_wise_max(&self, rhs: &Self) -> Self;
+
+    /// Clamp each component of `self` to the range defined by the
+    /// corresponding components of `lo` and `hi`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any component of `lo` is greater than the corresponding
+    /// component of `hi` (or the components are not comparable, e.g., NaN).
+    fn element_wise_clamp(&self, lo: &Self, hi: &Self) -> Self;
 }
 
 pub trait ElementWisePartialOrd {
@@ -39,6 +48,12 @@ fn num_max<T: BaseNum>(x: T, y: T) -> T {
     }
 }
 
+#[inline]
+fn num_clamp<T: BaseNum>(x: T, lo: T, hi: T) -> T {
+    assert!(lo <= hi, "lo > hi");
+    num_min(num_max(x, lo), hi)
+}
+
 impl<T: BaseNum> ElementWiseOp for Point2<T> {
     fn element_wise_min(&self, rhs: &Self) -> Self {
         Self::new(num_min(self.x, rhs.x), num_min(self.y, rhs.y))
@@ -46,6 +61,9 @@ impl<T: BaseNum> ElementWiseOp for Point2<T> {
     fn element_wise_max(&self, rhs: &Self) -> Self {
         Self::new(num_max(self.x, rhs.x), num_max(self.y, rhs.y))
     }
+    fn element_wise_clamp(&self, lo: &Self, hi: &Self) -> Self {
+        Self::new(num_clamp(self.x, lo.x, hi.x), num_clamp(self.y, lo.y, hi.y))
+    }
 }
 
 impl<T: BaseNum> ElementWiseOp for Point3<T> {
@@ -63,6 +81,13 @@ impl<T: BaseNum> ElementWiseOp for Point3<T> {
             num_max(self.z, rhs.z),
         )
     }
+    fn element_wise_clamp(&self, lo: &Self, hi: &Self) -> Self {
+        Self::new(
+            num_clamp(self.x, lo.x, hi.x),
+            num_clamp(self.y, lo.y, hi.y),
+            num_clamp(self.z, lo.z, hi.z),
+        )
+    }
 }
 
 impl<T: BaseNum> ElementWiseOp for Vector2<T> {
@@ -72,6 +97,9 @@ impl<T: BaseNum> ElementWiseOp for Vector2<T> {
     fn element_wise_max(&self, rhs: &Self) -> Self {
         Self::new(num_max(self.x, rhs.x), num_max(self.y, rhs.y))
     }
+    fn element_wise_clamp(&self, lo: &Self, hi: &Self) -> Self {
+        Self::new(num_clamp(self.x, lo.x, hi.x), num_clamp(self.y, lo.y, hi.y))
+    }
 }
 
 impl<T: BaseNum> ElementWiseOp for Vector3<T> {
@@ -89,6 +117,13 @@ impl<T: BaseNum> ElementWiseOp for Vector3<T> {
             num_max(self.z, rhs.z),
         )
     }
+    fn element_wise_clamp(&self, lo: &Self, hi: &Self) -> Self {
+        Self::new(
+            num_clamp(self.x, lo.x, hi.x),
+            num_clamp(self.y, lo.y, hi.y),
+            num_clamp(self.z, lo.z, hi.z),
+        )
+    }
 }
 
 impl<T: BaseNum> ElementWiseOp for Vector4<T> {
@@ -108,6 +143,14 @@ impl<T: BaseNum> ElementWiseOp for Vector4<T> {
             num_max(self.w, rhs.w),
         )
     }
+    fn element_wise_clamp(&self, lo: &Self, hi: &Self) -> Self {
+        Self::new(
+            num_clamp(self.x, lo.x, hi.x),
+            num_clamp(self.y, lo.y, hi.y),
+            num_clamp(self.z, lo.z, hi.z),
+            num_clamp(self.w, lo.w, hi.w),
+        )
+    }
 }
 
 impl<T: PartialOrd> ElementWisePartialOrd for Point2<T> {
@@ -242,3 +285,65 @@ impl BoolArray for [bool; 4] {
         self[0] && self[1] && self[2] && self[3]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_max_mixed_sign() {
+        let a = Point2::new(-3, 4);
+        let b = Point2::new(2, -5);
+        assert_eq!(a.element_wise_min(&b), Point2::new(-3, -5));
+        assert_eq!(a.element_wise_max(&b), Point2::new(2, 4));
+
+        let a = Vector3::new(-1.5, 0.0, 2.5);
+        let b = Vector3::new(1.0, -0.5, -2.0);
+        assert_eq!(a.element_wise_min(&b), Vector3::new(-1.5, -0.5, -2.0));
+        assert_eq!(a.element_wise_max(&b), Vector3::new(1.0, 0.0, 2.5));
+    }
+
+    #[test]
+    fn clamp_mixed_sign() {
+        let lo = Point2::new(-10, -10);
+        let hi = Point2::new(10, 10);
+        assert_eq!(
+            Point2::new(-20, 5).element_wise_clamp(&lo, &hi),
+            Point2::new(-10, 5)
+        );
+        assert_eq!(
+            Point2::new(3, 20).element_wise_clamp(&lo, &hi),
+            Point2::new(3, 10)
+        );
+
+        let lo = Vector2::new(-4.0, 1.0);
+        let hi = Vector2::new(-1.0, 2.0);
+        assert_eq!(
+            Vector2::new(0.0, -3.0).element_wise_clamp(&lo, &hi),
+            Vector2::new(-1.0, 1.0)
+        );
+
+        let lo = Point3::new(-1, -2, -3);
+        let hi = Point3::new(1, 2, 3);
+        assert_eq!(
+            Point3::new(-5, 0, 5).element_wise_clamp(&lo, &hi),
+            Point3::new(-1, 0, 3)
+        );
+    }
+
+    #[test]
+    fn clamp_empty_range() {
+        // `lo == hi` is allowed
+        let bound = Point2::new(1, -1);
+        assert_eq!(Point2::new(5, 5).element_wise_clamp(&bound, &bound), bound);
+    }
+
+    #[test]
+    #[should_panic]
+    fn clamp_inverted_range() {
+        // `lo.y > hi.y`
+        let lo = Point2::new(0, 5);
+        let hi = Point2::new(10, -5);
+        Point2::new(3, 0).element_wise_clamp(&lo, &hi);
+    }
+}
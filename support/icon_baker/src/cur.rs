@@ -0,0 +1,116 @@
+use crate::{Error, Ico, Icon, Result, Size, SourceImage};
+use image::RgbaImage;
+use std::{
+    fmt::{self, Debug, Formatter},
+    io::{self, Write},
+    result,
+};
+
+/// A cursor hotspot, measured in pixels from the top-left corner of an entry.
+pub type Hotspot = (u16, u16);
+
+/// A collection of entries stored in a single `.cur` file.
+///
+/// The entries added by `Icon::add_entry` have their hotspots at the
+/// top-left corner. Use [`Cur::add_entry_with_hotspot`] to specify them.
+#[derive(Clone)]
+pub struct Cur {
+    icon_dir: ico::IconDir,
+}
+
+impl Cur {
+    /// Adds an individual entry with a hotspot to the cursor.
+    ///
+    /// This method behaves like `Icon::add_entry` except that it takes an
+    /// additional argument `hotspot`, which specifies the hotspot position of
+    /// the entry in pixels.
+    ///
+    /// # Return Value
+    /// * Returns `Err(Error::InvalidSize(_))` if the dimensions provided in the
+    ///  `size` argument are not supported, or `hotspot` is not inside the
+    ///  entry.
+    /// * Otherwise return `Ok(())`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use icon_baker::*;
+    ///
+    /// fn main() -> icon_baker::Result<()> {
+    ///     let mut icon = Cur::new();
+    ///
+    ///     match SourceImage::from_path("image.svg") {
+    ///         Some(img) => icon.add_entry_with_hotspot(resample::linear, &img, 32, (4, 2)),
+    ///         None      => Ok(())
+    ///     }
+    /// }
+    /// ```
+    pub fn add_entry_with_hotspot<F: FnMut(&SourceImage, Size) -> Result<RgbaImage>>(
+        &mut self,
+        mut filter: F,
+        source: &SourceImage,
+        size: Size,
+        hotspot: Hotspot,
+    ) -> Result<()> {
+        if !Self::is_size_supported(size)
+            || Size::from(hotspot.0) >= size
+            || Size::from(hotspot.1) >= size
+        {
+            return Err(Error::InvalidSize(size));
+        }
+
+        let icon = filter(source, size)?;
+        assert_eq!([icon.width(), icon.height()], [size; 2]);
+
+        let size = icon.width();
+        let mut data = ico::IconImage::from_rgba_data(size, size, icon.into_vec());
+        data.set_cursor_hotspot(Some(hotspot));
+
+        let entry = ico::IconDirEntry::encode(&data).map_err(Error::Io)?;
+        self.icon_dir.add_entry(entry);
+
+        Ok(())
+    }
+}
+
+impl Icon for Cur {
+    fn new() -> Self {
+        Cur {
+            icon_dir: ico::IconDir::new(ico::ResourceType::Cursor),
+        }
+    }
+
+    fn is_size_supported(size: Size) -> bool {
+        Ico::is_size_supported(size)
+    }
+
+    fn add_entry<F: FnMut(&SourceImage, Size) -> Result<RgbaImage>>(
+        &mut self,
+        filter: F,
+        source: &SourceImage,
+        size: Size,
+    ) -> Result<()> {
+        self.add_entry_with_hotspot(filter, source, size, (0, 0))
+    }
+
+    fn write<W: Write>(&mut self, w: &mut W) -> io::Result<()> {
+        self.icon_dir.write(w)
+    }
+}
+
+impl Debug for Cur {
+    fn fmt(&self, f: &mut Formatter) -> result::Result<(), fmt::Error> {
+        let hotspots: Vec<_> = self
+            .icon_dir
+            .entries()
+            .iter()
+            .map(|entry| entry.cursor_hotspot().unwrap())
+            .collect();
+
+        write!(
+            f,
+            "icon_baker::Cur {{ icon_dir: ico::IconDir {{ restype: \
+             ico::ResourceType::Cursor, entries: /* hotspots: {:?} */ }} }} ",
+            hotspots
+        )
+    }
+}
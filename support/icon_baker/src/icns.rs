@@ -1,10 +1,10 @@
 extern crate icns;
 
 use crate::{Error, Icon, Result, Size, SourceImage};
-use image::RgbaImage;
+use image::{DynamicImage, RgbaImage};
 use std::{
     fmt::{self, Debug, Formatter},
-    io::{self, Write},
+    io::{self, Read, Write},
     result,
 };
 
@@ -13,6 +13,63 @@ pub struct Icns {
     icon_family: icns::IconFamily,
 }
 
+impl Icns {
+    /// The sizes of the entries supported by `Icns`.
+    pub const SIZES: [Size; 7] = [16, 32, 64, 128, 256, 512, 1024];
+
+    /// Decodes the entries of an existing `.icns` file.
+    ///
+    /// Returns a list of pairs of the width and the image of each entry.
+    /// Elements of unknown types (including metadata such as `TOC `) are
+    /// skipped with a warning.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use icon_baker::*;
+    /// use std::fs::File;
+    ///
+    /// fn main() -> icon_baker::Result<()> {
+    ///     let file = File::open("in.icns")?;
+    ///
+    ///     for (size, image) in Icns::read(file)? {
+    ///         println!("{}: {:?}", size, image.dimensions());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read<R: Read>(r: R) -> Result<Vec<(Size, DynamicImage)>> {
+        let icon_family = icns::IconFamily::read(r)?;
+
+        for element in &icon_family.elements {
+            if element.icon_type().is_none() {
+                log::warn!(
+                    "Skipping an icns element of unknown type {}",
+                    element.ostype
+                );
+            }
+        }
+
+        // `available_icons` excludes masks and icons without masks
+        icon_family
+            .available_icons()
+            .into_iter()
+            .map(|icon_type| {
+                let image = icon_family
+                    .get_icon_with_type(icon_type)?
+                    .convert_to(icns::PixelFormat::RGBA);
+                let (w, h) = (image.width(), image.height());
+
+                // `Image` guarantees that the buffer length matches the
+                // dimensions
+                let buffer = RgbaImage::from_raw(w, h, image.into_data().into_vec()).unwrap();
+
+                Ok((w, DynamicImage::ImageRgba8(buffer)))
+            })
+            .collect()
+    }
+}
+
 impl Icon for Icns {
     fn new() -> Self {
         Icns {
@@ -20,12 +77,20 @@ impl Icon for Icns {
         }
     }
 
+    fn is_size_supported(size: Size) -> bool {
+        Self::SIZES.contains(&size)
+    }
+
     fn add_entry<F: FnMut(&SourceImage, Size) -> Result<RgbaImage>>(
         &mut self,
         mut filter: F,
         source: &SourceImage,
         size: Size,
     ) -> Result<()> {
+        if !Self::is_size_supported(size) {
+            return Err(Error::InvalidSize(size));
+        }
+
         let icon = filter(source, size)?;
 
         // The Image::from_data method only fails when the specified
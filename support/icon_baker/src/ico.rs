@@ -1,10 +1,10 @@
 extern crate ico;
 
 use crate::{Error, Icon, Result, Size, SourceImage};
-use image::RgbaImage;
+use image::{DynamicImage, RgbaImage};
 use std::{
     fmt::{self, Debug, Formatter},
-    io::{self, Write},
+    io::{self, Read, Write},
     result,
 };
 
@@ -17,6 +17,50 @@ pub struct Ico {
     icon_dir: ico::IconDir,
 }
 
+impl Ico {
+    /// Decodes the entries of an existing `.ico` file.
+    ///
+    /// Returns a list of pairs of the width and the image of each entry. Both
+    /// of PNG- and BMP-encoded entries are supported.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use icon_baker::*;
+    /// use std::fs::File;
+    ///
+    /// fn main() -> icon_baker::Result<()> {
+    ///     let file = File::open("in.ico")?;
+    ///
+    ///     for (size, image) in Ico::read(file)? {
+    ///         println!("{}: {:?}", size, image.dimensions());
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn read<R: Read>(mut r: R) -> Result<Vec<(Size, DynamicImage)>> {
+        // `IconDir::read` requires `Seek`
+        let mut data = Vec::new();
+        r.read_to_end(&mut data)?;
+        let icon_dir = ico::IconDir::read(io::Cursor::new(data))?;
+
+        icon_dir
+            .entries()
+            .iter()
+            .map(|entry| {
+                let image = entry.decode()?;
+                let (w, h) = (image.width(), image.height());
+
+                // `IconImage` guarantees that the buffer length matches
+                // the dimensions
+                let buffer = RgbaImage::from_raw(w, h, image.rgba_data().to_vec()).unwrap();
+
+                Ok((w, DynamicImage::ImageRgba8(buffer)))
+            })
+            .collect()
+    }
+}
+
 impl Icon for Ico {
     fn new() -> Self {
         Ico {
@@ -24,13 +68,17 @@ impl Icon for Ico {
         }
     }
 
+    fn is_size_supported(size: Size) -> bool {
+        size >= MIN_ICO_SIZE && size <= MAX_ICO_SIZE
+    }
+
     fn add_entry<F: FnMut(&SourceImage, Size) -> Result<RgbaImage>>(
         &mut self,
         mut filter: F,
         source: &SourceImage,
         size: Size,
     ) -> Result<()> {
-        if size < MIN_ICO_SIZE || size > MAX_ICO_SIZE {
+        if !Self::is_size_supported(size) {
             return Err(Error::InvalidSize(size));
         }
 
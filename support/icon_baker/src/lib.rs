@@ -1,5 +1,5 @@
 //! A simple solution for encoding common icon file formats,
-//!  such as `.ico` and `.icns`. This crate is mostly a wrapper
+//!  such as `.ico`, `.cur`, and `.icns`. This crate is mostly a wrapper
 //!  for other libraries, unifying existing APIs into a single,
 //!  cohesive interface.
 //!
@@ -76,12 +76,14 @@ use std::{
     result,
 };
 
+pub use crate::cur::{Cur, Hotspot};
 pub use crate::icns::Icns;
 pub use crate::ico::Ico;
 
 pub type Size = u32;
 pub type Result<T> = result::Result<T, Error>;
 
+mod cur;
 mod icns;
 mod ico;
 pub mod resample;
@@ -101,6 +103,24 @@ pub trait Icon {
     /// ```
     fn new() -> Self;
 
+    /// Returns `true` if the icon format supports entries of the specified
+    /// size.
+    ///
+    /// The default implementation returns `true` for all sizes. Implementors
+    /// should override it if `add_entry` rejects some sizes.
+    ///
+    /// # Example
+    /// ```rust
+    /// use icon_baker::{Icns, Ico, Icon};
+    ///
+    /// assert!(Ico::is_size_supported(48));
+    /// assert!(!Icns::is_size_supported(48));
+    /// ```
+    fn is_size_supported(size: Size) -> bool {
+        let _ = size;
+        true
+    }
+
     /// Adds an individual entry to the icon.
     ///
     /// # Arguments
@@ -183,6 +203,124 @@ pub trait Icon {
         Ok(())
     }
 
+    /// Adds a series of entries to the icon, skipping the sizes not supported
+    /// by the icon format.
+    ///
+    /// This method behaves like `add_entries` except that it does not fail on
+    /// the sizes for which `is_size_supported` returns `false`.
+    ///
+    /// # Return Value
+    /// * Returns `Err(Error::Image(ImageError::DimensionError))`
+    ///  if the resampling filter provided in the `filter` argument produces
+    ///  results of dimensions other than the ones specified by `size`.
+    /// * Otherwise return `Ok(skipped)`, where `skipped` is the list of the
+    ///  sizes that were skipped.
+    ///
+    /// # Example
+    /// ```rust
+    /// use icon_baker::*;
+    ///
+    /// fn main() -> icon_baker::Result<()> {
+    ///     let mut icon = Icns::new();
+    ///
+    ///     if let Some(img) = SourceImage::from_path("image.svg") {
+    ///         let skipped = icon.add_entries_lenient(
+    ///             resample::linear,
+    ///             &img,
+    ///             vec![16, 24, 32, 48, 64, 128, 256, 512, 1024]
+    ///         )?;
+    ///
+    ///         assert_eq!(skipped, vec![24, 48]);
+    ///     }
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    fn add_entries_lenient<
+        F: FnMut(&SourceImage, Size) -> Result<RgbaImage>,
+        I: IntoIterator<Item = Size>,
+    >(
+        &mut self,
+        mut filter: F,
+        source: &SourceImage,
+        sizes: I,
+    ) -> Result<Vec<Size>> {
+        let mut skipped = Vec::new();
+
+        for size in sizes.into_iter() {
+            if Self::is_size_supported(size) {
+                self.add_entry(|src, size| filter(src, size), source, size)?;
+            } else {
+                skipped.push(size);
+            }
+        }
+
+        Ok(skipped)
+    }
+
+    /// Adds a series of entries to the icon, resampling the source image for
+    /// all sizes concurrently.
+    ///
+    /// This method behaves like `add_entries` except that `filter` is invoked
+    /// in parallel on a thread pool. The entries are still added in the order
+    /// specified by `sizes`, and the first error in that order is returned.
+    /// All sizes are validated before invoking `filter`, so if any of them is
+    /// unsupported, `Err(Error::InvalidSize(_))` is returned without adding
+    /// any entries.
+    ///
+    /// This method is only available with the `rayon` feature.
+    ///
+    /// # Example
+    /// ```rust
+    /// use icon_baker::*;
+    ///
+    /// fn main() -> icon_baker::Result<()> {
+    ///     let mut icon = Icns::new();
+    ///
+    ///     match SourceImage::from_path("image.svg") {
+    ///         Some(img) => icon.add_entries_parallel(
+    ///             resample::linear,
+    ///             &img,
+    ///             vec![16, 32, 64, 128, 256, 512, 1024]
+    ///         ),
+    ///         None => Ok(())
+    ///     }
+    /// }
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn add_entries_parallel<
+        F: Fn(&SourceImage, Size) -> Result<RgbaImage> + Sync,
+        I: IntoIterator<Item = Size>,
+    >(
+        &mut self,
+        filter: F,
+        source: &SourceImage,
+        sizes: I,
+    ) -> Result<()> {
+        use rayon::prelude::*;
+
+        let sizes: Vec<Size> = sizes.into_iter().collect();
+
+        // Reject unsupported sizes before resampling anything
+        if let Some(&size) = sizes.iter().find(|&&size| !Self::is_size_supported(size)) {
+            return Err(Error::InvalidSize(size));
+        }
+
+        let shared_source = SyncSourceImage(source);
+
+        let images: Vec<Result<RgbaImage>> = sizes
+            .par_iter()
+            .map(|&size| filter(shared_source.0, size))
+            .collect();
+
+        for (size, image) in sizes.into_iter().zip(images) {
+            let mut image = Some(image);
+            self.add_entry(|_, _| image.take().unwrap(), source, size)?;
+        }
+
+        Ok(())
+    }
+
     /// Writes the contents of the icon to `w`.
     ///
     /// # Example
@@ -223,6 +361,18 @@ pub enum Error {
     Io(io::Error),
 }
 
+/// Wraps `&SourceImage` to share it between the worker threads of
+/// `Icon::add_entries_parallel`.
+#[cfg(feature = "rayon")]
+struct SyncSourceImage<'a>(&'a SourceImage);
+
+// Safety: `SvgImage` is not `Sync` only because it owns a raw pointer to
+// `NSVGimage`. nanosvg's rasterizer never modifies the parsed image, and
+// `nsvg` creates a new rasterizer for every call, so rasterizing the same
+// image from multiple threads is safe. `DynamicImage` is `Sync`.
+#[cfg(feature = "rayon")]
+unsafe impl Sync for SyncSourceImage<'_> {}
+
 impl SourceImage {
     /// Attempts to create a `SourceImage` from a given path.
     ///
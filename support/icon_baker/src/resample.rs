@@ -12,7 +12,24 @@ pub fn linear(source: &SourceImage, size: Size) -> Result<RgbaImage> {
     }
 }
 
+/// [Lanczos resampling filter](https://en.wikipedia.org/wiki/Lanczos_resampling)
+/// with a window of size 3.
+///
+/// This filter is suitable for downscaling photographic images.
+pub fn lanczos3(source: &SourceImage, size: Size) -> Result<RgbaImage> {
+    match source {
+        SourceImage::Raster(bit) => Ok(overfit(
+            &scale(bit, size, imageops::FilterType::Lanczos3),
+            size,
+        )),
+        SourceImage::Svg(svg) => svg_linear(svg, size),
+    }
+}
+
 /// [Lanczos resampling filter](https://en.wikipedia.org/wiki/Lanczos_resampling).
+///
+/// Unlike `lanczos3`, this filter doesn't pad non-square images, so the
+/// output has the same aspect ratio as the source image.
 pub fn cubic(source: &SourceImage, size: Size) -> Result<RgbaImage> {
     match source {
         SourceImage::Raster(bit) => Ok(scale(bit, size, imageops::FilterType::Lanczos3).to_rgba()),
@@ -21,6 +38,9 @@ pub fn cubic(source: &SourceImage, size: Size) -> Result<RgbaImage> {
 }
 
 /// [Nearest-Neighbor resampling filter](https://en.wikipedia.org/wiki/Nearest-neighbor_interpolation).
+///
+/// This filter preserves hard edges and is suitable for pixel-art images.
+/// Images smaller than `size` are upscaled by an integral factor and centered.
 pub fn nearest(source: &SourceImage, size: Size) -> Result<RgbaImage> {
     match source {
         SourceImage::Raster(bit) => Ok(nearest::resample(bit, size)),
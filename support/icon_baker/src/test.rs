@@ -115,3 +115,263 @@ fn test_icns() {
         panic!("{:?}", err);
     }
 }
+
+#[test]
+fn test_cur() {
+    let mut dir = TestDir::new();
+    let path = dir.add_file("test.cur");
+
+    let mut file = BufWriter::new(File::create(&path).expect("Couldn't create file"));
+
+    let mut icon = Cur::new();
+    let img = SourceImage::from_path(input_svg_image_path()).expect("File not found");
+
+    if let Err(err) = icon.add_entry_with_hotspot(resample::nearest, &img, 32, (3, 5)) {
+        panic!("{:?}", err);
+    }
+
+    if let Err(err) = icon.add_entry(resample::nearest, &img, 64) {
+        panic!("{:?}", err);
+    }
+
+    match icon.add_entry_with_hotspot(resample::nearest, &img, 16, (16, 0)) {
+        Err(Error::InvalidSize(16)) => {}
+        result => panic!("{:?}", result),
+    }
+
+    if let Err(err) = icon.write(&mut file) {
+        panic!("{:?}", err);
+    }
+    drop(file);
+
+    // Read it back
+    let icon_dir = ::ico::IconDir::read(File::open(&path).expect("Couldn't open file"))
+        .expect("Couldn't parse the file");
+    assert_eq!(icon_dir.resource_type(), ::ico::ResourceType::Cursor);
+
+    let hotspots: Vec<_> = icon_dir
+        .entries()
+        .iter()
+        .map(|entry| (entry.width(), entry.cursor_hotspot()))
+        .collect();
+    assert_eq!(hotspots, vec![(32, Some((3, 5))), (64, Some((0, 0)))]);
+}
+
+#[test]
+#[cfg(feature = "rayon")]
+fn test_ico_parallel() {
+    let img = SourceImage::from_path(input_svg_image_path()).expect("File not found");
+    let sizes = vec![16, 32, 48, 64, 128, 256];
+
+    let mut icon = Ico::new();
+    icon.add_entries(resample::nearest, &img, sizes.clone())
+        .unwrap();
+    let mut expected = Vec::new();
+    icon.write(&mut expected).unwrap();
+
+    let mut icon = Ico::new();
+    icon.add_entries_parallel(resample::nearest, &img, sizes)
+        .unwrap();
+    let mut data = Vec::new();
+    icon.write(&mut data).unwrap();
+
+    assert_eq!(data, expected);
+
+    // The first error in the order of `sizes` wins
+    let mut icon = Ico::new();
+    let result = icon.add_entries_parallel(
+        |src, size| {
+            if size == 64 {
+                Ok(RgbaImage::new(1, 1))
+            } else {
+                resample::nearest(src, size)
+            }
+        },
+        &img,
+        vec![32, 300, 64],
+    );
+    match result {
+        Err(Error::InvalidSize(300)) => {}
+        result => panic!("{:?}", result),
+    }
+
+    // Unsupported sizes are rejected before invoking the filter
+    let mut icon = Ico::new();
+    let result = icon.add_entries_parallel(
+        |_, _| panic!("the filter was called"),
+        &img,
+        vec![32, Size::MAX, 300],
+    );
+    match result {
+        Err(Error::InvalidSize(Size::MAX)) => {}
+        result => panic!("{:?}", result),
+    }
+}
+
+#[test]
+fn test_add_entries_lenient() {
+    let img = SourceImage::from_path(input_svg_image_path()).expect("File not found");
+    let sizes = vec![16, 24, 32, 48, 64, 128, 256, 512, 1024];
+
+    let mut icon = Ico::new();
+    let skipped = icon
+        .add_entries_lenient(resample::nearest, &img, sizes.clone())
+        .unwrap();
+    assert_eq!(skipped, vec![512, 1024]);
+
+    let mut icon = Icns::new();
+    let skipped = icon
+        .add_entries_lenient(resample::nearest, &img, sizes.clone())
+        .unwrap();
+    assert_eq!(skipped, vec![24, 48]);
+
+    for &size in Icns::SIZES.iter() {
+        assert!(Icns::is_size_supported(size));
+    }
+
+    // Unsupported sizes are rejected before invoking the filter
+    let mut icon = Icns::new();
+    match icon.add_entry(|_, _| panic!("the filter was called"), &img, 48) {
+        Err(Error::InvalidSize(48)) => {}
+        result => panic!("{:?}", result),
+    }
+}
+
+#[test]
+fn test_ico_read() {
+    let img = SourceImage::from_path(input_svg_image_path()).expect("File not found");
+
+    let mut icon = Ico::new();
+    icon.add_entries(resample::nearest, &img, vec![32, 64])
+        .unwrap();
+
+    let mut data = Vec::new();
+    icon.write(&mut data).unwrap();
+
+    let entries = Ico::read(&data[..]).unwrap();
+    assert_eq!(entries.len(), 2);
+
+    for (&(size, ref image), &expected_size) in entries.iter().zip([32, 64].iter()) {
+        assert_eq!(size, expected_size);
+        assert_same_image(
+            &image.to_rgba(),
+            &resample::nearest(&img, expected_size).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn test_icns_read() {
+    let img = SourceImage::from_path(input_svg_image_path()).expect("File not found");
+
+    let mut icon = Icns::new();
+    icon.add_entries(resample::nearest, &img, vec![32, 64])
+        .unwrap();
+
+    let mut data = Vec::new();
+    icon.write(&mut data).unwrap();
+
+    let mut entries = Icns::read(&data[..]).unwrap();
+    entries.sort_by_key(|e| e.0);
+    assert_eq!(entries.len(), 2);
+
+    for (&(size, ref image), &expected_size) in entries.iter().zip([32, 64].iter()) {
+        assert_eq!(size, expected_size);
+        assert_same_image(
+            &image.to_rgba(),
+            &resample::nearest(&img, expected_size).unwrap(),
+        );
+    }
+}
+
+/// Compare two images, ignoring the color of fully transparent pixels, which
+/// some encodings do not preserve.
+fn assert_same_image(got: &image::RgbaImage, expected: &image::RgbaImage) {
+    assert_eq!(got.dimensions(), expected.dimensions());
+    for (x, y, pixel) in got.enumerate_pixels() {
+        let expected_pixel = expected.get_pixel(x, y);
+        if pixel[3] == 0 && expected_pixel[3] == 0 {
+            continue;
+        }
+        assert_eq!(pixel, expected_pixel, "pixel mismatch at ({}, {})", x, y);
+    }
+}
+
+/// A 4×4 pixel-art image with a distinct color for each pixel.
+fn pixel_art_source() -> SourceImage {
+    SourceImage::Raster(DynamicImage::ImageRgba8(RgbaImage::from_fn(
+        4,
+        4,
+        |x, y| {
+            image::Rgba([
+                (x * 64) as u8,
+                (y * 64) as u8,
+                255 - (x * y * 16) as u8,
+                255,
+            ])
+        },
+    )))
+}
+
+/// A 64×48 image with smooth gradients and a hard edge in the middle.
+fn photo_source() -> SourceImage {
+    SourceImage::Raster(DynamicImage::ImageRgba8(RgbaImage::from_fn(
+        64,
+        48,
+        |x, y| {
+            let edge = if x < 32 { 0 } else { 128 };
+            image::Rgba([(x * 4) as u8, (y * 5) as u8, edge, 255])
+        },
+    )))
+}
+
+/// FNV-1a
+fn hash_image(image: &RgbaImage) -> u64 {
+    image
+        .as_raw()
+        .iter()
+        .fold(0xcbf29ce484222325, |hash, &byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+        })
+}
+
+#[test]
+fn test_resample_nearest() {
+    let source = pixel_art_source();
+    let image = resample::nearest(&source, 32).unwrap();
+    assert_eq!(image.dimensions(), (32, 32));
+
+    // Every source pixel maps to an 8×8 block of the same color
+    let source_image = match &source {
+        SourceImage::Raster(bit) => bit.to_rgba(),
+        SourceImage::Svg(_) => unreachable!(),
+    };
+    for (x, y, pixel) in image.enumerate_pixels() {
+        assert_eq!(
+            pixel,
+            source_image.get_pixel(x / 8, y / 8),
+            "({}, {})",
+            x,
+            y
+        );
+    }
+
+    assert_eq!(hash_image(&image), 16371310465861830437);
+}
+
+#[test]
+fn test_resample_lanczos3() {
+    let source = photo_source();
+    let image = resample::lanczos3(&source, 16).unwrap();
+
+    // The image is fit into a 16×16 square, leaving transparent stripes on the
+    // top and the bottom
+    assert_eq!(image.dimensions(), (16, 16));
+    for x in 0..16 {
+        assert_eq!(image.get_pixel(x, 0)[3], 0);
+        assert_eq!(image.get_pixel(x, 15)[3], 0);
+        assert_eq!(image.get_pixel(x, 8)[3], 255);
+    }
+
+    assert_eq!(hash_image(&image), 571675476931150657);
+}
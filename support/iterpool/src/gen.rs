@@ -0,0 +1,237 @@
+//! Object pool with generational pointers.
+use std::{mem, ops};
+
+use super::{Entry, PoolPtr};
+
+/// Object pool similar to `Pool`, but detects the use of stale pointers.
+///
+/// Each entry has a generation counter, which is incremented every time
+/// the entry is deallocated. `GenPoolPtr` remembers the generation of the
+/// entry at the point of allocation, so a pointer to a deallocated object
+/// does not refer to a new object occupying the same entry. Note that the
+/// counter wraps around on overflow, so this is not a hard guarantee.
+///
+///     use iterpool::GenPool;
+///     let mut pool = GenPool::new();
+///     let ptr1 = pool.allocate(1);
+///     pool.deallocate(ptr1);
+///     let ptr2 = pool.allocate(2);
+///     assert_eq!(ptr1.index(), ptr2.index());
+///     assert_eq!(pool.get(ptr1), None);
+///     assert_eq!(pool.get(ptr2), Some(&2));
+#[derive(Debug, Clone)]
+pub struct GenPool<T> {
+    storage: Vec<(usize, Entry<T>)>,
+    first_free: Option<PoolPtr>,
+    /// The number of allocated objects.
+    len: usize,
+}
+
+/// A (potentially invalid) pointer to an object in `GenPool`, consisting of
+/// an index and a generation.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct GenPoolPtr {
+    index: PoolPtr,
+    gen: usize,
+}
+
+impl GenPoolPtr {
+    /// Get the index part of the pointer.
+    pub fn index(&self) -> PoolPtr {
+        self.index
+    }
+
+    /// Get the generation part of the pointer.
+    pub fn generation(&self) -> usize {
+        self.gen
+    }
+}
+
+impl<T> Default for GenPool<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> GenPool<T> {
+    pub const fn new() -> Self {
+        Self {
+            storage: Vec::new(),
+            first_free: None,
+            len: 0,
+        }
+    }
+
+    /// Get the number of allocated objects.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the pool contains no allocated objects.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Deallocate all objects. All existing pointers are invalidated.
+    pub fn clear(&mut self) {
+        let len = self.storage.len();
+        for (i, (gen, e)) in self.storage.iter_mut().enumerate() {
+            if let Entry::Used(_) = e {
+                *gen = gen.wrapping_add(1);
+            }
+            let next_free = if i + 1 < len {
+                Some(PoolPtr::new(i + 1))
+            } else {
+                None
+            };
+            *e = Entry::Free(next_free);
+        }
+        self.first_free = if len > 0 { Some(PoolPtr::new(0)) } else { None };
+        self.len = 0;
+    }
+
+    pub fn allocate(&mut self, x: T) -> GenPoolPtr {
+        self.len += 1;
+        match self.first_free {
+            None => {
+                self.storage.push((0, Entry::Used(x)));
+                GenPoolPtr {
+                    index: PoolPtr::new(self.storage.len() - 1),
+                    gen: 0,
+                }
+            }
+            Some(i) => {
+                let (gen, e) = &mut self.storage[i.get()];
+                self.first_free = e.next_free_index();
+                *e = Entry::Used(x);
+                GenPoolPtr {
+                    index: i,
+                    gen: *gen,
+                }
+            }
+        }
+    }
+
+    /// Deallocate the object pointed by `ptr`. Returns `None` if `ptr` is
+    /// stale or does not point an object.
+    pub fn deallocate(&mut self, ptr: GenPoolPtr) -> Option<T> {
+        let (gen, e) = self.storage.get_mut(ptr.index.get())?;
+        if *gen != ptr.gen {
+            return None;
+        }
+        match e {
+            Entry::Used(_) => {}
+            Entry::Free(_) => {
+                return None;
+            }
+        }
+        let x = match mem::replace(e, Entry::Free(self.first_free)) {
+            Entry::Used(x) => x,
+            Entry::Free(_) => unreachable!(),
+        };
+        *gen = gen.wrapping_add(1);
+        self.first_free = Some(ptr.index);
+        self.len -= 1;
+        Some(x)
+    }
+
+    pub fn get(&self, ptr: GenPoolPtr) -> Option<&T> {
+        match self.storage.get(ptr.index.get()) {
+            Some((gen, e)) if *gen == ptr.gen => e.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, ptr: GenPoolPtr) -> Option<&mut T> {
+        match self.storage.get_mut(ptr.index.get()) {
+            Some((gen, e)) if *gen == ptr.gen => e.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// Iterate over objects and their pointers.
+    pub fn ptr_iter(&self) -> impl Iterator<Item = (GenPoolPtr, &'_ T)> + '_ {
+        self.storage
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (gen, e))| match e {
+                Entry::Free(_) => None,
+                Entry::Used(x) => Some((
+                    GenPoolPtr {
+                        index: PoolPtr::new(i),
+                        gen: *gen,
+                    },
+                    x,
+                )),
+            })
+    }
+
+    /// Iterate over objects.
+    pub fn iter(&self) -> impl Iterator<Item = &'_ T> + '_ {
+        self.ptr_iter().map(|(_, x)| x)
+    }
+}
+
+impl<T> ops::Index<GenPoolPtr> for GenPool<T> {
+    type Output = T;
+
+    fn index(&self, index: GenPoolPtr) -> &Self::Output {
+        self.get(index).expect("dangling ptr")
+    }
+}
+
+impl<T> ops::IndexMut<GenPoolPtr> for GenPool<T> {
+    fn index_mut(&mut self, index: GenPoolPtr) -> &mut Self::Output {
+        self.get_mut(index).expect("dangling ptr")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_ptr() {
+        let mut pool = GenPool::new();
+        let ptr1 = pool.allocate(1);
+        assert_eq!(pool.deallocate(ptr1), Some(1));
+
+        let ptr2 = pool.allocate(2);
+        assert_eq!(ptr1.index(), ptr2.index());
+        assert_ne!(ptr1, ptr2);
+
+        assert_eq!(pool.get(ptr1), None);
+        assert_eq!(pool.get_mut(ptr1), None);
+        assert_eq!(pool.deallocate(ptr1), None);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool[ptr2], 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn dangling_ptr() {
+        let mut pool = GenPool::new();
+        let ptr = pool.allocate(1);
+        pool.deallocate(ptr);
+        pool.allocate(2);
+        let _ = pool[ptr];
+    }
+
+    #[test]
+    fn clear() {
+        let mut pool = GenPool::new();
+        let ptrs: Vec<_> = (0..10).map(|i| pool.allocate(i)).collect();
+        pool.clear();
+        assert!(pool.is_empty());
+
+        let new_ptrs: Vec<_> = (0..10).map(|i| pool.allocate(i)).collect();
+        for (&ptr, &new_ptr) in ptrs.iter().zip(new_ptrs.iter()) {
+            assert_eq!(ptr.index(), new_ptr.index());
+            assert_eq!(pool.get(ptr), None);
+        }
+        assert_eq!(
+            pool.ptr_iter().map(|(p, _)| p).collect::<Vec<_>>(),
+            new_ptrs
+        );
+    }
+}
@@ -21,18 +21,23 @@
 //! It is expected that it will exhibit slightly better performance characteristics
 //! on the real world use due to an improved spatial locality.
 //!
-//! It also comes with a sacrifice. It is impossible to return a free space to
-//! the global heap without destroying entire the pool.
+//! It also comes with a sacrifice. A free space can be returned to the global
+//! heap only if it's located at the end of the pool (see
+//! [`Pool::shrink_to_fit`]).
 #![allow(clippy::trivially_copy_pass_by_ref)]
 use std::{mem, num::NonZeroUsize, ops};
 
+mod gen;
 pub mod intrusive_list;
+pub use self::gen::*;
 
 /// High-performance non-thread safe object pool.
 #[derive(Debug, Clone)]
 pub struct Pool<T> {
     storage: Vec<Entry<T>>,
     first_free: Option<PoolPtr>,
+    /// The number of allocated objects.
+    len: usize,
 }
 
 /// High-performance non-thread safe object pool with an ability to iterate
@@ -42,6 +47,8 @@ pub struct IterablePool<T> {
     storage: Vec<ItEntry<T>>,
     first_free: Option<PoolPtr>,
     first_used: Option<PoolPtr>,
+    /// The number of allocated objects.
+    len: usize,
 }
 
 /// A (potentially invalid) pointer to an object in `Pool`, but without
@@ -161,6 +168,7 @@ impl<T> Pool<T> {
         Self {
             storage: Vec::new(),
             first_free: None,
+            len: 0,
         }
     }
 
@@ -168,6 +176,7 @@ impl<T> Pool<T> {
         let mut pool = Self {
             storage: Vec::with_capacity(capacity),
             first_free: None,
+            len: 0,
         };
         if capacity > 0 {
             for i in 0..capacity - 1 {
@@ -179,9 +188,23 @@ impl<T> Pool<T> {
         pool
     }
 
+    /// Deallocate all objects.
+    ///
+    /// The storage is retained, and every entry is made a part of the free
+    /// list, so the subsequent allocations return consecutive pointers
+    /// starting from `PoolPtr::new(0)` without growing the storage.
     pub fn clear(&mut self) {
-        self.storage.clear();
-        self.first_free = None;
+        let len = self.storage.len();
+        for (i, e) in self.storage.iter_mut().enumerate() {
+            let next_free = if i + 1 < len {
+                Some(PoolPtr::new(i + 1))
+            } else {
+                None
+            };
+            *e = Entry::Free(next_free);
+        }
+        self.first_free = if len > 0 { Some(PoolPtr::new(0)) } else { None };
+        self.len = 0;
     }
 
     pub fn reserve(&mut self, additional: usize) {
@@ -201,7 +224,48 @@ impl<T> Pool<T> {
         }
     }
 
+    /// Shrink the capacity of the pool as much as possible.
+    ///
+    /// The free entries at the end of the storage are removed, and the free
+    /// list is rebuilt to exclude them. Pointers to allocated objects remain
+    /// valid. Pointers into the removed region were already dangling; they
+    /// still are, but may be reused by future allocations.
+    pub fn shrink_to_fit(&mut self) {
+        let new_len = self
+            .storage
+            .iter()
+            .rposition(|e| matches!(e, Entry::Used(_)))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+
+        if new_len < self.storage.len() {
+            self.storage.truncate(new_len);
+
+            // Rebuild the free list
+            self.first_free = None;
+            for (i, e) in self.storage.iter_mut().enumerate().rev() {
+                if let Entry::Free(next_free) = e {
+                    *next_free = self.first_free;
+                    self.first_free = Some(PoolPtr::new(i));
+                }
+            }
+        }
+
+        self.storage.shrink_to_fit();
+    }
+
+    /// Get the number of allocated objects.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the pool contains no allocated objects.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn allocate(&mut self, x: T) -> PoolPtr {
+        self.len += 1;
         match self.first_free {
             None => {
                 self.storage.push(Entry::Used(x));
@@ -231,6 +295,7 @@ impl<T> Pool<T> {
             Entry::Free(_) => unreachable!(),
         };
         self.first_free = Some(i);
+        self.len -= 1;
         Some(x)
     }
 
@@ -283,6 +348,17 @@ impl<T> Pool<T> {
                 Entry::Used(x) => Some((PoolPtr::new(i), x)),
             })
     }
+
+    /// Deallocate all objects, returning them as an iterator.
+    ///
+    /// If the iterator is dropped before it's fully consumed, the remaining
+    /// objects are dropped. In either case, the pool is left empty.
+    pub fn drain(&mut self) -> impl ExactSizeIterator<Item = T> + '_ {
+        PoolDrain {
+            pool: self,
+            next: 0,
+        }
+    }
 }
 
 impl<T> IterablePool<T> {
@@ -291,6 +367,7 @@ impl<T> IterablePool<T> {
             storage: Vec::new(),
             first_free: None,
             first_used: None,
+            len: 0,
         }
     }
 
@@ -299,6 +376,7 @@ impl<T> IterablePool<T> {
             storage: Vec::with_capacity(capacity),
             first_free: None,
             first_used: None,
+            len: 0,
         };
         if capacity > 0 {
             for i in 0..capacity - 1 {
@@ -310,9 +388,23 @@ impl<T> IterablePool<T> {
         pool
     }
 
+    /// Deallocate all objects.
+    ///
+    /// The storage is retained, and every entry is made a part of the free
+    /// list, so the subsequent allocations return consecutive pointers
+    /// starting from `PoolPtr::new(0)` without growing the storage.
     pub fn clear(&mut self) {
-        self.storage.clear();
-        self.first_free = None;
+        let len = self.storage.len();
+        for (i, e) in self.storage.iter_mut().enumerate() {
+            let next_free = if i + 1 < len {
+                Some(PoolPtr::new(i + 1))
+            } else {
+                None
+            };
+            *e = ItEntry::Free(next_free);
+        }
+        self.first_free = if len > 0 { Some(PoolPtr::new(0)) } else { None };
+        self.len = 0;
         self.first_used = None;
     }
 
@@ -333,9 +425,51 @@ impl<T> IterablePool<T> {
         }
     }
 
+    /// Shrink the capacity of the pool as much as possible.
+    ///
+    /// The free entries at the end of the storage are removed, and the free
+    /// list is rebuilt to exclude them. Pointers to allocated objects remain
+    /// valid. Pointers into the removed region were already dangling; they
+    /// still are, but may be reused by future allocations.
+    pub fn shrink_to_fit(&mut self) {
+        let new_len = self
+            .storage
+            .iter()
+            .rposition(|e| matches!(e, ItEntry::Used(_, _)))
+            .map(|i| i + 1)
+            .unwrap_or(0);
+
+        if new_len < self.storage.len() {
+            self.storage.truncate(new_len);
+
+            // Rebuild the free list
+            self.first_free = None;
+            for (i, e) in self.storage.iter_mut().enumerate().rev() {
+                if let ItEntry::Free(next_free) = e {
+                    *next_free = self.first_free;
+                    self.first_free = Some(PoolPtr::new(i));
+                }
+            }
+        }
+
+        self.storage.shrink_to_fit();
+    }
+
+    /// Get the number of allocated objects.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return `true` if the pool contains no allocated objects.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn allocate(&mut self, x: T) -> PoolPtr {
         use std::mem::replace;
 
+        self.len += 1;
+
         if self.first_free.is_none() {
             self.storage.push(ItEntry::Free(None));
             self.first_free = Some(PoolPtr::new(self.storage.len() - 1));
@@ -365,7 +499,14 @@ impl<T> IterablePool<T> {
 
     pub fn deallocate<S: Into<PoolPtr>>(&mut self, i: S) -> Option<T> {
         let i = i.into();
-        let x = match mem::replace(&mut self.storage[i.get()], ItEntry::Free(self.first_free)) {
+        let e = &mut self.storage[i.get()];
+        match e {
+            ItEntry::Used(_, _) => {}
+            ItEntry::Free(_) => {
+                return None;
+            }
+        }
+        let x = match mem::replace(e, ItEntry::Free(self.first_free)) {
             ItEntry::Used(x, (next, prev)) => {
                 if next == i {
                     assert_eq!(self.first_used, Some(i));
@@ -383,9 +524,41 @@ impl<T> IterablePool<T> {
             ItEntry::Free(_) => unreachable!(),
         };
         self.first_free = Some(i);
+        self.len -= 1;
         Some(x)
     }
 
+    /// Retain only the objects specified by the predicate. The objects for
+    /// which `pred` returns `false` are deallocated.
+    ///
+    /// The objects are visited in the same order as `iter`.
+    pub fn retain(&mut self, mut pred: impl FnMut(PoolPtr, &mut T) -> bool) {
+        let first = if let Some(first) = self.first_used {
+            first
+        } else {
+            return;
+        };
+        let last = self.storage[first.get()].next_previous_used_index().1;
+
+        let mut cur = first;
+        loop {
+            let entry = &mut self.storage[cur.get()];
+            let next = entry.next_previous_used_index().0;
+            let keep = pred(cur, entry.as_mut().unwrap());
+
+            // `deallocate` updates `first_used` and the links of the
+            // neighboring entries, so `next` remains valid
+            if !keep {
+                self.deallocate(cur);
+            }
+
+            if cur == last {
+                break;
+            }
+            cur = next;
+        }
+    }
+
     pub fn get(&self, fp: PoolPtr) -> Option<&T> {
         self.storage.get(fp.get()).and_then(ItEntry::as_ref)
     }
@@ -417,6 +590,15 @@ impl<T> IterablePool<T> {
             pool: self,
         }
     }
+
+    /// Deallocate all objects, returning them as an iterator. The objects
+    /// are yielded in the same order as `iter`.
+    ///
+    /// If the iterator is dropped before it's fully consumed, the remaining
+    /// objects are dropped. In either case, the pool is left empty.
+    pub fn drain(&mut self) -> Drain<'_, T> {
+        Drain { pool: self }
+    }
 }
 
 impl<T> ops::Index<PoolPtr> for Pool<T> {
@@ -543,6 +725,68 @@ impl<'a, T> Iterator for PtrIterMut<'a, T> {
     }
 }
 
+/// A draining iterator for `Pool`.
+struct PoolDrain<'a, T> {
+    pool: &'a mut Pool<T>,
+    /// The index of the next entry to examine.
+    next: usize,
+}
+
+impl<T> Iterator for PoolDrain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.pool.storage.len() {
+            let i = PoolPtr::new(self.next);
+            self.next += 1;
+            if let Some(x) = self.pool.deallocate(i) {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        // Every remaining object is at or after `self.next`
+        (self.pool.len(), Some(self.pool.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for PoolDrain<'_, T> {}
+
+impl<T> Drop for PoolDrain<'_, T> {
+    fn drop(&mut self) {
+        self.pool.clear();
+    }
+}
+
+/// A draining iterator for `IterablePool`.
+#[derive(Debug)]
+pub struct Drain<'a, T> {
+    pool: &'a mut IterablePool<T>,
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first_used = self.pool.first_used?;
+        self.pool.deallocate(first_used)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.pool.len(), Some(self.pool.len()))
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        self.pool.clear();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -616,4 +860,271 @@ mod tests {
 
         assert_eq!(pool.iter_mut().size_hint(), (lower, upper));
     }
+
+    #[test]
+    fn shrink_to_fit() {
+        let mut pool = Pool::new();
+        let ptrs: Vec<_> = (0..100).map(|i| pool.allocate(i)).collect();
+        for &ptr in ptrs[50..].iter().rev() {
+            pool.deallocate(ptr);
+        }
+        // Create a free entry before the tail
+        pool.deallocate(ptrs[10]);
+
+        pool.shrink_to_fit();
+        assert_eq!(pool.storage.len(), 50);
+        assert!(pool.storage.capacity() < 100);
+
+        for (i, &ptr) in ptrs[..50].iter().enumerate() {
+            if i == 10 {
+                assert_eq!(pool.get(ptr), None);
+            } else {
+                assert_eq!(pool[ptr], i);
+            }
+        }
+        assert_eq!(pool.get(ptrs[50]), None);
+
+        // The free entry before the tail is reused first
+        assert_eq!(pool.allocate(200), ptrs[10]);
+        assert_eq!(pool.allocate(201), PoolPtr::new(50));
+    }
+
+    #[test]
+    fn it_shrink_to_fit() {
+        let mut pool = IterablePool::new();
+        let ptrs: Vec<_> = (0..100).map(|i| pool.allocate(i)).collect();
+        for &ptr in ptrs[50..].iter() {
+            pool.deallocate(ptr);
+        }
+        pool.deallocate(ptrs[10]);
+
+        pool.shrink_to_fit();
+        assert_eq!(pool.storage.len(), 50);
+        assert!(pool.storage.capacity() < 100);
+
+        for (i, &ptr) in ptrs[..50].iter().enumerate() {
+            if i == 10 {
+                assert_eq!(pool.get(ptr), None);
+            } else {
+                assert_eq!(pool[ptr], i);
+            }
+        }
+
+        let mut values: Vec<_> = pool.iter().cloned().collect();
+        values.sort();
+        assert_eq!(values, (0..50).filter(|&i| i != 10).collect::<Vec<_>>());
+
+        assert_eq!(pool.allocate(200), ptrs[10]);
+        assert_eq!(pool.allocate(201), PoolPtr::new(50));
+        assert_eq!(pool.iter().count(), 51);
+    }
+
+    #[test]
+    fn len() {
+        let mut pool = Pool::new();
+        assert!(pool.is_empty());
+        let ptr = pool.allocate(1);
+        pool.allocate(2);
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.is_empty());
+
+        assert_eq!(pool.deallocate(ptr), Some(1));
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.deallocate(ptr), None);
+        assert_eq!(pool.len(), 1);
+
+        pool.allocate(3);
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.drain().take(1).count(), 1);
+        assert_eq!(pool.len(), 0);
+
+        pool.allocate(4);
+        pool.clear();
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn it_len() {
+        let mut pool = IterablePool::new();
+        assert!(pool.is_empty());
+        let ptr1 = pool.allocate(1);
+        let ptr2 = pool.allocate(2);
+        assert_eq!(pool.len(), 2);
+        assert!(!pool.is_empty());
+
+        assert_eq!(pool.deallocate(ptr1), Some(1));
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.deallocate(ptr1), None);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.iter().cloned().collect::<Vec<_>>(), vec![2]);
+
+        pool.allocate(3);
+        pool.retain(|ptr, _| ptr != ptr2);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.drain().take(0).count(), 0);
+        assert_eq!(pool.len(), 0);
+
+        pool.allocate(4);
+        pool.clear();
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn clear() {
+        let mut pool = Pool::new();
+        let ptrs: Vec<_> = (0..100).map(|i| pool.allocate(i)).collect();
+        pool.deallocate(ptrs[42]);
+        let capacity = pool.storage.capacity();
+
+        pool.clear();
+        assert_eq!(pool.iter().count(), 0);
+        for &ptr in ptrs.iter() {
+            assert_eq!(pool.get(ptr), None);
+        }
+
+        for (i, &ptr) in ptrs.iter().enumerate() {
+            assert_eq!(pool.allocate(i + 200), ptr);
+        }
+        assert_eq!(pool.storage.capacity(), capacity);
+        assert_eq!(pool.iter().count(), 100);
+    }
+
+    #[test]
+    fn it_clear() {
+        let mut pool = IterablePool::new();
+        let ptrs: Vec<_> = (0..100).map(|i| pool.allocate(i)).collect();
+        pool.deallocate(ptrs[42]);
+        let capacity = pool.storage.capacity();
+
+        pool.clear();
+        assert_eq!(pool.iter().count(), 0);
+        assert_eq!(pool.first_used, None);
+        for &ptr in ptrs.iter() {
+            assert_eq!(pool.get(ptr), None);
+        }
+
+        for (i, &ptr) in ptrs.iter().enumerate() {
+            assert_eq!(pool.allocate(i + 200), ptr);
+        }
+        assert_eq!(pool.storage.capacity(), capacity);
+
+        let mut values: Vec<_> = pool.iter().cloned().collect();
+        values.sort();
+        assert_eq!(values, (200..300).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn drain() {
+        let mut pool = Pool::new();
+        let ptrs: Vec<_> = (0..10).map(|i| pool.allocate(i)).collect();
+        pool.deallocate(ptrs[4]);
+
+        let mut it = pool.drain();
+        assert_eq!(it.len(), 9);
+        it.next();
+        assert_eq!(it.size_hint(), (8, Some(8)));
+        assert_eq!(it.collect::<Vec<_>>(), vec![1, 2, 3, 5, 6, 7, 8, 9]);
+        assert_eq!(pool.iter().count(), 0);
+        assert_eq!(pool.allocate(10), ptrs[0]);
+    }
+
+    #[test]
+    fn drain_partial() {
+        use std::rc::Rc;
+        let token = Rc::new(());
+        let mut pool = Pool::new();
+        let ptrs: Vec<_> = (0..10).map(|_| pool.allocate(Rc::clone(&token))).collect();
+
+        assert_eq!(pool.drain().take(3).count(), 3);
+        assert_eq!(Rc::strong_count(&token), 1);
+        assert_eq!(pool.iter().count(), 0);
+
+        for &ptr in ptrs.iter() {
+            assert_eq!(pool.allocate(Rc::clone(&token)), ptr);
+        }
+        assert_eq!(pool.iter().count(), 10);
+    }
+
+    #[test]
+    fn it_drain() {
+        let mut pool = IterablePool::new();
+        let ptrs: Vec<_> = (0..10).map(|i| pool.allocate(i)).collect();
+        pool.deallocate(ptrs[4]);
+
+        let expected: Vec<_> = pool.iter().cloned().collect();
+        let mut it = pool.drain();
+        assert_eq!(it.len(), 9);
+        assert_eq!(it.next(), Some(expected[0]));
+        assert_eq!(it.size_hint(), (8, Some(8)));
+        assert_eq!(it.collect::<Vec<_>>(), expected[1..]);
+        assert_eq!(pool.iter().count(), 0);
+        assert_eq!(pool.first_used, None);
+    }
+
+    #[test]
+    fn it_drain_partial() {
+        use std::rc::Rc;
+        let token = Rc::new(());
+        let mut pool = IterablePool::new();
+        let ptrs: Vec<_> = (0..10).map(|_| pool.allocate(Rc::clone(&token))).collect();
+
+        assert_eq!(pool.drain().take(3).count(), 3);
+        assert_eq!(Rc::strong_count(&token), 1);
+        assert_eq!(pool.iter().count(), 0);
+
+        for &ptr in ptrs.iter() {
+            assert_eq!(pool.allocate(Rc::clone(&token)), ptr);
+        }
+        assert_eq!(pool.iter().count(), 10);
+    }
+
+    #[test]
+    fn it_retain() {
+        let mut pool = IterablePool::new();
+        let ptrs: Vec<_> = (0..100).map(|i| pool.allocate(i)).collect();
+        pool.deallocate(ptrs[42]);
+
+        let mut visited = Vec::new();
+        pool.retain(|ptr, x| {
+            assert_eq!(ptrs[*x], ptr);
+            visited.push(*x);
+            *x % 3 == 1 || *x % 7 == 0
+        });
+        visited.sort();
+        assert_eq!(visited, (0..100).filter(|&i| i != 42).collect::<Vec<_>>());
+
+        let expected: Vec<_> = (0..100)
+            .filter(|&i| i != 42 && (i % 3 == 1 || i % 7 == 0))
+            .collect();
+        let mut values: Vec<_> = pool.iter().cloned().collect();
+        values.sort();
+        assert_eq!(values, expected);
+        for (i, &ptr) in ptrs.iter().enumerate() {
+            assert_eq!(pool.get(ptr).is_some(), expected.contains(&i));
+        }
+
+        // Remove everything
+        pool.retain(|_, _| false);
+        assert_eq!(pool.iter().count(), 0);
+        assert_eq!(pool.first_used, None);
+
+        let ptr = pool.allocate(200);
+        assert_eq!(pool.iter().cloned().collect::<Vec<_>>(), vec![200]);
+
+        // Remove the last remaining element
+        pool.retain(|p, _| p != ptr);
+        assert_eq!(pool.iter().count(), 0);
+        pool.allocate(201);
+        assert_eq!(pool.iter().cloned().collect::<Vec<_>>(), vec![201]);
+    }
+
+    #[test]
+    fn shrink_to_fit_empty() {
+        let mut pool = Pool::new();
+        let ptr = pool.allocate(1);
+        pool.deallocate(ptr);
+        pool.shrink_to_fit();
+        assert_eq!(pool.storage.len(), 0);
+        assert_eq!(pool.allocate(2), PoolPtr::new(0));
+    }
 }
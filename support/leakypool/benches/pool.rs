@@ -0,0 +1,39 @@
+use criterion::{criterion_group, criterion_main, Criterion, Throughput};
+
+use leakypool::*;
+
+const COUNT: usize = 4096;
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("allocation");
+    group.throughput(Throughput::Elements(COUNT as u64));
+
+    group.bench_function("new", |b| {
+        b.iter(|| {
+            let mut pool = LeakyPool::with_token_store(LeakyToken::new());
+            let ptrs: Vec<_> = (0..COUNT).map(|i| pool.allocate(i)).collect();
+            for &ptr in ptrs.iter() {
+                pool.deallocate(ptr);
+            }
+            // Release the memory so that the benchmark doesn't run out of it.
+            // `ptrs` are never used after this point.
+            unsafe { pool.reclaim() };
+        })
+    });
+
+    group.bench_function("with_capacity", |b| {
+        b.iter(|| {
+            let mut pool = LeakyPool::with_capacity(LeakyToken::new(), COUNT);
+            let ptrs: Vec<_> = (0..COUNT).map(|i| pool.allocate(i)).collect();
+            for &ptr in ptrs.iter() {
+                pool.deallocate(ptr);
+            }
+            unsafe { pool.reclaim() };
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
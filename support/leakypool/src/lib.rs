@@ -7,8 +7,17 @@
 //! collected by `LeakyPool` for later reuse, thus bounding the memory usage,
 //! assuming only a predetermined number of `LeakyPool` are created and used
 //! throughout the program's lifetime. `LeakyPool` leaks memory only when
-//! `LeakyPool` is dropped.
-use std::{fmt, hash, hint::unreachable_unchecked, marker::PhantomData, ops, ptr::NonNull};
+//! `LeakyPool` is dropped. The memory regions of vacant entries can be released
+//! explicitly by `LeakyPool::reclaim`, provided that there are no `PoolPtr`s
+//! pointing to them.
+use std::{
+    fmt, hash,
+    hint::unreachable_unchecked,
+    marker::PhantomData,
+    ops,
+    ptr::NonNull,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 use tokenlock::TokenLock;
 use try_match::try_match;
 
@@ -23,15 +32,44 @@ pub struct LeakyPool<Element: 'static, TokenStoreTy: TokenStore = LazyToken<Leak
     /// A linked list of vacant entries. All `TokenLock`s in the list must be
     /// associated with `self.token_store`.
     first_free: Option<PoolPtr<Element, TokenStoreTy::TokenId>>,
+    /// A circular doubly-linked list of occupied entries. All `TokenLock`s in
+    /// the list must be associated with `self.token_store`.
+    first_used: Option<PoolPtr<Element, TokenStoreTy::TokenId>>,
+    /// The blocks allocated by `with_capacity`, each represented by the first
+    /// entry and the number of entries.
+    blocks: Vec<(PoolPtr<Element, TokenStoreTy::TokenId>, usize)>,
 }
 
-impl<Element: 'static, TokenStoreTy: TokenStore + fmt::Debug> fmt::Debug
-    for LeakyPool<Element, TokenStoreTy>
+/// Lists the live elements in addition to the token store. The elements are
+/// omitted if the token store is [`UncheckedToken`].
+impl<Element: 'static, TokenStoreTy> fmt::Debug for LeakyPool<Element, TokenStoreTy>
+where
+    Element: fmt::Debug,
+    TokenStoreTy: TokenStore + fmt::Debug,
+    TokenStoreTy::TokenId: fmt::Debug,
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_struct("LeakyPool")
-            .field("token_store", &self.token_store)
-            .finish()
+        let mut st = f.debug_struct("LeakyPool");
+        st.field("token_store", &self.token_store);
+        if TokenStoreTy::IS_UNIQUE {
+            st.field("elements", &ElementsDebug(self));
+        }
+        st.finish()
+    }
+}
+
+struct ElementsDebug<'a, Element: 'static, TokenStoreTy: TokenStore>(
+    &'a LeakyPool<Element, TokenStoreTy>,
+);
+
+impl<Element: 'static, TokenStoreTy> fmt::Debug for ElementsDebug<'_, Element, TokenStoreTy>
+where
+    Element: fmt::Debug,
+    TokenStoreTy: TokenStore,
+    TokenStoreTy::TokenId: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.0.iter()).finish()
     }
 }
 
@@ -92,15 +130,108 @@ impl<Element: 'static, TokenId: 'static> PoolPtr<Element, TokenId> {
             entry: &*(ptr.as_ptr() as *const _),
         }
     }
+
+    /// Create a [`WeakPoolPtr`] that refers to the same element as `self`.
+    ///
+    /// The returned `WeakPoolPtr` can be upgraded to `PoolPtr` as long as the
+    /// element is not deallocated.
+    pub fn downgrade(self) -> WeakPoolPtr<Element, TokenId> {
+        WeakPoolPtr {
+            ptr: self,
+            generation: self.entry.generation.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// A weak pointer to an element in [`LeakyPool`], which can detect the
+/// deallocation of the element.
+///
+/// Created by [`PoolPtr::downgrade`].
+pub struct WeakPoolPtr<Element: 'static, TokenId: 'static = LeakyTokenId> {
+    ptr: PoolPtr<Element, TokenId>,
+    /// The value of `Entry::generation` at the point of creation.
+    generation: usize,
+}
+
+impl<Element: 'static, TokenId: 'static + fmt::Debug> fmt::Debug for WeakPoolPtr<Element, TokenId> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("WeakPoolPtr")
+            .field("ptr", &self.ptr)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+impl<Element: 'static, TokenId: 'static> Clone for WeakPoolPtr<Element, TokenId> {
+    fn clone(&self) -> Self {
+        Self {
+            ptr: self.ptr,
+            generation: self.generation,
+        }
+    }
+}
+
+impl<Element: 'static, TokenId: 'static> Copy for WeakPoolPtr<Element, TokenId> {}
+
+impl<Element: 'static, TokenId: 'static> PartialEq for WeakPoolPtr<Element, TokenId> {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr && self.generation == other.generation
+    }
+}
+
+impl<Element: 'static, TokenId: 'static> Eq for WeakPoolPtr<Element, TokenId> {}
+
+impl<Element: 'static, TokenId: 'static> hash::Hash for WeakPoolPtr<Element, TokenId> {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        self.ptr.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<Element: 'static, TokenId: 'static> WeakPoolPtr<Element, TokenId> {
+    /// Get a `PoolPtr` for the referenced element. Returns `None` if the
+    /// element has been deallocated or `pool` is not the one the element
+    /// belongs to.
+    ///
+    /// The generation counter used to detect reallocation wraps around on
+    /// overflow, so this method might return `Some(_)` for a new element if
+    /// the same entry is reallocated `usize::max_value() + 1` times.
+    pub fn upgrade<TokenStoreTy>(
+        &self,
+        pool: &LeakyPool<Element, TokenStoreTy>,
+    ) -> Option<PoolPtr<Element, TokenId>>
+    where
+        TokenStoreTy: TokenStore<TokenId = TokenId>,
+    {
+        // Make sure the entry belongs to `pool` and is occupied. `pool` can't
+        // be modified while we are holding `&pool`, so `generation` is stable
+        pool.get(self.ptr)?;
+
+        if self.ptr.entry.generation.load(Ordering::Relaxed) == self.generation {
+            Some(self.ptr)
+        } else {
+            None
+        }
+    }
 }
 
 struct Entry<Element: 'static, TokenId: 'static> {
     lock: TokenLock<EntryState<Element, TokenId>, TokenId>,
+    /// `true` if the entry was allocated individually by `Box::new`. `false`
+    /// if it's a part of a block allocated by `LeakyPool::with_capacity`.
+    boxed: bool,
+    /// Incremented every time the entry is allocated. Used by `WeakPoolPtr` to
+    /// detect reallocation.
+    generation: AtomicUsize,
 }
 
+/// The next and previous occupied entries.
+type UsedLink<Element, TokenId> = (PoolPtr<Element, TokenId>, PoolPtr<Element, TokenId>);
+
 enum EntryState<Element: 'static, TokenId: 'static> {
-    /// The entry is occupied.
-    Occupied(Element),
+    /// The entry is occupied. Points the next and previous occupied entries,
+    /// forming a circular doubly-linked list headed by `LeakyPool::first_used`.
+    Occupied(Element, UsedLink<Element, TokenId>),
     /// The entry is vacant. Points the next vacant entry, forming a linked list
     /// headed by `LeakyPool::first_free`.
     Vacant(Option<PoolPtr<Element, TokenId>>),
@@ -121,6 +252,8 @@ where
             token_store: LazyToken::new(),
             _elements_phantom: PhantomData,
             first_free: None,
+            first_used: None,
+            blocks: Vec::new(),
         }
     }
 }
@@ -155,6 +288,8 @@ impl<Element> LeakyPool<Element, UncheckedToken> {
             token_store: UncheckedToken::new(),
             _elements_phantom: PhantomData,
             first_free: None,
+            first_used: None,
+            blocks: Vec::new(),
         }
     }
 }
@@ -171,9 +306,48 @@ where
             token_store,
             _elements_phantom: PhantomData,
             first_free: None,
+            first_used: None,
+            blocks: Vec::new(),
         }
     }
 
+    /// Construct a `LeakyPool` with `capacity` vacant entries.
+    ///
+    /// The vacant entries are allocated as a single memory block, which is
+    /// more efficient than allocating them one by one as `allocate` does.
+    /// `reclaim` releases the block only when all of its entries are vacant.
+    pub fn with_capacity(token_store: TokenStoreTy, capacity: usize) -> Self {
+        let mut this = Self::with_token_store(token_store);
+
+        if capacity > 0 {
+            let token_store = &mut this.token_store;
+            let entries: &'static [Entry<Element, TokenStoreTy::TokenId>] = Box::leak(
+                (0..capacity)
+                    .map(|_| Entry {
+                        lock: TokenLock::new(token_store.id(), EntryState::Vacant(None)),
+                        boxed: false,
+                        generation: AtomicUsize::new(0),
+                    })
+                    .collect(),
+            );
+
+            // Chain the entries into the linked list `this.first_free`.
+            // These entries are associated with `this.token_store`, so the
+            // `unsafe` is okay
+            for entry in entries.iter().rev() {
+                let entry_state = (entry.lock)
+                    .try_write(this.token_store.token_mut())
+                    .unwrap_or_else(|_| unsafe { unreachable_unchecked() });
+                *entry_state = EntryState::Vacant(this.first_free);
+                this.first_free = Some(PoolPtr { entry });
+            }
+
+            this.blocks.push((PoolPtr { entry: &entries[0] }, capacity));
+        }
+
+        this
+    }
+
     pub fn allocate(&mut self, x: Element) -> PoolPtr<Element, TokenStoreTy::TokenId> {
         // Get `PoolPtr` for the new element.
         let ptr = self.first_free.take().unwrap_or_else(|| {
@@ -182,11 +356,31 @@ where
 
             let entry = Box::leak(Box::new(Entry {
                 lock: TokenLock::new(token_id, EntryState::Vacant(None)),
+                boxed: true,
+                generation: AtomicUsize::new(0),
             }));
 
             PoolPtr { entry }
         });
 
+        // Invalidate `WeakPoolPtr`s pointing to the previous element. We have
+        // `&mut self`, so nobody else can modify `generation` at the same time.
+        let generation = &ptr.entry.generation;
+        generation.store(
+            generation.load(Ordering::Relaxed).wrapping_add(1),
+            Ordering::Relaxed,
+        );
+
+        // Insert the entry to the end of the list of occupied entries.
+        let link = if let Some(first) = self.first_used {
+            let last = std::mem::replace(&mut self.used_link_mut(first).1, ptr);
+            self.used_link_mut(last).0 = ptr;
+            (first, last)
+        } else {
+            self.first_used = Some(ptr);
+            (ptr, ptr)
+        };
+
         // Access the entry referred by `ptr`.
         // All entires in the linked list `self.first_free` are associated with
         // `self.token_store`, so this is okay
@@ -197,7 +391,7 @@ where
             .unwrap_or_else(|_| unsafe { unreachable_unchecked() });
 
         // Assign the new element.
-        let old_state = std::mem::replace(entry_state, EntryState::Occupied(x));
+        let old_state = std::mem::replace(entry_state, EntryState::Occupied(x, link));
 
         // Update `first_free` to point to the next vacant entry (if there's one).
         // This `unsafe` is safe because all entires in the linked list
@@ -227,9 +421,21 @@ where
         // first vacant entry. `unwrap` here is infallible since we've already
         // confirmed that it's `EntryState::Occupied` just above here.
         let taken_state = std::mem::replace(entry_state, new_state);
-        let x = try_match!(EntryState::Occupied(element) = taken_state)
-            .ok()
-            .unwrap_or_else(|| std::process::abort());
+        let (x, (next, prev)) = match taken_state {
+            EntryState::Occupied(element, link) => (element, link),
+            EntryState::Vacant(_) => std::process::abort(),
+        };
+
+        // Remove the entry from the list of occupied entries.
+        if next == ptr {
+            self.first_used = None;
+        } else {
+            if self.first_used == Some(ptr) {
+                self.first_used = Some(next);
+            }
+            self.used_link_mut(next).1 = prev;
+            self.used_link_mut(prev).0 = next;
+        }
 
         // Update the free list.
         self.first_free = Some(ptr);
@@ -241,7 +447,10 @@ where
         (ptr.entry.lock)
             .try_read(self.token_store.token_ref())
             .ok()
-            .and_then(|entry_state| try_match!(EntryState::Occupied(element) = entry_state).ok())
+            .and_then(|entry_state| match entry_state {
+                EntryState::Occupied(element, _) => Some(element),
+                EntryState::Vacant(_) => None,
+            })
     }
 
     pub fn get_mut(
@@ -251,10 +460,201 @@ where
         (ptr.entry.lock)
             .try_write(self.token_store.token_mut())
             .ok()
-            .and_then(|entry_state| try_match!(EntryState::Occupied(element) = entry_state).ok())
+            .and_then(|entry_state| match entry_state {
+                EntryState::Occupied(element, _) => Some(element),
+                EntryState::Vacant(_) => None,
+            })
+    }
+
+    /// Release the memory regions of all vacant entries.
+    ///
+    /// The occupied entries are not affected. The vacant entries allocated by
+    /// `with_capacity` are released only if all entries in the same block are
+    /// vacant. The pool keeps working after calling this method; new entries
+    /// are allocated as needed.
+    ///
+    /// # Safety
+    ///
+    /// `PoolPtr` is `Copy` and may outlive the element it points to. After
+    /// calling this method, `PoolPtr`s (and `WeakPoolPtr`s) pointing to the
+    /// entries that were vacant at the point of the call become dangling. They
+    /// must not be used in any way, including passing them to the methods of
+    /// any `LeakyPool` (such as `get`, which would normally return `None` for
+    /// such pointers).
+    ///
+    /// ```rust
+    /// use leakypool::LeakyPool;
+    ///
+    /// let mut pool: LeakyPool<_> = LeakyPool::new();
+    /// let ptr1 = pool.allocate(1);
+    /// let ptr2 = pool.allocate(2);
+    /// pool.deallocate(ptr1);
+    ///
+    /// // Safety: `ptr1` is never used again
+    /// unsafe { pool.reclaim() };
+    ///
+    /// assert_eq!(pool[ptr2], 2);
+    /// ```
+    pub unsafe fn reclaim(&mut self) {
+        // Find the blocks consisting only of vacant entries.
+        // All entries in `self.blocks` are associated with `self.token_store`.
+        let token = self.token_store.token_ref();
+        let (vacant_blocks, blocks): (Vec<_>, Vec<_>) =
+            self.blocks.drain(..).partition(|&(first, len)| {
+                block_entries(first, len).iter().all(|entry| {
+                    let entry_state = (entry.lock)
+                        .try_read(token)
+                        .unwrap_or_else(|_| unreachable_unchecked());
+                    matches!(entry_state, EntryState::Vacant(_))
+                })
+            });
+        self.blocks = blocks;
+
+        let is_in_vacant_block = |ptr: PoolPtr<_, _>| {
+            vacant_blocks
+                .iter()
+                .any(|&(first, len)| block_contains(first, len, ptr))
+        };
+
+        let mut cur = self.first_free.take();
+
+        while let Some(ptr) = cur {
+            // All entires in the linked list `self.first_free` are associated
+            // with `self.token_store` and vacant.
+            let entry_state = (ptr.entry.lock)
+                .try_write(self.token_store.token_mut())
+                .unwrap_or_else(|_| unreachable_unchecked());
+            let next_ptr = try_match!(EntryState::Vacant(next_ptr) = entry_state)
+                .unwrap_or_else(|_| unreachable_unchecked());
+            cur = *next_ptr;
+
+            if ptr.entry.boxed {
+                // The entry was created by `Box::leak` in `allocate`. The
+                // caller guarantees there are no `PoolPtr`s in use that point
+                // to it.
+                let entry = ptr.entry as *const Entry<Element, TokenStoreTy::TokenId>;
+                drop(Box::from_raw(
+                    entry as *mut Entry<Element, TokenStoreTy::TokenId>,
+                ));
+            } else if !is_in_vacant_block(ptr) {
+                // The entry is a part of a block that can't be released yet.
+                // Put it back to the free list.
+                *next_ptr = self.first_free;
+                self.first_free = Some(ptr);
+            }
+        }
+
+        // The blocks were created by `Box::leak` in `with_capacity`. No
+        // entries in them are in the free list anymore.
+        for (first, len) in vacant_blocks {
+            let entries: *const [Entry<Element, TokenStoreTy::TokenId>] = block_entries(first, len);
+            drop(Box::from_raw(
+                entries as *mut [Entry<Element, TokenStoreTy::TokenId>],
+            ));
+        }
+    }
+
+    /// Iterate over the elements and their pointers.
+    ///
+    /// The elements are visited in the order they were allocated.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (PoolPtr<Element, TokenStoreTy::TokenId>, &Element)> + '_ {
+        let token = self.token_store.token_ref();
+        let first_used = self.first_used;
+        let mut cur = first_used;
+
+        std::iter::from_fn(move || {
+            let ptr = cur?;
+
+            // All entries in the linked list `self.first_used` are associated
+            // with `self.token_store` and occupied, so this is okay
+            let entry_state = (ptr.entry.lock)
+                .try_read(token)
+                .unwrap_or_else(|_| unsafe { unreachable_unchecked() });
+            let (element, next) = match entry_state {
+                EntryState::Occupied(element, (next, _)) => (element, *next),
+                EntryState::Vacant(_) => unsafe { unreachable_unchecked() },
+            };
+
+            cur = Some(next).filter(|&next| Some(next) != first_used);
+
+            Some((ptr, element))
+        })
+    }
+
+    /// Iterate over the elements and their pointers, allowing modification.
+    ///
+    /// The elements are visited in the order they were allocated.
+    pub fn iter_mut(
+        &mut self,
+    ) -> impl Iterator<Item = (PoolPtr<Element, TokenStoreTy::TokenId>, &mut Element)> + '_ {
+        let token = self.token_store.token_mut();
+        let first_used = self.first_used;
+        let mut cur = first_used;
+
+        std::iter::from_fn(move || {
+            let ptr = cur?;
+
+            // All entries in the linked list `self.first_used` are associated
+            // with `self.token_store` and occupied, so this is okay
+            let entry_state = (ptr.entry.lock)
+                .try_write(&mut *token)
+                .unwrap_or_else(|_| unsafe { unreachable_unchecked() });
+            let (element, next) = match entry_state {
+                EntryState::Occupied(element, (next, _)) => (element, *next),
+                EntryState::Vacant(_) => unsafe { unreachable_unchecked() },
+            };
+
+            cur = Some(next).filter(|&next| Some(next) != first_used);
+
+            // Extend the lifetime of the mutable reference. Each entry is
+            // visited only once, so the returned references never alias.
+            let element: &mut Element = unsafe { &mut *(element as *mut Element) };
+
+            Some((ptr, element))
+        })
+    }
+
+    /// Get a mutable reference to the links of an entry in the linked list
+    /// `self.first_used`.
+    fn used_link_mut(
+        &mut self,
+        ptr: PoolPtr<Element, TokenStoreTy::TokenId>,
+    ) -> &mut UsedLink<Element, TokenStoreTy::TokenId> {
+        // All entries in the linked list `self.first_used` are associated
+        // with `self.token_store` and occupied, so this is okay
+        let entry_state = (ptr.entry.lock)
+            .try_write(self.token_store.token_mut())
+            .unwrap_or_else(|_| unsafe { unreachable_unchecked() });
+
+        match entry_state {
+            EntryState::Occupied(_, link) => link,
+            EntryState::Vacant(_) => unsafe { unreachable_unchecked() },
+        }
     }
 }
 
+/// Get the entries in a block allocated by `LeakyPool::with_capacity`.
+unsafe fn block_entries<Element, TokenId>(
+    first: PoolPtr<Element, TokenId>,
+    len: usize,
+) -> &'static [Entry<Element, TokenId>] {
+    std::slice::from_raw_parts(first.entry, len)
+}
+
+/// Check if `ptr` points to an entry in a block allocated by
+/// `LeakyPool::with_capacity`.
+fn block_contains<Element, TokenId>(
+    first: PoolPtr<Element, TokenId>,
+    len: usize,
+    ptr: PoolPtr<Element, TokenId>,
+) -> bool {
+    let start = first.entry as *const Entry<Element, TokenId> as usize;
+    let addr = ptr.entry as *const Entry<Element, TokenId> as usize;
+    addr >= start && addr - start < len * std::mem::size_of::<Entry<Element, TokenId>>()
+}
+
 impl<Element, TokenStoreTy> ops::Index<PoolPtr<Element, TokenStoreTy::TokenId>>
     for LeakyPool<Element, TokenStoreTy>
 where
@@ -309,6 +709,152 @@ mod tests {
         assert!(pool2.get(ptr).is_none());
     }
 
+    #[test]
+    fn iter() {
+        let mut pool: LeakyPool<_> = LeakyPool::new();
+        let ptrs: Vec<_> = (0..5).map(|i| pool.allocate(i)).collect();
+        assert_eq!(pool.deallocate(ptrs[2]), Some(2));
+
+        assert_eq!(
+            pool.iter().collect::<Vec<_>>(),
+            vec![(ptrs[0], &0), (ptrs[1], &1), (ptrs[3], &3), (ptrs[4], &4)],
+        );
+
+        for (_, x) in pool.iter_mut() {
+            *x *= 10;
+        }
+
+        // The vacant entry is reused and appended to the end
+        let ptr = pool.allocate(5);
+        assert_eq!(ptr, ptrs[2]);
+        assert_eq!(
+            pool.iter().collect::<Vec<_>>(),
+            vec![
+                (ptrs[0], &0),
+                (ptrs[1], &10),
+                (ptrs[3], &30),
+                (ptrs[4], &40),
+                (ptrs[2], &5),
+            ],
+        );
+
+        for &ptr in ptrs.iter() {
+            pool.deallocate(ptr);
+        }
+        assert_eq!(pool.iter().count(), 0);
+        assert_eq!(pool.iter_mut().count(), 0);
+    }
+
+    #[test]
+    fn reclaim() {
+        let mut pool: LeakyPool<_> = LeakyPool::new();
+        let ptrs: Vec<_> = (0..10).map(|i| pool.allocate(vec![i])).collect();
+        for (i, &ptr) in ptrs[..7].iter().enumerate() {
+            assert_eq!(pool.deallocate(ptr), Some(vec![i]));
+        }
+
+        // `ptrs[..7]` are never used after this point
+        unsafe { pool.reclaim() };
+
+        assert_eq!(
+            pool.iter().map(|(_, x)| x[0]).collect::<Vec<_>>(),
+            vec![7, 8, 9],
+        );
+
+        // The pool keeps working
+        let ptr = pool.allocate(vec![10]);
+        assert_eq!(pool[ptr], vec![10]);
+        assert_eq!(pool.deallocate(ptrs[8]), Some(vec![8]));
+        unsafe { pool.reclaim() };
+        assert_eq!(
+            pool.iter().map(|(_, x)| x[0]).collect::<Vec<_>>(),
+            vec![7, 9, 10],
+        );
+    }
+
+    #[test]
+    fn with_capacity() {
+        let mut pool = LeakyPool::with_capacity(LeakyToken::new(), 4);
+        let ptrs: Vec<_> = (0..6).map(|i| pool.allocate(i)).collect();
+        for (i, &ptr) in ptrs.iter().enumerate() {
+            assert_eq!(pool[ptr], i);
+        }
+
+        // The first four entries are contiguous
+        let addrs: Vec<_> = ptrs
+            .iter()
+            .map(|p| p.into_raw().as_ptr() as usize)
+            .collect();
+        let entry_size = std::mem::size_of::<Entry<usize, LeakyTokenId>>();
+        for i in 0..3 {
+            assert_eq!(addrs[i + 1] - addrs[i], entry_size);
+        }
+
+        // Deallocated entries are reused
+        assert_eq!(pool.deallocate(ptrs[1]), Some(1));
+        assert_eq!(pool.deallocate(ptrs[5]), Some(5));
+        assert_eq!(pool.allocate(10), ptrs[5]);
+        assert_eq!(pool.allocate(11), ptrs[1]);
+
+        // `reclaim` doesn't release the block while some of its entries are
+        // occupied
+        assert_eq!(pool.deallocate(ptrs[2]), Some(2));
+        assert_eq!(pool.deallocate(ptrs[4]), Some(4));
+        unsafe { pool.reclaim() };
+        assert_eq!(pool.allocate(12), ptrs[2]);
+
+        assert_eq!(
+            pool.iter().map(|(_, &x)| x).collect::<Vec<_>>(),
+            vec![0, 3, 10, 11, 12],
+        );
+
+        // `reclaim` releases the block when all of its entries are vacant
+        for &ptr in ptrs[..4].iter() {
+            pool.deallocate(ptr);
+        }
+        unsafe { pool.reclaim() };
+        assert_eq!(pool.blocks.len(), 0);
+        assert!(pool.first_free.is_none());
+        assert_eq!(pool.iter().map(|(_, &x)| x).collect::<Vec<_>>(), vec![10]);
+    }
+
+    #[test]
+    fn weak_ptr() {
+        let mut pool1: LeakyPool<_> = LeakyPool::new();
+        let pool2: LeakyPool<_> = LeakyPool::new();
+        let ptr = pool1.allocate(1);
+        let weak = ptr.downgrade();
+        assert_eq!(weak.upgrade(&pool1), Some(ptr));
+        assert_eq!(weak.upgrade(&pool2), None);
+
+        pool1.deallocate(ptr);
+        assert_eq!(weak.upgrade(&pool1), None);
+
+        // The entry is reused for a new element
+        let ptr2 = pool1.allocate(2);
+        assert_eq!(ptr2, ptr);
+        assert_eq!(weak.upgrade(&pool1), None);
+        assert_eq!(ptr2.downgrade().upgrade(&pool1), Some(ptr2));
+        assert_ne!(ptr2.downgrade(), weak);
+    }
+
+    #[test]
+    fn debug() {
+        let mut pool: LeakyPool<_> = LeakyPool::new();
+        pool.allocate("alive");
+        let ptr = pool.allocate("dead");
+        pool.deallocate(ptr);
+
+        let s = format!("{:?}", pool);
+        assert!(s.contains("\"alive\""), "{}", s);
+        assert!(!s.contains("\"dead\""), "{}", s);
+
+        let mut pool = unsafe { LeakyPool::new_unchecked() };
+        pool.allocate("alive");
+        let s = format!("{:?}", pool);
+        assert!(!s.contains("\"alive\""), "{}", s);
+    }
+
     #[test]
     fn unchecked() {
         let mut pool = unsafe { LeakyPool::new_unchecked() };
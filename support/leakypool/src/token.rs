@@ -76,6 +76,12 @@ pub unsafe trait TokenStore {
     type Token: tokenlock::Token<Self::TokenId>;
     type TokenId: 'static;
 
+    /// `false` if the implementation doesn't actually guarantee the
+    /// uniqueness of the token, as is the case with [`UncheckedToken`].
+    /// `LeakyPool`'s `Debug` implementation doesn't read elements if this is
+    /// `false`.
+    const IS_UNIQUE: bool = true;
+
     fn token_ref(&self) -> &Self::Token;
     fn token_mut(&mut self) -> &mut Self::Token;
     fn id(&mut self) -> Self::TokenId;
@@ -195,6 +201,8 @@ unsafe impl TokenStore for UncheckedToken {
     type Token = Self;
     type TokenId = ();
 
+    const IS_UNIQUE: bool = false;
+
     fn token_ref(&self) -> &Self::Token {
         self
     }
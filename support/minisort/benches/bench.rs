@@ -1,5 +1,5 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
-use minisort::{insertion_sort, qsort};
+use minisort::{insertion_sort, qsort, qsort_by_key, sort_by_cached_key};
 
 struct Xorshift32(u32);
 
@@ -20,6 +20,11 @@ fn fill<T>(a: &mut [T], i: impl IntoIterator<Item = T>) {
     }
 }
 
+/// An artificially expensive key function.
+fn heavy_key(x: &u32) -> u32 {
+    Xorshift32(*x | 1).take(64).fold(0, |acc, x| acc ^ x)
+}
+
 fn criterion_benchmark(c: &mut Criterion) {
     let sizes: Vec<_> = (0..10).map(|i| 1usize << i).collect();
 
@@ -74,6 +79,31 @@ fn criterion_benchmark(c: &mut Criterion) {
             });
         });
     }
+    group.finish();
+
+    let mut group = c.benchmark_group("sort_by_heavy_key");
+    for &size in &sizes {
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_function(BenchmarkId::new("qsort_by_key", size), move |b| {
+            let mut array = vec![0u32; size];
+
+            b.iter(|| {
+                let array = black_box(&mut array[..]);
+                fill(array, Xorshift32(42));
+                qsort_by_key(array, heavy_key);
+            });
+        });
+
+        group.bench_function(BenchmarkId::new("sort_by_cached_key", size), move |b| {
+            let mut array = vec![0u32; size];
+
+            b.iter(|| {
+                let array = black_box(&mut array[..]);
+                fill(array, Xorshift32(42));
+                sort_by_cached_key(array, heavy_key);
+            });
+        });
+    }
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,84 @@
+//! Provides a sort implementation that caches keys.
+use crate::cstdlib;
+use std::convert::TryFrom;
+
+/// Sort the slice with a key extraction function, calling the function only
+/// once per element.
+///
+/// The keys are collected into a scratch `Vec` along with the original
+/// indices, which is then sorted by [`qsort`]. Since the indices break ties,
+/// this sort is stable.
+///
+/// [`qsort`]: crate::qsort
+///
+/// # Performance
+///
+/// This is preferable to [`qsort_by_key`] when `f` is expensive. Otherwise,
+/// the overhead of the scratch buffer and the permutation step tends to
+/// outweigh the saved calls.
+///
+/// [`qsort_by_key`]: crate::qsort_by_key
+///
+/// # Panics
+///
+/// Panics if `a.len()` exceeds `u32::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// let mut v = [-5i32, 4, 1, -3, 2];
+///
+/// minisort::sort_by_cached_key(&mut v, |k| k.abs());
+/// assert!(v == [1, 2, -3, 4, -5]);
+/// ```
+pub fn sort_by_cached_key<T, K: Ord>(a: &mut [T], f: impl FnMut(&T) -> K) {
+    let len = a.len();
+
+    assert!(u32::try_from(len).is_ok(), "too many elements");
+
+    let mut indices: Vec<(K, u32)> = a.iter().map(f).zip(0..).collect();
+    cstdlib::qsort(&mut indices);
+
+    // Apply the permutation in place. `indices[i].1` is the original position
+    // of the element that should end up at `a[i]`. If it's less than `i`, the
+    // element was already moved by an earlier swap, and we follow the chain
+    // to find its current position.
+    for i in 0..len {
+        let mut index = indices[i].1;
+        while (index as usize) < i {
+            index = indices[index as usize].1;
+        }
+        indices[i].1 = index;
+        a.swap(i, index as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn result_is_sorted_by_key(mut v: Vec<(i32, i32)>) -> bool {
+        sort_by_cached_key(&mut v, |e| e.1);
+        v.is_sorted_by_key(|e| e.1)
+    }
+
+    #[quickcheck]
+    fn result_is_stable(v: Vec<u8>) -> bool {
+        let mut tagged: Vec<(u8, usize)> = v.iter().cloned().zip(0..).collect();
+        sort_by_cached_key(&mut tagged, |e| e.0 / 16);
+        tagged.is_sorted_by_key(|e| (e.0 / 16, e.1))
+    }
+
+    #[quickcheck]
+    fn calls_f_once_per_element(v: Vec<i32>) -> bool {
+        let mut num_calls = 0;
+        let mut v2 = v.clone();
+        sort_by_cached_key(&mut v2, |&x| {
+            num_calls += 1;
+            x
+        });
+        num_calls == v.len()
+    }
+}
@@ -46,7 +46,7 @@ pub fn insertion_sort_by<T>(a: &mut [T], mut f: impl FnMut(&T, &T) -> Ordering)
     insertion_sort_inner(a, |x, y| f(x, y) == Ordering::Less);
 }
 
-fn insertion_sort_inner<T>(a: &mut [T], mut f: impl FnMut(&T, &T) -> bool) {
+pub(crate) fn insertion_sort_inner<T>(a: &mut [T], mut f: impl FnMut(&T, &T) -> bool) {
     for i in 1..a.len() {
         let mut ap = &mut a[0..=i];
 
@@ -3,8 +3,14 @@
 #![feature(specialization)]
 
 mod auto;
+mod cached;
 mod cstdlib;
 mod insertion;
+mod partition;
+mod stable;
 pub use self::auto::*;
+pub use self::cached::*;
 pub use self::cstdlib::*;
 pub use self::insertion::*;
+pub use self::partition::*;
+pub use self::stable::*;
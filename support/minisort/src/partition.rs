@@ -0,0 +1,137 @@
+//! Provides a stable partition implementation.
+use std::ptr;
+
+/// Reorder the slice so that all elements satisfying the predicate precede
+/// all elements that don't, preserving the relative order of the elements in
+/// each group. Returns the number of elements satisfying the predicate.
+///
+/// The predicate is called exactly once for each element, in order.
+///
+/// # Performance
+///
+/// This function runs in O(n) time and allocates a scratch buffer of up to
+/// `a.len()` elements.
+///
+/// # Examples
+///
+/// ```
+/// let mut v = [1, 2, 3, 4, 5, 6];
+///
+/// let mid = minisort::stable_partition(&mut v, |x| x % 3 == 0);
+/// assert_eq!(mid, 2);
+/// assert!(v == [3, 6, 1, 2, 4, 5]);
+/// ```
+pub fn stable_partition<T>(a: &mut [T], mut pred: impl FnMut(&T) -> bool) -> usize {
+    let len = a.len();
+    let base = a.as_mut_ptr();
+
+    // Invariant: `a[0..num_accepted]` contains the accepted elements,
+    // `a[num_accepted..i]` is a hole whose size is equal to `hole.rejected.len()`,
+    // and `a[i..]` is untouched. The hole is filled with `hole.rejected` when
+    // `hole` is dropped, which also happens if `pred` panics.
+    let mut hole = Hole {
+        dst: base,
+        rejected: Vec::with_capacity(len),
+    };
+    let mut num_accepted = 0;
+
+    for i in 0..len {
+        unsafe {
+            let src = base.add(i);
+            if pred(&*src) {
+                // `hole.dst <= src`
+                ptr::copy(src, hole.dst, 1);
+                hole.dst = hole.dst.add(1);
+                num_accepted += 1;
+            } else {
+                // This doesn't reallocate
+                hole.rejected.push(ptr::read(src));
+            }
+        }
+    }
+
+    num_accepted
+}
+
+struct Hole<T> {
+    dst: *mut T,
+    rejected: Vec<T>,
+}
+
+impl<T> Drop for Hole<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::copy_nonoverlapping(self.rejected.as_ptr(), self.dst, self.rejected.len());
+            self.rejected.set_len(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        rc::Rc,
+    };
+
+    #[quickcheck]
+    fn preserves_relative_order(v: Vec<i32>) -> bool {
+        let mut tagged: Vec<(i32, usize)> = v.iter().cloned().zip(0..).collect();
+        let mid = stable_partition(&mut tagged, |&(x, _)| x % 2 == 0);
+
+        let (accepted, rejected) = tagged.split_at(mid);
+
+        accepted.iter().all(|&(x, _)| x % 2 == 0)
+            && rejected.iter().all(|&(x, _)| x % 2 != 0)
+            && accepted.is_sorted_by_key(|e| e.1)
+            && rejected.is_sorted_by_key(|e| e.1)
+            && tagged.len() == v.len()
+            && tagged.iter().all(|&(x, i)| v[i] == x)
+    }
+
+    #[quickcheck]
+    fn calls_pred_in_order(v: Vec<u8>) -> bool {
+        let mut visited = Vec::new();
+        let mut v2 = v.clone();
+        stable_partition(&mut v2, |&x| {
+            visited.push(x);
+            x < 128
+        });
+        visited == v
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut v = [(); 5];
+        let mut i = 0;
+        let mid = stable_partition(&mut v, |_| {
+            i += 1;
+            i % 2 == 0
+        });
+        assert_eq!(mid, 2);
+    }
+
+    #[test]
+    fn pred_panics() {
+        let elems: Vec<_> = (0..8).map(Rc::new).collect();
+        let mut v = elems.clone();
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            stable_partition(&mut v, |x| {
+                assert!(**x != 5);
+                **x % 2 == 0
+            })
+        }));
+        assert!(result.is_err());
+
+        // Every element must remain in the slice exactly once
+        for e in elems.iter() {
+            assert_eq!(Rc::strong_count(e), 2);
+        }
+        let mut values: Vec<_> = v.iter().map(|x| **x).collect();
+        values.sort();
+        assert_eq!(values, (0..8).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,220 @@
+//! Provides a stable sort implementation.
+use crate::insertion::insertion_sort_inner;
+use std::{cmp::Ordering, mem::size_of, ptr};
+
+/// The length of the runs sorted by insertion sort before merging. Inputs not
+/// longer than this are sorted without allocating a scratch buffer.
+const RUN_LEN: usize = 16;
+
+/// Sort the slice using a stable sort algorithm, i.e., the relative order of
+/// equal elements is preserved.
+///
+/// # Performance
+///
+/// This function uses insertion sort for a small input. For a larger input,
+/// it sorts runs of a fixed length by insertion sort and then merges them
+/// bottom-up. The merge passes share a single scratch buffer, which is
+/// allocated once per call and holds up to `a.len()` elements.
+///
+/// # Examples
+///
+/// ```
+/// let mut v = [-5, 4, 1, -3, 2];
+///
+/// minisort::stable_sort(&mut v);
+/// assert!(v == [-5, -3, 1, 2, 4]);
+/// ```
+pub fn stable_sort<T: Ord>(a: &mut [T]) {
+    stable_sort_inner(a, |x, y| x < y);
+}
+
+/// Sort the slice with a key extraction function, preserving the relative
+/// order of the elements with equal keys.
+///
+/// # Examples
+///
+/// ```
+/// let mut v = [-5i32, 4, 1, -3, 2, 5];
+///
+/// minisort::stable_sort_by_key(&mut v, |k| k.abs());
+/// assert!(v == [1, 2, -3, 4, -5, 5]);
+/// ```
+pub fn stable_sort_by_key<T, K: Ord>(a: &mut [T], mut f: impl FnMut(&T) -> K) {
+    stable_sort_inner(a, |x, y| f(x) < f(y));
+}
+
+/// Sort the slice with a comparator function, preserving the relative order
+/// of the elements that compare equal.
+///
+/// # Examples
+///
+/// ```
+/// let mut v = [5, 4, 1, 3, 2];
+/// minisort::stable_sort_by(&mut v, |a, b| a.cmp(b));
+/// assert!(v == [1, 2, 3, 4, 5]);
+/// ```
+pub fn stable_sort_by<T>(a: &mut [T], mut f: impl FnMut(&T, &T) -> Ordering) {
+    stable_sort_inner(a, |x, y| f(x, y) == Ordering::Less);
+}
+
+fn stable_sort_inner<T>(a: &mut [T], mut is_less: impl FnMut(&T, &T) -> bool) {
+    if size_of::<T>() == 0 {
+        // Zero-sized elements are indistinguishable from each other
+        return;
+    }
+
+    // Sort each run. `insertion_sort_inner` swaps adjacent elements unless the
+    // predicate returns `true`, so the predicate must return `true` for equal
+    // elements to preserve their order.
+    for run in a.chunks_mut(RUN_LEN) {
+        insertion_sort_inner(run, |x, y| !is_less(y, x));
+    }
+
+    let len = a.len();
+    if len <= RUN_LEN {
+        return;
+    }
+
+    let mut buf: Vec<T> = Vec::with_capacity(len);
+
+    let mut width = RUN_LEN;
+    while width < len {
+        let mut start = 0;
+        while start + width < len {
+            let end = (start + width * 2).min(len);
+            unsafe {
+                merge(&mut a[start..end], width, buf.as_mut_ptr(), &mut is_less);
+            }
+            start = end;
+        }
+        width *= 2;
+    }
+}
+
+/// Merge the sorted runs `v[..mid]` and `v[mid..]` in place. `buf` must be
+/// valid for writing `mid` elements.
+unsafe fn merge<T>(v: &mut [T], mid: usize, buf: *mut T, is_less: &mut impl FnMut(&T, &T) -> bool) {
+    let v_end = v.as_mut_ptr().add(v.len());
+    let v = v.as_mut_ptr();
+
+    // Move the left run to `buf`. The elements are moved back to `v` one by
+    // one. `hole` fills the remaining space with the remaining elements in
+    // `buf` when it's dropped, which also happens if `is_less` panics.
+    ptr::copy_nonoverlapping(v, buf, mid);
+    let mut hole = MergeHole {
+        start: buf,
+        end: buf.add(mid),
+        dest: v,
+    };
+
+    // Invariant: `hole.dest + (hole.end - hole.start) == right`
+    let mut right = v.add(mid);
+
+    while hole.start < hole.end && right < v_end {
+        // Take the right element only if it's strictly less than the left one
+        // to preserve stability
+        let src = if is_less(&*right, &*hole.start) {
+            let src = right;
+            right = right.add(1);
+            src
+        } else {
+            let src = hole.start;
+            hole.start = hole.start.add(1);
+            src
+        };
+        ptr::copy_nonoverlapping(src, hole.dest, 1);
+        hole.dest = hole.dest.add(1);
+    }
+
+    // The remaining elements of the right run are already in place. The
+    // remaining elements of the left run are moved by `hole`.
+}
+
+struct MergeHole<T> {
+    start: *mut T,
+    end: *mut T,
+    dest: *mut T,
+}
+
+impl<T> Drop for MergeHole<T> {
+    fn drop(&mut self) {
+        // `T` is not zero-sized
+        let len = (self.end as usize - self.start as usize) / size_of::<T>();
+        unsafe {
+            ptr::copy_nonoverlapping(self.start, self.dest, len);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        rc::Rc,
+    };
+
+    #[quickcheck]
+    fn result_is_sorted(mut v: Vec<i32>) -> bool {
+        stable_sort(&mut v);
+        v.is_sorted()
+    }
+
+    #[quickcheck]
+    fn result_is_sorted_by_key(mut v: Vec<(i32, i32)>) -> bool {
+        stable_sort_by_key(&mut v, |e| e.1);
+        v.is_sorted_by_key(|e| e.1)
+    }
+
+    #[quickcheck]
+    fn result_is_sorted_by(mut v: Vec<i32>) -> bool {
+        stable_sort_by(&mut v, |x, y| y.cmp(x));
+        v.is_sorted_by(|x, y| Some(y.cmp(x)))
+    }
+
+    #[quickcheck]
+    fn result_is_stable(v: Vec<u8>, repeat: u8) -> bool {
+        // Repeat the input to cover the merge passes well
+        let mut tagged: Vec<(u8, usize)> = v
+            .iter()
+            .cycle()
+            .take(v.len() * (repeat as usize % 32 + 1))
+            .map(|x| x % 8)
+            .zip(0..)
+            .collect();
+        stable_sort_by_key(&mut tagged, |e| e.0);
+        tagged.is_sorted_by_key(|e| *e)
+    }
+
+    #[test]
+    fn zero_sized() {
+        let mut v = [(); 100];
+        stable_sort(&mut v);
+    }
+
+    #[test]
+    fn comparator_panics() {
+        // The first 726 comparisons are made by insertion sort
+        let elems: Vec<_> = (0..100).rev().map(Rc::new).collect();
+        let mut v = elems.clone();
+
+        let mut num_calls = 0;
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            stable_sort_by(&mut v, |x, y| {
+                num_calls += 1;
+                assert!(num_calls != 730);
+                x.cmp(y)
+            })
+        }));
+        assert!(result.is_err());
+
+        // Every element must remain in the slice exactly once
+        for e in elems.iter() {
+            assert_eq!(Rc::strong_count(e), 2);
+        }
+        let mut values: Vec<_> = v.iter().map(|x| **x).collect();
+        values.sort();
+        assert_eq!(values, (0..100).collect::<Vec<_>>());
+    }
+}
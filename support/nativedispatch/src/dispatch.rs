@@ -1,10 +1,10 @@
 //! libdispatch backend
 use dispatch::ffi as disp;
-use std::ffi::c_void;
+use std::{ffi::c_void, ptr::null, time::Duration};
 
 use super::QueuePriority;
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug)]
 pub struct QueueImpl {
     queue: disp::dispatch_queue_t,
 }
@@ -38,12 +38,60 @@ impl QueueImpl {
         Self { queue }
     }
 
+    pub fn serial() -> Self {
+        let queue = unsafe { disp::dispatch_queue_create(null(), disp::DISPATCH_QUEUE_SERIAL) };
+        assert!(!queue.is_null());
+
+        Self { queue }
+    }
+
     pub fn invoke(&self, work: impl FnOnce() + Send + 'static) {
         let (ctx, func) = ctx_and_fn(work);
         unsafe {
             disp::dispatch_async_f(self.queue, ctx, func);
         }
     }
+
+    pub fn apply<F: Fn(usize) + Sync>(&self, iterations: usize, work: &F) {
+        extern "C" fn dispatch_apply_trampoline<F: Fn(usize) + Sync>(ctx: *mut c_void, i: usize) {
+            let work = unsafe { &*(ctx as *const F) };
+            work(i);
+        }
+
+        unsafe {
+            disp::dispatch_apply_f(
+                iterations,
+                self.queue,
+                work as *const F as *mut c_void,
+                dispatch_apply_trampoline::<F>,
+            );
+        }
+    }
+
+    pub fn invoke_after(&self, delay: Duration, work: impl FnOnce() + Send + 'static) {
+        let delta = delay.as_nanos().min(i64::MAX as u128) as i64;
+        let (ctx, func) = ctx_and_fn(work);
+        unsafe {
+            let when = disp::dispatch_time(disp::DISPATCH_TIME_NOW, delta);
+            disp::dispatch_after_f(when, self.queue, ctx, func);
+        }
+    }
+}
+
+impl Clone for QueueImpl {
+    fn clone(&self) -> Self {
+        // This is no-op for global queues
+        unsafe { disp::dispatch_retain(self.queue) };
+        Self { queue: self.queue }
+    }
+}
+
+impl Drop for QueueImpl {
+    fn drop(&mut self) {
+        // This is no-op for global queues. A serial queue is deallocated
+        // after all submitted blocks are executed.
+        unsafe { disp::dispatch_release(self.queue) };
+    }
 }
 
 fn ctx_and_fn<F: FnOnce() + Send + 'static>(work: F) -> (*mut c_void, disp::dispatch_function_t) {
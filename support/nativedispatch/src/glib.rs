@@ -1,5 +1,11 @@
 //! glib backend
-use std::{ffi::c_void, os::raw::c_int, ptr::null_mut};
+use std::{
+    ffi::c_void,
+    os::raw::{c_int, c_uint},
+    ptr::null_mut,
+    sync::Arc,
+    time::Duration,
+};
 
 use super::QueuePriority;
 
@@ -23,6 +29,23 @@ lazy_static::lazy_static! {
 
         AssertSendSync(thread_pool)
     };
+
+    /// A `GMainContext` used to implement `invoke_after`. The context is
+    /// iterated by a dedicated thread.
+    static ref TIMER_CONTEXT: AssertSendSync<*mut glib_sys::GMainContext> = unsafe {
+        let context = AssertSendSync(glib_sys::g_main_context_new());
+        assert!(!context.0.is_null());
+
+        std::thread::Builder::new()
+            .name("nativedispatch timer".to_owned())
+            .spawn(move || {
+                let main_loop = glib_sys::g_main_loop_new(context.0, 0);
+                glib_sys::g_main_loop_run(main_loop);
+            })
+            .unwrap();
+
+        context
+    };
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -61,10 +84,28 @@ unsafe extern "C" fn thread_pool_ord(
     work1.pri as c_int - work2.pri as c_int
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct QueueImpl {
     pool: AssertSendSync<*mut glib_sys::GThreadPool>,
     pri: QueuePriority,
+    /// Keeps the thread pool alive if this is a serial queue.
+    _serial_pool: Option<Arc<SerialPool>>,
+}
+
+/// Owns a thread pool created for a serial queue.
+#[derive(Debug)]
+struct SerialPool(AssertSendSync<*mut glib_sys::GThreadPool>);
+
+impl Drop for SerialPool {
+    fn drop(&mut self) {
+        unsafe {
+            glib_sys::g_thread_pool_free(
+                (self.0).0,
+                0, // process the remaining tasks before freeing the pool
+                0, // don't wait
+            );
+        }
+    }
 }
 
 unsafe impl Send for QueueImpl {}
@@ -75,6 +116,31 @@ impl QueueImpl {
         Self {
             pool: *THREAD_POOL,
             pri,
+            _serial_pool: None,
+        }
+    }
+
+    pub fn serial() -> Self {
+        // A thread pool with at most one thread processes tasks one at a time
+        // in FIFO order (we don't set a sort function). The pool is
+        // non-exclusive, so it can share idle threads with other pools.
+        let pool = unsafe {
+            glib_sys::g_thread_pool_new(
+                Some(thread_pool_process_work),
+                null_mut(), // user data
+                1,          // max threads
+                0,          // non-exclusive
+                null_mut(), // [out] error
+            )
+        };
+        assert!(!pool.is_null());
+
+        let pool = AssertSendSync(pool);
+
+        Self {
+            pool,
+            pri: QueuePriority::Medium,
+            _serial_pool: Some(Arc::new(SerialPool(pool))),
         }
     }
 
@@ -94,4 +160,37 @@ impl QueueImpl {
             unsafe { glib_sys::g_thread_pool_push(self.pool.0, work_ptr as _, null_mut()) };
         assert_ne!(success, 0);
     }
+
+    pub fn apply<F: Fn(usize) + Sync>(&self, iterations: usize, work: &F) {
+        super::apply_by_invoke(|work| self.invoke(work), iterations, work);
+    }
+
+    pub fn invoke_after<F: FnOnce() + Send + 'static>(&self, delay: Duration, work: F) {
+        unsafe extern "C" fn timeout_trampoline<F: FnOnce() + Send + 'static>(
+            data: glib_sys::gpointer,
+        ) -> glib_sys::gboolean {
+            let (queue, work) = *Box::from_raw(data as *mut (QueueImpl, F));
+            queue.invoke(work);
+
+            glib_sys::G_SOURCE_REMOVE
+        }
+
+        // Round up to make sure at least `delay` elapses
+        let delay_ms = (delay.as_nanos() + 999_999) / 1_000_000;
+        let delay_ms = delay_ms.min(c_uint::MAX as u128) as c_uint;
+
+        let data = Box::into_raw(Box::new((self.clone(), work)));
+
+        unsafe {
+            let source = glib_sys::g_timeout_source_new(delay_ms);
+            glib_sys::g_source_set_callback(
+                source,
+                Some(timeout_trampoline::<F>),
+                data as _,
+                None, // destroy notify
+            );
+            glib_sys::g_source_attach(source, TIMER_CONTEXT.0);
+            glib_sys::g_source_unref(source);
+        }
+    }
 }
@@ -1,5 +1,13 @@
 //! This crate provides a cross-platform interface to each target platform's
 //! thread pool facility.
+#[cfg(any(not(target_os = "macos"), test))]
+use std::sync::{Arc, Condvar, MutexGuard};
+use std::{
+    future::Future,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::Mutex,
+    time::Duration,
+};
 
 // --------------------------------------------------------------------------
 // Backend implementations
@@ -21,6 +29,8 @@ use self::glib::QueueImpl;
 
 // --------------------------------------------------------------------------
 
+mod oneshot;
+
 #[derive(Debug, Clone)]
 pub struct Queue {
     imp: QueueImpl,
@@ -62,16 +72,174 @@ impl Queue {
         Self::global(QueuePriority::Background)
     }
 
+    /// Create a serial queue, which executes submitted closures one at a time
+    /// in FIFO order. The closures are executed with
+    /// `QueuePriority::Medium`.
+    ///
+    /// Unlike global queues, each serial queue has its own state, which is
+    /// released when all clones of the `Queue` are dropped and all submitted
+    /// closures are executed.
+    pub fn serial() -> Self {
+        Self {
+            imp: QueueImpl::serial(),
+        }
+    }
+
     /// Execute a closure asynchronously.
     pub fn invoke(&self, work: impl FnOnce() + Send + 'static) {
         self.imp.invoke(work)
     }
+
+    /// Execute a closure asynchronously and return a future that resolves to
+    /// the closure's return value.
+    ///
+    /// The closure is executed even if the returned future is dropped before
+    /// completion. In this case, the return value is discarded.
+    pub fn invoke_async<R: Send + 'static>(
+        &self,
+        work: impl FnOnce() -> R + Send + 'static,
+    ) -> impl Future<Output = R> {
+        let (send, recv) = oneshot::channel();
+        self.invoke(move || send.send(work()));
+        recv
+    }
+
+    /// Execute a closure `iterations` times in parallel and wait until all
+    /// of them complete. The closure receives the iteration index in range
+    /// `0..iterations`.
+    ///
+    /// On a serial queue, the iterations are executed one at a time, and this
+    /// method deadlocks if called from the same queue.
+    ///
+    /// If the closure panics, the remaining iterations are still executed,
+    /// and then the panic is propagated to the caller. If more than one
+    /// iteration panics, only the first panic is propagated.
+    pub fn apply(&self, iterations: usize, work: impl Fn(usize) + Send + Sync) {
+        if iterations == 0 {
+            return;
+        }
+
+        // The iterations may be called from FFI callbacks, which must not
+        // unwind. Catch panics in each iteration and re-raise one here.
+        let panic = Mutex::new(None);
+        self.imp.apply(iterations, &|i| {
+            if let Err(e) = catch_unwind(AssertUnwindSafe(|| work(i))) {
+                let mut panic = panic.lock().unwrap_or_else(|e| e.into_inner());
+                panic.get_or_insert(e);
+            }
+        });
+
+        if let Some(e) = panic.into_inner().unwrap_or_else(|e| e.into_inner()) {
+            resume_unwind(e);
+        }
+    }
+
+    /// Execute a closure asynchronously after a specified delay.
+    ///
+    /// The closure is submitted to the queue when the delay elapses, and it
+    /// is executed with the queue's priority.
+    pub fn invoke_after(&self, delay: Duration, work: impl FnOnce() + Send + 'static) {
+        self.imp.invoke_after(delay, work)
+    }
+}
+
+/// Implements `Queue::apply` by submitting each iteration through `invoke`
+/// and waiting for all of them to complete. Used by the backends lacking a
+/// native facility.
+#[cfg(any(not(target_os = "macos"), test))]
+fn apply_by_invoke(
+    invoke: impl Fn(Box<dyn FnOnce() + Send>),
+    iterations: usize,
+    work: &(dyn Fn(usize) + Sync),
+) {
+    // Erase the lifetime. This is safe because we don't return (nor unwind)
+    // until all closures referencing `work` are dropped. This is ensured by
+    // `wait` and `CountdownGuard`.
+    let work = unsafe {
+        std::mem::transmute::<&(dyn Fn(usize) + Sync), &'static (dyn Fn(usize) + Sync)>(work)
+    };
+
+    let countdown = Arc::new(Countdown {
+        count: Mutex::new(0),
+        done: Condvar::new(),
+    });
+
+    // Wait for the submitted closures even if `invoke` panics
+    let wait = WaitGuard(&countdown);
+
+    for i in 0..iterations {
+        *countdown.lock() += 1;
+
+        // Decrements the count when the closure completes, panics, or is
+        // dropped without being called
+        let guard = CountdownGuard(Arc::clone(&countdown));
+
+        invoke(Box::new(move || {
+            let _guard = guard;
+            work(i);
+        }));
+    }
+
+    drop(wait);
+}
+
+/// The number of closures submitted by `apply_by_invoke` and not dropped yet.
+#[cfg(any(not(target_os = "macos"), test))]
+struct Countdown {
+    count: Mutex<usize>,
+    done: Condvar,
+}
+
+#[cfg(any(not(target_os = "macos"), test))]
+impl Countdown {
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        // The lock is never held during a panic, but don't panic in `drop`
+        // even if it's poisoned
+        self.count.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(any(not(target_os = "macos"), test))]
+struct CountdownGuard(Arc<Countdown>);
+
+#[cfg(any(not(target_os = "macos"), test))]
+impl Drop for CountdownGuard {
+    fn drop(&mut self) {
+        let mut count = self.0.lock();
+        *count -= 1;
+        if *count == 0 {
+            self.0.done.notify_all();
+        }
+    }
+}
+
+/// Blocks until `Countdown` reaches zero when dropped.
+#[cfg(any(not(target_os = "macos"), test))]
+struct WaitGuard<'a>(&'a Countdown);
+
+#[cfg(any(not(target_os = "macos"), test))]
+impl Drop for WaitGuard<'_> {
+    fn drop(&mut self) {
+        let mut count = self.0.lock();
+        while *count > 0 {
+            count = self.0.done.wait(count).unwrap_or_else(|e| e.into_inner());
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::sync::{Arc, Barrier};
+    use futures::executor::block_on;
+    use std::{
+        panic::{catch_unwind, AssertUnwindSafe},
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            mpsc, Barrier,
+        },
+        thread::sleep,
+        time::Instant,
+    };
 
     #[test]
     fn it_works() {
@@ -85,4 +253,168 @@ mod tests {
 
         barrier.wait();
     }
+
+    #[test]
+    fn invoke_after() {
+        let queue = Queue::global(QueuePriority::High);
+        let (send, recv) = mpsc::channel();
+
+        let start = Instant::now();
+        queue.invoke_after(Duration::from_millis(100), move || {
+            send.send(Instant::now()).unwrap();
+        });
+
+        let end = recv.recv().unwrap();
+
+        // Allow some errors because some backends have coarse timers
+        assert!(end.duration_since(start) >= Duration::from_millis(80));
+    }
+
+    #[test]
+    fn invoke_async() {
+        let queue = Queue::global(QueuePriority::High);
+        let result = block_on(queue.invoke_async(|| 42));
+        assert_eq!(result, 42);
+    }
+
+    #[test]
+    fn invoke_async_drop_future() {
+        let queue = Queue::global(QueuePriority::High);
+        let (send, recv) = mpsc::channel();
+
+        drop(queue.invoke_async(move || {
+            send.send(()).unwrap();
+            42
+        }));
+
+        // The closure is executed anyway
+        recv.recv().unwrap();
+    }
+
+    #[test]
+    fn apply() {
+        let queue = Queue::global(QueuePriority::High);
+        let counts: Vec<_> = (0..1000).map(|_| AtomicUsize::new(0)).collect();
+
+        queue.apply(counts.len(), |i| {
+            counts[i].fetch_add(1, Ordering::Relaxed);
+        });
+
+        for count in counts.iter() {
+            assert_eq!(count.load(Ordering::Relaxed), 1);
+        }
+
+        queue.apply(0, |_| unreachable!());
+    }
+
+    #[test]
+    fn apply_serial() {
+        let queue = Queue::serial();
+        let busy = AtomicBool::new(false);
+        let counts: Vec<_> = (0..100).map(|_| AtomicUsize::new(0)).collect();
+
+        queue.apply(counts.len(), |i| {
+            assert!(!busy.swap(true, Ordering::Acquire), "not serial");
+            counts[i].fetch_add(1, Ordering::Relaxed);
+            busy.store(false, Ordering::Release);
+        });
+
+        for count in counts.iter() {
+            assert_eq!(count.load(Ordering::Relaxed), 1);
+        }
+    }
+
+    #[test]
+    fn apply_panics() {
+        for queue in [Queue::global(QueuePriority::High), Queue::serial()].iter() {
+            let counts: Vec<_> = (0..100).map(|_| AtomicUsize::new(0)).collect();
+
+            let result = catch_unwind(AssertUnwindSafe(|| {
+                queue.apply(counts.len(), |i| {
+                    counts[i].fetch_add(1, Ordering::Relaxed);
+                    assert_ne!(i % 10, 3, "iteration failed");
+                });
+            }));
+            assert!(result.is_err());
+
+            // The remaining iterations are executed anyway
+            for count in counts.iter() {
+                assert_eq!(count.load(Ordering::Relaxed), 1);
+            }
+        }
+    }
+
+    #[test]
+    fn apply_by_invoke_invoke_panics() {
+        let num_invoked = AtomicUsize::new(0);
+        let visited: Vec<_> = (0..10).map(|_| AtomicBool::new(false)).collect();
+
+        let result = catch_unwind(AssertUnwindSafe(|| {
+            apply_by_invoke(
+                |work| {
+                    if num_invoked.fetch_add(1, Ordering::Relaxed) == 5 {
+                        panic!("invoke failed");
+                    }
+                    std::thread::spawn(move || {
+                        sleep(Duration::from_millis(50));
+                        work();
+                    });
+                },
+                visited.len(),
+                &|i| visited[i].store(true, Ordering::Relaxed),
+            );
+        }));
+        assert!(result.is_err());
+
+        // The iterations submitted before the panic have completed by the
+        // time `apply_by_invoke` unwinds
+        for (i, visited) in visited.iter().enumerate() {
+            assert_eq!(visited.load(Ordering::Relaxed), i < 5, "{}", i);
+        }
+    }
+
+    #[test]
+    fn apply_by_invoke_work_panics() {
+        let num_completed = AtomicUsize::new(0);
+
+        // Must not hang
+        apply_by_invoke(
+            |work| {
+                std::thread::spawn(work);
+            },
+            10,
+            &|i| {
+                assert_ne!(i, 3, "iteration failed");
+                num_completed.fetch_add(1, Ordering::Relaxed);
+            },
+        );
+
+        assert_eq!(num_completed.load(Ordering::Relaxed), 9);
+    }
+
+    #[test]
+    fn serial() {
+        let queue = Queue::serial();
+        let busy = Arc::new(AtomicBool::new(false));
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        for i in 0..100 {
+            let busy = Arc::clone(&busy);
+            let log = Arc::clone(&log);
+            queue.invoke(move || {
+                assert!(!busy.swap(true, Ordering::Acquire), "not serial");
+                if i % 10 == 0 {
+                    sleep(Duration::from_millis(1));
+                }
+                log.lock().unwrap().push(i);
+                busy.store(false, Ordering::Release);
+            });
+        }
+
+        let (send, recv) = mpsc::channel();
+        queue.invoke(move || send.send(()).unwrap());
+        recv.recv().unwrap();
+
+        assert_eq!(*log.lock().unwrap(), (0..100).collect::<Vec<_>>());
+    }
 }
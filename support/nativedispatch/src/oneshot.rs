@@ -0,0 +1,77 @@
+//! A single-producer, single-consumer channel that delivers a single value
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+struct Inner<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+    /// `true` if the `Sender` was dropped.
+    closed: bool,
+}
+
+pub struct Sender<T> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+/// A future that resolves to the value sent by the corresponding [`Sender`].
+pub struct Receiver<T> {
+    inner: Arc<Mutex<Inner<T>>>,
+}
+
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let inner = Arc::new(Mutex::new(Inner {
+        value: None,
+        waker: None,
+        closed: false,
+    }));
+    (
+        Sender {
+            inner: Arc::clone(&inner),
+        },
+        Receiver { inner },
+    )
+}
+
+impl<T> Sender<T> {
+    /// Send a value. The value is discarded if the `Receiver` was already
+    /// dropped.
+    pub fn send(self, value: T) {
+        self.inner.lock().unwrap().value = Some(value);
+        // `Drop::drop` wakes up the receiver
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let waker = {
+            let mut inner = self.inner.lock().unwrap();
+            inner.closed = true;
+            inner.waker.take()
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut inner = self.inner.lock().unwrap();
+
+        if let Some(value) = inner.value.take() {
+            Poll::Ready(value)
+        } else if inner.closed {
+            panic!("the sender was dropped without sending a value");
+        } else {
+            inner.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
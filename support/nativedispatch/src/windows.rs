@@ -1,14 +1,23 @@
 //! Windows backend
-use std::{ffi::c_void, ptr::null_mut};
+use std::{
+    collections::VecDeque,
+    ffi::c_void,
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 use winapi::{
-    shared::minwindef::DWORD,
+    shared::minwindef::{DWORD, FALSE, FILETIME},
     um::{threadpoolapiset, winnt},
 };
 
 use super::QueuePriority;
 
 mod utils;
-use self::utils::assert_win32_ok;
+use self::utils::{assert_win32_nonnull, assert_win32_ok};
 
 /// Const-initializable `TP_CALLBACK_ENVIRON_V3`
 #[allow(dead_code)]
@@ -69,28 +78,210 @@ struct AssertSendSync<T>(T);
 unsafe impl<T> Send for AssertSendSync<T> {}
 unsafe impl<T> Sync for AssertSendSync<T> {}
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct QueueImpl {
     tp_env: winnt::PTP_CALLBACK_ENVIRON,
+    /// `Some(_)` if this is a serial queue.
+    serial: Option<Arc<SerialQueue>>,
 }
 
 unsafe impl Send for QueueImpl {}
 unsafe impl Sync for QueueImpl {}
 
+/// The state of a serial queue. The works are executed by a thread pool
+/// callback, which is submitted only if it's not running already.
+struct SerialQueue {
+    state: Mutex<SerialQueueState>,
+}
+
+struct SerialQueueState {
+    works: VecDeque<Box<dyn FnOnce() + Send>>,
+    running: bool,
+}
+
+impl std::fmt::Debug for SerialQueue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerialQueue").finish()
+    }
+}
+
 impl QueueImpl {
     pub fn global(pri: QueuePriority) -> Self {
         Self {
             tp_env: (&TP_ENV_LIST.0[pri as usize]) as *const _ as _,
+            serial: None,
+        }
+    }
+
+    pub fn serial() -> Self {
+        Self {
+            serial: Some(Arc::new(SerialQueue {
+                state: Mutex::new(SerialQueueState {
+                    works: VecDeque::new(),
+                    running: false,
+                }),
+            })),
+            ..Self::global(QueuePriority::Medium)
         }
     }
 
     pub fn invoke<F: FnOnce() + Send + 'static>(&self, work: F) {
+        if let Some(serial) = &self.serial {
+            let mut state = serial.state.lock().unwrap();
+            state.works.push_back(Box::new(work));
+
+            if !state.running {
+                state.running = true;
+                let serial = Arc::clone(serial);
+                self.submit(move || serial.run());
+            }
+        } else {
+            self.submit(work);
+        }
+    }
+
+    pub fn apply<F: Fn(usize) + Sync>(&self, iterations: usize, work: &F) {
+        if self.serial.is_some() {
+            super::apply_by_invoke(|work| self.invoke(work), iterations, work);
+            return;
+        }
+
+        struct ApplyCtx<'a, F> {
+            work: &'a F,
+            next_index: AtomicUsize,
+        }
+
+        extern "system" fn tp_work_trampoline<F: Fn(usize) + Sync>(
+            _: winnt::PTP_CALLBACK_INSTANCE,
+            ctx: *mut c_void,
+            _: winnt::PTP_WORK,
+        ) {
+            let ctx = unsafe { &*(ctx as *const ApplyCtx<'_, F>) };
+            let i = ctx.next_index.fetch_add(1, Ordering::Relaxed);
+            (ctx.work)(i);
+        }
+
+        let ctx = ApplyCtx {
+            work,
+            next_index: AtomicUsize::new(0),
+        };
+
+        unsafe {
+            let tp_work = threadpoolapiset::CreateThreadpoolWork(
+                Some(tp_work_trampoline::<F>),
+                &ctx as *const _ as *mut c_void,
+                self.tp_env,
+            );
+            assert_win32_nonnull(tp_work);
+
+            for _ in 0..iterations {
+                threadpoolapiset::SubmitThreadpoolWork(tp_work);
+            }
+
+            // Wait for all callbacks, including the ones that haven't started
+            // yet
+            threadpoolapiset::WaitForThreadpoolWorkCallbacks(tp_work, FALSE);
+            threadpoolapiset::CloseThreadpoolWork(tp_work);
+        }
+    }
+
+    /// Submit a thread pool callback.
+    fn submit<F: FnOnce() + Send + 'static>(&self, work: F) {
         let (ctx, func) = ctx_and_fn(work);
 
         assert_win32_ok(unsafe {
             threadpoolapiset::TrySubmitThreadpoolCallback(func, ctx, self.tp_env)
         });
     }
+
+    pub fn invoke_after<F: FnOnce() + Send + 'static>(&self, delay: Duration, work: F) {
+        if self.serial.is_some() {
+            // Put `work` in the FIFO when the timer fires
+            let this = self.clone();
+            self.set_timer(delay, move || this.invoke(work));
+        } else {
+            self.set_timer(delay, work);
+        }
+    }
+
+    /// Create a thread pool timer that executes `work` after `delay`.
+    fn set_timer<F: FnOnce() + Send + 'static>(&self, delay: Duration, work: F) {
+        extern "system" fn tp_timer_trampoline<F: FnOnce() + Send + 'static>(
+            _: winnt::PTP_CALLBACK_INSTANCE,
+            ctx: *mut c_void,
+            timer: winnt::PTP_TIMER,
+        ) {
+            // The timer is one-shot, so we can release it now. (Even if there
+            // are outstanding callbacks, it's released only after they
+            // complete.)
+            unsafe { threadpoolapiset::CloseThreadpoolTimer(timer) };
+
+            let work = unsafe { Box::from_raw(ctx as *mut F) };
+            work();
+        }
+
+        let ctx = Box::into_raw(Box::new(work));
+
+        let timer = unsafe {
+            threadpoolapiset::CreateThreadpoolTimer(
+                Some(tp_timer_trampoline::<F>),
+                ctx as _,
+                self.tp_env,
+            )
+        };
+        assert_win32_nonnull(timer);
+
+        // A negative value represents a relative time in 100-nanosecond units
+        let due_time = -((delay.as_nanos() / 100).min(i64::MAX as u128) as i64);
+        let mut due_time = FILETIME {
+            dwLowDateTime: due_time as DWORD,
+            dwHighDateTime: (due_time >> 32) as DWORD,
+        };
+
+        unsafe {
+            threadpoolapiset::SetThreadpoolTimer(
+                timer,
+                &mut due_time,
+                0, // one-shot
+                0, // no window length
+            );
+        }
+    }
+}
+
+impl SerialQueue {
+    /// Execute the works in the FIFO until it's empty.
+    fn run(&self) {
+        // Clear `running` if a work panics. Otherwise, the queue would never
+        // submit a runner again. The remaining works are executed when a new
+        // one is submitted.
+        let guard = RunningGuard(self);
+
+        loop {
+            let work = {
+                let mut state = self.state.lock().unwrap();
+                if let Some(work) = state.works.pop_front() {
+                    work
+                } else {
+                    state.running = false;
+                    std::mem::forget(guard);
+                    return;
+                }
+            };
+
+            work();
+        }
+    }
+}
+
+/// Clears `SerialQueueState::running` when dropped.
+struct RunningGuard<'a>(&'a SerialQueue);
+
+impl Drop for RunningGuard<'_> {
+    fn drop(&mut self) {
+        let mut state = self.0.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.running = false;
+    }
 }
 
 fn ctx_and_fn<F: FnOnce() + Send + 'static>(work: F) -> (*mut c_void, winnt::PTP_SIMPLE_CALLBACK) {
@@ -8,6 +8,14 @@ pub fn assert_win32_ok<T: Default + PartialEq<T> + Copy>(b: T) {
     }
 }
 
+/// Panic with an error code returned by `GetLastError` if the given pointer
+/// is null.
+pub fn assert_win32_nonnull<T>(p: *mut T) {
+    if p.is_null() {
+        panic_last_error();
+    }
+}
+
 /// Panic with an error code returned by `GetLastError`.
 #[cold]
 fn panic_last_error() -> ! {
@@ -5,7 +5,8 @@
 //!
 //!  - All features which are unstable at the point of writing were
 //!    removed.
-//!  - `LinkedList::split_off` was removed.
+//!  - `LinkedList::split_off` takes a linear time in `at` because the list
+//!    doesn't know its length.
 //!  - The element count accounting was removed. Counting the elements now takes
 //!    a linear time.
 //!  - The elements can now be unsized. `Node` has a room to store the `vtable`
@@ -373,6 +374,73 @@ impl<T: ?Sized> LinkedList<T> {
         }
     }
 
+    /// Splits the list into two at the given index. Returns everything after
+    /// the given index, including the index.
+    ///
+    /// The nodes are relinked and not reallocated, so the pinned elements stay
+    /// at the same memory locations.
+    ///
+    /// This operation should compute in O(at) time and O(1) memory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `at > len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use neo_linked_list::LinkedList;
+    ///
+    /// let mut d = LinkedList::new();
+    ///
+    /// d.push_front(1);
+    /// d.push_front(2);
+    /// d.push_front(3);
+    ///
+    /// let mut split = d.split_off(2);
+    ///
+    /// assert_eq!(split.pop_front(), Some(1));
+    /// assert_eq!(split.pop_front(), None);
+    /// ```
+    pub fn split_off(&mut self, at: usize) -> LinkedList<T> {
+        if at == 0 {
+            return mem::replace(self, Self::new());
+        }
+
+        // Find the last node of the first half
+        let mut split_node = self.head;
+        for _ in 0..at - 1 {
+            match split_node {
+                // `as_ref` is okay here because we have exclusive access to
+                // the entirety of the list.
+                Some(hdr) => split_node = unsafe { hdr.as_ref().next },
+                None => break,
+            }
+        }
+        let split_node = split_node.expect("Cannot split off at a nonexistent index");
+
+        // This method takes care not to create mutable references to whole nodes,
+        // to maintain validity of aliasing pointers into `element`.
+        unsafe {
+            let second_head = (*split_node.as_ptr()).next.take();
+
+            let second_tail = if let Some(second_head) = second_head {
+                (*second_head.as_ptr()).prev = None;
+                self.tail
+            } else {
+                None
+            };
+
+            self.tail = Some(split_node);
+
+            LinkedList {
+                head: second_head,
+                tail: second_tail,
+                marker: PhantomData,
+            }
+        }
+    }
+
     /// Provides a forward iterator.
     ///
     /// # Examples
@@ -121,6 +121,129 @@ fn test_append() {
     check_links(&n);
 }
 
+#[test]
+fn test_split_off() {
+    // singleton
+    {
+        let mut m = LinkedList::new();
+        m.push_back(1);
+
+        let p = m.split_off(0);
+        assert_eq!(m.len(), 0);
+        assert_eq!(p.len(), 1);
+        assert_eq!(p.back(), Some(&1));
+        assert_eq!(p.front(), Some(&1));
+    }
+
+    // not singleton, forwards
+    {
+        let u = vec![1, 2, 3, 4, 5];
+        let mut m = list_from(&u);
+        let mut n = m.split_off(2);
+        assert_eq!(m.len(), 2);
+        assert_eq!(n.len(), 3);
+        for elt in 1..3 {
+            assert_eq!(m.pop_front(), Some(elt));
+        }
+        for elt in 3..6 {
+            assert_eq!(n.pop_front(), Some(elt));
+        }
+    }
+    // not singleton, backwards
+    {
+        let u = vec![1, 2, 3, 4, 5];
+        let mut m = list_from(&u);
+        let mut n = m.split_off(4);
+        assert_eq!(m.len(), 4);
+        assert_eq!(n.len(), 1);
+        for elt in 1..5 {
+            assert_eq!(m.pop_front(), Some(elt));
+        }
+        for elt in 5..6 {
+            assert_eq!(n.pop_front(), Some(elt));
+        }
+    }
+
+    // no-op on the last index
+    {
+        let mut m = LinkedList::new();
+        m.push_back(1);
+
+        let p = m.split_off(1);
+        assert_eq!(m.len(), 1);
+        assert_eq!(p.len(), 0);
+        assert_eq!(m.back(), Some(&1));
+        assert_eq!(m.front(), Some(&1));
+    }
+}
+
+#[test]
+fn test_split_off_links() {
+    let v = vec![1, 2, 3, 4, 5];
+    for at in 0..=v.len() {
+        let mut m = list_from(&v);
+        let mut n = m.split_off(at);
+        check_links(&m);
+        check_links(&n);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), &v[..at]);
+        assert_eq!(n.iter().cloned().collect::<Vec<_>>(), &v[at..]);
+
+        // Put them back together
+        m.append(&mut n);
+        check_links(&m);
+        check_links(&n);
+        assert_eq!(m.iter().cloned().collect::<Vec<_>>(), v);
+        assert!(n.is_empty());
+    }
+}
+
+#[test]
+fn test_split_off_append_empty() {
+    let v = vec![1, 2, 3];
+    let mut m = list_from(&v);
+
+    // The second half is empty
+    let mut n = m.split_off(3);
+    assert!(n.is_empty());
+    m.append(&mut n);
+    check_links(&m);
+    assert_eq!(m.iter().cloned().collect::<Vec<_>>(), v);
+
+    // The first half is empty
+    let mut n = m.split_off(0);
+    assert!(m.is_empty());
+    n.append(&mut m);
+    check_links(&n);
+    assert_eq!(n.iter().cloned().collect::<Vec<_>>(), v);
+}
+
+#[test]
+fn test_split_off_unsized_pinned() {
+    let mut m = LinkedList::<[u32]>::new();
+    m.push_back_node(Node::pin([1]));
+    m.push_back_node(Node::pin([2, 3]));
+    m.push_back_node(Node::pin([4, 5, 6]));
+
+    let ptr = m.back().unwrap() as *const [u32];
+
+    let n = m.split_off(1);
+    check_links(&m);
+    check_links(&n);
+    assert_eq!(m.len(), 1);
+    assert_eq!(n.len(), 2);
+    assert_eq!(n.front(), Some(&[2, 3][..]));
+
+    // The node wasn't moved
+    assert_eq!(n.back().unwrap() as *const [u32], ptr);
+}
+
+#[test]
+#[should_panic]
+fn test_split_off_out_of_bounds() {
+    let mut m = list_from(&[1, 2, 3]);
+    m.split_off(4);
+}
+
 #[test]
 fn test_clone_from() {
     // Short cloned from long
@@ -126,8 +126,60 @@ benchmark_group!(
     bench_search_random_000010,
 );
 
+fn bench_insert_loop(b: &mut Bencher, count: usize) {
+    let rope: Rope<_> = (0..100).map(|x| x.to_string()).collect();
+    let new_elems: Vec<String> = (0..count).map(|x| x.to_string()).collect();
+
+    b.iter(|| {
+        let mut rope = rope.clone();
+        let mut offset = 50;
+        for e in new_elems.iter() {
+            let len = e.len() as isize;
+            rope.insert_before(e.clone(), One::FirstAfter(by_ord(offset)))
+                .unwrap();
+            offset += len;
+        }
+        rope
+    });
+}
+
+fn bench_insert_loop_100000(b: &mut Bencher) {
+    bench_insert_loop(b, 100000);
+}
+fn bench_insert_loop_001000(b: &mut Bencher) {
+    bench_insert_loop(b, 1000);
+}
+
+fn bench_insert_many(b: &mut Bencher, count: usize) {
+    let rope: Rope<_> = (0..100).map(|x| x.to_string()).collect();
+    let new_elems: Vec<String> = (0..count).map(|x| x.to_string()).collect();
+
+    b.iter(|| {
+        let mut rope = rope.clone();
+        rope.insert_many(new_elems.iter().cloned(), One::FirstAfter(by_ord(50)))
+            .unwrap();
+        rope
+    });
+}
+
+fn bench_insert_many_100000(b: &mut Bencher) {
+    bench_insert_many(b, 100000);
+}
+fn bench_insert_many_001000(b: &mut Bencher) {
+    bench_insert_many(b, 1000);
+}
+
+benchmark_group!(
+    group_bench_insert,
+    bench_insert_loop_100000,
+    bench_insert_loop_001000,
+    bench_insert_many_100000,
+    bench_insert_many_001000,
+);
+
 benchmark_main!(
     group_bench_iter,
     group_bench_search_seq,
-    group_bench_search_random
+    group_bench_search_random,
+    group_bench_insert
 );
@@ -0,0 +1,240 @@
+//! Cursors for `Rope`
+use arrayvec::ArrayVec;
+use std::{cmp::Ordering, fmt, ptr::NonNull};
+
+use super::{Cursor, Offset, One, Rope, ToOffset, ORDER};
+
+impl<T, O> Rope<T, O>
+where
+    T: ToOffset<O>,
+    O: Offset,
+{
+    /// Construct a [`RopeCursor`] pointing at the element specified by `one`.
+    ///
+    /// Returns `None` if `one` does not correspond to any elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::{Rope, by_ord, One::FirstAfter};
+    /// let mut rope: Rope<String> = ["Pony", " ", "ipsum"]
+    ///     .iter().map(|x|x.to_string()).collect();
+    ///
+    /// let mut cursor = rope.cursor_at(FirstAfter(by_ord(4))).unwrap();
+    /// assert_eq!(cursor.current().map(String::as_str), Some(" "));
+    /// assert_eq!(cursor.offset(), 4);
+    ///
+    /// // Replace " " with ", "
+    /// cursor.insert_after(", ".to_string());
+    /// assert_eq!(cursor.remove().as_ref().map(String::as_str), Some(" "));
+    /// assert_eq!(cursor.current().map(String::as_str), Some(", "));
+    ///
+    /// cursor.move_next();
+    /// assert_eq!(cursor.current().map(String::as_str), Some("ipsum"));
+    /// assert_eq!(cursor.offset(), 6);
+    ///
+    /// assert_eq!(rope.iter().collect::<Vec<_>>(), ["Pony", ", ", "ipsum"]);
+    /// ```
+    pub fn cursor_at(
+        &mut self,
+        one: One<impl FnMut(&O) -> Ordering>,
+    ) -> Option<RopeCursor<'_, T, O>> {
+        let (cursor, offset) = self.find_one(one)?;
+        Some(RopeCursor::new(self, cursor, offset))
+    }
+
+    /// Construct a [`RopeCursor`] pointing at the first element. If the rope
+    /// is empty, the cursor points the "ghost" element.
+    pub fn cursor_front(&mut self) -> RopeCursor<'_, T, O> {
+        // For an empty rope, `begin()` is identical to `end()`
+        let cursor = self.begin();
+        RopeCursor::new(self, cursor, O::zero())
+    }
+}
+
+/// A cursor over a [`Rope`] with editing operations.
+///
+/// A cursor points an element or the "ghost" element located past the last
+/// element (and before the first element). Unlike [`Rope::get`] and
+/// [`Rope::remove`], moving the cursor to an adjacent element in the same
+/// leaf node does not involve a search operation. A search from the root
+/// node is only needed when the cursor crosses a leaf node boundary, so
+/// traversing the rope takes an amortized constant time per step. Editing
+/// operations through a cursor keep the cursor valid.
+pub struct RopeCursor<'a, T, O> {
+    rope: &'a mut Rope<T, O>,
+    /// Points the current element. Unspecified for the ghost element.
+    cursor: Cursor,
+    /// The leaf node containing the current element, or `None` for the ghost
+    /// element. This is borrowed from `rope` and must be updated by
+    /// `sync_leaf` whenever `rope` is modified.
+    leaf: Option<NonNull<Leaf<T>>>,
+    /// The offset of the current element. For the ghost element, this is
+    /// equal to `rope.len`.
+    offset: O,
+}
+
+type Leaf<T> = ArrayVec<[T; ORDER * 2]>;
+
+// `RopeCursor` is semantically `&mut Rope`
+unsafe impl<T: Send, O: Send> Send for RopeCursor<'_, T, O> {}
+unsafe impl<T: Sync, O: Sync> Sync for RopeCursor<'_, T, O> {}
+
+impl<T, O> fmt::Debug for RopeCursor<'_, T, O>
+where
+    T: ToOffset<O> + fmt::Debug,
+    O: Offset + fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RopeCursor")
+            .field("current", &self.current())
+            .field("offset", &self.offset)
+            .finish()
+    }
+}
+
+impl<'a, T, O> RopeCursor<'a, T, O>
+where
+    T: ToOffset<O>,
+    O: Offset,
+{
+    fn new(rope: &'a mut Rope<T, O>, cursor: Cursor, offset: O) -> Self {
+        let mut this = Self {
+            rope,
+            cursor,
+            leaf: None,
+            offset,
+        };
+        this.sync_leaf();
+        this
+    }
+
+    /// Update `leaf` after `cursor` crossed a leaf node boundary or `rope` was
+    /// modified. `cursor` must point an element or the one-past-end element
+    /// of a leaf node, the latter of which represents the ghost element.
+    fn sync_leaf(&mut self) {
+        let leaf = self.rope.leaf_at(&self.cursor);
+        self.leaf = if self.index() < leaf.len() {
+            Some(NonNull::from(leaf))
+        } else {
+            None
+        };
+    }
+
+    /// Get the leaf node containing the current element. The returned
+    /// reference must not be used after `rope` is modified.
+    fn leaf(&self) -> Option<&'a Leaf<T>> {
+        // Safety: `leaf` is kept up-to-date by `sync_leaf`, and `rope` can't
+        //         be modified except through `self`
+        self.leaf.map(|leaf| unsafe { &*leaf.as_ptr() })
+    }
+
+    /// Get the index of the current element in the leaf node.
+    fn index(&self) -> usize {
+        *self.cursor.indices.last().unwrap() as usize
+    }
+
+    fn is_ghost(&self) -> bool {
+        self.leaf.is_none()
+    }
+
+    /// Get the current element. Returns `None` if the cursor is pointing the
+    /// ghost element.
+    pub fn current(&self) -> Option<&T> {
+        self.leaf().map(|leaf| &leaf[self.index()])
+    }
+
+    /// Get the offset of the current element. Returns `offset_len()` if the
+    /// cursor is pointing the ghost element.
+    pub fn offset(&self) -> O {
+        self.offset.clone()
+    }
+
+    /// Move the cursor to the next element. If the cursor is pointing the
+    /// ghost element, this moves it to the first element. If it's pointing
+    /// the last element, this moves it to the ghost element.
+    pub fn move_next(&mut self) {
+        if let Some(leaf) = self.leaf() {
+            let i = self.index();
+            self.offset += leaf[i].to_offset();
+
+            if i + 1 < leaf.len() {
+                *self.cursor.indices.last_mut().unwrap() += 1;
+                return;
+            }
+
+            // Cross the leaf node boundary
+            self.rope.move_cursor_forward(&mut self.cursor);
+        } else {
+            self.cursor = self.rope.begin();
+            self.offset = O::zero();
+        }
+        self.sync_leaf();
+    }
+
+    /// Move the cursor to the previous element. If the cursor is pointing the
+    /// ghost element, this moves it to the last element. If it's pointing
+    /// the first element, this moves it to the ghost element.
+    pub fn move_prev(&mut self) {
+        if let Some(leaf) = self.leaf() {
+            let i = self.index();
+            if i > 0 {
+                *self.cursor.indices.last_mut().unwrap() -= 1;
+                self.offset += -leaf[i - 1].to_offset();
+                return;
+            }
+
+            // Cross the leaf node boundary
+            if !self.rope.move_cursor_backward(&mut self.cursor) {
+                self.leaf = None;
+                self.offset = self.rope.offset_len();
+                return;
+            }
+        } else if self.rope.is_empty() {
+            return;
+        } else {
+            self.cursor = self.rope.last_cursor();
+        }
+        self.sync_leaf();
+        self.offset += -self.current().unwrap().to_offset();
+    }
+
+    /// Remove the current element and move the cursor to the next element.
+    ///
+    /// Returns the removed element, or `None` if the cursor is pointing the
+    /// ghost element.
+    pub fn remove(&mut self) -> Option<T> {
+        if self.is_ghost() {
+            None
+        } else {
+            // The offset of the next element is equal to that of the removed
+            // element
+            let x = self.rope.remove_at_tracked(&mut self.cursor);
+            self.sync_leaf();
+            Some(x)
+        }
+    }
+
+    /// Insert an element after the current element. If the cursor is pointing
+    /// the ghost element, the new element is inserted to the front of the
+    /// rope.
+    ///
+    /// The cursor keeps pointing the same element.
+    pub fn insert_after(&mut self, x: T) {
+        if self.is_ghost() {
+            self.offset += x.to_offset();
+            self.rope.push_front(x);
+        } else {
+            // Insert `x` before the next element, which might be located
+            // past the end of the current leaf
+            let mut at = self.cursor.clone();
+            *at.indices.last_mut().unwrap() += 1;
+            self.rope.insert_tracked(x, &mut at);
+
+            // Move back to the original element
+            self.rope.move_cursor_backward(&mut at);
+            self.cursor = at;
+            self.sync_leaf();
+        }
+    }
+}
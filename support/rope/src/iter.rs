@@ -111,6 +111,34 @@ where
         self.range(..).0
     }
 
+    /// Construct an iterator over the elements in the rope, each paired with
+    /// its offset value (the sum of all preceding elements' lengths).
+    ///
+    /// This is more efficient than calling `get_with_offset` for each element.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::Rope;
+    /// let rope: Rope<String> = [
+    ///     "Pony ", "ipsum ", "dolor ",
+    /// ].iter().map(|x|x.to_string()).collect();
+    ///
+    /// assert_eq!(
+    ///     rope.iter_with_offsets()
+    ///         .map(|(offset, elem)| (offset, elem.as_str()))
+    ///         .collect::<Vec<_>>(),
+    ///     vec![(0, "Pony "), (5, "ipsum "), (11, "dolor ")],
+    /// );
+    /// ```
+    pub fn iter_with_offsets<'a>(&'a self) -> impl Iterator<Item = (O, &'a T)> + 'a {
+        let mut offset = O::zero();
+        self.iter().map(move |elem| {
+            let next_offset = offset.clone() + elem.to_offset();
+            (std::mem::replace(&mut offset, next_offset), elem)
+        })
+    }
+
     /// Construct a double-ended iterator over a sub-range of elements in the
     /// rope.
     ///
@@ -169,6 +197,93 @@ where
         (iter.map(|(_, item)| item), range)
     }
 
+    /// Construct a [`RangeMut`] providing mutable access to a sub-range of
+    /// elements in the rope.
+    ///
+    /// The range is interpreted in the same way as [`Rope::range`]. Unlike
+    /// [`Rope::get_mut`], the elements' lengths
+    /// (`<T as ToOffset<O>>::to_offset`) may be changed. The offset values are
+    /// recomputed in one go when the returned `RangeMut` is dropped. Until
+    /// then, the elements in the range are detached from the rope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::{Rope, range_by_ord, by_ord, Edge::Floor, One::FirstAfter};
+    /// let mut rope: Rope<String> = [
+    ///     "Pony ", "ipsum ", "dolor ", "sit ", "amet ", "ms ",
+    /// ].iter().map(|x|x.to_string()).collect();
+    ///
+    /// for elem in rope.range_mut(range_by_ord(Floor(7)..Floor(17))).iter_mut() {
+    ///     elem.insert(0, '<');
+    ///     elem.push('>');
+    /// }
+    ///
+    /// assert_eq!(
+    ///     rope.iter().collect::<Vec<_>>(),
+    ///     ["Pony ", "<ipsum >", "<dolor >", "sit ", "amet ", "ms "],
+    /// );
+    ///
+    /// // The subsequent elements are moved accordingly
+    /// assert_eq!(rope.get_with_offset(FirstAfter(by_ord(21))).unwrap().1, 21);
+    /// ```
+    pub fn range_mut(&mut self, mut range: impl RopeRangeBounds<O>) -> RangeMut<'_, T, O> {
+        let end_ty = range.end_ty();
+
+        // See `remove_range`
+        let tail = match end_ty {
+            Some(_) => self.split_off_at_edge(end_ty, |probe| range.end_cmp(probe)),
+            None => Self::new(),
+        };
+        let mid = self.split_off_at_edge(range.start_ty(), |probe| range.start_cmp(probe));
+
+        RangeMut {
+            rope: self,
+            mid,
+            tail,
+        }
+    }
+
+    /// Move `cursor` to the next element. If `cursor` points the last element,
+    /// it's moved to the one-past-end element (in the same representation as
+    /// `end()`).
+    ///
+    /// `cursor` must be a valid `Cursor` pointing at an element.
+    pub(crate) fn move_cursor_forward(&self, cursor: &mut Cursor) {
+        let mut path = ArrayVec::new();
+        let mut indices = [EMPTY_INDEX; CURSOR_LEN];
+        self.cursor_to_iter_cursor(std::mem::take(cursor), &mut path, &mut indices);
+
+        iter_cursor_move_forward(&mut path, &mut indices);
+
+        *cursor = if path.is_empty() {
+            self.end()
+        } else {
+            iter_cursor_to_cursor(&path, &indices)
+        };
+    }
+
+    /// Move `cursor` to the previous element. Returns `false` (and leaves
+    /// `cursor` unchanged) if `cursor` points the first element.
+    ///
+    /// `cursor` must be a valid `Cursor` pointing at an element.
+    pub(crate) fn move_cursor_backward(&self, cursor: &mut Cursor) -> bool {
+        let mut path = ArrayVec::new();
+        let mut indices = [EMPTY_INDEX; CURSOR_LEN];
+        self.cursor_to_iter_cursor(std::mem::take(cursor), &mut path, &mut indices);
+
+        iter_cursor_move_backward(&mut path, &mut indices);
+
+        if path.is_empty() {
+            // `cursor` was pointing the first element
+            *cursor = self.begin();
+            false
+        } else {
+            *cursor = iter_cursor_to_cursor(&path, &indices);
+            true
+        }
+    }
+
     /// Convert `Cursor` for an internal representation used by `IterWithCursor`.
     fn cursor_to_iter_cursor<'a>(
         &'a self,
@@ -452,3 +567,88 @@ where
         Some((cursor, elem))
     }
 }
+
+/// Provides mutable access to a sub-range of elements in a [`Rope`].
+///
+/// This `struct` is created by [`Rope::range_mut`]. The offset values are
+/// recomputed when it's dropped. If it's leaked (e.g., by `std::mem::forget`),
+/// the elements in the range and the subsequent elements are lost from the
+/// rope.
+pub struct RangeMut<'a, T, O>
+where
+    T: ToOffset<O>,
+    O: Offset,
+{
+    rope: &'a mut Rope<T, O>,
+    /// The elements in the range.
+    mid: Rope<T, O>,
+    /// The elements following the range.
+    tail: Rope<T, O>,
+}
+
+impl<T, O> RangeMut<'_, T, O>
+where
+    T: ToOffset<O>,
+    O: Offset,
+{
+    /// Construct an iterator over the elements in the range.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> + '_ {
+        let mut stack = ArrayVec::new();
+        stack.push(std::slice::from_mut(&mut self.mid.root).iter_mut());
+        IterMut {
+            stack,
+            leaf: [].iter_mut(),
+        }
+    }
+}
+
+impl<T, O> Drop for RangeMut<'_, T, O>
+where
+    T: ToOffset<O>,
+    O: Offset,
+{
+    fn drop(&mut self) {
+        self.mid.recompute_offsets();
+
+        self.rope.append(std::mem::take(&mut self.mid));
+        self.rope.append(std::mem::take(&mut self.tail));
+    }
+}
+
+/// A mutable iterator over all elements in a tree.
+struct IterMut<'a, T, O> {
+    /// The remaining nodes at each level.
+    stack: ArrayVec<[std::slice::IterMut<'a, NodeRef<T, O>>; CURSOR_LEN]>,
+    /// The remaining elements in the current leaf.
+    leaf: std::slice::IterMut<'a, T>,
+}
+
+impl<'a, T, O> Iterator for IterMut<'a, T, O> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(elem) = self.leaf.next() {
+                return Some(elem);
+            }
+
+            // The current leaf is exhausted. Find the next one.
+            let node = loop {
+                let nodes = self.stack.last_mut()?;
+                if let Some(node) = nodes.next() {
+                    break node;
+                }
+                self.stack.pop();
+            };
+
+            match node {
+                NodeRef::Internal(inode) => {
+                    self.stack.push(inode.children.iter_mut());
+                }
+                NodeRef::Leaf(elements) => {
+                    self.leaf = elements.iter_mut();
+                }
+            }
+        }
+    }
+}
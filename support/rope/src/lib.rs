@@ -33,12 +33,15 @@
 use arrayvec::ArrayVec;
 use std::cmp::Ordering;
 
+mod cursor;
 mod iter;
 mod misc;
 mod offset;
 mod ops;
 mod sel;
-pub use self::{iter::*, offset::*, sel::*};
+#[cfg(feature = "serde")]
+mod serde;
+pub use self::{cursor::*, iter::*, offset::*, sel::*};
 
 /// Represents a rope.
 ///
@@ -52,6 +55,12 @@ pub struct Rope<T, O = isize> {
 /// The minimum number of child nodes of elements in a single node. The actual
 /// number varies between `ORDER` and `ORDER * 2`. The root node is exempt from
 /// the minimum count limitation.
+///
+/// This is not a type parameter of `Rope` because node capacities
+/// (`ORDER * 2`) are array lengths, and array lengths computed from generic
+/// parameters (`[T; ORDER * 2]`) are not supported by the compiler yet.
+/// `arrayvec` also only implements its `Array` trait for a fixed set of
+/// lengths.
 const ORDER: usize = 1 << ORDER_SHIFT;
 
 const ORDER_SHIFT: u32 = 3;
@@ -92,6 +101,17 @@ impl<T, O> NodeRef<T, O> {
             _ => false,
         }
     }
+
+    /// Get the height of the subtree. Leaf nodes have the height `0`.
+    fn height(&self) -> usize {
+        let mut height = 0;
+        let mut node = self;
+        while let NodeRef::Internal(inode) = node {
+            node = &inode.children[0];
+            height += 1;
+        }
+        height
+    }
 }
 
 /// A non-leaf node.
@@ -133,7 +153,7 @@ struct INode<T, O> {
 /// `std::mem::size_of::<usize>() * 8 / ORDER_SHIFT as usize + 1`.
 const CURSOR_LEN: usize = 16;
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 struct Cursor {
     /// Each element represents an index into `INode::children` or
     /// `NodeRef::Leaf` at the corresponding level.
@@ -374,6 +394,152 @@ where
             Err(x)
         }
     }
+
+    /// Insert elements before an existing element.
+    ///
+    /// This is equivalent to but more efficient than calling `insert_before`
+    /// for each element at advancing offsets. Given `k` new elements, this
+    /// method runs in O(k + log n) time.
+    ///
+    /// Returns `Ok(offset)` if the elements were inserted at `offset`;
+    /// `Err(iter)` if `one` does not correspond to any elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::{Rope, by_ord, One::FirstAfter};
+    /// let mut rope: Rope<String> = ["Pony", "ipsum"]
+    ///     .iter().map(|x|x.to_string()).collect();
+    ///
+    /// let result = rope.insert_many(
+    ///     [" ", "yeehaw", " "].iter().map(|x|x.to_string()),
+    ///     FirstAfter(by_ord(4)),
+    /// );
+    /// assert!(result.is_ok());
+    /// assert_eq!(
+    ///     rope.iter().collect::<Vec<_>>(),
+    ///     ["Pony", " ", "yeehaw", " ", "ipsum"],
+    /// );
+    /// ```
+    pub fn insert_many<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+        one: One<impl FnMut(&O) -> Ordering>,
+    ) -> Result<O, I> {
+        if let Some((cursor, offset)) = self.find_one(one) {
+            let second = self.split_off_at(cursor);
+            self.append(Self::from_iter_bulk(iter));
+            self.append(second);
+            Ok(offset)
+        } else {
+            Err(iter)
+        }
+    }
+
+    /// Split the rope into two at the specified element. The element and all
+    /// subsequent elements are moved to the returned rope.
+    ///
+    /// Returns an empty rope if `one` does not correspond to any elements.
+    ///
+    /// This method runs in O(log n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::{Rope, by_ord, One::FirstAfter};
+    /// let mut rope: Rope<String> = ["Pony", " ", "ipsum"]
+    ///     .iter().map(|x|x.to_string()).collect();
+    ///
+    /// let rest = rope.split_at(FirstAfter(by_ord(4)));
+    /// assert_eq!(rope.iter().collect::<Vec<_>>(), ["Pony"]);
+    /// assert_eq!(rest.iter().collect::<Vec<_>>(), [" ", "ipsum"]);
+    /// assert_eq!(rest.offset_len(), 6);
+    /// ```
+    pub fn split_at(&mut self, one: One<impl FnMut(&O) -> Ordering>) -> Self {
+        if let Some((cursor, _)) = self.find_one(one) {
+            self.split_off_at(cursor)
+        } else {
+            Self::new()
+        }
+    }
+
+    /// Remove a sub-range of elements and return them as a new rope.
+    ///
+    /// The range is interpreted in the same way as [`Rope::range`]. This
+    /// method runs in O(log n) time regardless of the number of the removed
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::{Rope, range_by_ord, Edge::{Floor, Ceil}};
+    /// let mut rope: Rope<String> = [
+    ///     "Pony ", "ipsum ", "dolor ", "sit ", "amet ", "ms ",
+    /// ].iter().map(|x|x.to_string()).collect();
+    ///
+    /// let removed = rope.remove_range(range_by_ord(Floor(7)..Floor(17)));
+    /// assert_eq!(removed.iter().collect::<Vec<_>>(), ["ipsum ", "dolor "]);
+    /// assert_eq!(rope.iter().collect::<Vec<_>>(), ["Pony ", "sit ", "amet ", "ms "]);
+    /// assert_eq!(rope.offset_len(), 17);
+    /// ```
+    pub fn remove_range(&mut self, mut range: impl RopeRangeBounds<O>) -> Self {
+        let start_ty = range.start_ty();
+        let end_ty = range.end_ty();
+
+        // Split off the part following the range first. The offsets of the
+        // remaining elements are not affected by this, so the start endpoint
+        // can be searched for in the same way.
+        let tail = match end_ty {
+            Some(_) => self.split_off_at_edge(end_ty, |probe| range.end_cmp(probe)),
+            None => Self::new(),
+        };
+        let removed = self.split_off_at_edge(start_ty, |probe| range.start_cmp(probe));
+
+        self.append(tail);
+        removed
+    }
+
+    /// Move all elements of `other` to the back of the rope.
+    ///
+    /// This method runs in O(log n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::Rope;
+    /// let mut rope: Rope<String> = ["Pony", " "]
+    ///     .iter().map(|x|x.to_string()).collect();
+    /// let rope2: Rope<String> = ["ipsum"]
+    ///     .iter().map(|x|x.to_string()).collect();
+    ///
+    /// rope.append(rope2);
+    /// assert_eq!(rope.iter().collect::<Vec<_>>(), ["Pony", " ", "ipsum"]);
+    /// assert_eq!(rope.offset_len(), 10);
+    /// ```
+    pub fn append(&mut self, other: Self) {
+        self.append_rope(other);
+    }
+
+    /// Retain only the elements specified by the predicate.
+    ///
+    /// The elements are visited in order. This method runs in O(n) time and
+    /// does not move the surviving elements to new nodes except for those
+    /// in undersized nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rope::Rope;
+    /// let mut rope: Rope<String> = ["Pony", " ", "ipsum", " ", "dolor"]
+    ///     .iter().map(|x|x.to_string()).collect();
+    ///
+    /// rope.retain(|x| x != " ");
+    /// assert_eq!(rope.iter().collect::<Vec<_>>(), ["Pony", "ipsum", "dolor"]);
+    /// assert_eq!(rope.offset_len(), 14);
+    /// ```
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool) {
+        self.retain_leaves(f);
+    }
 }
 
 #[cfg(test)]
@@ -485,6 +651,30 @@ mod tests {
         assert_eq!(elems, (0..200).rev().collect::<Vec<u32>>());
     }
 
+    #[test]
+    fn iter_with_offsets() {
+        let mut rope: Rope<String> = Rope::new();
+        for i in 0..200 {
+            rope.push_back(i.to_string());
+        }
+        rope.validate();
+
+        let mut off = 0;
+        let expected: Vec<(isize, u32)> = (0..200)
+            .map(|i| {
+                let o = off;
+                off += i.to_string().len() as isize;
+                (o, i)
+            })
+            .collect();
+
+        let elems: Vec<(isize, u32)> = rope
+            .iter_with_offsets()
+            .map(|(o, x)| (o, x.parse().unwrap()))
+            .collect();
+        assert_eq!(elems, expected);
+    }
+
     #[test]
     fn range() {
         const COUNT: usize = ORDER * 4 + 7;
@@ -575,6 +765,120 @@ mod tests {
         }
     }
 
+    #[test]
+    fn remove_range() {
+        const COUNT: usize = ORDER * 4 + 7;
+
+        let list: Vec<String> = (0..COUNT).map(|x| x.to_string()).collect();
+
+        let rope: Rope<String> = list.iter().cloned().collect();
+        rope.validate();
+
+        let len = rope.offset_len();
+
+        // Try every possible range in a certain range, using `range` as
+        // the reference
+        for start in -1..=len + 1 {
+            for end in -1..=len + 1 {
+                for ty in 0..4 {
+                    let start_edge = if (ty & 1) != 0 {
+                        Edge::Floor(start)
+                    } else {
+                        Edge::Ceil(start)
+                    };
+                    let end_edge = if (ty & 2) != 0 {
+                        Edge::Floor(end)
+                    } else {
+                        Edge::Ceil(end)
+                    };
+
+                    let range = range_by_key(|o: &isize| *o, start_edge..end_edge);
+
+                    let (iter, offset_range) = rope.range(range.clone());
+                    let expected_removed: Vec<&String> = iter.collect();
+
+                    let mut new_rope = rope.clone();
+                    let removed = new_rope.remove_range(range);
+
+                    new_rope.validate();
+                    removed.validate();
+
+                    assert_eq!(removed.iter().collect::<Vec<_>>(), expected_removed);
+                    assert_eq!(removed.offset_len(), offset_range.end - offset_range.start);
+
+                    // `expected_removed` is a contiguous sub-slice of `list`
+                    let i = expected_removed
+                        .first()
+                        .map_or(0, |&e| list.iter().position(|x| x == e).unwrap());
+                    let expected_remaining: Vec<&String> = list[..i]
+                        .iter()
+                        .chain(list[i + expected_removed.len()..].iter())
+                        .collect();
+
+                    assert_eq!(new_rope.iter().collect::<Vec<_>>(), expected_remaining);
+                    assert_eq!(new_rope.offset_len() + removed.offset_len(), len);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn range_mut() {
+        const COUNT: usize = ORDER * ORDER * 4 + 7;
+
+        let list: Vec<String> = (0..COUNT).map(|x| x.to_string()).collect();
+
+        let ranges = [
+            (0, 0),
+            (0, 1),
+            (0, COUNT),
+            (5, ORDER * 3),
+            (ORDER * 2 + 1, COUNT - 1),
+            (COUNT - 1, COUNT),
+            (COUNT, COUNT),
+        ];
+
+        for &(start, end) in ranges.iter() {
+            for &grow in [false, true].iter() {
+                let mut rope: Rope<_, IndexOffset<isize>> = list.iter().cloned().collect();
+
+                let range = range_by_key(
+                    |key: &IndexOffset<isize>| key.0,
+                    Edge::Floor(start as isize)..Edge::Floor(end as isize),
+                );
+
+                // Change the elements' lengths
+                let mut expected = list.clone();
+                for (i, elem) in rope.range_mut(range).iter_mut().enumerate() {
+                    assert_eq!(*elem, list[start + i]);
+                    let new_elem = if grow {
+                        format!("{}{}", elem, i)
+                    } else {
+                        String::new()
+                    };
+                    *elem = new_elem.clone();
+                    expected[start + i] = new_elem;
+                }
+
+                rope.validate();
+                assert_eq!(
+                    rope.iter().collect::<Vec<_>>(),
+                    expected.iter().collect::<Vec<_>>()
+                );
+
+                // Check the offsets
+                let mut offset = 0;
+                for (i, e) in expected.iter().enumerate() {
+                    let one = One::FirstAfter(by_key(|key: &IndexOffset<isize>| key.0, i as isize));
+                    let (elem, elem_offset) = rope.get_with_offset(one).unwrap();
+                    assert_eq!(elem, e);
+                    assert_eq!(elem_offset, IndexOffset(i as isize, offset));
+                    offset += e.len() as isize;
+                }
+            }
+        }
+    }
+
     #[test]
     fn get() {
         const COUNT: usize = ORDER * 4 + 7;
@@ -660,6 +964,321 @@ mod tests {
         }
     }
 
+    #[test]
+    fn split_at() {
+        const COUNT: usize = ORDER * ORDER * 4 + 7;
+
+        let list: Vec<String> = (0..COUNT).map(|x| x.to_string()).collect();
+        let rope: Rope<_, Index> = list.iter().cloned().collect();
+
+        for i in 0..=list.len() {
+            let mut first = rope.clone();
+
+            let one = One::FirstAfter(by_key(|key: &Index| key.0, i as isize));
+            let second = first.split_at(one);
+
+            first.validate();
+            second.validate();
+
+            assert_eq!(
+                first.iter().collect::<Vec<_>>(),
+                list[..i].iter().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                second.iter().collect::<Vec<_>>(),
+                list[i..].iter().collect::<Vec<_>>()
+            );
+            assert_eq!(first.offset_len().0, i as isize);
+            assert_eq!(second.offset_len().0, (list.len() - i) as isize);
+
+            // Join them back
+            first.append(second);
+            first.validate();
+            assert_eq!(
+                first.iter().collect::<Vec<_>>(),
+                list.iter().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn append() {
+        let counts = [
+            0,
+            1,
+            5,
+            ORDER * 2,
+            ORDER * 2 + 1,
+            100,
+            ORDER * ORDER * 4 + 7,
+        ];
+
+        for &count1 in counts.iter() {
+            for &count2 in counts.iter() {
+                let list: Vec<String> = (0..count1 + count2).map(|x| x.to_string()).collect();
+
+                let mut rope1: Rope<String> = list[..count1].iter().cloned().collect();
+                let rope2: Rope<String> = list[count1..].iter().cloned().collect();
+                let len = rope1.offset_len() + rope2.offset_len();
+
+                rope1.append(rope2);
+                rope1.validate();
+
+                assert_eq!(
+                    rope1.iter().collect::<Vec<_>>(),
+                    list.iter().collect::<Vec<_>>()
+                );
+                assert_eq!(rope1.offset_len(), len);
+            }
+        }
+    }
+
+    #[test]
+    fn from_iter() {
+        for &count in [0, 1, ORDER, ORDER * 2, ORDER * 2 + 1, ORDER * ORDER * 4 + 7].iter() {
+            let list: Vec<String> = (0..count).map(|x| x.to_string()).collect();
+            let rope: Rope<String> = list.iter().cloned().collect();
+            rope.validate();
+            assert_eq!(
+                rope.iter().collect::<Vec<_>>(),
+                list.iter().collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn extend() {
+        let counts = [0, 1, ORDER, ORDER * 2 + 1, ORDER * ORDER * 4 + 7];
+        for &count1 in counts.iter() {
+            for &count2 in counts.iter() {
+                let list1: Vec<String> = (0..count1).map(|x| x.to_string()).collect();
+                let list2: Vec<String> = (0..count2).map(|x| format!("new{}", x)).collect();
+                let len1: isize = list1.iter().map(|x| x.len() as isize).sum();
+                let len2: isize = list2.iter().map(|x| x.len() as isize).sum();
+
+                let mut rope: Rope<String> = list1.iter().cloned().collect();
+                assert_eq!(rope.offset_len(), len1);
+                rope.extend(list2.iter().cloned());
+                rope.validate();
+                assert_eq!(rope.offset_len(), len1 + len2);
+                assert_eq!(
+                    rope.iter().collect::<Vec<_>>(),
+                    list1.iter().chain(list2.iter()).collect::<Vec<_>>()
+                );
+
+                let mut rope: Rope<String, IndexOffset<isize>> = list1.iter().cloned().collect();
+                assert_eq!(rope.offset_len(), IndexOffset(count1 as isize, len1));
+                rope.extend(list2.iter().cloned());
+                rope.validate();
+                assert_eq!(
+                    rope.offset_len(),
+                    IndexOffset((count1 + count2) as isize, len1 + len2)
+                );
+                assert_eq!(
+                    rope.iter().collect::<Vec<_>>(),
+                    list1.iter().chain(list2.iter()).collect::<Vec<_>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn insert_many() {
+        const COUNT: usize = ORDER * 4 + 7;
+
+        let list: Vec<String> = (0..COUNT).map(|x| x.to_string()).collect();
+
+        for &new_count in [0, 1, ORDER * 2 + 1, ORDER * ORDER * 4].iter() {
+            let new_list: Vec<String> = (0..new_count).map(|x| format!("new{}", x)).collect();
+
+            for i in 0..list.len() {
+                let mut rope: Rope<_, Index> = list.iter().cloned().collect();
+
+                let one = One::FirstAfter(by_key(|key: &Index| key.0, i as isize));
+                let result = rope.insert_many(new_list.iter().cloned(), one);
+                assert_eq!(result.ok(), Some(IndexOffset(i as isize, NullOffset)));
+
+                rope.validate();
+
+                // Compare against repeated `insert_before`
+                let mut expected: Rope<_, Index> = list.iter().cloned().collect();
+                for (k, e) in new_list.iter().enumerate() {
+                    let one = One::FirstAfter(by_key(|key: &Index| key.0, (i + k) as isize));
+                    expected.insert_before(e.clone(), one).unwrap();
+                }
+
+                assert_eq!(
+                    rope.iter().collect::<Vec<_>>(),
+                    expected.iter().collect::<Vec<_>>()
+                );
+                assert_eq!(rope.offset_len(), expected.offset_len());
+            }
+        }
+
+        // Out of range
+        let mut rope: Rope<_, Index> = list.iter().cloned().collect();
+        let one = One::FirstAfter(by_key(|key: &Index| key.0, COUNT as isize));
+        assert!(rope.insert_many(vec!["x".to_owned()], one).is_err());
+    }
+
+    #[test]
+    fn cursor_traverse() {
+        const COUNT: usize = ORDER * ORDER * 4 + 7;
+
+        let list: Vec<String> = (0..COUNT).map(|x| x.to_string()).collect();
+        let mut rope: Rope<String> = list.iter().cloned().collect();
+
+        let expected: Vec<(isize, String)> = rope
+            .iter_with_offsets()
+            .map(|(o, x)| (o, x.clone()))
+            .collect();
+
+        // Forward
+        let mut cursor = rope.cursor_front();
+        let mut elems = Vec::new();
+        while let Some(x) = cursor.current() {
+            elems.push((cursor.offset(), x.clone()));
+            cursor.move_next();
+        }
+        assert_eq!(elems, expected);
+        assert_eq!(cursor.offset(), expected_len(&list));
+
+        // Backward (starting from the ghost element)
+        let mut elems = Vec::new();
+        cursor.move_prev();
+        while let Some(x) = cursor.current() {
+            elems.push((cursor.offset(), x.clone()));
+            cursor.move_prev();
+        }
+        elems.reverse();
+        assert_eq!(elems, expected);
+
+        // The ghost element is between the last and first elements
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&list[0]));
+
+        // `cursor_at`
+        for i in 0..COUNT {
+            let one = One::FirstAfter(by_key(|key: &isize| *key, expected[i].0));
+            let cursor = rope.cursor_at(one).unwrap();
+            assert_eq!(cursor.current(), Some(&list[i]));
+            assert_eq!(cursor.offset(), expected[i].0);
+        }
+
+        let mut rope: Rope<String> = Rope::new();
+        let mut cursor = rope.cursor_front();
+        assert_eq!(cursor.current(), None);
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        assert_eq!(cursor.remove(), None);
+    }
+
+    fn expected_len(list: &[String]) -> isize {
+        list.iter().map(|x| x.len() as isize).sum()
+    }
+
+    #[test]
+    fn cursor_edit() {
+        const COUNT: usize = ORDER * ORDER * 4 + 7;
+
+        for &(remove_interval, insert_interval) in [(2, 3), (3, 2), (1, 7), (5, 1)].iter() {
+            let mut list: Vec<String> = (0..COUNT).map(|x| x.to_string()).collect();
+            let mut rope: Rope<String> = list.iter().cloned().collect();
+
+            let mut cursor = rope.cursor_front();
+            let mut i = 0;
+            let mut k = 0;
+            while let Some(x) = cursor.current() {
+                assert_eq!(*x, list[i]);
+                assert_eq!(cursor.offset(), expected_len(&list[..i]));
+
+                if k % remove_interval == 0 {
+                    assert_eq!(cursor.remove(), Some(list.remove(i)));
+                } else if k % insert_interval == 0 {
+                    let new_elem = format!("new{}", k);
+                    cursor.insert_after(new_elem.clone());
+                    list.insert(i + 1, new_elem);
+
+                    // Skip the inserted element
+                    cursor.move_next();
+                    cursor.move_next();
+                    i += 2;
+                } else {
+                    cursor.move_next();
+                    i += 1;
+                }
+                k += 1;
+            }
+            assert_eq!(i, list.len());
+            assert_eq!(cursor.offset(), expected_len(&list));
+
+            // Insert and remove backward
+            cursor.insert_after("front".to_owned());
+            list.insert(0, "front".to_owned());
+            assert_eq!(cursor.offset(), expected_len(&list));
+
+            cursor.move_prev();
+            let mut i = list.len();
+            while i > 0 {
+                i -= 1;
+                assert_eq!(cursor.current(), Some(&list[i]));
+                if i % 3 == 0 {
+                    assert_eq!(cursor.remove(), Some(list.remove(i)));
+                }
+                cursor.move_prev();
+            }
+
+            rope.validate();
+            assert_eq!(
+                rope.iter().collect::<Vec<_>>(),
+                list.iter().collect::<Vec<_>>()
+            );
+            assert_eq!(rope.offset_len(), expected_len(&list));
+        }
+    }
+
+    #[test]
+    fn retain() {
+        for &count in [0, 1, ORDER * 4 + 7, ORDER * ORDER * 4 + 7].iter() {
+            let list: Vec<String> = (0..count).map(|x| x.to_string()).collect();
+
+            for &modulus in [1, 2, 3, ORDER * 2 + 1, count + 1].iter() {
+                let mut rope: Rope<String> = list.iter().cloned().collect();
+
+                let mut i = 0;
+                rope.retain(|_| {
+                    i += 1;
+                    (i - 1) % modulus == 0
+                });
+                assert_eq!(i, count);
+
+                let expected: Vec<&String> = list.iter().step_by(modulus).collect();
+
+                rope.validate();
+                assert_eq!(rope.iter().collect::<Vec<_>>(), expected);
+                assert_eq!(
+                    rope.offset_len(),
+                    expected.iter().map(|x| x.len() as isize).sum::<isize>()
+                );
+            }
+        }
+
+        // Remove every other element
+        let list: Vec<String> = (0..ORDER * 4 + 7).map(|x| x.to_string()).collect();
+        let mut rope: Rope<String> = list.iter().cloned().collect();
+        rope.retain(|x| x.parse::<usize>().unwrap() % 2 == 1);
+        rope.validate();
+
+        let expected: Vec<&String> = list.iter().skip(1).step_by(2).collect();
+        assert_eq!(rope.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(
+            rope.offset_len(),
+            expected.iter().map(|x| x.len() as isize).sum::<isize>()
+        );
+    }
+
     #[test]
     fn update_with() {
         const COUNT: usize = ORDER * 4 + 7;
@@ -78,28 +78,32 @@ where
     } // fn validate_sub
 }
 
+/// Constructs a balanced tree directly from the elements in O(n) time.
+///
+/// `offset_len()` of the constructed rope is equal to the sum of the
+/// elements' lengths.
 impl<T, O> FromIterator<T> for Rope<T, O>
 where
     T: ToOffset<O>,
     O: Offset,
 {
     fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
-        let mut this = Self::new();
-        this.extend(iter);
-        this
+        Self::from_iter_bulk(iter)
     }
 }
 
+/// Appends the elements to the back of the rope. Instead of inserting the
+/// elements one by one, this builds a balanced tree from the elements first and
+/// then joins it with the rope.
+///
+/// `offset_len()` increases by the sum of the new elements' lengths.
 impl<T, O> Extend<T> for Rope<T, O>
 where
     T: ToOffset<O>,
     O: Offset,
 {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
-        // We could do bulk load, but for now take a naïve approach
-        for e in iter {
-            self.push_back(e);
-        }
+        self.append(Self::from_iter_bulk(iter));
     }
 }
 
@@ -2,7 +2,7 @@
 use arrayvec::ArrayVec;
 use std::cmp::Ordering;
 
-use super::{Cursor, INode, NodeRef, Offset, One, Rope, ToOffset, ORDER};
+use super::{Cursor, EdgeType, INode, NodeRef, Offset, One, Rope, ToOffset, ORDER};
 
 impl<T, O> Rope<T, O>
 where
@@ -147,16 +147,23 @@ where
             let mut i = 0;
             match node {
                 NodeRef::Internal(inode) => {
-                    let mut next_offset = offset.clone();
-                    while i < inode.offsets.len() {
-                        let next_child_offset = offset.clone() + inode.offsets[i].clone();
-                        if f(&next_child_offset) {
-                            break;
+                    // Find the first child whose right edge `f` evaluates to
+                    // `true` for. The last child's right edge is known to
+                    // meet the condition, so it's not included in `offsets`.
+                    let result = inode.offsets.binary_search_by(|child_offset| {
+                        if f(&(offset.clone() + child_offset.clone())) {
+                            Ordering::Greater
+                        } else {
+                            Ordering::Less
                         }
-                        next_offset = next_child_offset;
-                        i += 1;
+                    });
+                    i = match result {
+                        Ok(_) => unreachable!(),
+                        Err(i) => i,
+                    };
+                    if i > 0 {
+                        offset += inode.offsets[i - 1].clone();
                     }
-                    offset = next_offset;
                     cursor.indices.push(i as _);
                     node = &inode.children[i];
                 }
@@ -199,6 +206,26 @@ where
         }
     }
 
+    /// Get the reference to the leaf node containing the element specified
+    /// by `at`.
+    ///
+    /// `at` must be a valid `Cursor` pointing at an element or the
+    /// one-past-end element of a leaf node.
+    pub(crate) fn leaf_at(&self, at: &Cursor) -> &ArrayVec<[T; ORDER * 2]> {
+        let mut cur = &self.root;
+        for &i in at.indices.iter() {
+            match cur {
+                NodeRef::Internal(inode) => {
+                    cur = &inode.children[i as usize];
+                }
+                NodeRef::Leaf(elements) => {
+                    return elements;
+                }
+            }
+        }
+        unreachable!()
+    }
+
     /// Get the mutable reference to the element specified by `at`.
     ///
     /// `at` must be a valid `Cursor` pointing at an element.
@@ -221,51 +248,70 @@ where
     }
 
     /// Insert `x` before the element specified by `at`.
-    pub(crate) fn insert(&mut self, x: T, at: Cursor) {
+    pub(crate) fn insert(&mut self, x: T, mut at: Cursor) {
+        self.insert_tracked(x, &mut at);
+    }
+
+    /// Insert `x` before the element specified by `at`. `at` is updated to
+    /// point the inserted element.
+    pub(crate) fn insert_tracked(&mut self, x: T, at: &mut Cursor) {
         let len = x.to_offset();
 
-        if let Some((new_sibling, new_len)) = Self::insert_sub(&at.indices, &mut self.root, x, &len)
+        if let Some((new_sibling, new_len, moved)) =
+            Self::insert_sub(&mut at.indices, &mut self.root, x, &len)
         {
-            // Remove the current root, filling the place with a brand new
-            // internal root node.
-            let old_root = std::mem::replace(
-                &mut self.root,
-                NodeRef::Internal(Box::new(INode {
-                    children: ArrayVec::new(),
-                    offsets: ArrayVec::new(),
-                })),
-            );
+            Self::grow_root(&mut self.root, new_sibling, new_len);
+            at.indices.insert(0, moved as u8);
+        }
 
-            let new_inode = match &mut self.root {
-                NodeRef::Internal(inode) => inode,
-                _ => unreachable!(),
-            };
+        self.len += len;
+    }
 
-            // Add the former-root node and the new sibling node to it.
-            new_inode.children.push(old_root);
-            new_inode.children.push(new_sibling);
+    /// Replace `root` with a new internal root node having two children: the
+    /// former root node and `new_sibling`. `root_len` indicates the length of
+    /// the former root node.
+    fn grow_root(root: &mut NodeRef<T, O>, new_sibling: NodeRef<T, O>, root_len: O) {
+        // Remove the current root, filling the place with a brand new
+        // internal root node.
+        let old_root = std::mem::replace(
+            root,
+            NodeRef::Internal(Box::new(INode {
+                children: ArrayVec::new(),
+                offsets: ArrayVec::new(),
+            })),
+        );
 
-            new_inode.offsets.push(new_len);
-        }
+        let new_inode = match root {
+            NodeRef::Internal(inode) => inode,
+            _ => unreachable!(),
+        };
 
-        self.len += len;
+        // Add the former-root node and the new sibling node to it.
+        new_inode.children.push(old_root);
+        new_inode.children.push(new_sibling);
+
+        new_inode.offsets.push(root_len);
     }
 
     /// The internal method for `insert`.
     ///
-    /// Returns `Some((new_node, len))` if it needs a new node that is a sibling
-    /// of `node`. In this case, `len` indicates the new length of `node`
-    /// `len` may or may not include `x_len` depending on which node `x` was
-    /// inserted to.
+    /// Returns `Some((new_node, len, moved))` if it needs a new node that is a
+    /// sibling of `node`. In this case, `len` indicates the new length of
+    /// `node` `len` may or may not include `x_len` depending on which node `x`
+    /// was inserted to. `moved` indicates whether `x` was inserted to
+    /// `new_node`.
+    ///
+    /// `at` is updated to point `x` in `node` or `new_node` (depending on
+    /// `moved`).
     ///
     /// The algorithm is not recursive, but we need recursion to make borrowck
     /// happy.
     fn insert_sub(
-        at: &[u8],
+        at: &mut [u8],
         node: &mut NodeRef<T, O>,
         x: T,
         x_len: &O,
-    ) -> Option<(NodeRef<T, O>, O)> {
+    ) -> Option<(NodeRef<T, O>, O, bool)> {
         if at.is_empty() {
             unreachable!();
         }
@@ -289,11 +335,13 @@ where
                 // Prefer adding the new element to the newly created leaf
                 // so that the number of bytes copied is minimized. Hence
                 // the equality sign in this branch.
-                if i >= mid {
+                let moved = i >= mid;
+                if moved {
                     // The new element belongs to the newly created leaf
                     new_leaf.extend((&mut second_half).take(i - mid));
                     new_leaf.push(x);
                     new_leaf.extend(second_half);
+                    at[0] = (i - mid) as u8;
                 } else {
                     // The new element belongs to the current leaf
                     new_leaf.extend(second_half);
@@ -305,7 +353,7 @@ where
                     .map(ToOffset::to_offset)
                     .fold(O::zero(), |x, y| x + y);
 
-                Some((NodeRef::Leaf(new_leaf), first_half_len))
+                Some((NodeRef::Leaf(new_leaf), first_half_len, moved))
             } else {
                 // The leaf is full, just insert it there
                 elements.insert(i, x);
@@ -322,88 +370,549 @@ where
                 *offset += x_len.clone();
             }
 
-            if let Some((new_sibling, new_len)) =
-                Self::insert_sub(&at[1..], &mut inode.children[i], x, x_len)
-            {
-                // The child node has been split into two nodes.
-                if inode.children.len() == inode.children.capacity() {
-                    // Full; split the current internal node into two
-                    let mid = inode.children.capacity() / 2;
-
-                    let mut new_inode = Box::new(INode {
-                        children: ArrayVec::new(),
-                        offsets: ArrayVec::new(),
-                    });
-
-                    let mut second_half_children = inode.children.drain(mid..);
-                    let mut second_half_offsets = inode.offsets.drain(mid - 1..);
-
-                    let first_half_len = second_half_offsets.next().unwrap();
-
-                    // Offsets are relative to the split point, so they should be
-                    // adjusted when nodes are split
-                    let mut second_half_offsets =
-                        second_half_offsets.map(|i| i + -first_half_len.clone());
-
-                    // This condition was chosen so that I only have to consider
-                    // two cases, i.e., to exclude the case where
-                    // `inode.children[i]` and `new_sibling` belong to different
-                    // halves.
-                    if i >= mid {
-                        // `inode.children[i]` and `new_sibling` belongs to
-                        // the second half
-                        new_inode
-                            .children
-                            .extend((&mut second_half_children).take(i + 1 - mid));
-                        new_inode.children.push(new_sibling);
-                        new_inode.children.extend(second_half_children);
-
-                        new_inode
-                            .offsets
-                            .extend((&mut second_half_offsets).take(i - mid));
-                        if let Some(prev_len) = new_inode.offsets.last().cloned() {
-                            new_inode.offsets.push(prev_len + new_len);
-                        } else {
-                            new_inode.offsets.push(new_len);
+            Self::insert_sub(&mut at[1..], &mut inode.children[i], x, x_len).and_then(
+                |(new_sibling, new_len, moved)| {
+                    // The child node has been split into two nodes.
+                    at[0] += moved as u8;
+
+                    Self::insert_child(inode, i, new_sibling, new_len).map(|(node, len)| {
+                        // `insert_child` puts `inode.children[i]` and
+                        // `new_sibling` in the same half
+                        let moved = i >= ORDER;
+                        if moved {
+                            at[0] -= ORDER as u8;
                         }
-                        new_inode.offsets.extend(second_half_offsets);
-                    } else {
-                        // `inode.children[i]` and `new_sibling` belongs to
-                        // the first half
-                        new_inode.children.extend(second_half_children);
-                        inode.children.insert(i + 1, new_sibling);
-
-                        new_inode.offsets.extend(second_half_offsets);
-                        if i == 0 {
-                            inode.offsets.insert(i, new_len);
-                        } else {
-                            inode
-                                .offsets
-                                .insert(i, inode.offsets[i - 1].clone() + new_len);
-                        }
-                    }
+                        (node, len, moved)
+                    })
+                },
+            )
+        }
+    } // fn insert_sub
 
-                    debug_assert_eq!(inode.offsets.len(), inode.children.len() - 1);
-                    debug_assert_eq!(new_inode.offsets.len(), new_inode.children.len() - 1);
+    /// Insert `new_sibling` after `inode.children[i]`. `new_len` indicates the
+    /// new length of `inode.children[i]`.
+    ///
+    /// The offsets of `inode.children[i + 1..]` must be already adjusted to
+    /// reflect the insertion.
+    ///
+    /// Returns `Some((new_node, len))` if `inode` was split into two nodes.
+    /// In this case, `new_node` is a new sibling of `inode`, and `len`
+    /// indicates the new length of `inode`.
+    fn insert_child(
+        inode: &mut INode<T, O>,
+        i: usize,
+        new_sibling: NodeRef<T, O>,
+        new_len: O,
+    ) -> Option<(NodeRef<T, O>, O)> {
+        if inode.children.len() == inode.children.capacity() {
+            // Full; split the current internal node into two
+            let mid = inode.children.capacity() / 2;
+
+            let mut new_inode = Box::new(INode {
+                children: ArrayVec::new(),
+                offsets: ArrayVec::new(),
+            });
+
+            let mut second_half_children = inode.children.drain(mid..);
+            let mut second_half_offsets = inode.offsets.drain(mid - 1..);
+
+            let first_half_len = second_half_offsets.next().unwrap();
+
+            // Offsets are relative to the split point, so they should be
+            // adjusted when nodes are split
+            let mut second_half_offsets = second_half_offsets.map(|i| i + -first_half_len.clone());
+
+            // This condition was chosen so that I only have to consider
+            // two cases, i.e., to exclude the case where
+            // `inode.children[i]` and `new_sibling` belong to different
+            // halves.
+            if i >= mid {
+                // `inode.children[i]` and `new_sibling` belongs to
+                // the second half
+                new_inode
+                    .children
+                    .extend((&mut second_half_children).take(i + 1 - mid));
+                new_inode.children.push(new_sibling);
+                new_inode.children.extend(second_half_children);
+
+                new_inode
+                    .offsets
+                    .extend((&mut second_half_offsets).take(i - mid));
+                if let Some(prev_len) = new_inode.offsets.last().cloned() {
+                    new_inode.offsets.push(prev_len + new_len);
+                } else {
+                    new_inode.offsets.push(new_len);
+                }
+                new_inode.offsets.extend(second_half_offsets);
+            } else {
+                // `inode.children[i]` and `new_sibling` belongs to
+                // the first half
+                new_inode.children.extend(second_half_children);
+                inode.children.insert(i + 1, new_sibling);
+
+                new_inode.offsets.extend(second_half_offsets);
+                if i == 0 {
+                    inode.offsets.insert(i, new_len);
+                } else {
+                    inode
+                        .offsets
+                        .insert(i, inode.offsets[i - 1].clone() + new_len);
+                }
+            }
+
+            debug_assert_eq!(inode.offsets.len(), inode.children.len() - 1);
+            debug_assert_eq!(new_inode.offsets.len(), new_inode.children.len() - 1);
 
-                    Some((NodeRef::Internal(new_inode), first_half_len))
+            Some((NodeRef::Internal(new_inode), first_half_len))
+        } else {
+            // Not full
+            inode.children.insert(i + 1, new_sibling);
+            if i == 0 {
+                inode.offsets.insert(i, new_len);
+            } else {
+                inode
+                    .offsets
+                    .insert(i, inode.offsets[i - 1].clone() + new_len);
+            }
+            None
+        }
+    }
+
+    /// Move the elements starting at the one specified by `at` to a new rope.
+    ///
+    /// `at` must be a valid `Cursor` pointing at an element.
+    pub(crate) fn split_off_at(&mut self, at: Cursor) -> Self {
+        let root = std::mem::replace(&mut self.root, NodeRef::Leaf(Box::new(ArrayVec::new())));
+        let len = std::mem::replace(&mut self.len, O::zero());
+
+        let (left, right) = Self::split_sub(&at.indices, root, len);
+        *self = left;
+        right
+    }
+
+    /// Move the elements after the specified range endpoint to a new rope.
+    /// `None` represents an unbounded endpoint, which is located at the
+    /// front of the rope.
+    ///
+    /// See [`Rope::range`] for how an endpoint is interpreted.
+    pub(crate) fn split_off_at_edge(
+        &mut self,
+        ty: Option<EdgeType>,
+        f: impl FnMut(&O) -> Ordering,
+    ) -> Self {
+        let ty = if let Some(ty) = ty {
+            ty
+        } else {
+            return std::mem::take(self);
+        };
+
+        let search_result = match ty {
+            EdgeType::Floor => self.inclusive_lower_bound_by(f),
+            EdgeType::Ceil => self.inclusive_upper_bound_by(f),
+        };
+
+        if let Some((c, _)) = search_result {
+            if c == self.end() {
+                Self::new()
+            } else {
+                let mut second = self.split_off_at(c);
+                if ty == EdgeType::Ceil {
+                    // Exclude the element overlapping with the endpoint
+                    self.push_back(second.pop_front().unwrap());
+                }
+                second
+            }
+        } else {
+            std::mem::take(self)
+        }
+    }
+
+    /// The internal method for `split_off_at`. `node_len` indicates the
+    /// length of `node`.
+    ///
+    /// Splits `node` into two ropes, the second one of which starts with the
+    /// element specified by `at`. The pieces of `node` on each side of the
+    /// path are gathered by `append`. The height difference between the pieces
+    /// being joined is bounded by the level, so the total cost is O(log n).
+    fn split_sub(at: &[u8], node: NodeRef<T, O>, node_len: O) -> (Self, Self) {
+        let i = at[0] as usize;
+
+        match node {
+            NodeRef::Leaf(mut elements) => {
+                debug_assert_eq!(at.len(), 1);
+
+                let second_half: Box<ArrayVec<[T; ORDER * 2]>> =
+                    Box::new(elements.drain(i..).collect());
+                let second_half_len = second_half
+                    .iter()
+                    .map(ToOffset::to_offset)
+                    .fold(O::zero(), |x, y| x + y);
+
+                (
+                    Self {
+                        root: NodeRef::Leaf(elements),
+                        len: node_len + -second_half_len.clone(),
+                    },
+                    Self {
+                        root: NodeRef::Leaf(second_half),
+                        len: second_half_len,
+                    },
+                )
+            }
+            NodeRef::Internal(inode) => {
+                let INode {
+                    mut children,
+                    mut offsets,
+                } = *inode;
+
+                // The range of `children[i]`
+                let child_start = if i == 0 {
+                    O::zero()
                 } else {
-                    // Not full
-                    inode.children.insert(i + 1, new_sibling);
-                    if i == 0 {
-                        inode.offsets.insert(i, new_len);
-                    } else {
-                        inode
-                            .offsets
-                            .insert(i, inode.offsets[i - 1].clone() + new_len);
-                    }
-                    None
+                    offsets[i - 1].clone()
+                };
+                let child_end = if i < offsets.len() {
+                    offsets[i].clone()
+                } else {
+                    node_len.clone()
+                };
+
+                // Detach `children[i + 1..]`. Their offsets must be made
+                // relative to `children[i + 1]`.
+                let second_half_children: ArrayVec<_> = children.drain(i + 1..).collect();
+                let second_half_offsets: ArrayVec<_> = offsets
+                    .drain(i.min(offsets.len())..)
+                    .skip(1)
+                    .map(|x| x + -child_end.clone())
+                    .collect();
+                let second_half_len = node_len + -child_end.clone();
+
+                // Detach `children[i]`, leaving `children[..i]`
+                let child = children.pop().unwrap();
+                offsets.truncate(i.saturating_sub(1));
+
+                let (child_first, child_second) =
+                    Self::split_sub(&at[1..], child, child_end + -child_start.clone());
+
+                let mut first = Self::from_children(children, offsets, child_start);
+                first.append(child_first);
+
+                let mut second = child_second;
+                second.append(Self::from_children(
+                    second_half_children,
+                    second_half_offsets,
+                    second_half_len,
+                ));
+
+                (first, second)
+            }
+        }
+    }
+
+    /// Construct a rope from a list of sibling nodes. `len` indicates the
+    /// total length of the nodes.
+    fn from_children(
+        mut children: ArrayVec<[NodeRef<T, O>; ORDER * 2]>,
+        offsets: ArrayVec<[O; ORDER * 2 - 1]>,
+        len: O,
+    ) -> Self {
+        debug_assert_eq!(offsets.len(), children.len().saturating_sub(1));
+
+        let root = match children.len() {
+            0 => NodeRef::Leaf(Box::new(ArrayVec::new())),
+            // The root `INode` must have at least two children.
+            1 => children.pop().unwrap(),
+            _ => NodeRef::Internal(Box::new(INode { children, offsets })),
+        };
+
+        Self { root, len }
+    }
+
+    /// Move all elements of `other` to the back of `self`.
+    pub(crate) fn append_rope(&mut self, mut other: Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            *self = other;
+            return;
+        }
+
+        let self_height = self.root.height();
+        let other_height = other.root.height();
+        let len = self.len.clone() + other.len.clone();
+
+        match self_height.cmp(&other_height) {
+            Ordering::Equal => {
+                if let Some(new_len) =
+                    Self::balance_pair(&mut self.root, &mut other.root, self.len.clone())
+                {
+                    Self::grow_root(&mut self.root, other.root, new_len);
+                }
+            }
+            Ordering::Greater => {
+                if let Some((new_sibling, new_len)) = Self::append_sub(
+                    &mut self.root,
+                    self.len.clone(),
+                    self_height - other_height,
+                    other.root,
+                ) {
+                    Self::grow_root(&mut self.root, new_sibling, new_len);
+                }
+            }
+            Ordering::Less => {
+                let first = std::mem::replace(self, other);
+                if let Some((new_sibling, new_len)) = Self::prepend_sub(
+                    &mut self.root,
+                    other_height - self_height,
+                    first.root,
+                    first.len,
+                ) {
+                    Self::grow_root(&mut self.root, new_sibling, new_len);
                 }
+            }
+        }
+
+        self.len = len;
+    }
+
+    /// The internal method for `append_rope`.
+    ///
+    /// Attaches `other` to the back of `node`, which is `depth` levels higher
+    /// than `other`. `node_len` indicates the length of `node`.
+    ///
+    /// Returns `Some((new_node, len))` if it needs a new node that is a sibling
+    /// of `node`. In this case, `len` indicates the new length of `node`.
+    fn append_sub(
+        node: &mut NodeRef<T, O>,
+        node_len: O,
+        depth: usize,
+        mut other: NodeRef<T, O>,
+    ) -> Option<(NodeRef<T, O>, O)> {
+        let inode = match node {
+            NodeRef::Internal(inode) => inode,
+            NodeRef::Leaf(_) => unreachable!(),
+        };
+
+        // `INode` has at least two children
+        let i = inode.children.len() - 1;
+        let last_len = node_len + -inode.offsets[i - 1].clone();
+
+        // The offsets of the existing children are not affected because
+        // we are only adding things after them.
+        if depth == 1 {
+            // `other` is a root node, which might not meet the minimum child
+            // count of non-root nodes. Move some children from the new
+            // sibling to fix that.
+            let new_len = Self::balance_pair(&mut inode.children[i], &mut other, last_len)?;
+            Self::insert_child(inode, i, other, new_len)
+        } else {
+            Self::append_sub(&mut inode.children[i], last_len, depth - 1, other).and_then(
+                |(new_sibling, new_len)| Self::insert_child(inode, i, new_sibling, new_len),
+            )
+        }
+    }
+
+    /// The internal method for `append_rope`.
+    ///
+    /// Attaches `other` to the front of `node`, which is `depth` levels higher
+    /// than `other`. `other_len` indicates the length of `other`.
+    ///
+    /// Returns `Some((new_node, len))` if it needs a new node that is a sibling
+    /// of `node`. In this case, `len` indicates the new length of `node`.
+    fn prepend_sub(
+        node: &mut NodeRef<T, O>,
+        depth: usize,
+        mut other: NodeRef<T, O>,
+        other_len: O,
+    ) -> Option<(NodeRef<T, O>, O)> {
+        let inode = match node {
+            NodeRef::Internal(inode) => inode,
+            NodeRef::Leaf(_) => unreachable!(),
+        };
+
+        // Everything is moved by `other_len`
+        for offset in inode.offsets.iter_mut() {
+            *offset += other_len.clone();
+        }
+
+        if depth == 1 {
+            // See the comment in `append_sub`
+            if let Some(new_len) = Self::balance_pair(&mut other, &mut inode.children[0], other_len)
+            {
+                // Insert `other` before `children[0]`. `insert_child` can only
+                // insert a node after an existing one, so swap them first.
+                let first = std::mem::replace(&mut inode.children[0], other);
+                Self::insert_child(inode, 0, first, new_len)
             } else {
+                // `children[0]` was merged into `other`
+                inode.children[0] = other;
                 None
             }
+        } else {
+            Self::prepend_sub(&mut inode.children[0], depth - 1, other, other_len).and_then(
+                |(new_sibling, new_len)| Self::insert_child(inode, 0, new_sibling, new_len),
+            )
         }
-    } // fn insert_sub
+    }
+
+    /// Construct a rope from the elements of an iterator by building the tree
+    /// bottom-up.
+    ///
+    /// The nodes are filled up to their capacity, so this runs in O(n) time
+    /// and produces a more compact tree than repeated insertion.
+    pub(crate) fn from_iter_bulk(iter: impl IntoIterator<Item = T>) -> Self {
+        let mut iter = iter.into_iter().peekable();
+
+        // Build the leaves
+        let mut nodes = Vec::new();
+        while iter.peek().is_some() {
+            let elements: ArrayVec<[T; ORDER * 2]> = (&mut iter).take(ORDER * 2).collect();
+            let len = elements
+                .iter()
+                .map(ToOffset::to_offset)
+                .fold(O::zero(), |x, y| x + y);
+            nodes.push((NodeRef::Leaf(Box::new(elements)), len));
+        }
+        Self::balance_last_pair(&mut nodes);
+
+        Self::from_leaves(nodes)
+    }
+
+    /// Construct a rope from a list of leaf nodes and their lengths, building
+    /// the internal nodes bottom-up. All leaves must meet the minimum element
+    /// count of non-root nodes unless there is only one leaf.
+    fn from_leaves(mut nodes: Vec<(NodeRef<T, O>, O)>) -> Self {
+        // Build the internal nodes, one level at a time
+        while nodes.len() > 1 {
+            let mut parents = Vec::with_capacity(nodes.len() / ORDER);
+            let mut it = nodes.into_iter().peekable();
+
+            while it.peek().is_some() {
+                let mut inode = Box::new(INode {
+                    children: ArrayVec::new(),
+                    offsets: ArrayVec::new(),
+                });
+                let mut len = O::zero();
+
+                for (node, node_len) in (&mut it).take(ORDER * 2) {
+                    if !inode.children.is_empty() {
+                        inode.offsets.push(len.clone());
+                    }
+                    inode.children.push(node);
+                    len += node_len;
+                }
+
+                parents.push((NodeRef::Internal(inode), len));
+            }
+
+            Self::balance_last_pair(&mut parents);
+            nodes = parents;
+        }
+
+        if let Some((root, len)) = nodes.pop() {
+            Self { root, len }
+        } else {
+            Self::new()
+        }
+    }
+
+    /// The internal method for `from_iter_bulk` and `retain_leaves`.
+    ///
+    /// Given a list of sibling nodes and their lengths, where all nodes but
+    /// the last two meet the minimum child count of non-root nodes, fix the
+    /// last two nodes so that at most the last one is under the minimum child
+    /// count. A list having only one node is left intact because the node
+    /// might be the root.
+    fn balance_last_pair(nodes: &mut Vec<(NodeRef<T, O>, O)>) {
+        let n = nodes.len();
+        if n < 2 || (nodes[n - 1].0.len() >= ORDER && nodes[n - 2].0.len() >= ORDER) {
+            return;
+        }
+
+        let (mut right, right_len) = nodes.pop().unwrap();
+        let (left, left_len) = nodes.last_mut().unwrap();
+        let len = left_len.clone() + right_len;
+
+        if let Some(new_left_len) = Self::balance_pair(left, &mut right, left_len.clone()) {
+            *left_len = new_left_len.clone();
+            nodes.push((right, len + -new_left_len));
+        } else {
+            *left_len = len;
+        }
+    }
+
+    /// Remove the elements for which `f` returns `false`.
+    ///
+    /// The surviving elements stay in their original leaves (which are merged
+    /// as needed), and the internal nodes are rebuilt on top of them.
+    pub(crate) fn retain_leaves(&mut self, mut f: impl FnMut(&T) -> bool) {
+        let root = std::mem::take(self).root;
+
+        let mut leaves = Vec::new();
+        Self::retain_sub(root, &mut f, &mut leaves);
+
+        *self = Self::from_leaves(leaves);
+    }
+
+    /// The internal method for `retain_leaves`. Filters the elements in
+    /// `node` and pushes the non-empty leaves to `leaves`.
+    fn retain_sub(
+        node: NodeRef<T, O>,
+        f: &mut impl FnMut(&T) -> bool,
+        leaves: &mut Vec<(NodeRef<T, O>, O)>,
+    ) {
+        match node {
+            NodeRef::Internal(inode) => {
+                for child in inode.children {
+                    Self::retain_sub(child, f, leaves);
+                }
+            }
+            NodeRef::Leaf(mut elements) => {
+                elements.retain(|e| f(e));
+                if elements.is_empty() {
+                    return;
+                }
+
+                let len = elements
+                    .iter()
+                    .map(ToOffset::to_offset)
+                    .fold(O::zero(), |x, y| x + y);
+                leaves.push((NodeRef::Leaf(elements), len));
+
+                // Merge undersized leaves
+                Self::balance_last_pair(leaves);
+            }
+        }
+    }
+
+    /// Redistribute the children of `left` and `right` so that both of them
+    /// meet the minimum child count of non-root nodes. They are merged if
+    /// they can fit in a single node.
+    ///
+    /// `left` and `right` must be of the same type, i.e. they must be one of
+    /// `Leaf` and `Internal`.
+    ///
+    /// Returns the new length of `left`, or `None` if all children of `right`
+    /// were moved to `left`.
+    fn balance_pair(left: &mut NodeRef<T, O>, right: &mut NodeRef<T, O>, left_len: O) -> Option<O> {
+        if left.len() + right.len() <= ORDER * 2 {
+            Self::rotate_left_full(left, right, left_len);
+            return None;
+        }
+
+        let mut left_len = left_len;
+        while left.len() < ORDER {
+            let len = Self::rotate_left(left, right, left_len.clone());
+            left_len += len;
+        }
+        while right.len() < ORDER {
+            let len = Self::rotate_right(left, right, left_len.clone());
+            left_len += -len;
+        }
+
+        Some(left_len)
+    }
 
     /// Update the element specified by `at` using the function `f`.
     pub(crate) fn update_at_with<R>(&mut self, at: Cursor, f: impl FnOnce(&mut T) -> R) -> R {
@@ -458,9 +967,43 @@ where
         result
     }
 
+    /// Recalculate the offset values of all nodes. This is used after the
+    /// elements' lengths are changed without updating the tree.
+    pub(crate) fn recompute_offsets(&mut self) {
+        self.len = Self::recompute_offsets_sub(&mut self.root);
+    }
+
+    /// The internal method for `recompute_offsets`. Returns the length of
+    /// `node`.
+    fn recompute_offsets_sub(node: &mut NodeRef<T, O>) -> O {
+        match node {
+            NodeRef::Internal(inode) => {
+                let mut offset = O::zero();
+                for (i, child) in inode.children.iter_mut().enumerate() {
+                    offset += Self::recompute_offsets_sub(child);
+                    if let Some(o) = inode.offsets.get_mut(i) {
+                        *o = offset.clone();
+                    }
+                }
+                offset
+            }
+            NodeRef::Leaf(elements) => elements
+                .iter()
+                .map(ToOffset::to_offset)
+                .fold(O::zero(), |x, y| x + y),
+        }
+    }
+
     /// Remove the element specified by `at`.
-    pub(crate) fn remove_at(&mut self, at: Cursor) -> T {
-        let (elem, offset, underflow) = Self::remove_sub(&at.indices, &mut self.root);
+    pub(crate) fn remove_at(&mut self, mut at: Cursor) -> T {
+        self.remove_at_tracked(&mut at)
+    }
+
+    /// Remove the element specified by `at`. `at` is updated to point the
+    /// element following the removed one, which might be the one-past-end
+    /// element (in the same representation as `end()`).
+    pub(crate) fn remove_at_tracked(&mut self, at: &mut Cursor) -> T {
+        let (elem, offset, underflow) = Self::remove_sub(&mut at.indices, &mut self.root);
 
         if underflow {
             // If an underflow flag is returned, we must check for the invariant
@@ -470,21 +1013,51 @@ where
             // below `ORDER`, but for root inodes it's actually allowed to go
             // as low as `2`. Root leaves do not have a lower bound. So, this
             // might be a false alarm.
-            Self::flatten_root_if_needed(&mut self.root);
+            if Self::flatten_root_if_needed(&mut self.root) {
+                at.indices.remove(0);
+            }
         }
 
         self.len += -offset;
+
+        // `at` might be pointing past the end of a leaf. Move it to the first
+        // element of the next leaf in this case.
+        let last = at.indices.len() - 1;
+        if at.indices[last] as usize == self.leaf_len_at(at) && *at != self.end() {
+            at.indices[last] -= 1;
+            self.move_cursor_forward(at);
+        }
+
         elem
     }
 
+    /// Get the number of the elements in the leaf node containing the element
+    /// specified by `at`.
+    pub(crate) fn leaf_len_at(&self, at: &Cursor) -> usize {
+        let mut cur = &self.root;
+        for &i in at.indices.iter() {
+            match cur {
+                NodeRef::Internal(inode) => {
+                    cur = &inode.children[i as usize];
+                }
+                NodeRef::Leaf(elements) => {
+                    return elements.len();
+                }
+            }
+        }
+        unreachable!()
+    }
+
     /// The internal method for `remove_at`. See the comment in `remove_at`.
-    fn flatten_root_if_needed(node: &mut NodeRef<T, O>) {
+    ///
+    /// Returns `true` if the root node was replaced with its only child.
+    fn flatten_root_if_needed(node: &mut NodeRef<T, O>) -> bool {
         // Return early if it's a false alarm
         let child;
         match node {
             NodeRef::Internal(inode) => {
                 if inode.children.len() >= 2 {
-                    return;
+                    return false;
                 }
 
                 // The invariant violation is a result of the removal of a
@@ -493,12 +1066,13 @@ where
                 child = inode.children.pop().unwrap();
             }
             NodeRef::Leaf(_) => {
-                return;
+                return false;
             }
         }
 
         // Move the only child to the top-level
         *node = child;
+        true
     }
 
     /// The internal method for `remove_at`.
@@ -511,7 +1085,9 @@ where
     ///  - `offset` is the result of `elem.to_offset()`.
     ///  - `underflow` indicates if the new child count `node` is less than
     ///    `ORDER` or not.
-    fn remove_sub(at: &[u8], node: &mut NodeRef<T, O>) -> (T, O, bool) {
+    ///
+    /// `at` is updated to point the position where the removed element was.
+    fn remove_sub(at: &mut [u8], node: &mut NodeRef<T, O>) -> (T, O, bool) {
         if at.is_empty() {
             unreachable!();
         }
@@ -536,7 +1112,7 @@ where
                 NodeRef::Leaf(_) => unreachable!(),
             };
 
-            let (elem, len, mut underflow) = Self::remove_sub(&at[1..], &mut inode.children[i]);
+            let (elem, len, mut underflow) = Self::remove_sub(&mut at[1..], &mut inode.children[i]);
 
             for offset in inode.offsets[i..].iter_mut() {
                 *offset += -len.clone();
@@ -591,11 +1167,22 @@ where
                         // because we only moved an element between them.
                         inode.offsets[k] += displacement;
 
+                        if use_left {
+                            // An element was inserted before `at[1]`
+                            at[1] += 1;
+                        }
+
                         // We didn't change the child count of `inode`
                         underflow = false;
                     } else {
                         // Merge nodes
 
+                        if use_left {
+                            // `children[i]` is merged into `children[i - 1]`
+                            at[0] = k as u8;
+                            at[1] += left.len() as u8;
+                        }
+
                         // Move all children from `right` to `left`
                         Self::rotate_left_full(left, right, left_len);
 
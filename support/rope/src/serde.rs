@@ -0,0 +1,109 @@
+//! `serde` support
+use serde::{
+    de::{Deserialize, Deserializer, SeqAccess, Visitor},
+    ser::{Serialize, Serializer},
+};
+use std::{fmt, marker::PhantomData};
+
+use super::{Offset, Rope, ToOffset};
+
+/// Serializes the rope as a sequence of elements. The offset values are not
+/// included because they can be calculated from the elements.
+impl<T, O> Serialize for Rope<T, O>
+where
+    T: ToOffset<O> + Serialize,
+    O: Offset,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+/// Deserializes a sequence of elements. The tree is constructed in the same
+/// way as `FromIterator`.
+impl<'de, T, O> Deserialize<'de> for Rope<T, O>
+where
+    T: ToOffset<O> + Deserialize<'de>,
+    O: Offset,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(RopeVisitor(PhantomData))
+    }
+}
+
+struct RopeVisitor<T, O>(PhantomData<fn() -> Rope<T, O>>);
+
+impl<'de, T, O> Visitor<'de> for RopeVisitor<T, O>
+where
+    T: ToOffset<O> + Deserialize<'de>,
+    O: Offset,
+{
+    type Value = Rope<T, O>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sequence")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut error = None;
+
+        let rope = Rope::from_iter_bulk(std::iter::from_fn(|| {
+            if error.is_some() {
+                return None;
+            }
+            seq.next_element().unwrap_or_else(|e| {
+                error = Some(e);
+                None
+            })
+        }));
+
+        if let Some(e) = error {
+            Err(e)
+        } else {
+            Ok(rope)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Index;
+
+    #[test]
+    fn round_trip() {
+        for &count in [0, 1, 100, 1000].iter() {
+            let list: Vec<String> = (0..count).map(|x| x.to_string()).collect();
+
+            let rope: Rope<String> = list.iter().cloned().collect();
+            let json = serde_json::to_string(&rope).unwrap();
+            assert_eq!(json, serde_json::to_string(&list).unwrap());
+
+            let rope: Rope<String> = serde_json::from_str(&json).unwrap();
+            rope.validate();
+            assert_eq!(
+                rope.iter().collect::<Vec<_>>(),
+                list.iter().collect::<Vec<_>>()
+            );
+            assert_eq!(
+                rope.offset_len(),
+                list.iter().map(|x| x.len() as isize).sum::<isize>()
+            );
+
+            let rope: Rope<String, Index> = serde_json::from_str(&json).unwrap();
+            rope.validate();
+            assert_eq!(
+                rope.iter().collect::<Vec<_>>(),
+                list.iter().collect::<Vec<_>>()
+            );
+            assert_eq!(rope.offset_len().0, count);
+            assert_eq!(serde_json::to_string(&rope).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn invalid_element() {
+        let result: Result<Rope<String>, _> = serde_json::from_str(r#"["a", "b", 1, "c"]"#);
+        assert!(result.is_err());
+    }
+}
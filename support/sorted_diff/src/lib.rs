@@ -1,5 +1,5 @@
-//! Provides an iterator type that outputs the difference between two sorted
-//! sequences.
+//! Provides an iterator type that outputs the difference between two (or
+//! three) sorted sequences.
 //!
 //! It's implemented using an algorithm similar to merge sort and the time
 //! complexity is O(N) (where N is the number of elements).
@@ -26,16 +26,55 @@
 //!         ],
 //!     );
 //!
-use std::{
-    cmp::Ordering,
-    iter::{FusedIterator, Peekable},
-};
+//! The iterators can be reversed if the input iterators can be:
+//!
+//!     # use sorted_diff::{sorted_diff, In};
+//!     let v1 = vec![   2, 5, 6, 7,    9];
+//!     let v2 = vec![1, 2, 5, 6,    8   ];
+//!
+//!     let mut it = sorted_diff(v1, v2);
+//!     assert_eq!(it.size_hint(), (5, Some(10)));
+//!     assert_eq!(it.next_back(), Some(In::Left(9)));
+//!     assert_eq!(it.next(), Some(In::Right(1)));
+//!     assert_eq!(
+//!         it.rev().collect::<Vec<_>>(),
+//!         vec![
+//!             In::Right(8),
+//!             In::Left(7),
+//!             In::Both(6, 6),
+//!             In::Both(5, 5),
+//!             In::Both(2, 2),
+//!         ],
+//!     );
+//!
+//! [`sorted_diff3`] does the same for three sequences:
+//!
+//!     use sorted_diff::sorted_diff3;
+//!
+//!     let v1 = vec![   2, 5, 6, 7,    9];
+//!     let v2 = vec![1, 2, 5, 6,    8   ];
+//!     let v3 = vec![1,    5,    7, 8   ];
+//!
+//!     assert_eq!(
+//!         sorted_diff3(v1, v2, v3).collect::<Vec<_>>(),
+//!         vec![
+//!             (None,    Some(1), Some(1)),
+//!             (Some(2), Some(2), None   ),
+//!             (Some(5), Some(5), Some(5)),
+//!             (Some(6), Some(6), None   ),
+//!             (Some(7), None,    Some(7)),
+//!             (None,    Some(8), Some(8)),
+//!             (Some(9), None,    None   ),
+//!         ],
+//!     );
+//!
+use std::{cmp::Ordering, iter::FusedIterator};
 
 /// An iterator that outputs the difference between two sorted sequences.
 /// Elements are compared using the [comparer](Cmp) `C`.
 pub struct SortedDiffBy<I1: Iterator, I2: Iterator, C> {
-    it1: Peekable<I1>,
-    it2: Peekable<I2>,
+    it1: DoublePeekable<I1>,
+    it2: DoublePeekable<I2>,
     cmp: C,
 }
 
@@ -74,6 +113,39 @@ where
             },
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower1, upper1) = self.it1.size_hint();
+        let (lower2, upper2) = self.it2.size_hint();
+        let upper = match (upper1, upper2) {
+            (Some(x1), Some(x2)) => x1.checked_add(x2),
+            _ => None,
+        };
+        (lower1.max(lower2), upper)
+    }
+}
+
+impl<I1, I2, C> DoubleEndedIterator for SortedDiffBy<I1, I2, C>
+where
+    I1: DoubleEndedIterator,
+    I2: DoubleEndedIterator,
+    C: Cmp<I1::Item, I2::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match (self.it1.peek_back(), self.it2.peek_back()) {
+            (None, None) => None,
+            (Some(_), None) => Some(In::Left(self.it1.next_back().unwrap())),
+            (None, Some(_)) => Some(In::Right(self.it2.next_back().unwrap())),
+            (Some(x1), Some(x2)) => match self.cmp.cmp(x1, x2) {
+                Ordering::Greater => Some(In::Left(self.it1.next_back().unwrap())),
+                Ordering::Less => Some(In::Right(self.it2.next_back().unwrap())),
+                Ordering::Equal => Some(In::Both(
+                    self.it1.next_back().unwrap(),
+                    self.it2.next_back().unwrap(),
+                )),
+            },
+        }
+    }
 }
 
 impl<I1, I2, C> FusedIterator for SortedDiffBy<I1, I2, C>
@@ -95,11 +167,59 @@ where
     /// This is a low-level function of [`sorted_diff_by`].
     pub fn new_by(it1: I1, it2: I2, cmp: C) -> Self {
         Self {
-            it1: it1.peekable(),
-            it2: it2.peekable(),
+            it1: DoublePeekable::new(it1),
+            it2: DoublePeekable::new(it2),
             cmp,
         }
     }
+
+    /// Convert `self` into an iterator that only outputs elements included in
+    /// both sequences.
+    pub fn intersection(self) -> Intersection<I1, I2, C> {
+        Intersection { inner: self }
+    }
+
+    /// Convert `self` into an iterator that outputs all elements, merging
+    /// each pair of equivalent elements into one using `merge`.
+    pub fn union<F>(self, merge: F) -> Union<I1, I2, C, F>
+    where
+        I2: Iterator<Item = I1::Item>,
+        F: FnMut(I1::Item, I2::Item) -> I1::Item,
+    {
+        Union { inner: self, merge }
+    }
+
+    /// Convert `self` into an iterator that only outputs elements included in
+    /// exactly one of the sequences.
+    pub fn symmetric_difference(self) -> SymmetricDifference<I1, I2, C> {
+        SymmetricDifference { inner: self }
+    }
+
+    /// Consume `self`, calling the callbacks for each element to transform the
+    /// first sequence (old) into the second sequence (new). See
+    /// [`sorted_diff_apply`] for details.
+    pub fn apply<S>(
+        self,
+        state: &mut S,
+        mut on_remove: impl FnMut(&mut S, usize, I1::Item),
+        mut on_keep: impl FnMut(&mut S, usize, I1::Item, I2::Item),
+        mut on_insert: impl FnMut(&mut S, usize, I2::Item),
+    ) {
+        let mut i = 0;
+        for x in self {
+            match x {
+                In::Left(x1) => on_remove(state, i, x1),
+                In::Both(x1, x2) => {
+                    on_keep(state, i, x1, x2);
+                    i += 1;
+                }
+                In::Right(x2) => {
+                    on_insert(state, i, x2);
+                    i += 1;
+                }
+            }
+        }
+    }
 }
 
 /// Compares two objects.
@@ -133,6 +253,461 @@ where
     }
 }
 
+/// The element type of [`SortedDiff3By`].
+pub type Diff3Item<A, B, C> = (Option<A>, Option<B>, Option<C>);
+
+/// An iterator that outputs the difference between three sorted sequences.
+/// Elements are compared using the [comparer](Cmp) `C`.
+///
+/// Each output element is a tuple of the equivalent elements from the
+/// respective sequences, at least one of which is `Some(_)`.
+pub struct SortedDiff3By<I1: Iterator, I2: Iterator, I3: Iterator, C> {
+    it1: DoublePeekable<I1>,
+    it2: DoublePeekable<I2>,
+    it3: DoublePeekable<I3>,
+    cmp: C,
+}
+
+impl<I1, I2, I3, C> Iterator for SortedDiff3By<I1, I2, I3, C>
+where
+    I1: Iterator,
+    I2: Iterator,
+    I3: Iterator,
+    C: Cmp<I1::Item, I2::Item> + Cmp<I1::Item, I3::Item> + Cmp<I2::Item, I3::Item>,
+{
+    type Item = Diff3Item<I1::Item, I2::Item, I3::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (x1, x2, x3) = (self.it1.peek(), self.it2.peek(), self.it3.peek());
+        let [take1, take2, take3] = find_extremes3(&mut self.cmp, x1, x2, x3, Ordering::Less);
+
+        if !(take1 || take2 || take3) {
+            return None;
+        }
+
+        Some((
+            if take1 { self.it1.next() } else { None },
+            if take2 { self.it2.next() } else { None },
+            if take3 { self.it3.next() } else { None },
+        ))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (lower1, upper1) = self.it1.size_hint();
+        let (lower2, upper2) = self.it2.size_hint();
+        let (lower3, upper3) = self.it3.size_hint();
+        let upper = match (upper1, upper2, upper3) {
+            (Some(x1), Some(x2), Some(x3)) => x1.checked_add(x2).and_then(|x| x.checked_add(x3)),
+            _ => None,
+        };
+        (lower1.max(lower2).max(lower3), upper)
+    }
+}
+
+impl<I1, I2, I3, C> DoubleEndedIterator for SortedDiff3By<I1, I2, I3, C>
+where
+    I1: DoubleEndedIterator,
+    I2: DoubleEndedIterator,
+    I3: DoubleEndedIterator,
+    C: Cmp<I1::Item, I2::Item> + Cmp<I1::Item, I3::Item> + Cmp<I2::Item, I3::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (x1, x2, x3) = (
+            self.it1.peek_back(),
+            self.it2.peek_back(),
+            self.it3.peek_back(),
+        );
+        let [take1, take2, take3] = find_extremes3(&mut self.cmp, x1, x2, x3, Ordering::Greater);
+
+        if !(take1 || take2 || take3) {
+            return None;
+        }
+
+        Some((
+            if take1 { self.it1.next_back() } else { None },
+            if take2 { self.it2.next_back() } else { None },
+            if take3 { self.it3.next_back() } else { None },
+        ))
+    }
+}
+
+/// Find the smallest (if `order` is `Less`) or largest (if `order` is
+/// `Greater`) elements among the given ones by eliminating the other one of
+/// each pair.
+fn find_extremes3<T1, T2, T3, C>(
+    cmp: &mut C,
+    x1: Option<&T1>,
+    x2: Option<&T2>,
+    x3: Option<&T3>,
+    order: Ordering,
+) -> [bool; 3]
+where
+    C: Cmp<T1, T2> + Cmp<T1, T3> + Cmp<T2, T3>,
+{
+    let mut take = [x1.is_some(), x2.is_some(), x3.is_some()];
+
+    let mut eliminate = |i1: usize, i2: usize, o: Ordering| {
+        if o == order {
+            take[i2] = false;
+        } else if o == order.reverse() {
+            take[i1] = false;
+        }
+    };
+
+    if let (Some(x1), Some(x2)) = (x1, x2) {
+        eliminate(0, 1, Cmp::<T1, T2>::cmp(cmp, x1, x2));
+    }
+    if let (Some(x1), Some(x3)) = (x1, x3) {
+        eliminate(0, 2, Cmp::<T1, T3>::cmp(cmp, x1, x3));
+    }
+    if let (Some(x2), Some(x3)) = (x2, x3) {
+        eliminate(1, 2, Cmp::<T2, T3>::cmp(cmp, x2, x3));
+    }
+
+    take
+}
+
+impl<I1, I2, I3, C> FusedIterator for SortedDiff3By<I1, I2, I3, C>
+where
+    I1: Iterator + FusedIterator,
+    I2: Iterator + FusedIterator,
+    I3: Iterator + FusedIterator,
+    C: Cmp<I1::Item, I2::Item> + Cmp<I1::Item, I3::Item> + Cmp<I2::Item, I3::Item>,
+{
+}
+
+impl<I1, I2, I3, C> SortedDiff3By<I1, I2, I3, C>
+where
+    I1: Iterator,
+    I2: Iterator,
+    I3: Iterator,
+    C: Cmp<I1::Item, I2::Item> + Cmp<I1::Item, I3::Item> + Cmp<I2::Item, I3::Item>,
+{
+    /// Construct a `SortedDiff3By` using a specified comparer.
+    ///
+    /// This is a low-level function of [`sorted_diff3_by`].
+    pub fn new_by(it1: I1, it2: I2, it3: I3, cmp: C) -> Self {
+        Self {
+            it1: DoublePeekable::new(it1),
+            it2: DoublePeekable::new(it2),
+            it3: DoublePeekable::new(it3),
+            cmp,
+        }
+    }
+}
+
+/// An iterator that outputs the difference between two sorted slices,
+/// yielding references to the elements. Elements are compared using the
+/// [comparer](Cmp) `C`.
+///
+/// Unlike [`SortedDiffBy`], this type doesn't move elements out of the
+/// inputs, which may be beneficial for large elements.
+#[derive(Debug, Clone)]
+pub struct SortedDiffRefBy<'a, T1, T2, C> {
+    s1: &'a [T1],
+    s2: &'a [T2],
+    cmp: C,
+}
+
+impl<'a, T1, T2, C> Iterator for SortedDiffRefBy<'a, T1, T2, C>
+where
+    C: Cmp<T1, T2>,
+{
+    type Item = In<&'a T1, &'a T2>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (s1, s2) = (self.s1, self.s2);
+        let (ord, out) = match (s1.first(), s2.first()) {
+            (None, None) => return None,
+            (Some(x1), None) => (Ordering::Less, In::Left(x1)),
+            (None, Some(x2)) => (Ordering::Greater, In::Right(x2)),
+            (Some(x1), Some(x2)) => match self.cmp.cmp(x1, x2) {
+                Ordering::Less => (Ordering::Less, In::Left(x1)),
+                Ordering::Greater => (Ordering::Greater, In::Right(x2)),
+                Ordering::Equal => (Ordering::Equal, In::Both(x1, x2)),
+            },
+        };
+
+        if ord != Ordering::Greater {
+            self.s1 = &s1[1..];
+        }
+        if ord != Ordering::Less {
+            self.s2 = &s2[1..];
+        }
+
+        Some(out)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len1, len2) = (self.s1.len(), self.s2.len());
+        (len1.max(len2), Some(len1 + len2))
+    }
+}
+
+impl<'a, T1, T2, C> DoubleEndedIterator for SortedDiffRefBy<'a, T1, T2, C>
+where
+    C: Cmp<T1, T2>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (s1, s2) = (self.s1, self.s2);
+        let (ord, out) = match (s1.last(), s2.last()) {
+            (None, None) => return None,
+            (Some(x1), None) => (Ordering::Greater, In::Left(x1)),
+            (None, Some(x2)) => (Ordering::Less, In::Right(x2)),
+            (Some(x1), Some(x2)) => match self.cmp.cmp(x1, x2) {
+                Ordering::Greater => (Ordering::Greater, In::Left(x1)),
+                Ordering::Less => (Ordering::Less, In::Right(x2)),
+                Ordering::Equal => (Ordering::Equal, In::Both(x1, x2)),
+            },
+        };
+
+        if ord != Ordering::Less {
+            self.s1 = &s1[..s1.len() - 1];
+        }
+        if ord != Ordering::Greater {
+            self.s2 = &s2[..s2.len() - 1];
+        }
+
+        Some(out)
+    }
+}
+
+impl<'a, T1, T2, C> FusedIterator for SortedDiffRefBy<'a, T1, T2, C> where C: Cmp<T1, T2> {}
+
+impl<'a, T1, T2, C> SortedDiffRefBy<'a, T1, T2, C>
+where
+    C: Cmp<T1, T2>,
+{
+    /// Construct a `SortedDiffRefBy` using a specified comparer.
+    ///
+    /// This is a low-level function of [`sorted_diff_ref_by`].
+    pub fn new_by(s1: &'a [T1], s2: &'a [T2], cmp: C) -> Self {
+        Self { s1, s2, cmp }
+    }
+}
+
+// ------------------------------------------------------------------------
+//  Adapters
+
+/// An iterator that outputs the elements included in both of two sorted
+/// sequences. Created by [`SortedDiffBy::intersection`].
+pub struct Intersection<I1: Iterator, I2: Iterator, C> {
+    inner: SortedDiffBy<I1, I2, C>,
+}
+
+impl<I1, I2, C> Iterator for Intersection<I1, I2, C>
+where
+    I1: Iterator,
+    I2: Iterator,
+    C: Cmp<I1::Item, I2::Item>,
+{
+    type Item = (I1::Item, I2::Item);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let In::Both(x1, x2) = self.inner.next()? {
+                return Some((x1, x2));
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper1) = self.inner.it1.size_hint();
+        let (_, upper2) = self.inner.it2.size_hint();
+        let upper = match (upper1, upper2) {
+            (Some(x1), Some(x2)) => Some(x1.min(x2)),
+            (x1, x2) => x1.or(x2),
+        };
+        (0, upper)
+    }
+}
+
+impl<I1, I2, C> DoubleEndedIterator for Intersection<I1, I2, C>
+where
+    I1: DoubleEndedIterator,
+    I2: DoubleEndedIterator,
+    C: Cmp<I1::Item, I2::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            if let In::Both(x1, x2) = self.inner.next_back()? {
+                return Some((x1, x2));
+            }
+        }
+    }
+}
+
+impl<I1, I2, C> FusedIterator for Intersection<I1, I2, C>
+where
+    I1: Iterator + FusedIterator,
+    I2: Iterator + FusedIterator,
+    C: Cmp<I1::Item, I2::Item>,
+{
+}
+
+/// An iterator that outputs the elements included in either or both of two
+/// sorted sequences. Created by [`SortedDiffBy::union`].
+pub struct Union<I1: Iterator, I2: Iterator, C, F> {
+    inner: SortedDiffBy<I1, I2, C>,
+    merge: F,
+}
+
+impl<I1, I2, C, F> Iterator for Union<I1, I2, C, F>
+where
+    I1: Iterator,
+    I2: Iterator<Item = I1::Item>,
+    C: Cmp<I1::Item, I2::Item>,
+    F: FnMut(I1::Item, I2::Item) -> I1::Item,
+{
+    type Item = I1::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            In::Left(x) | In::Right(x) => x,
+            In::Both(x1, x2) => (self.merge)(x1, x2),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<I1, I2, C, F> DoubleEndedIterator for Union<I1, I2, C, F>
+where
+    I1: DoubleEndedIterator,
+    I2: DoubleEndedIterator<Item = I1::Item>,
+    C: Cmp<I1::Item, I2::Item>,
+    F: FnMut(I1::Item, I2::Item) -> I1::Item,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next_back()? {
+            In::Left(x) | In::Right(x) => x,
+            In::Both(x1, x2) => (self.merge)(x1, x2),
+        })
+    }
+}
+
+impl<I1, I2, C, F> FusedIterator for Union<I1, I2, C, F>
+where
+    I1: Iterator + FusedIterator,
+    I2: Iterator<Item = I1::Item> + FusedIterator,
+    C: Cmp<I1::Item, I2::Item>,
+    F: FnMut(I1::Item, I2::Item) -> I1::Item,
+{
+}
+
+/// An iterator that outputs the elements included in exactly one of two
+/// sorted sequences. Created by [`SortedDiffBy::symmetric_difference`].
+///
+/// The output elements are never [`In::Both`].
+pub struct SymmetricDifference<I1: Iterator, I2: Iterator, C> {
+    inner: SortedDiffBy<I1, I2, C>,
+}
+
+impl<I1, I2, C> Iterator for SymmetricDifference<I1, I2, C>
+where
+    I1: Iterator,
+    I2: Iterator,
+    C: Cmp<I1::Item, I2::Item>,
+{
+    type Item = In<I1::Item, I2::Item>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next()? {
+                In::Both(_, _) => {}
+                x => return Some(x),
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.inner.size_hint().1)
+    }
+}
+
+impl<I1, I2, C> DoubleEndedIterator for SymmetricDifference<I1, I2, C>
+where
+    I1: DoubleEndedIterator,
+    I2: DoubleEndedIterator,
+    C: Cmp<I1::Item, I2::Item>,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.inner.next_back()? {
+                In::Both(_, _) => {}
+                x => return Some(x),
+            }
+        }
+    }
+}
+
+impl<I1, I2, C> FusedIterator for SymmetricDifference<I1, I2, C>
+where
+    I1: Iterator + FusedIterator,
+    I2: Iterator + FusedIterator,
+    C: Cmp<I1::Item, I2::Item>,
+{
+}
+
+// ------------------------------------------------------------------------
+//  Utilities
+
+/// Like `std::iter::Peekable`, but supports peeking at both ends.
+struct DoublePeekable<I: Iterator> {
+    iter: I,
+    front: Option<I::Item>,
+    back: Option<I::Item>,
+}
+
+impl<I: Iterator> DoublePeekable<I> {
+    fn new(iter: I) -> Self {
+        Self {
+            iter,
+            front: None,
+            back: None,
+        }
+    }
+
+    fn peek(&mut self) -> Option<&I::Item> {
+        if self.front.is_none() {
+            self.front = self.iter.next().or_else(|| self.back.take());
+        }
+        self.front.as_ref()
+    }
+
+    fn next(&mut self) -> Option<I::Item> {
+        (self.front.take())
+            .or_else(|| self.iter.next())
+            .or_else(|| self.back.take())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let num_peeked = self.front.is_some() as usize + self.back.is_some() as usize;
+        let (lower, upper) = self.iter.size_hint();
+        (
+            lower.saturating_add(num_peeked),
+            upper.and_then(|x| x.checked_add(num_peeked)),
+        )
+    }
+}
+
+impl<I: DoubleEndedIterator> DoublePeekable<I> {
+    fn peek_back(&mut self) -> Option<&I::Item> {
+        if self.back.is_none() {
+            self.back = self.iter.next_back().or_else(|| self.front.take());
+        }
+        self.back.as_ref()
+    }
+
+    fn next_back(&mut self) -> Option<I::Item> {
+        (self.back.take())
+            .or_else(|| self.iter.next_back())
+            .or_else(|| self.front.take())
+    }
+}
+
 // ------------------------------------------------------------------------
 //  Variations
 
@@ -150,8 +725,49 @@ where
     /// This is a low-level function of [`sorted_diff`].
     pub fn new(it1: I1, it2: I2) -> Self {
         Self {
-            it1: it1.peekable(),
-            it2: it2.peekable(),
+            it1: DoublePeekable::new(it1),
+            it2: DoublePeekable::new(it2),
+            cmp: DefaultCmp,
+        }
+    }
+}
+
+/// An iterator that outputs the difference between three sorted sequences.
+/// Elements are compared using `<I1::Item as Ord>::cmp`.
+pub type SortedDiff3<I1, I2, I3> = SortedDiff3By<I1, I2, I3, DefaultCmp>;
+
+impl<I1, I2, I3> SortedDiff3<I1, I2, I3>
+where
+    I1: Iterator,
+    I2: Iterator,
+    I3: Iterator,
+{
+    /// Construct a `SortedDiff3By` using `Ord::cmp` for comparison.
+    ///
+    /// This is a low-level function of [`sorted_diff3`].
+    pub fn new(it1: I1, it2: I2, it3: I3) -> Self {
+        Self {
+            it1: DoublePeekable::new(it1),
+            it2: DoublePeekable::new(it2),
+            it3: DoublePeekable::new(it3),
+            cmp: DefaultCmp,
+        }
+    }
+}
+
+/// An iterator that outputs the difference between two sorted slices,
+/// yielding references to the elements. Elements are compared using
+/// `<T as Ord>::cmp`.
+pub type SortedDiffRef<'a, T> = SortedDiffRefBy<'a, T, T, DefaultCmp>;
+
+impl<'a, T: Ord> SortedDiffRef<'a, T> {
+    /// Construct a `SortedDiffRefBy` using `Ord::cmp` for comparison.
+    ///
+    /// This is a low-level function of [`sorted_diff_ref`].
+    pub fn new(s1: &'a [T], s2: &'a [T]) -> Self {
+        Self {
+            s1,
+            s2,
             cmp: DefaultCmp,
         }
     }
@@ -188,3 +804,261 @@ where
 {
     SortedDiffBy::new_by(it1.into_iter(), it2.into_iter(), cmp)
 }
+
+/// Construct an iterator that outputs the elements included in both of the
+/// specified sorted sequences.
+///
+/// A shorthand for `sorted_diff(it1, it2).intersection()`.
+///
+/// # Examples
+///
+///     use sorted_diff::sorted_intersection;
+///
+///     let v1 = vec![   2, 5, 6, 7,    9];
+///     let v2 = vec![1, 2, 5, 6,    8   ];
+///
+///     assert_eq!(
+///         sorted_intersection(v1, v2).collect::<Vec<_>>(),
+///         vec![(2, 2), (5, 5), (6, 6)],
+///     );
+///
+pub fn sorted_intersection<I1, I2>(
+    it1: I1,
+    it2: I2,
+) -> Intersection<I1::IntoIter, I2::IntoIter, DefaultCmp>
+where
+    I1: IntoIterator,
+    I2: IntoIterator<Item = I1::Item>,
+    I1::Item: Ord,
+{
+    sorted_diff(it1, it2).intersection()
+}
+
+/// Construct an iterator that outputs the elements included in either or
+/// both of the specified sorted sequences. Each pair of equivalent elements
+/// is merged into one using `merge`.
+///
+/// A shorthand for `sorted_diff(it1, it2).union(merge)`.
+///
+/// # Examples
+///
+///     use sorted_diff::sorted_union;
+///
+///     let v1 = vec![   2, 5, 6, 7,    9];
+///     let v2 = vec![1, 2, 5, 6,    8   ];
+///
+///     assert_eq!(
+///         sorted_union(v1, v2, |x, _| x).collect::<Vec<_>>(),
+///         vec![1, 2, 5, 6, 7, 8, 9],
+///     );
+///
+pub fn sorted_union<I1, I2, F>(
+    it1: I1,
+    it2: I2,
+    merge: F,
+) -> Union<I1::IntoIter, I2::IntoIter, DefaultCmp, F>
+where
+    I1: IntoIterator,
+    I2: IntoIterator<Item = I1::Item>,
+    I1::Item: Ord,
+    F: FnMut(I1::Item, I1::Item) -> I1::Item,
+{
+    sorted_diff(it1, it2).union(merge)
+}
+
+/// Construct an iterator that outputs the elements included in exactly one
+/// of the specified sorted sequences.
+///
+/// A shorthand for `sorted_diff(it1, it2).symmetric_difference()`.
+///
+/// # Examples
+///
+///     use sorted_diff::{sorted_symmetric_difference, In};
+///
+///     let v1 = vec![   2, 5, 6, 7,    9];
+///     let v2 = vec![1, 2, 5, 6,    8   ];
+///
+///     assert_eq!(
+///         sorted_symmetric_difference(v1, v2).collect::<Vec<_>>(),
+///         vec![In::Right(1), In::Left(7), In::Right(8), In::Left(9)],
+///     );
+///
+pub fn sorted_symmetric_difference<I1, I2>(
+    it1: I1,
+    it2: I2,
+) -> SymmetricDifference<I1::IntoIter, I2::IntoIter, DefaultCmp>
+where
+    I1: IntoIterator,
+    I2: IntoIterator<Item = I1::Item>,
+    I1::Item: Ord,
+{
+    sorted_diff(it1, it2).symmetric_difference()
+}
+
+/// Consturct a `SortedDiffRef` using the specified two slices as the input
+/// sequences.
+///
+/// # Examples
+///
+///     use sorted_diff::{sorted_diff_ref, In};
+///
+///     let v1 = vec![   2, 5];
+///     let v2 = vec![1, 2   ];
+///
+///     assert_eq!(
+///         sorted_diff_ref(&v1, &v2).collect::<Vec<_>>(),
+///         vec![In::Right(&1), In::Both(&2, &2), In::Left(&5)],
+///     );
+///
+pub fn sorted_diff_ref<'a, T: Ord>(s1: &'a [T], s2: &'a [T]) -> SortedDiffRef<'a, T> {
+    SortedDiffRef::new(s1, s2)
+}
+
+/// Consturct a `SortedDiffRefBy` using the specified two slices as the input
+/// sequences, and a custom comparer.
+///
+/// `cmp` is usually `impl FnMut(&T1, &T2) -> std::cmp::Ordering`, but other
+/// types implementing [`Cmp`]`<T1, T2>` can be used, too.
+///
+/// # Examples
+///
+///     use sorted_diff::{sorted_diff_ref_by, In};
+///
+///     struct Item { key: u32, payload: [u8; 256] }
+///     let v1 = vec![Item { key: 2, payload: [1; 256] }];
+///     let v2 = vec![Item { key: 1, payload: [2; 256] }, Item { key: 2, payload: [3; 256] }];
+///
+///     let payloads: Vec<_> = sorted_diff_ref_by(&v1, &v2, |x1: &Item, x2: &Item| x1.key.cmp(&x2.key))
+///         .map(|x| match x {
+///             In::Left(x1) => (Some(x1.payload[0]), None),
+///             In::Right(x2) => (None, Some(x2.payload[0])),
+///             In::Both(x1, x2) => (Some(x1.payload[0]), Some(x2.payload[0])),
+///         })
+///         .collect();
+///
+///     assert_eq!(payloads, [(None, Some(2)), (Some(1), Some(3))]);
+///
+pub fn sorted_diff_ref_by<'a, T1, T2, C>(
+    s1: &'a [T1],
+    s2: &'a [T2],
+    cmp: C,
+) -> SortedDiffRefBy<'a, T1, T2, C>
+where
+    C: Cmp<T1, T2>,
+{
+    SortedDiffRefBy::new_by(s1, s2, cmp)
+}
+
+/// Call the callbacks for each element to transform the sorted sequence `old`
+/// into the sorted sequence `new`. This is useful for updating a structure
+/// that mirrors `old` (e.g., a list of views representing model items).
+///
+///  - `on_remove(state, i, x)` is called for an element `x` only included in
+///    `old`.
+///  - `on_keep(state, i, x_old, x_new)` is called for a pair of equivalent
+///    elements.
+///  - `on_insert(state, i, x)` is called for an element `x` only included in
+///    `new`.
+///
+/// The callbacks are called in the merged order of the sequences, like the
+/// elements produced by [`sorted_diff`]. `i` is the index of the element in
+/// the sequence being transformed (i.e., the elements of `new` before `x`
+/// followed by the elements of `old` after `x`). `state` is passed to all
+/// callbacks so that they can modify the same object.
+///
+/// The time complexity is O(N) (where N is the number of elements), not
+/// counting the callbacks.
+///
+/// # Examples
+///
+///     use sorted_diff::sorted_diff_apply;
+///
+///     let old = vec![1, 3, 5, 7];
+///     let new = vec![1, 2, 5, 6];
+///
+///     // A structure mirroring `old`
+///     let mut views: Vec<String> = old.iter().map(|x| format!("view {}", x)).collect();
+///
+///     sorted_diff_apply(
+///         old,
+///         new,
+///         &mut views,
+///         |views, i, _| {
+///             views.remove(i);
+///         },
+///         |_, _, _, _| {},
+///         |views, i, x| views.insert(i, format!("view {}", x)),
+///     );
+///
+///     assert_eq!(views, ["view 1", "view 2", "view 5", "view 6"]);
+///
+pub fn sorted_diff_apply<I1, I2, S>(
+    old: I1,
+    new: I2,
+    state: &mut S,
+    on_remove: impl FnMut(&mut S, usize, I1::Item),
+    on_keep: impl FnMut(&mut S, usize, I1::Item, I1::Item),
+    on_insert: impl FnMut(&mut S, usize, I1::Item),
+) where
+    I1: IntoIterator,
+    I2: IntoIterator<Item = I1::Item>,
+    I1::Item: Ord,
+{
+    sorted_diff(old, new).apply(state, on_remove, on_keep, on_insert)
+}
+
+/// Consturct a `SortedDiff3` using the specified three `IntoIterator`s as the
+/// input sequences.
+pub fn sorted_diff3<I1, I2, I3>(
+    it1: I1,
+    it2: I2,
+    it3: I3,
+) -> SortedDiff3<I1::IntoIter, I2::IntoIter, I3::IntoIter>
+where
+    I1: IntoIterator,
+    I2: IntoIterator<Item = I1::Item>,
+    I3: IntoIterator<Item = I1::Item>,
+    I1::Item: Ord,
+{
+    SortedDiff3::new(it1.into_iter(), it2.into_iter(), it3.into_iter())
+}
+
+/// Consturct a `SortedDiff3` using the specified three `IntoIterator`s as the
+/// input sequences, and a custom comparer.
+///
+/// `cmp` must implement [`Cmp`] for every pair of the input sequences' item
+/// types. If all sequences have the same item type `T`, `cmp` can be
+/// `impl FnMut(&T, &T) -> std::cmp::Ordering`.
+///
+/// # Examples
+///
+///     use sorted_diff::sorted_diff3_by;
+///
+///     let v1 = vec![(1, "a"), (3, "b")];
+///     let v2 = vec![(2, "c"), (3, "d")];
+///     let v3 = vec![(1, "e")];
+///
+///     assert_eq!(
+///         sorted_diff3_by(v1, v2, v3, |x: &(i32, &str), y: &(i32, &str)| x.0.cmp(&y.0))
+///             .collect::<Vec<_>>(),
+///         vec![
+///             (Some((1, "a")), None, Some((1, "e"))),
+///             (None, Some((2, "c")), None),
+///             (Some((3, "b")), Some((3, "d")), None),
+///         ],
+///     );
+///
+pub fn sorted_diff3_by<I1, I2, I3, C>(
+    it1: I1,
+    it2: I2,
+    it3: I3,
+    cmp: C,
+) -> SortedDiff3By<I1::IntoIter, I2::IntoIter, I3::IntoIter, C>
+where
+    I1: IntoIterator,
+    I2: IntoIterator,
+    I3: IntoIterator,
+    C: Cmp<I1::Item, I2::Item> + Cmp<I1::Item, I3::Item> + Cmp<I2::Item, I3::Item>,
+{
+    SortedDiff3By::new_by(it1.into_iter(), it2.into_iter(), it3.into_iter(), cmp)
+}
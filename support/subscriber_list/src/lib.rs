@@ -1,25 +1,113 @@
 //! Provides a type representing a list of subscribers. When adding an
 //! element, the caller gets a ticket for deleting (i.e., unsubscribing) that
 //! element.
-use iterpool::{self, IterablePool, PoolPtr};
+use iterpool::{IterablePool, PoolPtr};
 use std::{
     cell::{Ref, RefCell, RefMut},
+    collections::{btree_map, BTreeMap},
     fmt,
-    mem::transmute,
+    mem::{take, transmute},
     rc::{Rc, Weak},
 };
 
 /// A type representing a list of subscribers.
+///
+/// The elements are ordered by their priorities (see
+/// [`SubscriberList::insert_with_priority`]) and then by their insertion
+/// order.
 #[derive(Debug)]
 pub struct SubscriberList<T> {
-    pool: Rc<RefCell<IterablePool<T>>>,
+    pool: Rc<Shared<T>>,
 }
 
 /// An element (subscriber) in [`SubscriberList`].
 #[derive(Debug)]
 pub struct Subscription<T> {
-    pool: Weak<RefCell<IterablePool<T>>>,
+    pool: Weak<Shared<T>>,
     ptr: PoolPtr,
+    id: usize,
+}
+
+#[derive(Debug)]
+struct Shared<T> {
+    inner: RefCell<Inner<T>>,
+    /// The elements to be removed when the last iterator is dropped. See
+    /// [`Subscription::unsubscribe_deferred`].
+    pending_removals: RefCell<Vec<(PoolPtr, usize)>>,
+}
+
+#[derive(Debug)]
+struct Inner<T> {
+    pool: IterablePool<Element<T>>,
+    /// The elements in `pool` sorted in the iteration order.
+    index: BTreeMap<IndexKey, PoolPtr>,
+    /// The `id` assigned to the next element.
+    next_id: usize,
+}
+
+/// `(priority, id)`
+type IndexKey = (i32, usize);
+
+#[derive(Debug)]
+struct Element<T> {
+    x: T,
+    priority: i32,
+    /// Distinguishes the element from the ones previously occupying the same
+    /// entry in `pool`. Tokens of elements removed by
+    /// [`SubscriberList::retain`] are not consumed, so they might be used
+    /// after the entry is reused.
+    id: usize,
+}
+
+impl<T> Inner<T> {
+    fn deallocate(&mut self, ptr: PoolPtr, id: usize) -> Option<T> {
+        match self.pool.get(ptr) {
+            Some(e) if e.id == id => {
+                self.index.remove(&(e.priority, id));
+                Some(self.pool.deallocate(ptr).unwrap().x)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<T> Shared<T> {
+    fn unsubscribe(&self, ptr: PoolPtr, id: usize) -> Result<Option<T>, IterationActive> {
+        let mut inner = self.inner.try_borrow_mut().map_err(|_| IterationActive)?;
+        Ok(inner.deallocate(ptr, id))
+    }
+
+    fn unsubscribe_deferred(&self, ptr: PoolPtr, id: usize) {
+        if let Err(IterationActive) = self.unsubscribe(ptr, id) {
+            self.pending_removals.borrow_mut().push((ptr, id));
+        }
+    }
+
+    /// Process `pending_removals` if there are no active iterators.
+    fn flush_pending_removals(&self) {
+        loop {
+            let pending = take(&mut *self.pending_removals.borrow_mut());
+            if pending.is_empty() {
+                return;
+            }
+
+            let removed: Vec<T> = if let Ok(mut inner) = self.inner.try_borrow_mut() {
+                pending
+                    .iter()
+                    .filter_map(|&(ptr, id)| inner.deallocate(ptr, id))
+                    .collect()
+            } else {
+                // There still are active iterators
+                self.pending_removals.borrow_mut().extend(pending);
+                return;
+            };
+
+            // Drop the removed elements after releasing the borrow. Their
+            // `drop` might call `unsubscribe_deferred`, in which case we have
+            // to process `pending_removals` again.
+            drop(removed);
+        }
+    }
 }
 
 impl<T> Default for SubscriberList<T> {
@@ -31,37 +119,117 @@ impl<T> Default for SubscriberList<T> {
 impl<T> SubscriberList<T> {
     pub fn new() -> Self {
         Self {
-            pool: Rc::new(RefCell::new(IterablePool::new())),
+            pool: Rc::new(Shared {
+                inner: RefCell::new(Inner {
+                    pool: IterablePool::new(),
+                    index: BTreeMap::new(),
+                    next_id: 0,
+                }),
+                pending_removals: RefCell::new(Vec::new()),
+            }),
         }
     }
 
     /// Insert an element to a subscriber list.
     ///
     /// Returns a token that can be used to remove the inserted element.
+    ///
+    /// This is equivalent to `insert_with_priority(x, 0)`.
     pub fn insert(&mut self, x: T) -> Subscription<T> {
-        let mut pool = self.pool.borrow_mut();
-        let ptr = pool.allocate(x);
+        self.insert_with_priority(x, 0)
+    }
+
+    /// Insert an element with a priority to a subscriber list. The elements
+    /// with lower `priority` values come first in the iteration order.
+    /// The elements with an identical priority are ordered by their insertion
+    /// order.
+    ///
+    /// Returns a token that can be used to remove the inserted element.
+    ///
+    /// This method takes `O(log n)` time because it updates the index used
+    /// to maintain the iteration order.
+    pub fn insert_with_priority(&mut self, x: T, priority: i32) -> Subscription<T> {
+        let mut inner = self.pool.inner.borrow_mut();
+        let id = inner.next_id;
+        inner.next_id = id.wrapping_add(1);
+        let ptr = inner.pool.allocate(Element { x, priority, id });
+        inner.index.insert((priority, id), ptr);
         Subscription {
             pool: Rc::downgrade(&self.pool),
             ptr,
+            id,
         }
     }
 
+    /// Retain only the elements specified by the predicate. The elements for
+    /// which `pred` returns `false` are removed.
+    ///
+    /// The tokens of the removed elements become stale. Calling `unsubscribe`
+    /// on them is a no-op that returns `Ok(None)`.
+    pub fn retain(&mut self, mut pred: impl FnMut(&mut T) -> bool) {
+        {
+            // This `borrow_mut()` always succeeds because `&mut self`
+            // guarantees that there are no active iterators
+            let inner = &mut *self.pool.inner.borrow_mut();
+            let index = &mut inner.index;
+            inner.pool.retain(|_, e| {
+                let keep = pred(&mut e.x);
+                if !keep {
+                    index.remove(&(e.priority, e.id));
+                }
+                keep
+            });
+        }
+
+        // `pred` or the removed elements' `drop` might have called
+        // `unsubscribe_deferred` while we were holding the borrow
+        self.pool.flush_pending_removals();
+    }
+
     pub fn iter(&self) -> Iter<'_, T> {
         // This `borrow()` always succeeds because of the following reasons:
         //  1. The calls to `borrow` and `borrow_mut` in this `impl` follows the
         //     receiver mutability of the calling methods
         //  2. These methods are never called when `unsubscribe` has a mutable
         //     borrow.
-        let borrow = self.pool.borrow();
-        let inner = unsafe { transmute(borrow.iter()) };
-        Iter { borrow, inner }
+        let borrow = self.pool.inner.borrow();
+        let pool = unsafe { transmute(&borrow.pool) };
+        let index = unsafe { transmute(borrow.index.values()) };
+        Iter {
+            shared: &self.pool,
+            borrow: Some(borrow),
+            pool,
+            index,
+        }
     }
 
     pub fn iter_mut(&mut self) -> IterMut<'_, T> {
-        let mut borrow = self.pool.borrow_mut();
-        let inner = unsafe { transmute(borrow.iter_mut()) };
-        IterMut { borrow, inner }
+        let mut borrow = self.pool.inner.borrow_mut();
+        let inner = &mut *borrow;
+        let pool = unsafe { transmute(&mut inner.pool) };
+        let index = unsafe { transmute(inner.index.values()) };
+        IterMut {
+            shared: &self.pool,
+            borrow: Some(borrow),
+            pool,
+            index,
+        }
+    }
+}
+
+impl<F> SubscriberList<F> {
+    /// Call every subscriber with `args`.
+    ///
+    /// The subscribers may remove themselves or other subscribers by
+    /// [`Subscription::unsubscribe_deferred`] during the call. The removed
+    /// subscribers that haven't been called yet are still called.
+    pub fn notify<Args: ?Sized>(&self, args: &Args)
+    where
+        F: Fn(&Args),
+    {
+        for handler in self.iter() {
+            handler(args);
+        }
     }
 }
 
@@ -69,16 +237,29 @@ impl<T> Subscription<T> {
     /// Remove the element that `self` represents.
     ///
     /// Returns `Some(x)` if the element `x` was removed; `None` if the list
-    /// has already been dropped.
+    /// has already been dropped or the element has already been removed by
+    /// [`SubscriberList::retain`].
     pub fn unsubscribe(self) -> Result<Option<T>, IterationActive> {
         if let Some(pool) = self.pool.upgrade() {
-            let mut pool = pool.try_borrow_mut().map_err(|_| IterationActive)?;
-            Ok(Some(pool.deallocate(self.ptr).unwrap()))
+            pool.unsubscribe(self.ptr, self.id)
         } else {
             Ok(None)
         }
     }
 
+    /// Remove the element that `self` represents. If there is an active
+    /// iterator, the removal is deferred until all iterators are dropped.
+    ///
+    /// Unlike [`Subscription::unsubscribe`], this method can be called from
+    /// inside the loop iterating over the subscriber list (e.g., by a
+    /// subscriber unsubscribing itself). The element remains visible to the
+    /// active iterators until the removal takes place.
+    pub fn unsubscribe_deferred(self) {
+        if let Some(pool) = self.pool.upgrade() {
+            pool.unsubscribe_deferred(self.ptr, self.id);
+        }
+    }
+
     pub fn untype(self) -> UntypedSubscription
     where
         T: 'static,
@@ -86,58 +267,94 @@ impl<T> Subscription<T> {
         UntypedSubscription {
             pool: self.pool,
             ptr: self.ptr,
+            id: self.id,
         }
     }
 }
 
 #[derive(Debug)]
 pub struct Iter<'a, T> {
-    borrow: Ref<'a, IterablePool<T>>,
-    inner: iterpool::Iter<'a, T>,
+    shared: &'a Shared<T>,
+    /// Always `Some(_)` until dropped.
+    borrow: Option<Ref<'a, Inner<T>>>,
+    pool: &'a IterablePool<Element<T>>,
+    index: btree_map::Values<'a, IndexKey, PoolPtr>,
+}
+
+impl<T> Drop for Iter<'_, T> {
+    fn drop(&mut self) {
+        self.borrow = None;
+        self.shared.flush_pending_removals();
+    }
 }
 
 impl<'a, T> Iterator for Iter<'a, T> {
     type Item = &'a T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next()
+        let ptr = *self.index.next()?;
+        Some(&self.pool[ptr].x)
     }
 }
 
 #[derive(Debug)]
 pub struct IterMut<'a, T> {
-    borrow: RefMut<'a, IterablePool<T>>,
-    inner: iterpool::IterMut<'a, T>,
+    shared: &'a Shared<T>,
+    /// Always `Some(_)` until dropped.
+    borrow: Option<RefMut<'a, Inner<T>>>,
+    pool: &'a mut IterablePool<Element<T>>,
+    index: btree_map::Values<'a, IndexKey, PoolPtr>,
+}
+
+impl<T> Drop for IterMut<'_, T> {
+    fn drop(&mut self) {
+        self.borrow = None;
+        self.shared.flush_pending_removals();
+    }
 }
 
 impl<'a, T> Iterator for IterMut<'a, T> {
     type Item = &'a mut T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.inner.next()
+        let ptr = *self.index.next()?;
+        let e: *mut Element<T> = &mut self.pool[ptr];
+        // Safety: `index` never yields the same element twice, so the
+        //         returned references don't alias
+        Some(unsafe { &mut (*e).x })
     }
 }
 
 /// Type-erased [`Subscription`].
 pub struct UntypedSubscription {
-    pool: Weak<RefCell<dyn ErasedPool>>,
+    pool: Weak<dyn ErasedShared>,
     ptr: PoolPtr,
+    id: usize,
 }
 
-trait ErasedPool {
-    fn deallocate(&mut self, ptr: PoolPtr);
+trait ErasedShared {
+    fn unsubscribe(&self, ptr: PoolPtr, id: usize) -> Result<Option<()>, IterationActive>;
+    fn unsubscribe_deferred(&self, ptr: PoolPtr, id: usize);
 }
 
-impl<T> ErasedPool for IterablePool<T> {
-    fn deallocate(&mut self, ptr: PoolPtr) {
-        self.deallocate(ptr);
+impl<T> ErasedShared for Shared<T> {
+    fn unsubscribe(&self, ptr: PoolPtr, id: usize) -> Result<Option<()>, IterationActive> {
+        self.unsubscribe(ptr, id).map(|x| x.map(|_| ()))
+    }
+
+    fn unsubscribe_deferred(&self, ptr: PoolPtr, id: usize) {
+        self.unsubscribe_deferred(ptr, id)
     }
 }
 
 enum Never {}
 
-impl ErasedPool for Never {
-    fn deallocate(&mut self, _: PoolPtr) {
+impl ErasedShared for Never {
+    fn unsubscribe(&self, _: PoolPtr, _: usize) -> Result<Option<()>, IterationActive> {
+        match *self {}
+    }
+
+    fn unsubscribe_deferred(&self, _: PoolPtr, _: usize) {
         match *self {}
     }
 }
@@ -160,21 +377,30 @@ impl UntypedSubscription {
     /// Construct an `UntypedSubscription` that refers to no backing object.
     pub fn new() -> Self {
         Self {
-            pool: Weak::<RefCell<Never>>::new(),
+            pool: Weak::<Never>::new(),
             ptr: PoolPtr::uninitialized(),
+            id: 0,
         }
     }
 
     /// Remove the element that `self` represents.
-    #[allow(clippy::unit_arg)] // for symmetry with `Subscription::unsubscribe`
     pub fn unsubscribe(self) -> Result<Option<()>, IterationActive> {
         if let Some(pool) = self.pool.upgrade() {
-            let mut pool = pool.try_borrow_mut().map_err(|_| IterationActive)?;
-            Ok(Some(pool.deallocate(self.ptr)))
+            pool.unsubscribe(self.ptr, self.id)
         } else {
             Ok(None)
         }
     }
+
+    /// Remove the element that `self` represents. If there is an active
+    /// iterator, the removal is deferred until all iterators are dropped.
+    ///
+    /// See [`Subscription::unsubscribe_deferred`].
+    pub fn unsubscribe_deferred(self) {
+        if let Some(pool) = self.pool.upgrade() {
+            pool.unsubscribe_deferred(self.ptr, self.id);
+        }
+    }
 }
 
 /// An error type returned when a subscription could not be removed because
@@ -220,6 +446,203 @@ mod tests {
         assert_eq!(ss.unsubscribe(), Ok(None));
     }
 
+    /// Get the elements of `list` in ascending order.
+    fn sorted_elements(list: &SubscriberList<i32>) -> Vec<i32> {
+        let mut v: Vec<_> = list.iter().cloned().collect();
+        v.sort();
+        v
+    }
+
+    #[test]
+    fn retain() {
+        let mut list = SubscriberList::new();
+        let ss1 = list.insert(1);
+        let ss2 = list.insert(2);
+        let ss3 = list.insert(3);
+        let ss4 = list.insert(4).untype();
+
+        list.retain(|x| *x % 2 == 1);
+        assert_eq!(sorted_elements(&list), vec![1, 3]);
+
+        // Reuse the vacant entries
+        let ss5 = list.insert(5);
+        let ss6 = list.insert(6);
+        assert_eq!(sorted_elements(&list), vec![1, 3, 5, 6]);
+
+        // Tokens of the removed elements don't affect the new elements
+        assert_eq!(ss2.unsubscribe(), Ok(None));
+        assert_eq!(ss4.unsubscribe(), Ok(None));
+        assert_eq!(sorted_elements(&list), vec![1, 3, 5, 6]);
+
+        // Tokens of the surviving elements are still valid
+        assert_eq!(ss3.unsubscribe(), Ok(Some(3)));
+        assert_eq!(ss5.unsubscribe(), Ok(Some(5)));
+        assert_eq!(sorted_elements(&list), vec![1, 6]);
+
+        list.retain(|x| {
+            *x += 10;
+            true
+        });
+        assert_eq!(sorted_elements(&list), vec![11, 16]);
+
+        assert_eq!(ss1.unsubscribe(), Ok(Some(11)));
+        assert_eq!(ss6.unsubscribe(), Ok(Some(16)));
+        assert_eq!(sorted_elements(&list), vec![]);
+    }
+
+    #[test]
+    fn retain_flushes_pending_removals() {
+        struct UnsubscribeOnDrop(Option<UntypedSubscription>);
+
+        impl Drop for UnsubscribeOnDrop {
+            fn drop(&mut self) {
+                if let Some(ss) = self.0.take() {
+                    ss.unsubscribe_deferred();
+                }
+            }
+        }
+
+        let mut list = SubscriberList::new();
+        let ss1 = list.insert((1, UnsubscribeOnDrop(None))).untype();
+        let _ss2 = list.insert((2, UnsubscribeOnDrop(Some(ss1))));
+        let _ss3 = list.insert((3, UnsubscribeOnDrop(None)));
+
+        // Removing the second element unsubscribes the first one
+        list.retain(|e| e.0 != 2);
+
+        let remaining: Vec<_> = list.iter().map(|e| e.0).collect();
+        assert_eq!(remaining, vec![3]);
+    }
+
+    #[test]
+    fn unsubscribe_deferred() {
+        let mut list = SubscriberList::new();
+        let mut sss: Vec<_> = (0..4).map(|i| Some(list.insert(i))).collect();
+
+        // Subscribers unsubscribing themselves
+        let mut visited = Vec::new();
+        for &x in list.iter() {
+            visited.push(x);
+            if x % 2 == 0 {
+                sss[x as usize].take().unwrap().unsubscribe_deferred();
+            }
+        }
+        visited.sort();
+        assert_eq!(visited, vec![0, 1, 2, 3]);
+        assert_eq!(sorted_elements(&list), vec![1, 3]);
+
+        // The removal is deferred until the last iterator is dropped
+        let it1 = list.iter();
+        let it2 = list.iter();
+        sss[1].take().unwrap().unsubscribe_deferred();
+        assert_eq!(sss[3].take().unwrap().unsubscribe(), Err(IterationActive));
+        drop(it1);
+        let mut remaining: Vec<_> = it2.cloned().collect();
+        remaining.sort();
+        assert_eq!(remaining, vec![1, 3]);
+        assert_eq!(sorted_elements(&list), vec![3]);
+
+        // No active iterators; removed immediately
+        let ss = list.insert(4).untype();
+        ss.unsubscribe_deferred();
+        assert_eq!(sorted_elements(&list), vec![3]);
+    }
+
+    #[test]
+    fn unsubscribe_deferred_iter_mut() {
+        let mut list = SubscriberList::new();
+        let ss1 = list.insert(1).untype();
+        let ss2 = list.insert(2);
+
+        let mut ss1 = Some(ss1);
+        for x in list.iter_mut() {
+            *x += 10;
+            if let Some(ss1) = ss1.take() {
+                ss1.unsubscribe_deferred();
+            }
+        }
+
+        assert_eq!(sorted_elements(&list), vec![12]);
+        assert_eq!(ss2.unsubscribe(), Ok(Some(12)));
+    }
+
+    #[test]
+    fn unsubscribe_deferred_list_dropped() {
+        let mut list = SubscriberList::new();
+        let ss = list.insert(1);
+        drop(list);
+        ss.unsubscribe_deferred();
+    }
+
+    #[test]
+    fn notify() {
+        type Handler = Box<dyn Fn(&i32)>;
+
+        let calls = Rc::new(RefCell::new(Vec::new()));
+        let sibling_ss: Rc<RefCell<Option<Subscription<Handler>>>> = Rc::default();
+
+        let mut list: SubscriberList<Handler> = SubscriberList::new();
+        let _ss1 = list.insert(Box::new({
+            let calls = Rc::clone(&calls);
+            let sibling_ss = Rc::clone(&sibling_ss);
+            move |&x| {
+                calls.borrow_mut().push(("unsubscriber", x));
+                if let Some(ss) = sibling_ss.borrow_mut().take() {
+                    ss.unsubscribe_deferred();
+                }
+            }
+        }));
+        *sibling_ss.borrow_mut() = Some(list.insert(Box::new({
+            let calls = Rc::clone(&calls);
+            move |&x| calls.borrow_mut().push(("sibling", x))
+        })));
+
+        // The sibling is still called even if it's removed before its turn
+        list.notify(&1);
+        calls.borrow_mut().sort();
+        assert_eq!(*calls.borrow(), vec![("sibling", 1), ("unsubscriber", 1)]);
+        calls.borrow_mut().clear();
+
+        // The sibling has been removed by now
+        list.notify(&2);
+        assert_eq!(*calls.borrow(), vec![("unsubscriber", 2)]);
+    }
+
+    #[test]
+    fn priority() {
+        let mut list = SubscriberList::new();
+        let _ss1 = list.insert_with_priority("paint1", 10);
+        let ss2 = list.insert("default1");
+        let _ss3 = list.insert_with_priority("layout1", -10);
+        let ss4 = list.insert_with_priority("paint2", 10);
+        let _ss5 = list.insert_with_priority("layout2", -10);
+
+        assert_eq!(
+            list.iter().cloned().collect::<Vec<_>>(),
+            vec!["layout1", "layout2", "default1", "paint1", "paint2"]
+        );
+
+        assert_eq!(ss2.unsubscribe(), Ok(Some("default1")));
+        assert_eq!(ss4.unsubscribe(), Ok(Some("paint2")));
+
+        // Reuse the vacant entries
+        let _ss6 = list.insert_with_priority("paint3", 10);
+        let _ss7 = list.insert_with_priority("layout3", -10);
+        let _ss8 = list.insert("default2");
+
+        assert_eq!(
+            list.iter().cloned().collect::<Vec<_>>(),
+            vec!["layout1", "layout2", "layout3", "default2", "paint1", "paint3"]
+        );
+
+        list.retain(|x| !x.ends_with('2'));
+
+        assert_eq!(
+            list.iter_mut().map(|x| *x).collect::<Vec<_>>(),
+            vec!["layout1", "layout3", "paint1", "paint3"]
+        );
+    }
+
     #[test]
     fn untyped_remove_subscription() {
         let mut list = SubscriberList::new();
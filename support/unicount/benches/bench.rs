@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use std::convert::TryFrom;
-use unicount::{num_scalars_in_str, str_next, str_prev};
+use unicount::{num_scalars_checked, num_scalars_in_str, str_next, str_prev};
 
 struct Xorshift32(u32);
 
@@ -65,6 +65,22 @@ fn criterion_benchmark(c: &mut Criterion) {
                 b.iter(|| st.chars().count());
             });
         }
+        {
+            let mut group = c.benchmark_group("num_scalars_checked");
+            group.throughput(Throughput::Elements(len as u64));
+
+            group.bench_function(BenchmarkId::new("unicount", len), move |b| {
+                let st = random_utf8(len, &mut Xorshift32(42));
+                let st = st.as_bytes();
+                b.iter(|| num_scalars_checked(st));
+            });
+
+            group.bench_function(BenchmarkId::new("str::from_utf8", len), move |b| {
+                let st = random_utf8(len, &mut Xorshift32(42));
+                let st = st.as_bytes();
+                b.iter(|| std::str::from_utf8(st).map(|st| st.chars().count()));
+            });
+        }
         {
             let mut group = c.benchmark_group("str_next");
             group.throughput(Throughput::Elements(len as u64));
@@ -1,7 +1,16 @@
 //! Provides functions for measuring strings by the number of Unicode scalar
 //! values.
+//!
+//! The functions for navigating through extended grapheme clusters require
+//! the `unicode-segmentation` feature.
 use packed_simd::{i8x32, m8x32, u8x32, FromCast};
+use std::ops::Range;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+use std::sync::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "unicode-segmentation")]
+use unicode_segmentation::GraphemeCursor;
 
+/// `true` means the target architecture natively supports `u8x16`.
 const HAS_U8X16: bool = cfg!(all(
     any(target_arch = "x86", target_arch = "x86_64"),
     target_feature = "sse2"
@@ -12,6 +21,46 @@ const HAS_U8X16: bool = cfg!(all(
         target_feature = "neon"
     ));
 
+/// Check if the processor supports SSE2. The result is cached.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[inline]
+fn has_sse2() -> bool {
+    // 0 = unknown, 1 = unsupported, 2 = supported
+    static CACHE: AtomicU8 = AtomicU8::new(0);
+
+    match CACHE.load(Ordering::Relaxed) {
+        1 => false,
+        2 => true,
+        _ => {
+            let supported = is_x86_feature_detected!("sse2");
+            CACHE.store(if supported { 2 } else { 1 }, Ordering::Relaxed);
+            supported
+        }
+    }
+}
+
+/// Call `$inner` with `use_simd` set to whether `u8x16` can be used on the
+/// current processor.
+///
+/// If the target natively supports `u8x16`, this is resolved at compile
+/// time. Otherwise, on x86 and x86_64, SSE2 is detected at runtime.
+macro_rules! dispatch_simd {
+    ($inner:ident($($arg:ident: $ty:ty),*) -> $ret:ty) => {{
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            if !HAS_U8X16 && has_sse2() {
+                #[target_feature(enable = "sse2")]
+                unsafe fn inner_sse2($($arg: $ty),*) -> $ret {
+                    $inner(true, $($arg),*)
+                }
+                // Safety: We just checked that SSE2 is supported.
+                return unsafe { inner_sse2($($arg),*) };
+            }
+        }
+        $inner(HAS_U8X16, $($arg),*)
+    }};
+}
+
 fn is_utf8_continuation(x: u8) -> bool {
     (x as i8) < -0x40
 }
@@ -86,6 +135,150 @@ pub fn str_floor(s: &str, i: usize) -> usize {
     utf8_str_floor(s.as_bytes(), i)
 }
 
+/// Find the first UTF-16 unit boundary after `i` in a given UTF-8 string.
+/// Returns `(s.len(), false)` if there is no such boundary.
+///
+/// UTF-16 unit boundaries are represented by pairs of a byte offset and a
+/// flag. The byte offset is always on a scalar boundary. The flag is `true`
+/// if the position is between the surrogate pair representing the scalar
+/// value starting at the byte offset (which must be outside the Basic
+/// Multilingual Plane in this case).
+///
+/// `i.0` must be on a scalar boundary.
+///
+/// # Example
+///
+///     use unicount::utf16_unit_next;
+///     let s = "a\u{1f600}b";
+///     assert_eq!(utf16_unit_next(s, (0, false)), (1, false));
+///     assert_eq!(utf16_unit_next(s, (1, false)), (1, true));
+///     assert_eq!(utf16_unit_next(s, (1, true)), (5, false));
+///     assert_eq!(utf16_unit_next(s, (5, false)), (6, false));
+///     assert_eq!(utf16_unit_next(s, (6, false)), (6, false));
+///
+pub fn utf16_unit_next(s: &str, (i, mid_surrogate): (usize, bool)) -> (usize, bool) {
+    let bytes = s.as_bytes();
+    if mid_surrogate {
+        debug_assert!(
+            i < bytes.len() && bytes[i] >= 0xf0,
+            "`i` is not before a supplementary character"
+        );
+        (str_next(s, i), false)
+    } else if i < bytes.len() && bytes[i] >= 0xf0 {
+        // Stop between the high and low surrogates
+        (i, true)
+    } else {
+        (str_next(s, i), false)
+    }
+}
+
+/// Find the first UTF-16 unit boundary before `i` in a given UTF-8 string.
+/// Returns `(0, false)` if there is no such boundary.
+///
+/// See [`utf16_unit_next`] for how UTF-16 unit boundaries are represented.
+///
+/// `i.0` must be on a scalar boundary.
+///
+/// # Example
+///
+///     use unicount::utf16_unit_prev;
+///     let s = "a\u{1f600}b";
+///     assert_eq!(utf16_unit_prev(s, (6, false)), (5, false));
+///     assert_eq!(utf16_unit_prev(s, (5, false)), (1, true));
+///     assert_eq!(utf16_unit_prev(s, (1, true)), (1, false));
+///     assert_eq!(utf16_unit_prev(s, (1, false)), (0, false));
+///     assert_eq!(utf16_unit_prev(s, (0, false)), (0, false));
+///
+pub fn utf16_unit_prev(s: &str, (i, mid_surrogate): (usize, bool)) -> (usize, bool) {
+    if mid_surrogate {
+        debug_assert!(
+            i < s.len() && s.as_bytes()[i] >= 0xf0,
+            "`i` is not before a supplementary character"
+        );
+        (i, false)
+    } else if i == 0 {
+        (0, false)
+    } else {
+        let i = str_prev(s, i);
+        // Stop between the high and low surrogates
+        (i, s.as_bytes()[i] >= 0xf0)
+    }
+}
+
+/// Find the byte offset of the first extended grapheme cluster boundary after
+/// `i` in a given UTF-8 string. Returns `s.len()` if there is no such
+/// boundary.
+///
+/// `i` must be on a scalar boundary.
+///
+/// # Example
+///
+///     use unicount::grapheme_next;
+///     // "a", "👩‍🔬" (U+1F469 U+200D U+1F52C), "b"
+///     let s = "a\u{1f469}\u{200d}\u{1f52c}b";
+///     assert_eq!(grapheme_next(s, 0), 1);
+///     assert_eq!(grapheme_next(s, 1), 12);
+///     assert_eq!(grapheme_next(s, 12), 13);
+///     assert_eq!(grapheme_next(s, 13), 13);
+///
+#[cfg(feature = "unicode-segmentation")]
+pub fn grapheme_next(s: &str, i: usize) -> usize {
+    debug_assert!(s.is_char_boundary(i), "`i` is not on a scalar boundary");
+
+    // Fast path: ASCII characters never form a cluster except for CR LF
+    let bytes = s.as_bytes();
+    if i < bytes.len() && bytes[i] < 0x80 {
+        match bytes.get(i + 1) {
+            None => return i + 1,
+            Some(&b) if b < 0x80 && (bytes[i], b) != (b'\r', b'\n') => return i + 1,
+            _ => {}
+        }
+    }
+
+    // The chunk is the whole string, so `GraphemeIncomplete` is impossible
+    GraphemeCursor::new(i, s.len(), true)
+        .next_boundary(s, 0)
+        .unwrap()
+        .unwrap_or(s.len())
+}
+
+/// Find the byte offset of the first extended grapheme cluster boundary
+/// before `i` in a given UTF-8 string. Returns `0` if there is no such
+/// boundary.
+///
+/// `i` must be on a scalar boundary.
+///
+/// # Example
+///
+///     use unicount::grapheme_prev;
+///     // "a", "👩‍🔬" (U+1F469 U+200D U+1F52C), "b"
+///     let s = "a\u{1f469}\u{200d}\u{1f52c}b";
+///     assert_eq!(grapheme_prev(s, 13), 12);
+///     assert_eq!(grapheme_prev(s, 12), 1);
+///     assert_eq!(grapheme_prev(s, 1), 0);
+///     assert_eq!(grapheme_prev(s, 0), 0);
+///
+#[cfg(feature = "unicode-segmentation")]
+pub fn grapheme_prev(s: &str, i: usize) -> usize {
+    debug_assert!(s.is_char_boundary(i), "`i` is not on a scalar boundary");
+
+    // Fast path: ASCII characters never form a cluster except for CR LF
+    let bytes = s.as_bytes();
+    if i > 0 && bytes[i - 1] < 0x80 {
+        match i.checked_sub(2).map(|k| bytes[k]) {
+            None => return i - 1,
+            Some(b) if b < 0x80 && (b, bytes[i - 1]) != (b'\r', b'\n') => return i - 1,
+            _ => {}
+        }
+    }
+
+    // The chunk is the whole string, so `GraphemeIncomplete` is impossible
+    GraphemeCursor::new(i, s.len(), true)
+        .prev_boundary(s, 0)
+        .unwrap()
+        .unwrap_or(0)
+}
+
 /// Mapping from the hi-nibbles of UTF-8-encoded bytes to continuation byte
 /// counts.
 //
@@ -194,11 +387,21 @@ pub fn num_scalars_in_str(s: &str) -> usize {
 
 /// Calculate the number of scalar values in a given byte slice assumed to be a
 /// UTF-8 string.
-pub fn num_scalars_in_utf8_str(mut s: &[u8]) -> usize {
+///
+/// If the target doesn't natively support SIMD, on x86 and x86_64, SSE2 is
+/// detected at runtime.
+pub fn num_scalars_in_utf8_str(s: &[u8]) -> usize {
+    dispatch_simd!(num_scalars_in_utf8_str_inner(s: &[u8]) -> usize)
+}
+
+/// The implementation of `num_scalars_in_utf8_str`. `use_simd` must be `false`
+/// unless `u8x16` is supported natively or enabled by `#[target_feature]`.
+#[inline(always)]
+fn num_scalars_in_utf8_str_inner(use_simd: bool, mut s: &[u8]) -> usize {
     let mut count = 0usize;
 
     // Native `u8x16` support is enough for emulating `u8x32`
-    if HAS_U8X16 {
+    if use_simd {
         // Count the non-continuation bytes
         while s.len() >= 32 {
             let s32 = u8x32::from_slice_unaligned(&s[0..32]);
@@ -216,6 +419,174 @@ pub fn num_scalars_in_utf8_str(mut s: &[u8]) -> usize {
     count
 }
 
+/// Calculate the number of scalar values in a given byte slice while
+/// validating it as a UTF-8 string.
+///
+/// Returns `Err(i)` if the byte slice is not a valid UTF-8 string, where `i`
+/// is the byte offset of the first invalid sequence (which is identical to
+/// [`std::str::Utf8Error::valid_up_to`]).
+///
+/// If the target doesn't natively support SIMD, on x86 and x86_64, SSE2 is
+/// detected at runtime.
+///
+/// # Example
+///
+///     use unicount::num_scalars_checked;
+///     assert_eq!(num_scalars_checked("искра".as_bytes()), Ok(5));
+///     assert_eq!(num_scalars_checked(b"abc\xd0"), Err(3));
+///     assert_eq!(num_scalars_checked(b"abc\xed\xa0\x80def"), Err(3));
+///
+pub fn num_scalars_checked(s: &[u8]) -> Result<usize, usize> {
+    dispatch_simd!(num_scalars_checked_inner(s: &[u8]) -> Result<usize, usize>)
+}
+
+/// The implementation of `num_scalars_checked`. `use_simd` must be `false`
+/// unless `u8x16` is supported natively or enabled by `#[target_feature]`.
+#[inline(always)]
+fn num_scalars_checked_inner(use_simd: bool, s: &[u8]) -> Result<usize, usize> {
+    let mut count = 0usize;
+    let mut i = 0;
+
+    // Until `i` reaches `careful_end`, skip the SIMD path
+    let mut careful_end = 0;
+
+    while i < s.len() {
+        // Native `u8x16` support is enough for emulating `u8x32`
+        if use_simd && i >= careful_end && s.len() - i >= 32 {
+            let s32 = u8x32::from_slice_unaligned(&s[i..i + 32]);
+            if i8x32::from_cast(s32).ge(i8x32::splat(0)).all() {
+                // The chunk is all ASCII
+                count += 32;
+                i += 32;
+                continue;
+            }
+
+            // The chunk contains a non-ASCII byte. Validate it one sequence at
+            // a time.
+            careful_end = i + 32;
+        }
+
+        let b = s[i];
+        let (len, second) = match b {
+            0x00..=0x7f => (1, 0..=0),
+            0xc2..=0xdf => (2, 0x80..=0xbf),
+            0xe0 => (3, 0xa0..=0xbf),
+            0xed => (3, 0x80..=0x9f), // Exclude surrogates
+            0xe1..=0xef => (3, 0x80..=0xbf),
+            0xf0 => (4, 0x90..=0xbf),
+            0xf4 => (4, 0x80..=0x8f), // Exclude > U+10FFFF
+            0xf1..=0xf3 => (4, 0x80..=0xbf),
+            _ => return Err(i),
+        };
+
+        if len > 1 {
+            match s.get(i + 1) {
+                Some(b) if second.contains(b) => {}
+                _ => return Err(i),
+            }
+            for k in i + 2..i + len {
+                match s.get(k) {
+                    Some(&b) if is_utf8_continuation(b) => {}
+                    _ => return Err(i),
+                }
+            }
+        }
+
+        count += 1;
+        i += len;
+    }
+
+    Ok(count)
+}
+
+/// Calculate the number of scalar values in the specified range of a given
+/// byte slice assumed to be a UTF-8 string.
+///
+/// Both endpoints of `range` must be on a scalar boundary.
+///
+/// # Example
+///
+///     use unicount::num_scalars_in_range;
+///     assert_eq!(num_scalars_in_range("искра".as_bytes(), 2..8), 3);
+///
+pub fn num_scalars_in_range(s: &[u8], range: Range<usize>) -> usize {
+    debug_assert!(range.start <= range.end && range.end <= s.len());
+    debug_assert!(
+        range.start >= s.len() || !is_utf8_continuation(s[range.start]),
+        "`range.start` is not on a scalar boundary"
+    );
+    debug_assert!(
+        range.end >= s.len() || !is_utf8_continuation(s[range.end]),
+        "`range.end` is not on a scalar boundary"
+    );
+
+    num_scalars_in_utf8_str(&s[range])
+}
+
+/// Convert scalar indices to byte offsets in a given UTF-8 string.
+///
+/// `indices` is expected to be sorted in ascending order, in which case the
+/// string is scanned only once. Unsorted indices are still converted
+/// correctly, but each index smaller than its predecessor causes a rescan
+/// from the beginning of the string. Indices greater than or equal to the
+/// number of scalar values in `s` are clamped to `s.len()`.
+///
+/// # Example
+///
+///     use unicount::scalar_indices_to_byte_offsets;
+///     assert_eq!(
+///         scalar_indices_to_byte_offsets("искра", &[0, 1, 3, 5, 42]),
+///         vec![0, 2, 6, 10, 10],
+///     );
+///
+pub fn scalar_indices_to_byte_offsets(s: &str, indices: &[usize]) -> Vec<usize> {
+    let s = s.as_bytes();
+    dispatch_simd!(scalar_indices_to_byte_offsets_inner(s: &[u8], indices: &[usize]) -> Vec<usize>)
+}
+
+/// The implementation of `scalar_indices_to_byte_offsets`. `use_simd` must be
+/// `false` unless `u8x16` is supported natively or enabled by
+/// `#[target_feature]`.
+#[inline(always)]
+fn scalar_indices_to_byte_offsets_inner(use_simd: bool, s: &[u8], indices: &[usize]) -> Vec<usize> {
+    // The current position. `i` is on a scalar boundary and is the byte offset
+    // of the `scalar_i`-th scalar value (or `s.len()`).
+    let mut i = 0;
+    let mut scalar_i = 0;
+
+    indices
+        .iter()
+        .map(|&target| {
+            if target < scalar_i {
+                // Unsorted; start over
+                i = 0;
+                scalar_i = 0;
+            }
+
+            // Native `u8x16` support is enough for emulating `u8x32`
+            if use_simd {
+                // Skip 32-byte chunks not containing the target
+                while s.len() - i >= 32 {
+                    let s32 = u8x32::from_slice_unaligned(&s[i..i + 32]);
+                    let count = is_not_utf8_continuation_u8x32(s32).bitmask().count_ones() as usize;
+                    if target - scalar_i < count {
+                        break;
+                    }
+                    i = utf8_str_ceil(s, i + 32);
+                    scalar_i += count;
+                }
+            }
+
+            while scalar_i < target && i < s.len() {
+                i = utf8_str_next(s, i);
+                scalar_i += 1;
+            }
+
+            i
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -238,6 +609,133 @@ mod tests {
         true
     }
 
+    #[quickcheck]
+    fn test_num_scalars_checked(encoded: Vec<u8>, corrupt: Vec<(usize, u8)>) -> bool {
+        // Prepend an ASCII run to exercise the SIMD path
+        let mut bytes = b"0123456789abcdef0123456789abcdef".to_vec();
+        bytes.extend_from_slice(mk_random_str(&encoded).as_bytes());
+        for &(i, b) in corrupt.iter() {
+            if !bytes.is_empty() {
+                let i = i % bytes.len();
+                bytes[i] = b;
+            }
+        }
+        log::debug!("bytes = {:x?}", bytes);
+
+        let expected = match std::str::from_utf8(&bytes) {
+            Ok(st) => Ok(st.chars().count()),
+            Err(e) => Err(e.valid_up_to()),
+        };
+        assert_eq!(num_scalars_checked(&bytes), expected);
+        true
+    }
+
+    #[test]
+    fn test_num_scalars_checked_edge_cases() {
+        let cases: &[&[u8]] = &[
+            b"\x7f",
+            b"\x80",
+            b"\xc1\xbf",
+            b"\xc2\x80",
+            b"\xe0\x9f\xbf",
+            b"\xe0\xa0\x80",
+            b"\xed\x9f\xbf",
+            b"\xed\xa0\x80",
+            b"\xef\xbf\xbf",
+            b"\xf0\x8f\xbf\xbf",
+            b"\xf0\x90\x80\x80",
+            b"\xf4\x8f\xbf\xbf",
+            b"\xf4\x90\x80\x80",
+            b"\xf5\x80\x80\x80",
+            b"\xf0\x90\x80",
+            b"\xf0\x90\x80a",
+        ];
+        for &bytes in cases {
+            let expected = match std::str::from_utf8(bytes) {
+                Ok(st) => Ok(st.chars().count()),
+                Err(e) => Err(e.valid_up_to()),
+            };
+            assert_eq!(num_scalars_checked(bytes), expected, "{:x?}", bytes);
+        }
+    }
+
+    #[quickcheck]
+    fn test_scalar_indices_to_byte_offsets(encoded: Vec<u8>, mut indices: Vec<usize>) -> bool {
+        let st = mk_random_str(&encoded);
+        log::debug!("st = {:?} ({:x?})", st, st.as_bytes());
+
+        let boundaries: Vec<_> = st
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(st.len()))
+            .collect();
+
+        // Include out-of-range indices occasionally
+        for i in indices.iter_mut() {
+            *i %= boundaries.len() + 2;
+        }
+        log::debug!("indices = {:?}", indices);
+
+        let expected = |indices: &[usize]| -> Vec<usize> {
+            indices
+                .iter()
+                .map(|&i| boundaries[i.min(boundaries.len() - 1)])
+                .collect()
+        };
+
+        // Unsorted
+        assert_eq!(
+            scalar_indices_to_byte_offsets(&st, &indices),
+            expected(&indices)
+        );
+
+        // Sorted
+        indices.sort();
+        assert_eq!(
+            scalar_indices_to_byte_offsets(&st, &indices),
+            expected(&indices)
+        );
+
+        true
+    }
+
+    #[quickcheck]
+    fn test_num_scalars_in_range(encoded: Vec<u8>, i1: usize, i2: usize) -> bool {
+        let st = mk_random_str(&encoded);
+        log::debug!("st = {:?} ({:x?})", st, st.as_bytes());
+
+        let boundaries: Vec<_> = st
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(st.len()))
+            .collect();
+
+        let mut i1 = boundaries[i1 % boundaries.len()];
+        let mut i2 = boundaries[i2 % boundaries.len()];
+        if i1 > i2 {
+            std::mem::swap(&mut i1, &mut i2);
+        }
+        log::debug!("range = {:?}", i1..i2);
+
+        let bytes = st.as_bytes();
+        assert_eq!(
+            num_scalars_in_range(bytes, i1..i2),
+            st[i1..i2].chars().count()
+        );
+
+        // Boundary cases
+        assert_eq!(num_scalars_in_range(bytes, 0..0), 0);
+        assert_eq!(num_scalars_in_range(bytes, st.len()..st.len()), 0);
+        assert_eq!(num_scalars_in_range(bytes, 0..st.len()), st.chars().count());
+        assert_eq!(num_scalars_in_range(bytes, 0..i2), st[..i2].chars().count());
+        assert_eq!(
+            num_scalars_in_range(bytes, i1..st.len()),
+            st[i1..].chars().count()
+        );
+
+        true
+    }
+
     #[quickcheck]
     fn test_str_next_prev(encoded: Vec<u8>) -> bool {
         let st = mk_random_str(&encoded);
@@ -262,6 +760,76 @@ mod tests {
         true
     }
 
+    #[quickcheck]
+    fn test_utf16_unit_next_prev(encoded: Vec<u8>) -> bool {
+        let st = mk_random_str(&encoded);
+        log::debug!("st = {:?}", st);
+
+        // Enumerate UTF-16 unit boundaries
+        let units: Vec<_> = st
+            .char_indices()
+            .flat_map(|(i, c)| {
+                std::iter::once((i, false)).chain(if c.len_utf16() == 2 {
+                    Some((i, true))
+                } else {
+                    None
+                })
+            })
+            .chain(std::iter::once((st.len(), false)))
+            .collect();
+        assert_eq!(units.len(), st.encode_utf16().count() + 1);
+
+        assert_eq!(utf16_unit_next(&st, (st.len(), false)), (st.len(), false));
+        for w in units.windows(2) {
+            assert_eq!(utf16_unit_next(&st, w[0]), w[1]);
+        }
+
+        assert_eq!(utf16_unit_prev(&st, (0, false)), (0, false));
+        for w in units.windows(2) {
+            assert_eq!(utf16_unit_prev(&st, w[1]), w[0]);
+        }
+
+        true
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    #[quickcheck]
+    fn test_grapheme_next_prev(encoded: Vec<u8>) -> bool {
+        use unicode_segmentation::UnicodeSegmentation;
+
+        let st = mk_random_str(&encoded);
+        log::debug!("st = {:?}", st);
+
+        let clusters: Vec<_> = st
+            .grapheme_indices(true)
+            .map(|(i, _)| i)
+            .chain(std::iter::once(st.len()))
+            .collect();
+
+        assert_eq!(grapheme_next(&st, st.len()), st.len());
+        for w in clusters.windows(2) {
+            assert_eq!(grapheme_next(&st, w[0]), w[1]);
+        }
+
+        assert_eq!(grapheme_prev(&st, 0), 0);
+        for w in clusters.windows(2) {
+            assert_eq!(grapheme_prev(&st, w[1]), w[0]);
+        }
+
+        true
+    }
+
+    #[cfg(feature = "unicode-segmentation")]
+    #[test]
+    fn test_grapheme_next_prev_crlf() {
+        let st = "a\r\nb\r\r\n";
+        let clusters = [0, 1, 3, 4, 5, 7];
+        for w in clusters.windows(2) {
+            assert_eq!(grapheme_next(st, w[0]), w[1]);
+            assert_eq!(grapheme_prev(st, w[1]), w[0]);
+        }
+    }
+
     #[quickcheck]
     fn test_str_floor_ceil(encoded: Vec<u8>) -> bool {
         let st = mk_random_str(&encoded);
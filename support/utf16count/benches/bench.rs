@@ -1,6 +1,6 @@
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use std::convert::TryFrom;
-use utf16count::utf16_len;
+use utf16count::{find_utf16_pos, utf16_len};
 
 struct Xorshift32(u32);
 
@@ -33,6 +33,15 @@ fn criterion_benchmark(c: &mut Criterion) {
             b.iter(|| utf16_len(&st));
         });
 
+        // On a target without SSSE3 enabled at compile time, short inputs
+        // show the overhead of the runtime feature detection
+        group.bench_function(BenchmarkId::new("utf16count_find", len), move |b| {
+            let st = random_utf8(len, &mut Xorshift32(42));
+            let st = &st[..];
+            let pos = utf16_len(st) / 2;
+            b.iter(|| find_utf16_pos(pos, &st));
+        });
+
         group.bench_function(BenchmarkId::new("str::encode_utf16", len), move |b| {
             let st = random_utf8(len, &mut Xorshift32(42));
             let st = &st[..];
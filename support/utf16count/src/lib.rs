@@ -1,4 +1,14 @@
 //! Provides functions for measuring strings by the number of UTF-16 units.
+//!
+//! This crate supports `no_std` environments. The default feature `std` must
+//! be disabled in such environments. The SIMD implementation is still used if
+//! the target supports it, but the runtime feature detection is not.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+use core::{
+    mem::transmute,
+    sync::atomic::{AtomicU8, Ordering},
+};
 use packed_simd::u8x16;
 
 /// Mapping from the hi-nibbles of UTF-8-encoded bytes to UTF-16 unit counts.
@@ -35,6 +45,39 @@ const NIBBLE_TO_UTF16_LEN_U8X16: u8x16 = u8x16::new(
     nibble_to_utf16_len(15),
 );
 
+/// Mapping from the hi-nibbles of UTF-8-encoded bytes to scalar value counts
+/// (i.e., whether they are the first byte of a scalar value).
+//
+//                                     ┌ 10?? (continuation bytes)
+//                                     ╧═══
+const NIBBLE_TO_SCALAR_LEN: u16 = 0b1111000011111111;
+//                                  ╤═══    ╤═══════
+//                                  │       └ 0???? (U+0000–U+007F)
+//                                  └ 11?? (U+0080–U+10FFFF)
+
+const fn nibble_to_scalar_len(x: u8) -> u8 {
+    ((NIBBLE_TO_SCALAR_LEN >> x) & 1) as u8
+}
+
+const NIBBLE_TO_SCALAR_LEN_U8X16: u8x16 = u8x16::new(
+    nibble_to_scalar_len(0),
+    nibble_to_scalar_len(1),
+    nibble_to_scalar_len(2),
+    nibble_to_scalar_len(3),
+    nibble_to_scalar_len(4),
+    nibble_to_scalar_len(5),
+    nibble_to_scalar_len(6),
+    nibble_to_scalar_len(7),
+    nibble_to_scalar_len(8),
+    nibble_to_scalar_len(9),
+    nibble_to_scalar_len(10),
+    nibble_to_scalar_len(11),
+    nibble_to_scalar_len(12),
+    nibble_to_scalar_len(13),
+    nibble_to_scalar_len(14),
+    nibble_to_scalar_len(15),
+);
+
 /// Mapping from the hi-nibbles of UTF-8-encoded bytes to continuation byte
 /// counts.
 //
@@ -66,6 +109,113 @@ const HAS_U8X16_SHUFFLE1_DYN: bool =
             target_feature = "neon"
         ));
 
+/// Specifies how to perform the table lookup on `u8x16`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SimdMode {
+    /// Don't use SIMD.
+    None,
+    /// Use `u8x16::shuffle1_dyn`, which is natively supported by the target
+    /// (`HAS_U8X16_SHUFFLE1_DYN`).
+    Static,
+    /// Use SSSE3 intrinsics. Must only be used in a function where SSSE3 is
+    /// enabled by `#[target_feature]`.
+    #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+    Ssse3,
+}
+
+/// Get the `SimdMode` usable without runtime feature detection.
+#[inline]
+fn static_simd_mode() -> SimdMode {
+    if HAS_U8X16_SHUFFLE1_DYN {
+        SimdMode::Static
+    } else {
+        SimdMode::None
+    }
+}
+
+/// Check if the processor supports SSSE3. The result is cached.
+#[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+#[inline]
+fn has_ssse3() -> bool {
+    // 0 = unknown, 1 = unsupported, 2 = supported
+    static CACHE: AtomicU8 = AtomicU8::new(0);
+
+    match CACHE.load(Ordering::Relaxed) {
+        1 => false,
+        2 => true,
+        _ => {
+            let supported = is_x86_feature_detected!("ssse3");
+            CACHE.store(if supported { 2 } else { 1 }, Ordering::Relaxed);
+            supported
+        }
+    }
+}
+
+/// Call `$inner` with the best `SimdMode` available on the current
+/// processor.
+///
+/// If the target natively supports `u8x16::shuffle1_dyn`, this is resolved at
+/// compile time. Otherwise, on x86 and x86_64, SSSE3 is detected at runtime
+/// if the `std` feature is enabled.
+/// (On AArch64, NEON is enabled by default, so the compile-time check
+/// suffices in most cases.)
+macro_rules! dispatch_simd {
+    ($inner:ident($($arg:ident: $ty:ty),*) -> $ret:ty) => {{
+        #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            if !HAS_U8X16_SHUFFLE1_DYN && has_ssse3() {
+                #[target_feature(enable = "ssse3")]
+                unsafe fn inner_ssse3($($arg: $ty),*) -> $ret {
+                    $inner(SimdMode::Ssse3, $($arg),*)
+                }
+                // Safety: We just checked that SSSE3 is supported.
+                return unsafe { inner_ssse3($($arg),*) };
+            }
+        }
+        $inner(static_simd_mode(), $($arg),*)
+    }};
+}
+
+/// Map the hi-nibbles of UTF-8-encoded bytes (`x`) to UTF-16 unit counts.
+/// `mode` must not be `SimdMode::None`.
+#[inline(always)]
+fn nibbles_to_utf16_len(mode: SimdMode, x: u8x16) -> u8x16 {
+    shuffle_table(mode, NIBBLE_TO_UTF16_LEN_U8X16, x)
+}
+
+/// Map the hi-nibbles of UTF-8-encoded bytes (`x`) to scalar value counts.
+/// `mode` must not be `SimdMode::None`.
+#[inline(always)]
+fn nibbles_to_scalar_len(mode: SimdMode, x: u8x16) -> u8x16 {
+    shuffle_table(mode, NIBBLE_TO_SCALAR_LEN_U8X16, x)
+}
+
+/// Equivalent to `table.shuffle1_dyn(x)`. `mode` must not be
+/// `SimdMode::None`.
+#[inline(always)]
+fn shuffle_table(mode: SimdMode, table: u8x16, x: u8x16) -> u8x16 {
+    match mode {
+        #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+        SimdMode::Ssse3 => {
+            #[cfg(target_arch = "x86")]
+            use core::arch::x86::{__m128i, _mm_shuffle_epi8};
+            #[cfg(target_arch = "x86_64")]
+            use core::arch::x86_64::{__m128i, _mm_shuffle_epi8};
+
+            // `packed_simd` only uses `pshufb` if SSSE3 is enabled at compile
+            // time, so we have to use the intrinsic directly here.
+            // Safety: `u8x16` and `__m128i` have the same size. SSSE3 is
+            // enabled by the caller.
+            unsafe {
+                let table: __m128i = transmute(table);
+                let x: __m128i = transmute(x);
+                transmute::<__m128i, u8x16>(_mm_shuffle_epi8(table, x))
+            }
+        }
+        _ => table.shuffle1_dyn(x),
+    }
+}
+
 /// Get the number of UTF-16 units for a given UTF-8 string.
 ///
 /// # Performance
@@ -81,10 +231,15 @@ const HAS_U8X16_SHUFFLE1_DYN: bool =
 ///     assert_eq!(utf16_len("рыба"), 4);
 ///     assert_eq!(utf16_len("👨‍👩‍👦"), 8);
 ///
-pub fn utf16_len_of_utf8_str(mut s: &[u8]) -> usize {
+pub fn utf16_len_of_utf8_str(s: &[u8]) -> usize {
+    dispatch_simd!(utf16_len_of_utf8_str_inner(s: &[u8]) -> usize)
+}
+
+#[inline(always)]
+fn utf16_len_of_utf8_str_inner(mode: SimdMode, mut s: &[u8]) -> usize {
     let mut count = 0;
 
-    if HAS_U8X16_SHUFFLE1_DYN {
+    if mode != SimdMode::None {
         while s.len() >= 64 {
             // When building with `-Copt-level=3`, the codegen automatically
             // unrolls this loop by a factor of 4, but doesn't factor out
@@ -93,16 +248,16 @@ pub fn utf16_len_of_utf8_str(mut s: &[u8]) -> usize {
             // code size and throughput.
             let accum = {
                 let s16 = u8x16::from_slice_unaligned(&s[0..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&s[16..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&s[32..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&s[48..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             };
 
             count += accum.wrapping_sum() as usize;
@@ -189,23 +344,33 @@ impl FindUtf16PosResult {
 ///         utf16_extra: 1,
 ///     });
 ///
-pub fn find_utf16_pos_in_utf8_str(mut utf16_pos: usize, mut s: &[u8]) -> FindUtf16PosResult {
+pub fn find_utf16_pos_in_utf8_str(utf16_pos: usize, s: &[u8]) -> FindUtf16PosResult {
+    dispatch_simd!(find_utf16_pos_in_utf8_str_inner(utf16_pos: usize, s: &[u8])
+        -> FindUtf16PosResult)
+}
+
+#[inline(always)]
+fn find_utf16_pos_in_utf8_str_inner(
+    mode: SimdMode,
+    mut utf16_pos: usize,
+    mut s: &[u8],
+) -> FindUtf16PosResult {
     let mut utf8_cursor = 0;
 
-    if HAS_U8X16_SHUFFLE1_DYN {
+    if mode != SimdMode::None {
         while s.len() >= 64 {
             let accum = {
                 let s16 = u8x16::from_slice_unaligned(&s[0..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&s[16..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&s[32..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&s[48..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             };
 
             let chunk_u16len = accum.wrapping_sum() as usize;
@@ -244,6 +409,155 @@ pub fn find_utf16_pos(utf16_pos: usize, s: &str) -> FindUtf16PosResult {
     find_utf16_pos_in_utf8_str(utf16_pos, s.as_bytes())
 }
 
+/// Convert the given UTF-16 index to a UTF-8 index and count the scalar
+/// values preceding the found position.
+///
+/// The first element of the returned tuple is identical to the result of
+/// [`find_utf16_pos_in_utf8_str`]. The second element is the number of scalar
+/// values in `s[..utf8_cursor]`.
+///
+/// # Examples
+///
+///     use utf16count::{find_utf16_pos_with_scalar, FindUtf16PosResult};
+///
+///     assert_eq!(find_utf16_pos_with_scalar(2, "рыба"), (FindUtf16PosResult {
+///         utf8_cursor: 4,
+///         utf16_extra: 0,
+///     }, 2));
+///
+///     // 👨‍👩‍👦 starts with a surrogate pair
+///     assert_eq!(find_utf16_pos_with_scalar(3, "👨‍👩‍👦"), (FindUtf16PosResult {
+///         utf8_cursor: 7,
+///         utf16_extra: 0,
+///     }, 2));
+///
+pub fn find_utf16_pos_with_scalar_in_utf8_str(
+    utf16_pos: usize,
+    s: &[u8],
+) -> (FindUtf16PosResult, usize) {
+    dispatch_simd!(find_utf16_pos_with_scalar_in_utf8_str_inner(utf16_pos: usize, s: &[u8])
+        -> (FindUtf16PosResult, usize))
+}
+
+#[inline(always)]
+fn find_utf16_pos_with_scalar_in_utf8_str_inner(
+    mode: SimdMode,
+    mut utf16_pos: usize,
+    mut s: &[u8],
+) -> (FindUtf16PosResult, usize) {
+    let mut utf8_cursor = 0;
+    let mut scalar_count = 0;
+
+    if mode != SimdMode::None {
+        while s.len() >= 64 {
+            let (accum, scalar_accum) = {
+                let s16 = u8x16::from_slice_unaligned(&s[0..]) >> 4;
+                (
+                    nibbles_to_utf16_len(mode, s16),
+                    nibbles_to_scalar_len(mode, s16),
+                )
+            };
+            let (accum, scalar_accum) = {
+                let s16 = u8x16::from_slice_unaligned(&s[16..]) >> 4;
+                (
+                    accum + nibbles_to_utf16_len(mode, s16),
+                    scalar_accum + nibbles_to_scalar_len(mode, s16),
+                )
+            };
+            let (accum, scalar_accum) = {
+                let s16 = u8x16::from_slice_unaligned(&s[32..]) >> 4;
+                (
+                    accum + nibbles_to_utf16_len(mode, s16),
+                    scalar_accum + nibbles_to_scalar_len(mode, s16),
+                )
+            };
+            let (accum, scalar_accum) = {
+                let s16 = u8x16::from_slice_unaligned(&s[48..]) >> 4;
+                (
+                    accum + nibbles_to_utf16_len(mode, s16),
+                    scalar_accum + nibbles_to_scalar_len(mode, s16),
+                )
+            };
+
+            let chunk_u16len = accum.wrapping_sum() as usize;
+
+            if chunk_u16len > utf16_pos {
+                break;
+            }
+
+            s = &s[64..];
+            utf8_cursor += 64;
+            utf16_pos -= chunk_u16len;
+            scalar_count += scalar_accum.wrapping_sum() as usize;
+        }
+    }
+
+    for b in s.iter() {
+        let u16len = nibble_to_utf16_len(b >> 4) as usize;
+
+        if u16len > utf16_pos {
+            break;
+        }
+
+        utf8_cursor += 1;
+        utf16_pos -= u16len;
+        scalar_count += nibble_to_scalar_len(b >> 4) as usize;
+    }
+
+    (
+        FindUtf16PosResult {
+            utf8_cursor,
+            utf16_extra: utf16_pos,
+        },
+        scalar_count,
+    )
+}
+
+/// Convert the given UTF-16 index to a UTF-8 index and count the scalar
+/// values preceding the found position.
+///
+/// See [`find_utf16_pos_with_scalar_in_utf8_str`] for more.
+pub fn find_utf16_pos_with_scalar(utf16_pos: usize, s: &str) -> (FindUtf16PosResult, usize) {
+    find_utf16_pos_with_scalar_in_utf8_str(utf16_pos, s.as_bytes())
+}
+
+/// Find the longest prefix of the given UTF-8 string that fits in `max_units`
+/// UTF-16 units.
+///
+/// Returns a tuple `(utf8_len, utf16_len)`, where `utf8_len` is the length of
+/// the prefix in bytes and `utf16_len` is the number of UTF-16 units in the
+/// prefix (`utf16_len <= max_units`). The prefix always ends at a scalar value
+/// boundary. If `max_units` would split a surrogate pair, the whole scalar
+/// value is excluded from the prefix (i.e., it's rounded down).
+///
+/// # Examples
+///
+///     use utf16count::utf16_len_up_to;
+///
+///     assert_eq!(utf16_len_up_to("рыба", 2), (4, 2));
+///     assert_eq!(utf16_len_up_to("рыба", 7), (8, 4));
+///
+///     // 👨‍👩‍👦 starts with a surrogate pair
+///     assert_eq!(utf16_len_up_to("👨‍👩‍👦", 1), (0, 0));
+///     assert_eq!(utf16_len_up_to("👨‍👩‍👦", 3), (7, 3));
+///
+pub fn utf16_len_up_to_in_utf8_str(s: &[u8], max_units: usize) -> (usize, usize) {
+    let result = find_utf16_pos_in_utf8_str(max_units, s);
+
+    // If `utf8_cursor < s.len()`, `utf16_extra` is either zero or one. In the
+    // latter case, `max_units` falls between a surrogate pair, and
+    // `utf8_cursor` points the start of the scalar value.
+    (result.utf8_cursor, max_units - result.utf16_extra)
+}
+
+/// Find the longest prefix of the given UTF-8 string that fits in `max_units`
+/// UTF-16 units.
+///
+/// See [`utf16_len_up_to_in_utf8_str`] for more.
+pub fn utf16_len_up_to(s: &str, max_units: usize) -> (usize, usize) {
+    utf16_len_up_to_in_utf8_str(s.as_bytes(), max_units)
+}
+
 /// Convert the given UTF-16 index to a UTF-8 index This method traverses the
 /// input string in a reverse direction.
 ///
@@ -299,22 +613,32 @@ pub fn find_utf16_pos(utf16_pos: usize, s: &str) -> FindUtf16PosResult {
 ///         utf16_extra: 0,
 ///     });
 ///
-pub fn rfind_utf16_pos_in_utf8_str(mut utf16_pos: usize, mut s: &[u8]) -> FindUtf16PosResult {
-    if HAS_U8X16_SHUFFLE1_DYN {
+pub fn rfind_utf16_pos_in_utf8_str(utf16_pos: usize, s: &[u8]) -> FindUtf16PosResult {
+    dispatch_simd!(rfind_utf16_pos_in_utf8_str_inner(utf16_pos: usize, s: &[u8])
+        -> FindUtf16PosResult)
+}
+
+#[inline(always)]
+fn rfind_utf16_pos_in_utf8_str_inner(
+    mode: SimdMode,
+    mut utf16_pos: usize,
+    mut s: &[u8],
+) -> FindUtf16PosResult {
+    if mode != SimdMode::None {
         while s.len() >= 64 {
             let plot = &s[s.len() - 64..];
             let accum = {
                 let s16 = u8x16::from_slice_unaligned(&plot[0..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&plot[16..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&plot[32..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             } + {
                 let s16 = u8x16::from_slice_unaligned(&plot[48..]);
-                NIBBLE_TO_UTF16_LEN_U8X16.shuffle1_dyn(s16 >> 4)
+                nibbles_to_utf16_len(mode, s16 >> 4)
             };
 
             let chunk_u16len = accum.wrapping_sum() as usize;
@@ -358,6 +682,134 @@ pub fn rfind_utf16_pos(utf16_pos: usize, s: &str) -> FindUtf16PosResult {
     rfind_utf16_pos_in_utf8_str(utf16_pos, s.as_bytes())
 }
 
+/// Get the number of UTF-8 bytes of the scalar value starting at `units[0]`
+/// and the number of UTF-16 units it occupies.
+///
+/// An unpaired surrogate is treated as U+FFFD REPLACEMENT CHARACTER, which is
+/// encoded in 3 bytes.
+#[inline]
+fn utf8_len_of_utf16_head(units: &[u16]) -> (usize, usize) {
+    match units[0] {
+        0..=0x7f => (1, 1),
+        0x80..=0x7ff => (2, 1),
+        0xd800..=0xdbff => match units.get(1) {
+            Some(0xdc00..=0xdfff) => (4, 2),
+            _ => (3, 1),
+        },
+        _ => (3, 1),
+    }
+}
+
+/// Get the number of UTF-8 bytes required to encode a given UTF-16 string.
+///
+/// Unpaired surrogates are counted as U+FFFD REPLACEMENT CHARACTER (3 bytes),
+/// which is what `String::from_utf16_lossy` would produce.
+///
+/// # Examples
+///
+///     use utf16count::utf8_len_of_utf16;
+///     let units: Vec<u16> = "рыба 👨‍👩‍👦".encode_utf16().collect();
+///     assert_eq!(utf8_len_of_utf16(&units), 27);
+///
+///     // Unpaired surrogates
+///     assert_eq!(utf8_len_of_utf16(&[0xdc00, 0xd800]), 6);
+///
+pub fn utf8_len_of_utf16(mut units: &[u16]) -> usize {
+    let mut count = 0;
+
+    while !units.is_empty() {
+        let (utf8_len, utf16_len) = utf8_len_of_utf16_head(units);
+        count += utf8_len;
+        units = &units[utf16_len..];
+    }
+
+    count
+}
+
+/// Result type of [`find_utf8_pos_in_utf16`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FindUtf8PosResult {
+    /// The search position within the input string.
+    pub utf16_cursor: usize,
+    /// The provided `utf8_pos` minus the number of UTF-8 bytes found in the
+    /// input string.
+    pub utf8_extra: usize,
+}
+
+impl FindUtf8PosResult {
+    /// Return `Some(i)` if the position corresponding to `utf8_pos` was found
+    /// in the input string `units` and the position is `i` (in range
+    /// `0..=units.len()`).
+    pub fn utf16_pos(&self) -> Option<usize> {
+        if self.utf8_extra == 0 {
+            Some(self.utf16_cursor)
+        } else {
+            None
+        }
+    }
+}
+
+/// Convert the given UTF-8 index to a UTF-16 index.
+///
+/// This is the inverse of [`find_utf16_pos_in_utf8_str`]. Unpaired
+/// surrogates are counted as U+FFFD REPLACEMENT CHARACTER (3 bytes). The
+/// result can be one of the following:
+///
+///  - `FindUtf8PosResult { utf16_cursor: i, utf8_extra: 0 }`: The position
+///    corresponding to `utf8_pos` was found in `units` and the position is
+///    `i`. Use [`FindUtf8PosResult::utf16_pos`] if you are only interested
+///    in handling this case.
+///
+///  - `FindUtf8PosResult { utf16_cursor: i, utf8_extra: j } if i < units.len()`:
+///    `utf8_pos` falls in the middle of the UTF-8 sequence of the scalar
+///    value starting at `units[i]`, `j` bytes past its start.
+///
+///  - `FindUtf8PosResult { utf16_cursor: units.len(), utf8_extra: i } if i > 0`:
+///    The corresponding position was not found in `units` because `units`
+///    encodes to only `utf8_pos - i` UTF-8 bytes.
+///
+/// # Examples
+///
+///     use utf16count::{find_utf8_pos_in_utf16, FindUtf8PosResult};
+///     let units: Vec<u16> = "рыба".encode_utf16().collect();
+///
+///     assert_eq!(find_utf8_pos_in_utf16(4, &units), FindUtf8PosResult {
+///         utf16_cursor: 2,
+///         utf8_extra: 0,
+///     });
+///
+///     // In the middle of "б"
+///     assert_eq!(find_utf8_pos_in_utf16(5, &units), FindUtf8PosResult {
+///         utf16_cursor: 2,
+///         utf8_extra: 1,
+///     });
+///
+///     // Out of bounds
+///     assert_eq!(find_utf8_pos_in_utf16(11, &units), FindUtf8PosResult {
+///         utf16_cursor: 4,
+///         utf8_extra: 3,
+///     });
+///
+pub fn find_utf8_pos_in_utf16(mut utf8_pos: usize, units: &[u16]) -> FindUtf8PosResult {
+    let mut utf16_cursor = 0;
+
+    while utf16_cursor < units.len() {
+        let (utf8_len, utf16_len) = utf8_len_of_utf16_head(&units[utf16_cursor..]);
+
+        if utf8_len > utf8_pos {
+            break;
+        }
+
+        utf16_cursor += utf16_len;
+        utf8_pos -= utf8_len;
+    }
+
+    FindUtf8PosResult {
+        utf16_cursor,
+        utf8_extra: utf8_pos,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -408,6 +860,112 @@ mod tests {
         true
     }
 
+    #[quickcheck]
+    fn test_find_utf16_pos_with_scalar(v: Vec<u8>, extra: usize) -> bool {
+        let st = mk_random_str(&v);
+        log::debug!("st = {:?}", st);
+
+        let u16_len = utf16_len(&st);
+
+        for i in 0..=u16_len {
+            let (ret, scalar_count) = find_utf16_pos_with_scalar(i, &st);
+            if ret != find_utf16_pos(i, &st)
+                || scalar_count != st[..ret.utf8_cursor].chars().count()
+            {
+                return false;
+            }
+        }
+
+        find_utf16_pos_with_scalar(u16_len.saturating_add(extra), &st)
+            == (
+                find_utf16_pos(u16_len.saturating_add(extra), &st),
+                st.chars().count(),
+            )
+    }
+
+    #[quickcheck]
+    fn test_utf16_len_up_to(v: Vec<u8>, extra: usize) -> bool {
+        let st = mk_random_str(&v);
+        log::debug!("st = {:?}", st);
+
+        let u16_len = utf16_len(&st);
+
+        for i in 0..=u16_len {
+            let (utf8_len, utf16_len_got) = utf16_len_up_to(&st, i);
+            log::debug!("{:?} = {:?}", i, (utf8_len, utf16_len_got));
+            if !st.is_char_boundary(utf8_len) || utf16_len(&st[..utf8_len]) != utf16_len_got {
+                return false;
+            }
+
+            // Must be rounded down to a scalar value boundary, but not
+            // further
+            let next_len = st[utf8_len..]
+                .chars()
+                .next()
+                .map(char::len_utf16)
+                .unwrap_or(0);
+            if utf16_len_got > i || (utf8_len < st.len() && utf16_len_got + next_len <= i) {
+                return false;
+            }
+        }
+
+        utf16_len_up_to(&st, u16_len.saturating_add(extra)) == (st.len(), u16_len)
+    }
+
+    fn utf8_len_of_utf16_naive(units: &[u16]) -> usize {
+        std::char::decode_utf16(units.iter().cloned())
+            .map(|c| c.unwrap_or(std::char::REPLACEMENT_CHARACTER).len_utf8())
+            .sum()
+    }
+
+    #[quickcheck]
+    fn test_utf8_len_of_utf16(units: Vec<u16>) -> bool {
+        log::debug!("units = {:?}", units);
+
+        let got = utf8_len_of_utf16(&units);
+        let expected = utf8_len_of_utf16_naive(&units);
+        log::debug!("got = {:?}, expected = {:?}", got, expected);
+
+        got == expected
+    }
+
+    #[quickcheck]
+    fn test_find_utf8_pos_in_utf16(units: Vec<u16>, extra: usize) -> bool {
+        log::debug!("units = {:?}", units);
+
+        let u8_len = utf8_len_of_utf16(&units);
+
+        for i in 0..=u8_len {
+            let ret = find_utf8_pos_in_utf16(i, &units);
+            let cursor = ret.utf16_cursor;
+            if i - ret.utf8_extra != utf8_len_of_utf16_naive(&units[..cursor]) {
+                return false;
+            }
+
+            // Must not split a surrogate pair
+            if cursor > 0
+                && cursor < units.len()
+                && (0xd800..0xdc00).contains(&units[cursor - 1])
+                && (0xdc00..0xe000).contains(&units[cursor])
+            {
+                return false;
+            }
+
+            // `utf8_extra` must be less than the length of the next scalar
+            // value
+            if cursor < units.len() && ret.utf8_extra >= utf8_len_of_utf16_head(&units[cursor..]).0
+            {
+                return false;
+            }
+        }
+
+        find_utf8_pos_in_utf16(u8_len.saturating_add(extra), &units)
+            == FindUtf8PosResult {
+                utf16_cursor: units.len(),
+                utf8_extra: u8_len.saturating_add(extra) - u8_len,
+            }
+    }
+
     #[quickcheck]
     fn test_rfind_utf16_pos(v: Vec<u8>, extra: usize) -> bool {
         let st = mk_random_str(&v);
@@ -13,6 +13,7 @@ use crate::metadata::Repo;
 
 mod diag;
 mod implgen;
+mod inherit;
 mod metagen;
 mod parser;
 mod resolve;
@@ -357,6 +358,9 @@ impl<'a, 'b, 'c> BuildScriptConfig<'a, 'b, 'c> {
             return Err(BuildError::Emitted);
         }
 
+        // Propagate `style_manager` etc. to object initialization literals
+        inherit::add_inherited_obj_init_fields(&mut comps, &repo);
+
         // Generate implementation code
         let tcw3_path = self.tcw3_path.as_str();
         let designer_runtime_path = self.designer_runtime_path.as_str();
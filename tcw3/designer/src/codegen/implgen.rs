@@ -13,6 +13,7 @@ mod accessorgen;
 mod analysis;
 mod bitsetgen;
 mod buildergen;
+mod constfoldgen;
 mod dropgen;
 mod evalgen;
 mod initgen;
@@ -296,9 +297,10 @@ pub fn gen_comp(
         .unwrap();
     }
 
-    for item in comp.items.iter() {
+    for (item_i, item) in comp.items.iter().enumerate() {
         match item {
             sem::CompItemDef::Field(item) => match item.field_ty {
+                sem::FieldType::Const if dep_analysis.folded_fields[item_i] => {}
                 sem::FieldType::Const => {
                     writeln!(
                         out,
@@ -342,6 +344,14 @@ pub fn gen_comp(
     writeln!(scoped_out, "impl {} {{", CompSharedTy(comp_path)).unwrap();
     // `ComponentTypeShared::set_dirty_flags`
     initgen::gen_set_dirty_flags(&dep_analysis, ctx, &mut scoped_out);
+    // `ComponentTypeShared::FOLDED_*`
+    constfoldgen::gen_folded_consts(
+        &dep_analysis.folded_fields,
+        &analysis,
+        ctx,
+        &item_meta2sem_map,
+        &mut scoped_out,
+    );
     writeln!(scoped_out, "}}").unwrap();
 
     // `<ComponentTypeShared as Drop>::drop`
@@ -461,6 +471,11 @@ impl<T: fmt::Display> fmt::Display for InnerValueField<T> {
     fn_fmt_write! { |this| ("value_{}", this.0) }
 }
 
+struct FoldedConst<T>(T);
+impl<T: fmt::Display> fmt::Display for FoldedConst<T> {
+    fn_fmt_write! { |this| ("FOLDED_{}", this.0) }
+}
+
 struct EventInnerSubList<T>(T);
 impl<T: fmt::Display> fmt::Display for EventInnerSubList<T> {
     fn_fmt_write! { |this| ("subscriptions_{}", this.0) }
@@ -3,6 +3,7 @@ use quote::ToTokens;
 use std::fmt::Write;
 
 use super::{
+    constfoldgen,
     docgen::{gen_doc_attrs, MdCode},
     fields, initgen, paths, sem, CommaSeparated, CompTy, Ctx, EventBoxHandlerTy, EventInnerSubList,
     GetterMethod, InnerValueField, RaiseMethod, SetterMethod, SubscribeMethod, TempVar,
@@ -13,6 +14,17 @@ pub fn gen_accessors(dep_analysis: &initgen::DepAnalysis, ctx: &Ctx<'_>, out: &m
 
     writeln!(out, "impl {} {{", CompTy(&comp.ident.sym)).unwrap();
 
+    let var_shared = format!("self.{}", fields::SHARED);
+    let gen_const_field_ref = |item_i, out: &mut String| {
+        constfoldgen::gen_const_field_ref(
+            &dep_analysis.folded_fields,
+            comp,
+            item_i,
+            &var_shared,
+            out,
+        );
+    };
+
     for (item_i, item) in comp.items.iter().enumerate() {
         match item {
             sem::CompItemDef::Field(field) => {
@@ -87,13 +99,9 @@ pub fn gen_accessors(dep_analysis: &initgen::DepAnalysis, ctx: &Ctx<'_>, out: &m
                                 ty = field.ty.as_ref().unwrap().to_token_stream(),
                             )
                             .unwrap();
-                            writeln!(
-                                out,
-                                "        &self.{shared}.{field}",
-                                shared = fields::SHARED,
-                                field = InnerValueField(&field.ident.sym),
-                            )
-                            .unwrap();
+                            write!(out, "        ").unwrap();
+                            gen_const_field_ref(item_i, out);
+                            writeln!(out).unwrap();
                         }
                         (Const, Clone) => {
                             writeln!(
@@ -102,14 +110,9 @@ pub fn gen_accessors(dep_analysis: &initgen::DepAnalysis, ctx: &Ctx<'_>, out: &m
                                 ty = field.ty.as_ref().unwrap().to_token_stream(),
                             )
                             .unwrap();
-                            writeln!(
-                                out,
-                                "        {clone}::clone(&self.{shared}.{field})",
-                                clone = paths::CLONE,
-                                shared = fields::SHARED,
-                                field = InnerValueField(&field.ident.sym),
-                            )
-                            .unwrap();
+                            write!(out, "        {}::clone", paths::CLONE).unwrap();
+                            gen_const_field_ref(item_i, out);
+                            writeln!(out).unwrap();
                         }
                     }
 
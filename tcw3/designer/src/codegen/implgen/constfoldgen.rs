@@ -0,0 +1,211 @@
+//! Identifies `const` fields that can be evaluated at compile time and
+//! generates associated constants for them.
+//!
+//! A `const` field is *folded* if all of the following conditions hold:
+//!
+//!  - It has an explicit primitive type (e.g., `u32`, `f32`, or `bool`).
+//!  - It doesn't have a setter, i.e., it can't be supplied to the builder.
+//!  - Its value is a function whose inputs all refer to folded fields of the
+//!    same component by value (e.g., `get!(other_const)`).
+//!  - The function body consists only of literals, references to the
+//!    inputs, and unary/binary/cast operations on them.
+//!
+//! Folded fields are not stored in `ComponentTypeShared`. Instead, their
+//! values are defined as associated constants of `ComponentTypeShared`, which
+//! are evaluated by the compiler.
+use quote::ToTokens;
+use std::{fmt, fmt::Write};
+
+use super::{analysis, sem, CompSharedTy, Ctx, FoldedConst, InnerValueField};
+
+/// Find folded fields. Returns a `Vec` indexed by `sem::CompDef::items`.
+pub fn find_folded_fields(
+    analysis: &analysis::Analysis,
+    ctx: &Ctx,
+    item_meta2sem_map: &[usize],
+) -> Vec<bool> {
+    let comp = ctx.cur_comp;
+    let mut folded = vec![false; comp.items.len()];
+
+    // Find the fixed point. Circular references are reported elsewhere, and
+    // the fields involved in them never become folded here.
+    loop {
+        let mut changed = false;
+
+        for (item_i, item) in comp.items.iter().enumerate() {
+            if folded[item_i] {
+                continue;
+            }
+
+            let func = match item.field() {
+                Some(field) if is_foldable_field_decl(field) => field.value.as_ref().unwrap(),
+                _ => continue,
+            };
+            let func = match func.func() {
+                Some(func) => func,
+                None => continue,
+            };
+
+            let inputs_folded = func.inputs.iter().all(|func_input| {
+                if func_input.by_ref {
+                    return false;
+                }
+                match analysis.get_input(&func_input.input) {
+                    analysis::InputInfo::Item(item_input) if item_input.indirections.len() == 1 => {
+                        let ind = &item_input.indirections[0];
+                        folded[item_meta2sem_map[ind.item_i]]
+                    }
+                    _ => false,
+                }
+            });
+
+            if inputs_folded && is_foldable_expr(&func.body, func) {
+                folded[item_i] = true;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            return folded;
+        }
+    }
+}
+
+fn is_foldable_field_decl(field: &sem::FieldDef<'_>) -> bool {
+    field.field_ty == sem::FieldType::Const
+        && field.accessors.set.is_none()
+        && field.value.is_some()
+        && field.ty.as_ref().map(is_primitive_ty) == Some(true)
+}
+
+fn is_primitive_ty(ty: &syn::Type) -> bool {
+    const PRIMITIVES: &[&str] = &[
+        "bool", "char", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64",
+        "i128", "isize", "f32", "f64",
+    ];
+
+    match ty {
+        syn::Type::Path(syn::TypePath { qself: None, path }) => path
+            .get_ident()
+            .map(|ident| PRIMITIVES.iter().any(|p| ident == p))
+            .unwrap_or(false),
+        syn::Type::Paren(syn::TypeParen { elem, .. })
+        | syn::Type::Group(syn::TypeGroup { elem, .. }) => is_primitive_ty(elem),
+        _ => false,
+    }
+}
+
+fn is_foldable_expr(expr: &syn::Expr, func: &sem::Func) -> bool {
+    match expr {
+        syn::Expr::Lit(_) => true,
+        syn::Expr::Path(syn::ExprPath {
+            qself: None,
+            path,
+            attrs,
+        }) if attrs.is_empty() => path
+            .get_ident()
+            .map(|ident| func.inputs.iter().any(|i| *ident == i.ident.sym))
+            .unwrap_or(false),
+        syn::Expr::Unary(syn::ExprUnary { op, expr, attrs }) if attrs.is_empty() => match op {
+            syn::UnOp::Neg(_) | syn::UnOp::Not(_) => is_foldable_expr(expr, func),
+            syn::UnOp::Deref(_) => false,
+        },
+        syn::Expr::Binary(syn::ExprBinary {
+            left, right, attrs, ..
+        }) if attrs.is_empty() => is_foldable_expr(left, func) && is_foldable_expr(right, func),
+        syn::Expr::Cast(syn::ExprCast {
+            expr, ty, attrs, ..
+        }) if attrs.is_empty() => is_primitive_ty(ty) && is_foldable_expr(expr, func),
+        syn::Expr::Paren(syn::ExprParen { expr, attrs, .. })
+        | syn::Expr::Group(syn::ExprGroup { expr, attrs, .. })
+            if attrs.is_empty() =>
+        {
+            is_foldable_expr(expr, func)
+        }
+        _ => false,
+    }
+}
+
+/// Generate associated constants for folded fields. The generated items are
+/// meant to be included in `impl ComponentTypeShared`.
+pub fn gen_folded_consts(
+    folded: &[bool],
+    analysis: &analysis::Analysis,
+    ctx: &Ctx,
+    item_meta2sem_map: &[usize],
+    out: &mut String,
+) {
+    let comp = ctx.cur_comp;
+    let shared_ty = CompSharedTy(&comp.path);
+
+    for (item_i, item) in comp.items.iter().enumerate() {
+        if !folded[item_i] {
+            continue;
+        }
+
+        let field = item.field().unwrap();
+        let func = field.value.as_ref().unwrap().func().unwrap();
+
+        // `match` in a constant context isn't stable yet, so the inputs are
+        // bound by `let` statements instead of `evalgen::gen_func_eval`'s
+        // `match`
+        writeln!(out, "    #[allow(non_upper_case_globals)]").unwrap();
+        writeln!(
+            out,
+            "    pub(super) const {ident}: {ty} = {{",
+            ident = FoldedConst(&field.ident.sym),
+            ty = field.ty.to_token_stream(),
+        )
+        .unwrap();
+        for func_input in func.inputs.iter() {
+            // `find_folded_fields` ensures the input refers to a folded field
+            let item_input = match analysis.get_input(&func_input.input) {
+                analysis::InputInfo::Item(item_input) => item_input,
+                _ => unreachable!(),
+            };
+            let input_item_i = item_meta2sem_map[item_input.indirections[0].item_i];
+            let input_field = comp.items[input_item_i].field().unwrap();
+            writeln!(
+                out,
+                "        let {ident} = {ty}::{cnst};",
+                ident = func_input.ident.sym,
+                ty = shared_ty,
+                cnst = FoldedConst(&input_field.ident.sym),
+            )
+            .unwrap();
+        }
+        writeln!(out, "        {}", func.body.to_token_stream()).unwrap();
+        writeln!(out, "    }};").unwrap();
+    }
+}
+
+/// Generate an expression that evaluates to a reference to the value of
+/// the specified `const` field. `var_shared` is an expression of type
+/// `&ComponentTypeShared` and is used only if the field isn't folded.
+pub fn gen_const_field_ref(
+    folded: &[bool],
+    comp: &sem::CompDef<'_>,
+    item_i: usize,
+    var_shared: &dyn fmt::Display,
+    out: &mut String,
+) {
+    let field = comp.items[item_i].field().unwrap();
+
+    if folded[item_i] {
+        write!(
+            out,
+            "(&{}::{})",
+            CompSharedTy(&comp.path),
+            FoldedConst(&field.ident.sym)
+        )
+        .unwrap();
+    } else {
+        write!(
+            out,
+            "(&{}.{})",
+            var_shared,
+            InnerValueField(&field.ident.sym)
+        )
+        .unwrap();
+    }
+}
@@ -13,9 +13,9 @@ use super::super::{diag::Diag, sem, EmittedError};
 use super::{
     analysis,
     bitsetgen::{self, BitsetTy},
-    evalgen, fields, known_fields, methods, paths, CommaSeparated, CompBuilderTy, CompSharedTy,
-    CompStateTy, CompTy, Ctx, EventInnerSubList, FactorySetterForField, InnerValueField,
-    RaiseMethod, SetterMethod, SubscribeMethod, TempVar,
+    constfoldgen, evalgen, fields, known_fields, methods, paths, CommaSeparated, CompBuilderTy,
+    CompSharedTy, CompStateTy, CompTy, Ctx, EventInnerSubList, FactorySetterForField, FoldedConst,
+    InnerValueField, RaiseMethod, SetterMethod, SubscribeMethod, TempVar,
 };
 use crate::metadata;
 
@@ -81,6 +81,10 @@ pub struct DepAnalysis {
     pub cdf_ty: BitsetTy,
 
     input2handlers: HashMap<analysis::ItemInput, Vec<EventHandler>>,
+
+    /// Indicates which fields are folded by `constfoldgen`. Indexed by
+    /// `sem::CompDef::items`.
+    pub folded_fields: Vec<bool>,
 }
 
 impl DepAnalysis {
@@ -869,6 +873,8 @@ fn analyze_dep(
         cdf_ty,
 
         input2handlers,
+
+        folded_fields: constfoldgen::find_folded_fields(analysis, ctx, item_meta2sem_map),
     })
 }
 
@@ -974,6 +980,7 @@ pub fn gen_construct(
                                 )
                                 .unwrap();
                             }
+                            sem::FieldType::Const if dep_analysis.folded_fields[i] => {}
                             sem::FieldType::Const => {
                                 writeln!(
                                     out,
@@ -1046,6 +1053,23 @@ pub fn gen_construct(
 
             DepNode::Field { item_i } => {
                 let field = comp.items[*item_i].field().unwrap();
+
+                if dep_analysis.folded_fields[*item_i] {
+                    // The value is known at compile time. This variable might
+                    // not be used by anyone because the field isn't stored
+                    // in `ComponentTypeShared`.
+                    writeln!(
+                        out,
+                        "#[allow(unused_variables)] let {}: {} = {}::{};",
+                        var,
+                        field.ty.to_token_stream(),
+                        CompSharedTy(comp_path),
+                        FoldedConst(&field.ident.sym),
+                    )
+                    .unwrap();
+                    continue;
+                }
+
                 write!(out, "let {}: {} = ", var, field.ty.to_token_stream()).unwrap();
 
                 if field.value.is_none() {
@@ -1122,6 +1146,7 @@ pub fn gen_construct(
 
     struct PostInitFuncInputGen<'a> {
         comp: &'a sem::CompDef<'a>,
+        folded_fields: &'a [bool],
         var_this: &'a dyn std::fmt::Display,
         var_shared: &'a dyn std::fmt::Display,
         var_state: &'a dyn std::fmt::Display,
@@ -1142,6 +1167,7 @@ pub fn gen_construct(
 
     let mut func_input_gen = PostInitFuncInputGen {
         comp,
+        folded_fields: &dep_analysis.folded_fields,
         var_this: &var_this,
         var_shared: &var_shared,
         var_state: &var_state,
@@ -1160,7 +1186,13 @@ pub fn gen_construct(
 
             match field.field_ty {
                 sem::FieldType::Const => {
-                    write!(out, "(&{}.{})", self.var_shared, inner_field).unwrap();
+                    constfoldgen::gen_const_field_ref(
+                        self.folded_fields,
+                        self.comp,
+                        item_i,
+                        self.var_shared,
+                        out,
+                    );
                 }
                 sem::FieldType::Prop | sem::FieldType::Wire => {
                     self.needs_state = true;
@@ -1190,6 +1222,7 @@ pub fn gen_construct(
 
     struct EvtHandlerFuncInputGen<'a> {
         comp: &'a sem::CompDef<'a>,
+        folded_fields: &'a [bool],
         var_this: &'a dyn std::fmt::Display,
         var_shared: &'a dyn std::fmt::Display,
         var_state: &'a dyn std::fmt::Display,
@@ -1210,7 +1243,13 @@ pub fn gen_construct(
 
             match field.field_ty {
                 sem::FieldType::Const => {
-                    write!(out, "(&{}.{})", self.var_shared, inner_field).unwrap();
+                    constfoldgen::gen_const_field_ref(
+                        self.folded_fields,
+                        self.comp,
+                        item_i,
+                        self.var_shared,
+                        out,
+                    );
                 }
                 sem::FieldType::Prop | sem::FieldType::Wire => {
                     self.needs_state = true;
@@ -1306,6 +1345,7 @@ pub fn gen_construct(
 
                 let mut func_input_gen2 = EvtHandlerFuncInputGen {
                     comp,
+                    folded_fields: &dep_analysis.folded_fields,
                     var_this: &var_this,
                     var_shared: &var_shared,
                     var_state: &var_state,
@@ -1778,6 +1818,7 @@ pub fn gen_commit(
 
     struct RecalcFuncInputGen<'a> {
         comp: &'a sem::CompDef<'a>,
+        folded_fields: &'a [bool],
         var_latest: &'a dyn Fn(usize) -> TempVar<usize>,
     }
 
@@ -1790,13 +1831,13 @@ pub fn gen_commit(
             }
 
             if field.field_ty == sem::FieldType::Const {
-                write!(
+                constfoldgen::gen_const_field_ref(
+                    self.folded_fields,
+                    self.comp,
+                    item_i,
+                    &VAR_SHARED,
                     out,
-                    "(&{}.{})",
-                    VAR_SHARED,
-                    InnerValueField(&field.ident.sym)
-                )
-                .unwrap();
+                );
             } else {
                 write!(out, "({})", (self.var_latest)(item_i)).unwrap();
             }
@@ -1818,6 +1859,7 @@ pub fn gen_commit(
 
     let mut func_input_gen = RecalcFuncInputGen {
         comp,
+        folded_fields: &dep_analysis.folded_fields,
         var_latest: &var_latest,
     };
 
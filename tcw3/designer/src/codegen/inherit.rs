@@ -0,0 +1,141 @@
+//! Adds implicit initializers to object initialization literals for fields
+//! that conventionally carry context down a component hierarchy.
+use codemap::Span;
+
+use super::sem;
+use crate::metadata;
+
+/// The names of fields propagated from an enclosing component to the
+/// components instantiated by its object initialization literals.
+const INHERITED_FIELD_NAMES: &[&str] = &["style_manager"];
+
+/// For each object initialization literal in `comps`, add an initializer
+/// `field = get!(field)` for every field listed in `INHERITED_FIELD_NAMES`
+/// if all of the following conditions are met:
+///
+///  - The literal doesn't initialize the field explicitly.
+///  - The instantiated component has a settable `const` field with that name.
+///  - The enclosing component has a `const` field with that name, and it's
+///    not the field being initialized by the literal.
+///  - The two fields have the same type. Non-component types are compared
+///    as written in the source code (`ty_src` in the metadata), so this works
+///    even if the instantiated component is defined in another crate.
+///
+/// The added initializers use the span of the field being initialized by
+/// the literal so that diagnostics regarding them point to the literal.
+///
+/// `repo` must include the metadata generated from `comps`.
+pub fn add_inherited_obj_init_fields(comps: &mut [sem::CompDef<'_>], repo: &metadata::Repo) {
+    for (comp_i, comp) in comps.iter_mut().enumerate() {
+        let meta_comp = repo.comp_by_ref(&metadata::CompRef {
+            crate_i: repo.main_crate_i,
+            comp_i,
+        });
+
+        let mut num_inputs = comp.num_inputs;
+
+        for item in comp.items.iter_mut() {
+            let field = if let Some(x) = item.field_mut() {
+                x
+            } else {
+                continue;
+            };
+
+            let init = if let Some(sem::DynExpr::ObjInit(x)) = &mut field.value {
+                x
+            } else {
+                continue;
+            };
+
+            // Find the component we are constructing. If it can't be found,
+            // leave the literal as it is; `implgen::analysis` will report
+            // the error.
+            let target_comp_ref = meta_comp
+                .find_item_by_ident(&field.ident.sym)
+                .and_then(|(_, item)| item.field())
+                .and_then(|field| field.ty);
+            let target_comp = if let Some(x) = target_comp_ref {
+                repo.comp_by_ref(&x)
+            } else {
+                continue;
+            };
+
+            let span = field.ident.span;
+
+            for &name in INHERITED_FIELD_NAMES.iter() {
+                if field.ident.sym == name || init.fields.iter().any(|f| f.ident.sym == name) {
+                    continue;
+                }
+
+                let source_field = meta_comp
+                    .find_item_by_ident(name)
+                    .and_then(|(_, item)| item.field());
+                let source_field = match source_field {
+                    Some(f) if f.field_ty == metadata::FieldType::Const => f,
+                    _ => continue,
+                };
+
+                let target_field = target_comp
+                    .find_item_by_ident(name)
+                    .and_then(|(_, item)| item.field());
+                let target_field = match target_field {
+                    Some(f)
+                        if f.field_ty == metadata::FieldType::Const
+                            && f.accessors.set.is_some() =>
+                    {
+                        f
+                    }
+                    _ => continue,
+                };
+
+                // Compare the field types
+                let ty_eq = match (&source_field.ty, &target_field.ty) {
+                    (None, None) => source_field.ty_src == target_field.ty_src,
+                    (Some(x), Some(y)) => x.crate_i == y.crate_i && x.comp_i == y.comp_i,
+                    _ => false,
+                };
+                if !ty_eq {
+                    continue;
+                }
+
+                init.fields.push(sem::ObjInitField {
+                    ident: sem::Ident {
+                        sym: name.to_owned(),
+                        span,
+                    },
+                    value: mk_func_with_named_input(name, num_inputs, span),
+                });
+                num_inputs += 1;
+            }
+        }
+
+        comp.num_inputs = num_inputs;
+    }
+}
+
+/// Construct a `Func` that looks like it was created from `get!(ident)`.
+fn mk_func_with_named_input(ident: &str, input_index: usize, span: Option<Span>) -> sem::Func {
+    let ident = sem::Ident {
+        sym: ident.to_owned(),
+        span,
+    };
+
+    // `|this.ident as ident| ident`
+    sem::Func {
+        inputs: vec![sem::FuncInput {
+            by_ref: false,
+            input: sem::Input {
+                origin: sem::InputOrigin::This,
+                selectors: vec![ident.clone()],
+                index: input_index,
+                span,
+            },
+            ident: ident.clone(),
+        }],
+        body: syn::Expr::Path(syn::ExprPath {
+            attrs: vec![],
+            qself: None,
+            path: syn::Ident::new(&ident.sym, proc_macro2::Span::call_site()).into(),
+        }),
+    }
+}
@@ -301,8 +301,10 @@ fn gen_field(
 
     debug!("Analyzing the field `{}`", field.ident.sym);
 
+    let ty = field.ty.as_ref().unwrap();
+
     // See if `field.ty` refers to a known component.
-    let comp_ty = match field.ty.as_ref().unwrap() {
+    let comp_ty = match ty {
         syn::Type::Path(syn::TypePath { qself: None, path }) => {
             if let Some((crate_i, comp_i)) = ctx.resolver.find_comp_by_path(path) {
                 debug!(
@@ -332,6 +334,7 @@ fn gen_field(
         field_ty: field.field_ty,
         flags,
         ty: comp_ty,
+        ty_src: ty.to_token_stream().to_string(),
         ident: gen_sem_ident(&field.ident),
         accessors: metadata::FieldAccessors {
             set: field.accessors.set.as_ref().map(|a| metadata::FieldSetter {
@@ -158,6 +158,9 @@ pub struct CompDef<'a> {
     pub items: Vec<CompItemDef<'a>>,
     pub syn: &'a parser::Comp,
     pub import_scope: ImportScope<'a>,
+    /// The number of `Input`s in this component. `Input::index` is less than
+    /// this value.
+    pub num_inputs: usize,
 }
 
 pub use crate::metadata::CompFlags;
@@ -452,6 +455,7 @@ impl AnalyzeCtx<'_, '_> {
                 .collect(),
             syn: comp,
             import_scope,
+            num_inputs: 0,
         };
 
         this.items
@@ -581,6 +585,8 @@ impl AnalyzeCtx<'_, '_> {
             }
         }
 
+        this.num_inputs = self.next_input_index;
+
         this
     }
 
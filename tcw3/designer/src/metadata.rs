@@ -81,6 +81,10 @@ pub struct FieldDef {
     pub accessors: FieldAccessors,
     /// `Some(_)` if the field type refers to a component. `None` otherwise.
     pub ty: Option<CompRef>,
+    /// The field type as written in the source code. This is only useful for
+    /// a rough comparison of non-component types because the same type may be
+    /// written differently.
+    pub ty_src: String,
 }
 
 bitflags::bitflags! {
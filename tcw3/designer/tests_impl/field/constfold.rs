@@ -0,0 +1,23 @@
+designer_impl! { crate::field::constfold::Comp }
+
+// Folded fields are defined as associated constants of `CompShared`. Using
+// them in a constant context confirms they are evaluated at compile time.
+const _: u32 = CompShared::FOLDED_derived;
+const _: bool = CompShared::FOLDED_flag;
+
+#[test]
+fn folded_values() {
+    let comp = CompBuilder::new().build();
+    assert_eq!(comp.base(), 6);
+    assert_eq!(comp.derived(), 42);
+    assert_eq!(comp.chained().to_bits(), 42.5f32.to_bits());
+    assert!(comp.flag());
+    assert_eq!(comp.mixed(), 43);
+}
+
+#[test]
+fn unfolded_values() {
+    let comp = CompBuilder::new().with_given(2).build();
+    assert_eq!(comp.derived(), 42);
+    assert_eq!(comp.mixed(), 44);
+}
@@ -35,3 +35,17 @@ fn prop_watch(twm: &dyn TestingWm) {
     twm.step_unsend();
     assert_eq!(vec![(3, 3)], *comp.prop1_history().borrow());
 }
+
+#[use_testing_wm]
+#[test]
+fn prop_set_flush(twm: &dyn TestingWm) {
+    use tcw3::uicore::WmExt;
+    let wm = twm.wm();
+    let comp = CompBuilder::new().with_wm(wm).build();
+    comp.set_prop1(3);
+    assert!(wm.update_pending());
+    assert_eq!(1, comp.prop1());
+    wm.flush_updates();
+    assert!(!wm.update_pending());
+    assert_eq!(3, comp.prop1());
+}
@@ -11,6 +11,7 @@ mod commit {
 mod field {
     mod accessors;
     mod bug_type_deduction;
+    mod constfold;
     mod lifetime_elision;
     mod prop;
 }
@@ -32,5 +33,7 @@ mod misc {
 
 mod objinit {
     mod alias;
+    mod handler;
+    mod inherit;
     mod shorthand;
 }
@@ -0,0 +1,28 @@
+use tcw3::testing::{prelude::*, use_testing_wm};
+
+designer_impl! { crate::objinit::handler::Comp }
+designer_impl! { crate::objinit::handler::CompOther }
+
+impl CompOther {
+    fn bump(&self) {
+        self.bump_by(1);
+    }
+
+    fn bump_by(&self, amount: u32) {
+        let counter = self.counter();
+        counter.set(counter.get() + amount);
+    }
+}
+
+#[use_testing_wm]
+#[test]
+fn call_method_from_handler(twm: &dyn TestingWm) {
+    let comp = CompBuilder::new().with_wm(twm.wm()).build();
+
+    // `on (init)`
+    assert_eq!(comp.other().counter().get(), 1);
+
+    // `on (other.activated)`
+    comp.other().raise_activated(10);
+    assert_eq!(comp.other().counter().get(), 11);
+}
@@ -0,0 +1,35 @@
+use tcw3::testing::{prelude::*, use_testing_wm};
+
+designer_impl! { crate::objinit::inherit::Comp }
+designer_impl! { crate::objinit::inherit::CompOther }
+designer_impl! { crate::objinit::inherit::CompNested }
+designer_impl! { crate::objinit::inherit::CompMismatch }
+designer_impl! { crate::objinit::inherit::CompDefaulted }
+designer_impl! { crate::objinit::inherit::CompCrossCrate }
+
+#[test]
+fn check_inherited_values() {
+    let comp = CompBuilder::new().build();
+
+    assert_eq!(*comp.other().style_manager(), 42);
+    assert_eq!(*comp.other().nested().style_manager(), 42);
+
+    assert_eq!(*comp.other2().style_manager(), 1);
+    assert_eq!(*comp.other2().nested().style_manager(), 1);
+}
+
+#[test]
+fn check_mismatched_type_not_inherited() {
+    let comp = CompMismatchBuilder::new().build();
+
+    assert_eq!(*comp.defaulted().style_manager(), 1);
+}
+
+#[use_testing_wm]
+#[test]
+fn check_inherited_by_other_crate(twm: &dyn TestingWm) {
+    // `Label` doesn't have a default value for `style_manager`, so this
+    // wouldn't compile if it weren't inherited
+    let comp = CompCrossCrateBuilder::new().with_wm(twm.wm()).build();
+    let _ = comp.label();
+}
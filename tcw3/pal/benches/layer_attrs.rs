@@ -0,0 +1,88 @@
+//! Compares `Wm::set_layer_attr` and `Wm::set_layer_attrs_batch` on the native
+//! backend.
+use cggeom::box2;
+use cgmath::{Matrix3, Vector2};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use tcw3_pal::{prelude::*, HLayer, LayerAttrs, Wm};
+
+/// Create a layer containing `num_layers` sublayers.
+fn new_layers(wm: Wm, num_layers: usize) -> (HLayer, Vec<HLayer>) {
+    let sublayers: Vec<_> = (0..num_layers)
+        .map(|i| {
+            let (x, y) = ((i % 32) as f32 * 10.0, (i / 32) as f32 * 10.0);
+            wm.new_layer(LayerAttrs {
+                bounds: Some(box2! { top_left: [x, y], size: [8.0, 8.0] }),
+                bg_color: Some([0.2, 0.3, 0.4, 1.0].into()),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    let root = wm.new_layer(LayerAttrs {
+        sublayers: Some(sublayers.clone()),
+        ..Default::default()
+    });
+
+    (root, sublayers)
+}
+
+/// Construct the attributes of the `i`-th sublayer in the `frame`-th frame of
+/// an imaginary animation.
+fn layer_attrs(i: usize, frame: usize) -> LayerAttrs {
+    let t = (i + frame) as f32 * 0.1;
+    LayerAttrs {
+        transform: Some(Matrix3::from_translation(Vector2::new(t.sin(), t.cos()))),
+        bg_color: Some([0.2, 0.3, 0.4, t.sin() * 0.5 + 0.5].into()),
+        ..Default::default()
+    }
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let wm = Wm::global();
+
+    let mut group = c.benchmark_group("set_layer_attrs");
+
+    for &num_layers in &[100, 500] {
+        let (root, sublayers) = new_layers(wm, num_layers);
+        let mut frame = 0;
+
+        group.bench_with_input(
+            BenchmarkId::new("individual", num_layers),
+            &sublayers,
+            |b, sublayers| {
+                b.iter(|| {
+                    frame += 1;
+                    for (i, hlayer) in sublayers.iter().enumerate() {
+                        wm.set_layer_attr(hlayer, layer_attrs(i, frame));
+                    }
+                });
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("batch", num_layers),
+            &sublayers,
+            |b, sublayers| {
+                b.iter(|| {
+                    frame += 1;
+                    wm.set_layer_attrs_batch(
+                        sublayers
+                            .iter()
+                            .enumerate()
+                            .map(|(i, hlayer)| (hlayer, layer_attrs(i, frame))),
+                    );
+                });
+            },
+        );
+
+        for hlayer in sublayers.iter() {
+            wm.remove_layer(hlayer);
+        }
+        wm.remove_layer(&root);
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,240 @@
+//! Extends `Wm` with a simple timeline-based animation driver.
+use std::{
+    cell::Cell,
+    fmt,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+use crate::{prelude::*, HInvoke, Wm};
+
+/// The interval between animation frames.
+///
+/// There's no portable way to synchronize with the display's refresh cycle
+/// without a window, so frames are driven by `Wm::invoke_after`.
+const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// The tolerance of `FRAME_INTERVAL`.
+const FRAME_INTERVAL_MAX: Duration = Duration::from_millis(20);
+
+/// Get the current time. The testing backend can advance it artificially.
+fn now(wm: Wm) -> Instant {
+    #[cfg(feature = "testing")]
+    {
+        crate::testing::now(wm)
+    }
+    #[cfg(not(feature = "testing"))]
+    {
+        let _ = wm;
+        Instant::now()
+    }
+}
+
+/// Extends [`Wm`] with a simple timeline-based animation driver.
+///
+/// [`Wm`]: crate::Wm
+pub trait WmAnimationExt {
+    /// Start an animation that lasts for `duration`.
+    ///
+    /// `f` is called on each frame with the current progress value, which is
+    /// mapped by `easing` and is in range `[0, 1]`. The last call always
+    /// receives `1.0` unless the animation is cancelled. If `duration` is
+    /// zero or the user prefers reduced motion
+    /// ([`Wm::prefers_reduced_motion`]), the animation jumps to the end, i.e.,
+    /// `f` is called only once with `1.0`.
+    ///
+    /// [`Wm::prefers_reduced_motion`]: crate::iface::Wm::prefers_reduced_motion
+    ///
+    /// The animation keeps running even if the returned [`Animation`] is
+    /// dropped.
+    fn animate(
+        self,
+        duration: Duration,
+        easing: Easing,
+        f: impl FnMut(Wm, f32) + 'static,
+    ) -> Animation;
+}
+
+impl WmAnimationExt for Wm {
+    fn animate(
+        self,
+        duration: Duration,
+        easing: Easing,
+        f: impl FnMut(Wm, f32) + 'static,
+    ) -> Animation {
+        Animation::new(self, duration, easing, Box::new(f))
+    }
+}
+
+/// An easing function.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Easing {
+    Linear,
+    /// Cubic ease-in.
+    EaseIn,
+    /// Cubic ease-out.
+    EaseOut,
+    /// Cubic ease-in-out.
+    EaseInOut,
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Easing::Linear
+    }
+}
+
+impl Easing {
+    /// Map a linear progress value in range `[0, 1]`.
+    pub fn apply(self, t: f32) -> f32 {
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => {
+                let t = 1.0 - t;
+                1.0 - t * t * t
+            }
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    let t = 2.0 - 2.0 * t;
+                    1.0 - t * t * t * 0.5
+                }
+            }
+        }
+    }
+}
+
+/// A handle to an animation started by [`WmAnimationExt::animate`].
+#[derive(Clone)]
+pub struct Animation {
+    inner: Rc<AnimationInner>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Running,
+    Complete,
+    Cancelled,
+}
+
+struct AnimationInner {
+    wm: Wm,
+    start: Instant,
+    duration: Duration,
+    easing: Easing,
+    state: Cell<State>,
+    /// The callback function. Moved out while it's being called.
+    func: Cell<Option<Box<dyn FnMut(Wm, f32)>>>,
+    /// The pending frame.
+    hinvoke: Cell<Option<HInvoke>>,
+}
+
+impl Animation {
+    fn new(wm: Wm, duration: Duration, easing: Easing, func: Box<dyn FnMut(Wm, f32)>) -> Self {
+        let duration = if wm.prefers_reduced_motion() {
+            Duration::from_secs(0)
+        } else {
+            duration
+        };
+
+        let inner = Rc::new(AnimationInner {
+            wm,
+            start: now(wm),
+            duration,
+            easing,
+            state: Cell::new(State::Running),
+            func: Cell::new(Some(func)),
+            hinvoke: Cell::new(None),
+        });
+
+        AnimationInner::schedule_frame(&inner);
+
+        Self { inner }
+    }
+
+    /// Cancel the animation. The callback function is dropped without being
+    /// called again. This method can be called from the callback function.
+    ///
+    /// Returns `true` if the animation is successfully cancelled; `false`
+    /// otherwise, e.g., because the animation is already complete or
+    /// cancelled.
+    pub fn cancel(&self) -> bool {
+        if self.inner.state.get() != State::Running {
+            return false;
+        }
+
+        self.inner.state.set(State::Cancelled);
+
+        if let Some(hinvoke) = self.inner.hinvoke.take() {
+            self.inner.wm.cancel_invoke(&hinvoke);
+        }
+
+        // If the callback function is being called, this is `None`, and
+        // the function is dropped by `AnimationInner::frame` instead
+        drop(self.inner.func.take());
+
+        true
+    }
+
+    /// Get a flag indicating whether the animation is neither complete nor
+    /// cancelled.
+    pub fn is_running(&self) -> bool {
+        self.inner.state.get() == State::Running
+    }
+}
+
+impl fmt::Debug for Animation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Animation")
+            .field("wm", &self.inner.wm)
+            .field("start", &self.inner.start)
+            .field("duration", &self.inner.duration)
+            .field("easing", &self.inner.easing)
+            .field("state", &self.inner.state.get())
+            .finish()
+    }
+}
+
+impl AnimationInner {
+    fn schedule_frame(this: &Rc<Self>) {
+        let this2 = Rc::clone(this);
+        let hinvoke = this
+            .wm
+            .invoke_after(FRAME_INTERVAL..FRAME_INTERVAL_MAX, move |_| {
+                Self::frame(&this2);
+            });
+        this.hinvoke.set(Some(hinvoke));
+    }
+
+    fn frame(this: &Rc<Self>) {
+        this.hinvoke.set(None);
+
+        let mut func = match this.func.take() {
+            Some(func) => func,
+            None => return,
+        };
+
+        let elapsed = now(this.wm).saturating_duration_since(this.start);
+        let t = if elapsed >= this.duration {
+            1.0
+        } else {
+            elapsed.as_secs_f32() / this.duration.as_secs_f32()
+        };
+
+        func(this.wm, this.easing.apply(t));
+
+        if this.state.get() != State::Running {
+            // Cancelled by `func`
+            return;
+        }
+
+        if t < 1.0 {
+            this.func.set(Some(func));
+            Self::schedule_frame(this);
+        } else {
+            this.state.set(State::Complete);
+        }
+    }
+}
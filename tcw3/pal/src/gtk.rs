@@ -18,6 +18,7 @@ pub use self::{
     text::{CharStyle, TextLayout},
 };
 
+mod clipboard;
 mod comp;
 mod textinput;
 mod timer;
@@ -182,6 +183,19 @@ impl iface::Wm for Wm {
         window.is_wnd_focused(self)
     }
 
+    fn get_wnd_monitor_info(self, window: &Self::HWnd) -> iface::MonitorInfo {
+        window.get_wnd_monitor_info(self)
+    }
+
+    fn prefers_reduced_motion(self) -> bool {
+        // GTK doesn't have a dedicated setting. `gtk-enable-animations` is
+        // the closest thing, which GNOME maps from its `enable-animations`
+        // desktop setting
+        gtk::Settings::get_default()
+            .map(|settings| !settings.get_property_gtk_enable_animations())
+            .unwrap_or(false)
+    }
+
     fn request_update_ready_wnd(self, window: &Self::HWnd) {
         window.request_update_ready_wnd(self)
     }
@@ -198,6 +212,15 @@ impl iface::Wm for Wm {
             .borrow_mut()
             .set_layer_attr(layer, attrs)
     }
+    fn set_layer_attrs_batch<'a>(
+        self,
+        updates: impl IntoIterator<Item = (&'a Self::HLayer, LayerAttrs)>,
+    ) {
+        let mut compositor = window::COMPOSITOR.get_with_wm(self).borrow_mut();
+        for (layer, attrs) in updates {
+            compositor.set_layer_attr(layer, attrs);
+        }
+    }
     fn remove_layer(self, layer: &Self::HLayer) {
         window::COMPOSITOR
             .get_with_wm(self)
@@ -230,6 +253,14 @@ impl iface::Wm for Wm {
     fn remove_text_input_ctx(self, htictx: &Self::HTextInputCtx) {
         htictx.remove(self);
     }
+
+    fn set_clipboard_text(self, kind: iface::ClipboardKind, text: &str) {
+        clipboard::set_clipboard_text(self, kind, text);
+    }
+
+    fn get_clipboard_text(self, kind: iface::ClipboardKind) -> Option<String> {
+        clipboard::get_clipboard_text(self, kind)
+    }
 }
 
 struct AssertSend<T>(T);
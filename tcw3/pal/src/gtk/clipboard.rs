@@ -0,0 +1,43 @@
+//! Clipboard
+use gtk::Clipboard;
+use std::cell::Cell;
+
+use super::Wm;
+use crate::{iface, MtSticky};
+
+/// Set while `get_clipboard_text` is running a nested main loop.
+static GETTING_TEXT: MtSticky<Cell<bool>, Wm> = MtSticky::new(Cell::new(false));
+
+fn gtk_clipboard(kind: iface::ClipboardKind) -> Clipboard {
+    let selection = match kind {
+        iface::ClipboardKind::Clipboard => &gdk::SELECTION_CLIPBOARD,
+        iface::ClipboardKind::Primary => &gdk::SELECTION_PRIMARY,
+    };
+    Clipboard::get(selection)
+}
+
+/// Implements `Wm::set_clipboard_text`.
+pub fn set_clipboard_text(_: Wm, kind: iface::ClipboardKind, text: &str) {
+    gtk_clipboard(kind).set_text(text);
+}
+
+/// Implements `Wm::get_clipboard_text`.
+pub fn get_clipboard_text(wm: Wm, kind: iface::ClipboardKind) -> Option<String> {
+    let getting_text = GETTING_TEXT.get_with_wm(wm);
+    if getting_text.replace(true) {
+        // Called by a callback invoked by the nested main loop below. Don't
+        // nest main loops any further.
+        log::warn!("get_clipboard_text: reentrant call - returning `None`");
+        return None;
+    }
+
+    // This runs a nested main loop until the data is received. See the
+    // documentation of `iface::Wm::get_clipboard_text`.
+    let text = gtk_clipboard(kind)
+        .wait_for_text()
+        .map(|text| text.as_str().to_owned());
+
+    getting_text.set(false);
+
+    text
+}
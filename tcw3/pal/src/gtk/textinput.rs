@@ -221,8 +221,7 @@ impl HTextInputCtx {
         log::trace!("on_layout_change({:?})", self);
 
         let mut edit = ctx.listener.edit(wm, self, false);
-        let sel_i = edit.selected_range().end;
-        let (bounds, _) = edit.slice_bounds(sel_i..sel_i);
+        let bounds = edit.caret_bounds();
         drop(edit);
 
         log::trace!("... bounds = {:?}", bounds.display_im());
@@ -430,8 +429,7 @@ impl HTextInputCtx {
 
 impl Ctx {
     fn recalculate_and_set_cursor_location(&self, wm: Wm, mut edit: Box<DynTextInputCtxEdit<'_>>) {
-        let sel_i = edit.selected_range().end;
-        let (bounds, _) = edit.slice_bounds(sel_i..sel_i);
+        let bounds = edit.caret_bounds();
         drop(edit);
 
         log::trace!(
@@ -33,6 +33,16 @@ pub(super) static COMPOSITOR: MtSticky<RefCell<comp::Compositor>, Wm> =
 
 static DRAWING_WND: MtSticky<Cell<Option<WndPoolPtr>>, Wm> = MtSticky::new(Cell::new(None));
 
+/// Indicates whether `NO_SHADOW_CSS` has been installed.
+static NO_SHADOW_CSS_INSTALLED: MtSticky<Cell<bool>, Wm> = MtSticky::new(Cell::new(false));
+
+/// The style class added to windows with `WndFlags::NO_SHADOW`.
+const NO_SHADOW_CLASS: &str = "tcw3-no-shadow";
+
+/// Removes the shadow of client-side decorations, which is drawn by the
+/// `decoration` CSS node.
+const NO_SHADOW_CSS: &str = "window.tcw3-no-shadow decoration { box-shadow: none; margin: 0; }";
+
 struct Wnd {
     gtk_wnd: gtk::Window,
     gtk_widget: WndWidget,
@@ -43,6 +53,9 @@ struct Wnd {
     /// The last known size of the window.
     size: [i32; 2],
 
+    /// The last known position of the window.
+    pos: [i32; 2],
+
     tick_callback_active: bool,
     tick_callback_continue: bool,
 
@@ -96,10 +109,27 @@ impl HWnd {
         gtk_wnd.set_app_paintable(true);
 
         // On X11, we also have to request an RGBA visual
-        if let Some(vis) = gtk_wnd.get_screen().unwrap().get_rgba_visual() {
+        let screen = gtk_wnd.get_screen().unwrap();
+        if let Some(vis) = screen.get_rgba_visual() {
             gtk_wnd.set_visual(Some(&vis));
         }
 
+        // Install the style sheet for `NO_SHADOW` (It's application-wide, so
+        // we only have to do this once)
+        let no_shadow_css_installed = NO_SHADOW_CSS_INSTALLED.get_with_wm(wm);
+        if !no_shadow_css_installed.get() {
+            let provider = gtk::CssProvider::new();
+            provider
+                .load_from_data(NO_SHADOW_CSS.as_bytes())
+                .expect("Failed to load a style sheet");
+            gtk::StyleContext::add_provider_for_screen(
+                &screen,
+                &provider,
+                gtk::STYLE_PROVIDER_PRIORITY_APPLICATION as _,
+            );
+            no_shadow_css_installed.set(true);
+        }
+
         let comp_wnd = COMPOSITOR
             .get_with_wm(wm)
             .borrow_mut()
@@ -112,6 +142,7 @@ impl HWnd {
             flags: iface::WndFlags::default(),
             listener: Rc::new(()),
             size: [0, 0],
+            pos: [0, 0],
             tick_callback_active: false,
             tick_callback_continue: false,
             drag_state: None,
@@ -145,6 +176,35 @@ impl HWnd {
             listener.focus(wm, &Self { ptr });
         });
 
+        wnd.gtk_wnd
+            .connect_property_is_active_notify(move |gtk_wnd| {
+                let listener = {
+                    let wnds = WNDS.get_with_wm(wm).borrow();
+                    Rc::clone(&wnds[ptr].listener)
+                };
+
+                listener.active(wm, &Self { ptr }, gtk_wnd.is_active());
+            });
+
+        wnd.gtk_wnd.connect_configure_event(move |_, event| {
+            let (x, y) = event.get_position();
+
+            let listener = {
+                let mut wnds = WNDS.get_with_wm(wm).borrow_mut();
+                let wnd = &mut wnds[ptr];
+                if wnd.pos == [x, y] {
+                    return false;
+                }
+                wnd.pos = [x, y];
+                Rc::clone(&wnd.listener)
+            };
+
+            listener.moved(wm, &Self { ptr }, Point2::new(x as f32, y as f32));
+
+            // Propagate the event so that the widget gets resized
+            false
+        });
+
         // `set_wnd_attr` borrows `WNDS`, so unborrow it before calling that
         drop(wnds);
 
@@ -187,6 +247,15 @@ impl HWnd {
                     });
             }
 
+            // This only affects client-side decorations. The shadow of
+            // server-side decorations is up to the window manager.
+            let style_ctx = wnd.gtk_wnd.get_style_context();
+            if flags.contains(iface::WndFlags::NO_SHADOW) {
+                style_ctx.add_class(NO_SHADOW_CLASS);
+            } else {
+                style_ctx.remove_class(NO_SHADOW_CLASS);
+            }
+
             wnd.flags = flags;
         }
 
@@ -356,6 +425,40 @@ impl HWnd {
             .contains(gtk::StateFlags::BACKDROP)
     }
 
+    /// Implements `Wm::get_wnd_monitor_info`.
+    #[cfg(feature = "gtk_v3_22")]
+    pub(super) fn get_wnd_monitor_info(&self, wm: Wm) -> iface::MonitorInfo {
+        let wnds = WNDS.get_with_wm(wm).borrow();
+        let gtk_wnd = &wnds[self.ptr].gtk_wnd;
+
+        let monitor = gtk_wnd.get_window().and_then(|gdk_wnd| {
+            gtk_wnd
+                .get_display()
+                .and_then(|display| display.get_monitor_at_window(&gdk_wnd))
+        });
+
+        iface::MonitorInfo {
+            // `gdk_monitor_get_refresh_rate` returns a value in millihertz.
+            // Zero means unknown.
+            refresh_rate: monitor
+                .map(|m| m.get_refresh_rate() as f32 / 1000.0)
+                .unwrap_or(0.0),
+            // GDK doesn't expose HDR capabilities
+            hdr: false,
+            max_luminance: None,
+        }
+    }
+
+    /// Implements `Wm::get_wnd_monitor_info`.
+    ///
+    /// `gdk::Monitor` isn't available before GTK 3.22, so this version returns
+    /// the fallback value. Enable the `gtk_v3_22` feature to get the actual
+    /// refresh rate.
+    #[cfg(not(feature = "gtk_v3_22"))]
+    pub(super) fn get_wnd_monitor_info(&self, _: Wm) -> iface::MonitorInfo {
+        iface::MonitorInfo::default()
+    }
+
     /// Implements `Wm::request_update_ready_wnd`.
     pub(super) fn request_update_ready_wnd(&self, wm: Wm) {
         let mut wnds = WNDS.get_with_wm(wm).borrow_mut();
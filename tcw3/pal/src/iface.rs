@@ -163,6 +163,45 @@ pub trait Wm: Clone + Copy + Sized + Debug + 'static {
     /// Get a flag indicating whether the specified window has focus.
     fn is_wnd_focused(self, window: &Self::HWnd) -> bool;
 
+    /// Get the characteristics of the display device the specified window is
+    /// currently on.
+    ///
+    /// The default implementation returns `MonitorInfo::default()`, which
+    /// represents a display with unknown characteristics.
+    fn get_wnd_monitor_info(self, _window: &Self::HWnd) -> MonitorInfo {
+        MonitorInfo::default()
+    }
+
+    /// Get the insets of the region in a window's content region occupied by
+    /// system-provided controls and decorations.
+    ///
+    /// This is mainly useful for windows with [`WndFlags::FULL_SIZE_CONTENT`],
+    /// whose content region extends to the title bar. The application should
+    /// avoid placing interactive elements inside the insets.
+    ///
+    /// The default implementation returns `Insets::default()` (all zeros).
+    ///
+    /// Backend-specific notes:
+    ///
+    ///  - On Windows, the system doesn't draw caption buttons for a window
+    ///    with `FULL_SIZE_CONTENT`; the application is expected to provide its
+    ///    own. The insets only cover the sizing border, which is placed inside
+    ///    the content region.
+    ///  - On GTK, `FULL_SIZE_CONTENT` replaces the header bar with an empty
+    ///    widget placed outside the content region, so the insets are always
+    ///    zero.
+    fn get_wnd_content_insets(self, _window: &Self::HWnd) -> Insets {
+        Insets::default()
+    }
+
+    /// Get a flag indicating whether the user has requested the system to
+    /// minimize non-essential motion, e.g., animations.
+    ///
+    /// The default implementation returns `false`.
+    fn prefers_reduced_motion(self) -> bool {
+        false
+    }
+
     /// Create a layer.
     fn new_layer(self, attrs: LayerAttrs<Self::Bitmap, Self::HLayer>) -> Self::HLayer;
 
@@ -171,6 +210,24 @@ pub trait Wm: Clone + Copy + Sized + Debug + 'static {
     /// The behavior is unspecified if the layer has already been removed.
     fn set_layer_attr(self, layer: &Self::HLayer, attrs: LayerAttrs<Self::Bitmap, Self::HLayer>);
 
+    /// Set the attributes of multiple layers.
+    ///
+    /// This is equivalent to calling `set_layer_attr` for each element of
+    /// `updates`, but the backend may process the updates more efficiently,
+    /// e.g., by grouping them into a single transaction. Like
+    /// `set_layer_attr`, the updates are presented to the screen all at once
+    /// on the next call to `update_wnd`.
+    fn set_layer_attrs_batch<'a>(
+        self,
+        updates: impl IntoIterator<Item = (&'a Self::HLayer, LayerAttrs<Self::Bitmap, Self::HLayer>)>,
+    ) where
+        Self::HLayer: 'a,
+    {
+        for (layer, attrs) in updates {
+            self.set_layer_attr(layer, attrs);
+        }
+    }
+
     /// Delete a layer.
     ///
     /// If the layer has a superlayer, the deletion will be postponed until it's
@@ -226,6 +283,68 @@ pub trait Wm: Clone + Copy + Sized + Debug + 'static {
     ///
     /// [`TextInputCtxListener::edit`] may be called in this method.
     fn remove_text_input_ctx(self, ctx: &Self::HTextInputCtx);
+
+    /// Replace the contents of the specified clipboard with a text string.
+    fn set_clipboard_text(self, kind: ClipboardKind, text: &str);
+
+    /// Get a text string from the specified clipboard. Returns `None` if the
+    /// clipboard doesn't contain a text string.
+    ///
+    /// This method may block until the clipboard owner (which may be another
+    /// process) provides the data.
+    ///
+    /// On GTK, this method runs a nested main loop while waiting for the data.
+    /// Therefore, listener methods, timers, and functions pended by `invoke`
+    /// or `invoke_after` may be called in this method. The caller must not
+    /// hold borrows of a state that these callbacks may access (e.g., a
+    /// `RefCell` also borrowed by a listener). If this method is called again
+    /// by such a callback, it returns `None` without waiting.
+    fn get_clipboard_text(self, kind: ClipboardKind) -> Option<String>;
+
+    /// Get a list of the keyboard input sources (keyboard layouts and input
+    /// methods) available for selection.
+    ///
+    /// Returns an empty list if the backend doesn't support enumerating input
+    /// sources. The GTK backend doesn't because input methods are managed by
+    /// an input method framework (e.g., IBus) outside of GTK.
+    fn input_sources(self) -> Vec<InputSourceInfo> {
+        Vec::new()
+    }
+
+    /// Select the input source specified by [`InputSourceInfo::id`]. Returns
+    /// `true` on success.
+    ///
+    /// The system may refuse the request. On macOS, the input source must be
+    /// enabled by the user. On Windows, only keyboard layouts installed for
+    /// the current user can be selected, and the selection applies to the
+    /// current process only.
+    fn set_input_source(self, _id: &str) -> bool {
+        false
+    }
+}
+
+/// Describes a keyboard input source. Returned by [`Wm::input_sources`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputSourceInfo {
+    /// A backend-specific identifier of the input source, which can be passed
+    /// to [`Wm::set_input_source`].
+    pub id: String,
+    /// The localized name of the input source.
+    pub name: String,
+    /// Indicates whether the input source is currently selected.
+    pub selected: bool,
+}
+
+/// Specifies a clipboard to operate on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ClipboardKind {
+    /// The system clipboard used by the cut, copy, and paste commands.
+    Clipboard,
+    /// The primary selection of X11, which is conventionally updated when
+    /// text is selected and pasted by a middle click.
+    ///
+    /// On systems not supporting this, this is treated as `Clipboard`.
+    Primary,
 }
 
 /// Returned when a function/method is called from an invalid thread.
@@ -240,6 +359,42 @@ impl std::fmt::Display for BadThread {
 
 impl std::error::Error for BadThread {}
 
+/// Describes the characteristics of a display device.
+///
+/// Returned by [`Wm::get_wnd_monitor_info`].
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MonitorInfo {
+    /// The refresh rate in hertz. `0.0` indicates the refresh rate is unknown.
+    ///
+    /// On GTK, this is only available if the `gtk_v3_22` feature is enabled.
+    pub refresh_rate: f32,
+    /// Indicates whether the display is capable of high dynamic range (HDR)
+    /// output.
+    ///
+    /// The backends currently do not render HDR contents. This only serves as
+    /// a hint for the application to decide on color handling.
+    ///
+    /// This is always `false` on GTK, which doesn't expose this information.
+    pub hdr: bool,
+    /// The maximum luminance of the display in nits (cd/m²), if known.
+    pub max_luminance: Option<f32>,
+}
+
+/// The distances from the edges of a window's content region, measured in
+/// logical pixels.
+///
+/// Returned by [`Wm::get_wnd_content_insets`]. System controls do not
+/// necessarily span the entire edge. For example, the standard window buttons
+/// on macOS only occupy the top-left corner, in which case both `top` and
+/// `left` are non-zero and the application may use them to locate the corner.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Insets {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
 #[allow(clippy::option_option)] // for consistency between fields
 pub struct WndAttrs<'a, T: Wm, TLayer> {
     /// The size of the content region.
@@ -298,6 +453,22 @@ bitflags! {
         /// On macOS, the standard window buttons (a.k.a. “stoplight”) are
         /// displayed.
         const FULL_SIZE_CONTENT = 1 << 3;
+
+        /// Removes the drop shadow drawn by the system around the window.
+        /// This flag will be ignored if not supported by the system.
+        ///
+        /// This is useful for borderless windows attached flush to another
+        /// element (e.g., tooltips). Popup menus usually want to keep the
+        /// shadow.
+        ///
+        /// On Windows, a borderless window has a shadow only if it's created
+        /// without this flag. Changing this flag later doesn't add or remove
+        /// the shadow of a borderless window.
+        ///
+        /// On GTK, this flag only affects windows with client-side
+        /// decorations (e.g., `FULL_SIZE_CONTENT`). The shadow of other windows
+        /// is controlled by the window manager.
+        const NO_SHADOW = 1 << 4;
     }
 }
 
@@ -478,6 +649,24 @@ pub trait WndListener<T: Wm> {
     /// The window got or lost focus.
     fn focus(&self, _: T, _: &T::HWnd) {}
 
+    /// The window became the application's active window or lost that status.
+    /// `active` is the new state.
+    ///
+    /// Activation is distinct from focus (see [`Wm::is_wnd_focused`]). For
+    /// example, on macOS, the active window is the main window, which remains
+    /// so while a panel has keyboard focus. Applications can use this to
+    /// render the window chrome of inactive windows differently.
+    ///
+    /// Backend-specific notes:
+    ///
+    ///  - On macOS, this is triggered by `windowDidBecomeMain` and
+    ///    `windowDidResignMain`.
+    ///  - On Windows, this follows `WM_NCACTIVATE`, which indicates whether
+    ///    the non-client area should be drawn as active. This may be called
+    ///    repeatedly with the same state.
+    ///  - On GTK, this follows the `is-active` property of the `GtkWindow`.
+    fn active(&self, _: T, _: &T::HWnd, _active: bool) {}
+
     /// The window is ready to accept a new update.
     ///
     /// This method gets called after the client calls
@@ -498,6 +687,13 @@ pub trait WndListener<T: Wm> {
     /// in this method.
     fn resize(&self, _: T, _: &T::HWnd) {}
 
+    /// A window has been moved.
+    ///
+    /// `new_pos` is the new location of the top-left corner of the window's
+    /// content region in the global screen coordinate space, measured in
+    /// logical pixels. The y axis points down.
+    fn moved(&self, _: T, _: &T::HWnd, _new_pos: Point2<f32>) {}
+
     /// The DPI scaling factor of a window has been updated.
     fn dpi_scale_changed(&self, _: T, _: &T::HWnd) {}
 
@@ -1039,6 +1235,27 @@ pub trait TextInputCtxEdit<T: Wm> {
     /// This method roughly corresponds to: `ITextStoreACP::GetTextExt`,
     /// `[NSTextInputClient firstRectForCharacterRange:actualRange:]`
     fn slice_bounds(&mut self, range: Range<usize>) -> (Box2<f32>, usize);
+
+    /// Get the bounding rectangle in the window coordinates of the caret
+    /// (the insertion point).
+    ///
+    /// The system uses this to position the candidate window of an input
+    /// method near the insertion point. The backend is responsible for
+    /// converting it to the screen coordinates if needed. An invalid `Box2`
+    /// means the caret is currently invisible.
+    ///
+    /// The default implementation calls [`slice_bounds`] with an empty range
+    /// located at the end of the current selection.
+    ///
+    /// This method roughly corresponds to:
+    /// `[NSTextInputClient firstRectForCharacterRange:actualRange:]`
+    /// (with an empty range), `gtk_im_context_set_cursor_location`
+    ///
+    /// [`slice_bounds`]: TextInputCtxEdit::slice_bounds
+    fn caret_bounds(&mut self) -> Box2<f32> {
+        let i = self.selected_range().end;
+        self.slice_bounds(i..i).0
+    }
 }
 
 bitflags! {
@@ -1126,6 +1343,19 @@ pub trait Canvas: Debug {
     /// After the operation, this method resets the current path to an empty
     /// path.
     fn fill(&mut self);
+    /// Fill the area within the current path, using the specified fill rule.
+    ///
+    /// After the operation, this method resets the current path to an empty
+    /// path.
+    ///
+    /// The default implementation calls [`fill`] regardless of `rule`, which
+    /// is only correct for [`FillRule::NonZero`]. Implementations supporting
+    /// [`FillRule::EvenOdd`] should override this method.
+    ///
+    /// [`fill`]: Canvas::fill
+    fn fill_with_rule(&mut self, _rule: FillRule) {
+        self.fill()
+    }
     /// Draw a line along the current path.
     ///
     /// After the operation, this method resets the current path to an empty
@@ -1207,6 +1437,16 @@ pub enum LineJoin {
     Bevel,
 }
 
+/// Specifies how to determine the area within a path. Used by
+/// [`Canvas::fill_with_rule`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FillRule {
+    /// The non-zero winding number rule.
+    NonZero,
+    /// The even-odd rule.
+    EvenOdd,
+}
+
 /// A builder type for [`Bitmap`] supporting 2D drawing operations via
 /// [`Canvas`].
 pub trait BitmapBuilder: Canvas {
@@ -10,6 +10,7 @@
 // deduced to `()`. Thus a call to `msg_send!` needs a unit value binding
 #![allow(clippy::let_unit_value)]
 
+pub mod animation;
 mod canvas;
 pub mod futuresext;
 pub mod iface;
@@ -26,7 +27,7 @@ pub mod prelude {
         Wm as WmTrait, WndListener,
     };
 
-    pub use super::futuresext::WmFuturesExt;
+    pub use super::{animation::WmAnimationExt, futuresext::WmFuturesExt};
 }
 
 // TODO: Color theme
@@ -205,9 +206,10 @@ pub type TextLayout = current::TextLayout;
 // the default backend.
 
 pub use self::iface::{
-    actions, ActionId, ActionStatus, BadThread, Beam, CursorShape, IndexFromPointFlags,
-    InterpretEventCtx, LayerFlags, LineCap, LineJoin, NcHit, RunFlags, RunMetrics, ScrollDelta,
-    SysFontType, TextDecorFlags, TextInputCtxEventFlags, WndFlags, RGBAF32,
+    actions, ActionId, ActionStatus, BadThread, Beam, ClipboardKind, CursorShape, FillRule,
+    IndexFromPointFlags, InputSourceInfo, Insets, InterpretEventCtx, LayerFlags, LineCap, LineJoin,
+    MonitorInfo, NcHit, RunFlags, RunMetrics, ScrollDelta, SysFontType, TextDecorFlags,
+    TextInputCtxEventFlags, WndFlags, RGBAF32,
 };
 
 /// The window handle type of [`Wm`].
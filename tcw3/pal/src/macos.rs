@@ -8,7 +8,9 @@ pub type LayerAttrs = iface::LayerAttrs<Bitmap, HLayer>;
 pub type MtSticky<T> = super::MtSticky<T, Wm>;
 
 mod bitmap;
+mod clipboard;
 mod drawutils;
+mod inputsource;
 mod layer;
 mod text;
 mod utils;
@@ -137,12 +139,36 @@ impl iface::Wm for Wm {
         window.is_focused(self)
     }
 
+    fn get_wnd_monitor_info(self, window: &Self::HWnd) -> iface::MonitorInfo {
+        window.get_monitor_info(self)
+    }
+
+    fn get_wnd_content_insets(self, window: &Self::HWnd) -> iface::Insets {
+        window.get_content_insets(self)
+    }
+
+    fn prefers_reduced_motion(self) -> bool {
+        use cocoa::base::id;
+        use objc::{class, runtime::BOOL};
+        unsafe {
+            let workspace: id = msg_send![class!(NSWorkspace), sharedWorkspace];
+            let value: BOOL = msg_send![workspace, accessibilityDisplayShouldReduceMotion];
+            value != objc::runtime::NO
+        }
+    }
+
     fn new_layer(self, attrs: LayerAttrs) -> Self::HLayer {
         HLayer::new(self, attrs)
     }
     fn set_layer_attr(self, layer: &Self::HLayer, attrs: LayerAttrs) {
         layer.set_attrs(self, attrs);
     }
+    fn set_layer_attrs_batch<'a>(
+        self,
+        updates: impl IntoIterator<Item = (&'a Self::HLayer, LayerAttrs)>,
+    ) {
+        HLayer::set_attrs_batch(self, updates);
+    }
     fn remove_layer(self, layer: &Self::HLayer) {
         layer.remove(self);
     }
@@ -174,4 +200,20 @@ impl iface::Wm for Wm {
     fn remove_text_input_ctx(self, htictx: &Self::HTextInputCtx) {
         self.text_input_ctx_set_active(htictx, false)
     }
+
+    fn set_clipboard_text(self, kind: iface::ClipboardKind, text: &str) {
+        clipboard::set_clipboard_text(self, kind, text);
+    }
+
+    fn get_clipboard_text(self, kind: iface::ClipboardKind) -> Option<String> {
+        clipboard::get_clipboard_text(self, kind)
+    }
+
+    fn input_sources(self) -> Vec<iface::InputSourceInfo> {
+        inputsource::input_sources(self)
+    }
+
+    fn set_input_source(self, id: &str) -> bool {
+        inputsource::set_input_source(self, id)
+    }
 }
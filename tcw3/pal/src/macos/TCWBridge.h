@@ -23,9 +23,11 @@ typedef OPAQUE_HANDLE TCWScrollListenerUserData;
 extern BOOL tcw_wndlistener_should_close(TCWListenerUserData ud);
 extern void tcw_wndlistener_close(TCWListenerUserData ud);
 extern void tcw_wndlistener_resize(TCWListenerUserData ud);
+extern void tcw_wndlistener_moved(TCWListenerUserData ud, NSPoint new_pos);
 extern void tcw_wndlistener_dpi_scale_changed(TCWListenerUserData ud);
 extern void tcw_wndlistener_update_ready(TCWListenerUserData ud);
 extern void tcw_wndlistener_focus(TCWListenerUserData ud);
+extern void tcw_wndlistener_active(TCWListenerUserData ud, BOOL active);
 extern int tcw_wndlistener_key_down(TCWListenerUserData ud, uint16_t mod,
                                     uint16_t charcode_unmod);
 extern int tcw_wndlistener_key_up(TCWListenerUserData ud, uint16_t mod,
@@ -90,6 +92,7 @@ extern void tcw_scrolllistener_motion(TCWScrollListenerUserData ud,
 #define kTCW3WndFlagsBorderless ((uint32_t)(1 << 1))
 #define kTCW3WndFlagsTransparentBackdropBlur ((uint32_t)(1 << 2))
 #define kTCW3WndFlagsFullSizeContent ((uint32_t)(1 << 3))
+#define kTCW3WndFlagsNoShadow ((uint32_t)(1 << 4))
 
 // These callbacks are defined in `timer.rs`
 typedef struct _TraitObject {
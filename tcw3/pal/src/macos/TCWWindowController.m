@@ -150,6 +150,8 @@ extern CGSConnection CGSDefaultConnectionForThread();
     self->window.titleVisibility = (flags & kTCW3WndFlagsFullSizeContent) != 0;
     self->window.movable = (flags & kTCW3WndFlagsFullSizeContent) == 0;
 
+    self->window.hasShadow = (flags & kTCW3WndFlagsNoShadow) == 0;
+
     self->window.styleMask = masks;
 }
 
@@ -189,6 +191,100 @@ extern CGSConnection CGSDefaultConnectionForThread();
     return self->window.keyWindow;
 }
 
+/** Called by `window.rs` */
+- (float)monitorRefreshRate {
+    NSScreen *screen = self->window.screen;
+    if (!screen) {
+        return 0.0f;
+    }
+
+    NSNumber *displayIDNum =
+        [screen.deviceDescription objectForKey:@"NSScreenNumber"];
+    CGDirectDisplayID displayID =
+        (CGDirectDisplayID)displayIDNum.unsignedIntegerValue;
+
+    double rate = 0.0;
+
+    CGDisplayModeRef mode = CGDisplayCopyDisplayMode(displayID);
+    if (mode) {
+        rate = CGDisplayModeGetRefreshRate(mode);
+        CGDisplayModeRelease(mode);
+    }
+
+    // Built-in displays report `0`. Fall back to `CVDisplayLink`'s nominal
+    // refresh period in this case.
+    if (rate == 0.0) {
+        CVDisplayLinkRef link;
+        if (CVDisplayLinkCreateWithCGDisplay(displayID, &link) ==
+            kCVReturnSuccess) {
+            CVTime period =
+                CVDisplayLinkGetNominalOutputVideoRefreshPeriod(link);
+            if (!(period.flags & kCVTimeIsIndefinite) &&
+                period.timeValue != 0) {
+                rate = (double)period.timeScale / (double)period.timeValue;
+            }
+            CVDisplayLinkRelease(link);
+        }
+    }
+
+    return (float)rate;
+}
+
+/** Called by `window.rs` */
+- (BOOL)monitorSupportsHdr {
+    NSScreen *screen = self->window.screen;
+    if (!screen) {
+        return NO;
+    }
+
+    if (@available(macOS 10.15, *)) {
+        return screen.maximumPotentialExtendedDynamicRangeColorComponentValue >
+               1.0;
+    }
+
+    return NO;
+}
+
+/** Called by `window.rs` */
+- (NSSize)windowButtonsExtent {
+    NSView *contentView = self->window.contentView;
+
+    if (!(self->window.styleMask & NSWindowStyleMaskFullSizeContentView)) {
+        // The standard window buttons are outside the content view
+        return NSZeroSize;
+    }
+
+    NSRect buttonsRect = NSZeroRect;
+    NSWindowButton kinds[] = {NSWindowCloseButton, NSWindowMiniaturizeButton,
+                              NSWindowZoomButton};
+    for (size_t i = 0; i < sizeof(kinds) / sizeof(kinds[0]); ++i) {
+        NSButton *button = [self->window standardWindowButton:kinds[i]];
+        if (!button || button.hidden) {
+            continue;
+        }
+
+        // Convert to the window's coordinate space
+        NSRect rect = [button convertRect:button.bounds toView:nil];
+        buttonsRect = NSIsEmptyRect(buttonsRect)
+                          ? rect
+                          : NSUnionRect(buttonsRect, rect);
+    }
+
+    if (NSIsEmptyRect(buttonsRect)) {
+        return NSZeroSize;
+    }
+
+    // `contentView` covers the whole window, so its frame is in the window's
+    // coordinate space (whose origin is at the bottom-left corner). Include
+    // the margin between the buttons and the window edges on both sides.
+    NSRect contentRect = contentView.frame;
+    CGFloat marginLeft = NSMinX(buttonsRect) - NSMinX(contentRect);
+    CGFloat marginTop = NSMaxY(contentRect) - NSMaxY(buttonsRect);
+
+    return NSMakeSize(NSMaxX(buttonsRect) - NSMinX(contentRect) + marginLeft,
+                      NSMaxY(contentRect) - NSMinY(buttonsRect) + marginTop);
+}
+
 /** Called by `window.rs` */
 - (void)requestUpdateReady {
     if (!self->displayLink) {
@@ -346,6 +442,18 @@ extern CGSConnection CGSDefaultConnectionForThread();
     tcw_wndlistener_resize(self.listenerUserData);
 }
 
+/** Implements `NSWindowDelegate`. */
+- (void)windowDidMove:(NSNotification *)notification {
+    (void)notification;
+
+    // Convert the content rect's origin to a top-left-origin coordinate space
+    NSRect rect = [self->window contentRectForFrameRect:self->window.frame];
+    CGFloat screenHeight = NSScreen.screens[0].frame.size.height;
+    NSPoint pos = NSMakePoint(rect.origin.x, screenHeight - NSMaxY(rect));
+
+    tcw_wndlistener_moved(self.listenerUserData, pos);
+}
+
 /** Implements `NSWindowDelegate`. */
 - (void)windowDidChangeBackingProperties:(NSNotification *)notification {
     (void)notification;
@@ -370,6 +478,18 @@ extern CGSConnection CGSDefaultConnectionForThread();
     tcw_wndlistener_focus(self.listenerUserData);
 }
 
+/** Implements `NSWindowDelegate`. */
+- (void)windowDidBecomeMain:(NSNotification *)notification {
+    (void)notification;
+    tcw_wndlistener_active(self.listenerUserData, YES);
+}
+
+/** Implements `NSWindowDelegate`. */
+- (void)windowDidResignMain:(NSNotification *)notification {
+    (void)notification;
+    tcw_wndlistener_active(self.listenerUserData, NO);
+}
+
 /**
  * Create a new `TCWGestureHandlerView` and add it to the window.
  */
@@ -117,6 +117,14 @@ impl iface::Canvas for BitmapBuilder {
     fn fill(&mut self) {
         self.cg_context.fill_path();
     }
+    fn fill_with_rule(&mut self, rule: iface::FillRule) {
+        match rule {
+            iface::FillRule::NonZero => self.cg_context.fill_path(),
+            iface::FillRule::EvenOdd => unsafe {
+                CGContextEOFillPath((&*self.cg_context) as *const CGContextRef as *const u8);
+            },
+        }
+    }
     fn stroke(&mut self) {
         self.cg_context.stroke_path();
     }
@@ -183,4 +191,5 @@ impl iface::Canvas for BitmapBuilder {
 #[link(name = "CoreGraphics", kind = "framework")]
 extern "C" {
     fn CGContextSetStrokeColorWithColor(context: *const u8, color: SysCGColorRef);
+    fn CGContextEOFillPath(context: *const u8);
 }
@@ -0,0 +1,37 @@
+//! Clipboard
+use cocoa::{
+    appkit::{NSPasteboard, NSPasteboardTypeString},
+    base::nil,
+    foundation::NSString,
+};
+use std::ffi::CStr;
+
+use super::{utils::IdRef, Wm};
+use crate::iface;
+
+/// Implements `Wm::set_clipboard_text`.
+///
+/// macOS doesn't have the primary selection, so `kind` is ignored.
+pub fn set_clipboard_text(_: Wm, _kind: iface::ClipboardKind, text: &str) {
+    unsafe {
+        let pasteboard = NSPasteboard::generalPasteboard(nil);
+        let ns_text = IdRef::new(NSString::alloc(nil).init_str(text));
+
+        pasteboard.clearContents();
+        pasteboard.setString_forType(*ns_text, NSPasteboardTypeString);
+    }
+}
+
+/// Implements `Wm::get_clipboard_text`.
+pub fn get_clipboard_text(_: Wm, _kind: iface::ClipboardKind) -> Option<String> {
+    unsafe {
+        let pasteboard = NSPasteboard::generalPasteboard(nil);
+        let ns_text = pasteboard.stringForType(NSPasteboardTypeString);
+        if ns_text == nil {
+            return None;
+        }
+
+        let st = CStr::from_ptr(ns_text.UTF8String());
+        Some(st.to_string_lossy().into_owned())
+    }
+}
@@ -0,0 +1,121 @@
+//! Keyboard input sources (Text Input Sources Services)
+use core_foundation::{
+    array::{CFArray, CFArrayRef},
+    base::{Boolean, CFType, CFTypeRef, OSStatus, TCFType},
+    boolean::CFBoolean,
+    dictionary::{CFDictionary, CFDictionaryRef},
+    string::{CFString, CFStringRef},
+};
+
+use super::Wm;
+use crate::iface;
+
+type TISInputSourceRef = CFTypeRef;
+
+/// Implements `Wm::input_sources`.
+pub fn input_sources(_: Wm) -> Vec<iface::InputSourceInfo> {
+    let filter = unsafe {
+        CFDictionary::from_CFType_pairs(&[
+            (
+                CFString::wrap_under_get_rule(kTISPropertyInputSourceCategory),
+                CFString::wrap_under_get_rule(kTISCategoryKeyboardInputSource).as_CFType(),
+            ),
+            (
+                CFString::wrap_under_get_rule(kTISPropertyInputSourceIsSelectCapable),
+                CFBoolean::true_value().as_CFType(),
+            ),
+        ])
+    };
+
+    let sources = match create_input_source_list(&filter) {
+        Some(sources) => sources,
+        None => return Vec::new(),
+    };
+
+    sources
+        .iter()
+        .map(|source| {
+            let source = source.as_CFTypeRef();
+            unsafe {
+                iface::InputSourceInfo {
+                    id: get_string_property(source, kTISPropertyInputSourceID),
+                    name: get_string_property(source, kTISPropertyLocalizedName),
+                    selected: TISGetInputSourceProperty(source, kTISPropertyInputSourceIsSelected)
+                        == CFBoolean::true_value().as_CFTypeRef(),
+                }
+            }
+        })
+        .collect()
+}
+
+/// Implements `Wm::set_input_source`.
+pub fn set_input_source(_: Wm, id: &str) -> bool {
+    let filter = unsafe {
+        CFDictionary::from_CFType_pairs(&[(
+            CFString::wrap_under_get_rule(kTISPropertyInputSourceID),
+            CFString::new(id).as_CFType(),
+        )])
+    };
+
+    let sources = match create_input_source_list(&filter) {
+        Some(sources) => sources,
+        None => return false,
+    };
+
+    let source = match sources.iter().next() {
+        Some(source) => source,
+        None => {
+            log::warn!("set_input_source: unknown input source {:?}", id);
+            return false;
+        }
+    };
+
+    let status = unsafe { TISSelectInputSource(source.as_CFTypeRef()) };
+    if status != 0 {
+        log::warn!("TISSelectInputSource failed with status {}", status);
+        false
+    } else {
+        true
+    }
+}
+
+fn create_input_source_list(filter: &CFDictionary<CFString, CFType>) -> Option<CFArray<CFType>> {
+    unsafe {
+        let array_ref = TISCreateInputSourceList(filter.as_concrete_TypeRef(), 0);
+        if array_ref.is_null() {
+            None
+        } else {
+            Some(CFArray::wrap_under_create_rule(array_ref))
+        }
+    }
+}
+
+/// Get a string property of an input source. Returns an empty string if the
+/// property is missing.
+unsafe fn get_string_property(source: TISInputSourceRef, key: CFStringRef) -> String {
+    let value = TISGetInputSourceProperty(source, key) as CFStringRef;
+    if value.is_null() {
+        String::new()
+    } else {
+        CFString::wrap_under_get_rule(value).to_string()
+    }
+}
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    static kTISPropertyInputSourceCategory: CFStringRef;
+    static kTISPropertyInputSourceIsSelectCapable: CFStringRef;
+    static kTISPropertyInputSourceIsSelected: CFStringRef;
+    static kTISPropertyInputSourceID: CFStringRef;
+    static kTISPropertyLocalizedName: CFStringRef;
+    static kTISCategoryKeyboardInputSource: CFStringRef;
+
+    fn TISCreateInputSourceList(
+        properties: CFDictionaryRef,
+        include_all_installed: Boolean,
+    ) -> CFArrayRef;
+
+    fn TISGetInputSourceProperty(source: TISInputSourceRef, key: CFStringRef) -> CFTypeRef;
+
+    fn TISSelectInputSource(source: TISInputSourceRef) -> OSStatus;
+}
@@ -61,6 +61,19 @@ impl HLayer {
         layer_pool.deallocate(self.ptr).unwrap();
     }
 
+    pub(super) fn set_attrs_batch<'a>(
+        wm: Wm,
+        updates: impl IntoIterator<Item = (&'a Self, LayerAttrs)>,
+    ) {
+        // Group the updates into a single transaction so that Core Animation
+        // doesn't have to process them one by one
+        transaction::begin();
+        for (layer, attrs) in updates {
+            layer.set_attrs(wm, attrs);
+        }
+        transaction::commit();
+    }
+
     pub(super) fn set_attrs(&self, wm: Wm, attrs: LayerAttrs) {
         let mut layer_pool = LAYER_POOL.get_with_wm(wm).borrow_mut();
         let layer_pool = &mut *layer_pool; // enable split borrow
@@ -217,6 +217,26 @@ impl HWnd {
         let value: BOOL = unsafe { msg_send![*self.ctrler, isKeyWindow] };
         value != NO
     }
+
+    pub(super) fn get_monitor_info(&self, _: Wm) -> iface::MonitorInfo {
+        let refresh_rate: f32 = unsafe { msg_send![*self.ctrler, monitorRefreshRate] };
+        let hdr: BOOL = unsafe { msg_send![*self.ctrler, monitorSupportsHdr] };
+        iface::MonitorInfo {
+            refresh_rate,
+            hdr: hdr != NO,
+            // There is no public API to retrieve this
+            max_luminance: None,
+        }
+    }
+
+    pub(super) fn get_content_insets(&self, _: Wm) -> iface::Insets {
+        let extent: NSSize = unsafe { msg_send![*self.ctrler, windowButtonsExtent] };
+        iface::Insets {
+            top: extent.height as f32,
+            left: extent.width as f32,
+            ..Default::default()
+        }
+    }
 }
 
 // ---------------------------------------------------------------------------
@@ -466,6 +486,17 @@ unsafe extern "C" fn tcw_wndlistener_resize(ud: TCWListenerUserData) {
     });
 }
 
+#[no_mangle]
+unsafe extern "C" fn tcw_wndlistener_moved(ud: TCWListenerUserData, new_pos: NSPoint) {
+    method_impl(ud, |wm, state| {
+        state.listener.borrow().moved(
+            wm,
+            &state.hwnd,
+            point2_from_ns_point(new_pos).cast().unwrap(),
+        );
+    });
+}
+
 #[no_mangle]
 unsafe extern "C" fn tcw_wndlistener_dpi_scale_changed(ud: TCWListenerUserData) {
     method_impl(ud, |wm, state| {
@@ -480,6 +511,16 @@ unsafe extern "C" fn tcw_wndlistener_focus(ud: TCWListenerUserData) {
     });
 }
 
+#[no_mangle]
+unsafe extern "C" fn tcw_wndlistener_active(ud: TCWListenerUserData, active: BOOL) {
+    method_impl(ud, |wm, state| {
+        state
+            .listener
+            .borrow()
+            .active(wm, &state.hwnd, active != NO);
+    });
+}
+
 #[no_mangle]
 unsafe extern "C" fn tcw_wndlistener_update_ready(ud: TCWListenerUserData) {
     method_impl(ud, |wm, state| {
@@ -1374,7 +1415,14 @@ unsafe extern "C" fn tcw_wnd_get_text_rect(
 
         // Query the bounding rectangle. This may return a shorter range
         // `range_u8.start .. head_end_u8`
-        let (cg_bounds, head_end_u8) = edit.slice_bounds(range_u8.clone());
+        let (cg_bounds, head_end_u8) =
+            if range_u8.start == range_u8.end && range_u8.start == edit.selected_range().end {
+                // The input method is probably asking for the caret position
+                // to place the candidate window
+                (edit.caret_bounds(), range_u8.start)
+            } else {
+                edit.slice_bounds(range_u8.clone())
+            };
         log::trace!(
             "... new actual range (UTF-8) = {:?}",
             range_u8.start..head_end_u8
@@ -105,6 +105,7 @@ use cgmath::{Matrix3, Point2};
 use lazy_static::lazy_static;
 use log::{debug, trace};
 use std::{
+    cell::{Cell, RefCell},
     fmt,
     marker::PhantomData,
     ops::Range,
@@ -331,6 +332,15 @@ fn try_start_testing_main_thread() {
 
 mt_lazy_static! {
     static <Wm> ref SCREEN: screen::Screen => |_| screen::Screen::new();
+
+    // The contents of the virtual clipboards, indexed by `ClipboardKind`
+    static <Wm> ref CLIPBOARDS: RefCell<[Option<String>; 2]> => |_| Default::default();
+
+    // The virtual input sources
+    static <Wm> ref INPUT_SOURCES: RefCell<Vec<iface::InputSourceInfo>> => |_| Vec::new();
+
+    // The value returned by `Wm::prefers_reduced_motion`
+    static <Wm> ref PREFERS_REDUCED_MOTION: Cell<bool> => |_| Cell::new(false);
 }
 
 impl Wm {
@@ -338,6 +348,18 @@ impl Wm {
         self.eradicate_events();
         SCREEN.get_with_wm(self).reset();
         textinput::reset(self);
+        *CLIPBOARDS.get_with_wm(self).borrow_mut() = Default::default();
+        INPUT_SOURCES.get_with_wm(self).borrow_mut().clear();
+        PREFERS_REDUCED_MOTION.get_with_wm(self).set(false);
+    }
+}
+
+/// Get the current time. If the testing backend is active, this is based on
+/// the virtual clock advanced by `TestingWm::advance_time`.
+pub(crate) fn now(wm: Wm) -> std::time::Instant {
+    match wm.backend_and_wm() {
+        BackendAndWm::Native { .. } => std::time::Instant::now(),
+        BackendAndWm::Testing => wm.now(),
     }
 }
 
@@ -361,6 +383,11 @@ impl wmapi::TestingWm for Wm {
         self.step_timeout(Some(duration));
     }
 
+    fn advance_time(&self, duration: Duration) {
+        trace!("advance_time({:?})", duration);
+        (*self).advance_time(duration);
+    }
+
     fn hwnds(&self) -> Vec<HWnd> {
         (SCREEN.get_with_wm(*self).hwnds())
             .iter()
@@ -390,6 +417,13 @@ impl wmapi::TestingWm for Wm {
         SCREEN.get_with_wm(*self).set_wnd_size(*self, hwnd, size)
     }
 
+    fn simulate_move(&self, hwnd: &HWnd, new_pos: Point2<f32>) {
+        let hwnd = hwnd.testing_hwnd_ref().unwrap();
+        SCREEN
+            .get_with_wm(*self)
+            .simulate_move(*self, hwnd, new_pos)
+    }
+
     fn set_wnd_focused(&self, hwnd: &HWnd, focused: bool) {
         let hwnd = hwnd.testing_hwnd_ref().unwrap();
         SCREEN
@@ -397,6 +431,32 @@ impl wmapi::TestingWm for Wm {
             .set_wnd_focused(*self, hwnd, focused)
     }
 
+    fn simulate_activation(&self, hwnd: &HWnd, active: bool) {
+        let hwnd = hwnd.testing_hwnd_ref().unwrap();
+        SCREEN
+            .get_with_wm(*self)
+            .simulate_activation(*self, hwnd, active)
+    }
+
+    fn is_wnd_active(&self, hwnd: &HWnd) -> bool {
+        let hwnd = hwnd.testing_hwnd_ref().unwrap();
+        SCREEN.get_with_wm(*self).is_wnd_active(hwnd)
+    }
+
+    fn set_wnd_monitor_info(&self, hwnd: &HWnd, monitor_info: iface::MonitorInfo) {
+        let hwnd = hwnd.testing_hwnd_ref().unwrap();
+        SCREEN
+            .get_with_wm(*self)
+            .set_wnd_monitor_info(hwnd, monitor_info)
+    }
+
+    fn set_wnd_content_insets(&self, hwnd: &HWnd, insets: iface::Insets) {
+        let hwnd = hwnd.testing_hwnd_ref().unwrap();
+        SCREEN
+            .get_with_wm(*self)
+            .set_wnd_content_insets(hwnd, insets)
+    }
+
     fn read_wnd_snapshot(&self, hwnd: &HWnd, out: &mut wmapi::WndSnapshot) {
         let hwnd = hwnd.testing_hwnd_ref().unwrap();
         SCREEN.get_with_wm(*self).read_wnd_snapshot(hwnd, out)
@@ -471,6 +531,10 @@ impl wmapi::TestingWm for Wm {
             .raise_edit(*self, write)
     }
 
+    fn text_input_ctx_caret_bounds(&self, htictx: &HTextInputCtx) -> Option<Box2<f32>> {
+        htictx.testing_htictx_ref().unwrap().caret_bounds(*self)
+    }
+
     fn translate_action(
         &self,
         hwnd: &HWnd,
@@ -526,6 +590,14 @@ impl wmapi::TestingWm for Wm {
 
         self.raise_key_up(hwnd, source, pattern);
     }
+
+    fn set_input_sources(&self, sources: Vec<iface::InputSourceInfo>) {
+        *INPUT_SOURCES.get_with_wm(*self).borrow_mut() = sources;
+    }
+
+    fn set_prefers_reduced_motion(&self, value: bool) {
+        PREFERS_REDUCED_MOTION.get_with_wm(*self).set(value);
+    }
 }
 
 impl iface::Wm for Wm {
@@ -747,6 +819,43 @@ impl iface::Wm for Wm {
         }
     }
 
+    fn get_wnd_monitor_info(self, hwnd: &Self::HWnd) -> iface::MonitorInfo {
+        match (self.backend_and_wm(), &hwnd.inner) {
+            (BackendAndWm::Native { wm }, HWndInner::Native(hwnd)) => wm.get_wnd_monitor_info(hwnd),
+            (BackendAndWm::Testing, HWndInner::Testing(tc_hwnd)) => {
+                let value = SCREEN.get_with_wm(self).get_wnd_monitor_info(tc_hwnd);
+                trace!("get_wnd_monitor_info({:?}) -> {:?}", hwnd, value);
+                value
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn get_wnd_content_insets(self, hwnd: &Self::HWnd) -> iface::Insets {
+        match (self.backend_and_wm(), &hwnd.inner) {
+            (BackendAndWm::Native { wm }, HWndInner::Native(hwnd)) => {
+                wm.get_wnd_content_insets(hwnd)
+            }
+            (BackendAndWm::Testing, HWndInner::Testing(tc_hwnd)) => {
+                let value = SCREEN.get_with_wm(self).get_wnd_content_insets(tc_hwnd);
+                trace!("get_wnd_content_insets({:?}) -> {:?}", hwnd, value);
+                value
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn prefers_reduced_motion(self) -> bool {
+        match self.backend_and_wm() {
+            BackendAndWm::Native { wm } => wm.prefers_reduced_motion(),
+            BackendAndWm::Testing => {
+                let value = PREFERS_REDUCED_MOTION.get_with_wm(self).get();
+                trace!("prefers_reduced_motion() -> {:?}", value);
+                value
+            }
+        }
+    }
+
     fn new_layer(self, attrs: LayerAttrs) -> Self::HLayer {
         match self.backend_and_wm() {
             BackendAndWm::Native { wm } => {
@@ -781,6 +890,31 @@ impl iface::Wm for Wm {
             _ => unreachable!(),
         }
     }
+    fn set_layer_attrs_batch<'a>(
+        self,
+        updates: impl IntoIterator<Item = (&'a Self::HLayer, LayerAttrs)>,
+    ) {
+        match self.backend_and_wm() {
+            BackendAndWm::Native { wm } => {
+                wm.set_layer_attrs_batch(updates.into_iter().map(|(hlayer, attrs)| {
+                    (
+                        hlayer.native_hlayer_ref().unwrap(),
+                        layer_attrs_to_native(attrs),
+                    )
+                }));
+            }
+            BackendAndWm::Testing => {
+                let updates = updates.into_iter().map(|(hlayer, attrs)| {
+                    debug!("set_layer_attrs_batch(.., ({:?}, {:?}), ..)", hlayer, attrs);
+                    (
+                        hlayer.testing_hlayer_ref().unwrap(),
+                        layer_attrs_to_testing(attrs),
+                    )
+                });
+                SCREEN.get_with_wm(self).set_layer_attrs_batch(updates);
+            }
+        }
+    }
     fn remove_layer(self, hlayer: &Self::HLayer) {
         match (self.backend_and_wm(), &hlayer.inner) {
             (BackendAndWm::Native { wm }, HLayerInner::Native(hlayer)) => {
@@ -851,9 +985,9 @@ impl iface::Wm for Wm {
             (BackendAndWm::Native { wm }, HTextInputCtxInner::Native(htictx)) => {
                 wm.text_input_ctx_on_layout_change(htictx)
             }
-            (BackendAndWm::Testing, HTextInputCtxInner::Testing(_htictx)) => {
+            (BackendAndWm::Testing, HTextInputCtxInner::Testing(tc_htictx)) => {
                 debug!("text_input_ctx_on_layout_change({:?})", htictx);
-                // TODO: Forward this event
+                tc_htictx.on_layout_change(self);
             }
             _ => unreachable!(),
         }
@@ -884,6 +1018,52 @@ impl iface::Wm for Wm {
             _ => unreachable!(),
         }
     }
+
+    fn set_clipboard_text(self, kind: iface::ClipboardKind, text: &str) {
+        match self.backend_and_wm() {
+            BackendAndWm::Native { wm } => wm.set_clipboard_text(kind, text),
+            BackendAndWm::Testing => {
+                debug!("set_clipboard_text({:?}, {:?})", kind, text);
+                CLIPBOARDS.get_with_wm(self).borrow_mut()[kind as usize] = Some(text.to_owned());
+            }
+        }
+    }
+
+    fn get_clipboard_text(self, kind: iface::ClipboardKind) -> Option<String> {
+        match self.backend_and_wm() {
+            BackendAndWm::Native { wm } => wm.get_clipboard_text(kind),
+            BackendAndWm::Testing => {
+                let value = CLIPBOARDS.get_with_wm(self).borrow()[kind as usize].clone();
+                trace!("get_clipboard_text({:?}) -> {:?}", kind, value);
+                value
+            }
+        }
+    }
+
+    fn input_sources(self) -> Vec<iface::InputSourceInfo> {
+        match self.backend_and_wm() {
+            BackendAndWm::Native { wm } => wm.input_sources(),
+            BackendAndWm::Testing => INPUT_SOURCES.get_with_wm(self).borrow().clone(),
+        }
+    }
+
+    fn set_input_source(self, id: &str) -> bool {
+        match self.backend_and_wm() {
+            BackendAndWm::Native { wm } => wm.set_input_source(id),
+            BackendAndWm::Testing => {
+                debug!("set_input_source({:?})", id);
+                let mut sources = INPUT_SOURCES.get_with_wm(self).borrow_mut();
+                if sources.iter().any(|source| source.id == id) {
+                    for source in sources.iter_mut() {
+                        source.selected = source.id == id;
+                    }
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Hash)]
@@ -942,6 +1122,20 @@ impl HLayer {
             HLayerInner::Testing(imp) => Some(imp),
         }
     }
+
+    fn native_hlayer_ref(&self) -> Option<&native::HLayer> {
+        match &self.inner {
+            HLayerInner::Native(imp) => Some(imp),
+            HLayerInner::Testing(_) => None,
+        }
+    }
+
+    fn testing_hlayer_ref(&self) -> Option<&screen::HLayer> {
+        match &self.inner {
+            HLayerInner::Native(_) => None,
+            HLayerInner::Testing(imp) => Some(imp),
+        }
+    }
 }
 
 impl fmt::Debug for HLayer {
@@ -1252,6 +1446,7 @@ impl iface::Canvas for BitmapBuilder {
         fn cubic_bezier_to(&mut self, cp1: Point2<f32>, cp2: Point2<f32>, p: Point2<f32>);
         fn quad_bezier_to(&mut self, cp: Point2<f32>, p: Point2<f32>);
         fn fill(&mut self);
+        fn fill_with_rule(&mut self, rule: iface::FillRule);
         fn stroke(&mut self);
         fn clip(&mut self);
         fn set_fill_rgb(&mut self, rgb: iface::RGBAF32);
@@ -106,6 +106,33 @@ impl Wm {
         }
     }
 
+    /// Get the current time on the virtual clock used by `invoke_after`.
+    pub(super) fn now(self) -> Instant {
+        Instant::now() + TIMER_QUEUE.get_with_wm(self).borrow().skew()
+    }
+
+    /// Advance the virtual clock used by `invoke_after` and process the
+    /// delayed invocations that become runnable by that.
+    pub(super) fn advance_time(self, duration: Duration) {
+        TIMER_QUEUE.get_with_wm(self).borrow_mut().advance(duration);
+
+        loop {
+            let runnable_tasks: Vec<_> = {
+                let mut timer_queue = TIMER_QUEUE.get_with_wm(self).borrow_mut();
+                timer_queue.drain_runnable_tasks().collect()
+            };
+            if runnable_tasks.is_empty() {
+                break;
+            }
+            for (_, e) in runnable_tasks {
+                e(self);
+            }
+
+            // The tasks might push dispatches to `UNSEND_DISPATCHES`
+            self.step_unsend();
+        }
+    }
+
     pub(super) fn enter_main_loop(self) {
         while let Ok(fun) = self.dispatch_receiver().recv() {
             fun(self);
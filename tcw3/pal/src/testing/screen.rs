@@ -52,6 +52,9 @@ pub struct Wnd {
 
     dpi_scale: f32,
     focused: bool,
+    active: bool,
+    monitor_info: iface::MonitorInfo,
+    content_insets: iface::Insets,
     attrs: wmapi::WndAttrs,
     listener: Rc<dyn iface::WndListener<Wm>>,
 
@@ -94,6 +97,13 @@ impl Screen {
             sr_wnd: state.sr_scrn.new_wnd(),
             dpi_scale: 1.0, // TODO
             focused: false,
+            active: false,
+            monitor_info: iface::MonitorInfo {
+                refresh_rate: 60.0,
+                hdr: false,
+                max_luminance: None,
+            },
+            content_insets: iface::Insets::default(),
             dirty_rect: None,
             attrs: wmapi::WndAttrs {
                 size: attrs.size.unwrap_or([100, 100]),
@@ -183,6 +193,18 @@ impl Screen {
         let state = self.state.borrow();
         state.wnds[hwnd.ptr].focused
     }
+    pub(super) fn is_wnd_active(&self, hwnd: &HWnd) -> bool {
+        let state = self.state.borrow();
+        state.wnds[hwnd.ptr].active
+    }
+    pub(super) fn get_wnd_monitor_info(&self, hwnd: &HWnd) -> iface::MonitorInfo {
+        let state = self.state.borrow();
+        state.wnds[hwnd.ptr].monitor_info
+    }
+    pub(super) fn get_wnd_content_insets(&self, hwnd: &HWnd) -> iface::Insets {
+        let state = self.state.borrow();
+        state.wnds[hwnd.ptr].content_insets
+    }
 
     pub(super) fn new_layer(&self, attrs: LayerAttrs) -> HLayer {
         let mut state = self.state.borrow_mut();
@@ -200,6 +222,18 @@ impl Screen {
             .sr_scrn
             .set_layer_attr(&layer.sr_layer, layer_attrs_to_sr_layer_attrs(attrs));
     }
+    pub(super) fn set_layer_attrs_batch<'a>(
+        &self,
+        updates: impl IntoIterator<Item = (&'a HLayer, LayerAttrs)>,
+    ) {
+        let mut state = self.state.borrow_mut();
+
+        for (layer, attrs) in updates {
+            state
+                .sr_scrn
+                .set_layer_attr(&layer.sr_layer, layer_attrs_to_sr_layer_attrs(attrs));
+        }
+    }
     pub(super) fn remove_layer(&self, layer: &HLayer) {
         let mut state = self.state.borrow_mut();
 
@@ -266,6 +300,12 @@ impl Screen {
         listener.resize(wm, &hwnd.into());
     }
 
+    /// Implements `TestingWm::simulate_move`.
+    pub(super) fn simulate_move(&self, wm: Wm, hwnd: &HWnd, new_pos: Point2<f32>) {
+        let listener = self.wnd_listener(hwnd).unwrap();
+        listener.moved(wm, &hwnd.into(), new_pos);
+    }
+
     /// Implements `TestingWm::set_wnd_focused`.
     pub(super) fn set_wnd_focused(&self, wm: Wm, hwnd: &HWnd, focused: bool) {
         let mut state = self.state.borrow_mut();
@@ -276,6 +316,52 @@ impl Screen {
         listener.focus(wm, &hwnd.into());
     }
 
+    /// Implements `TestingWm::simulate_activation`.
+    pub(super) fn simulate_activation(&self, wm: Wm, hwnd: &HWnd, active: bool) {
+        let mut state = self.state.borrow_mut();
+
+        // Find the windows whose states change
+        let mut changed: Vec<PoolPtr> = if active {
+            state
+                .wnds
+                .ptr_iter()
+                .filter(|&(ptr, wnd)| ptr != hwnd.ptr && wnd.active)
+                .map(|(ptr, _)| ptr)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if state.wnds[hwnd.ptr].active != active {
+            changed.push(hwnd.ptr);
+        }
+
+        for &ptr in changed.iter() {
+            let wnd = &mut state.wnds[ptr];
+            wnd.active = !wnd.active;
+        }
+        drop(state);
+
+        // The deactivated windows are notified first
+        for ptr in changed {
+            let wnd_active = ptr == hwnd.ptr && active;
+            let hwnd = HWnd { ptr };
+            let listener = self.wnd_listener(&hwnd).unwrap();
+            listener.active(wm, &(&hwnd).into(), wnd_active);
+        }
+    }
+
+    /// Implements `TestingWm::set_wnd_monitor_info`.
+    pub(super) fn set_wnd_monitor_info(&self, hwnd: &HWnd, monitor_info: iface::MonitorInfo) {
+        let mut state = self.state.borrow_mut();
+        state.wnds[hwnd.ptr].monitor_info = monitor_info;
+    }
+
+    /// Implements `TestingWm::set_wnd_content_insets`.
+    pub(super) fn set_wnd_content_insets(&self, hwnd: &HWnd, insets: iface::Insets) {
+        let mut state = self.state.borrow_mut();
+        state.wnds[hwnd.ptr].content_insets = insets;
+    }
+
     /// Implements `TestingWm::read_wnd_snapshot`.
     pub(super) fn read_wnd_snapshot(&self, hwnd: &HWnd, out: &mut wmapi::WndSnapshot) {
         let mut state = self.state.borrow_mut();
@@ -53,6 +53,9 @@ pub fn reset(wm: Wm) {
 
 struct TextInputCtx {
     listener: Rc<dyn iface::TextInputCtxListener<Wm>>,
+    /// The value returned by `TextInputCtxEdit::caret_bounds` when
+    /// `on_layout_change` was called for the last time.
+    caret_bounds: Option<Box2<f32>>,
 }
 
 const BORROW_ERROR: &str = "Couldn't lock the input context state. \
@@ -63,6 +66,7 @@ impl HTextInputCtx {
         let mut gis = GIS.get_with_wm(wm).try_borrow_mut().expect(BORROW_ERROR);
         let ptr = gis.ctxs.allocate(TextInputCtx {
             listener: listener.into(),
+            caret_bounds: None,
         });
         drop(gis);
 
@@ -96,6 +100,20 @@ impl HTextInputCtx {
         gis.active_ctxs.iter().cloned().collect()
     }
 
+    pub(super) fn on_layout_change(&self, wm: Wm) {
+        // A real backend would move the candidate window of the active input
+        // method. We just remember the caret position for `caret_bounds`.
+        let caret_bounds = self.raise_edit(wm, false).caret_bounds();
+
+        let mut gis = GIS.get_with_wm(wm).try_borrow_mut().expect(BORROW_ERROR);
+        gis.ctxs[self.ptr].caret_bounds = Some(caret_bounds);
+    }
+
+    pub(super) fn caret_bounds(&self, wm: Wm) -> Option<Box2<f32>> {
+        let gis = GIS.get_with_wm(wm).try_borrow().expect(BORROW_ERROR);
+        gis.ctxs[self.ptr].caret_bounds
+    }
+
     pub(super) fn raise_edit(&self, wm: Wm, write: bool) -> Box<dyn iface::TextInputCtxEdit<Wm>> {
         let gis = GIS.get_with_wm(wm).try_borrow().expect(BORROW_ERROR);
         let pool = &gis.ctxs;
@@ -158,5 +176,6 @@ impl iface::TextInputCtxEdit<Wm> for CtxEdit {
             -> Option<usize>;
         fn frame(&mut self) -> Box2<f32>;
         fn slice_bounds(&mut self, range: Range<usize>) -> (Box2<f32>, usize);
+        fn caret_bounds(&mut self) -> Box2<f32>;
     }
 }
@@ -108,4 +108,8 @@ impl iface::TextInputCtxEdit<native::Wm> for NativeTextInputCtxEdit<'_> {
     fn slice_bounds(&mut self, range: Range<usize>) -> (Box2<f32>, usize) {
         forward!(self.0, slice_bounds, range)
     }
+
+    fn caret_bounds(&mut self) -> Box2<f32> {
+        forward!(self.0, caret_bounds)
+    }
 }
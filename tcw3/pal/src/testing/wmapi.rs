@@ -1,5 +1,6 @@
+use cggeom::Box2;
 use cgmath::{Point2, Vector2};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crate::{iface, HTextInputCtx, HWnd};
 
@@ -25,6 +26,17 @@ pub trait TestingWm: 'static {
     /// until the specified instant.
     fn step_until(&self, till: Instant);
 
+    /// Advance the virtual clock by `duration` without waiting, and process
+    /// the delayed invocations (those generated by `Wm::invoke_after`) that
+    /// become runnable by that, as well as the `!Send` dispatches they
+    /// generate.
+    ///
+    /// The virtual clock also drives [`WmAnimationExt::animate`]. It advances
+    /// along with the real time as well.
+    ///
+    /// [`WmAnimationExt::animate`]: crate::animation::WmAnimationExt::animate
+    fn advance_time(&self, duration: Duration);
+
     /// Get a list of currently open windows.
     fn hwnds(&self) -> Vec<HWnd>;
 
@@ -47,9 +59,39 @@ pub trait TestingWm: 'static {
     /// `size` is not automatically clipped by `min_size` or `max_size`.
     fn set_wnd_size(&self, hwnd: &HWnd, size: [u32; 2]);
 
+    /// Simulate the movement of a given window by triggering
+    /// `WndListener::moved`.
+    fn simulate_move(&self, hwnd: &HWnd, new_pos: Point2<f32>);
+
     /// Set the focus state of a given window and trigger `WndListener::focus`.
     fn set_wnd_focused(&self, hwnd: &HWnd, focused: bool);
 
+    /// Simulate the activation or deactivation of a given window by the user.
+    ///
+    /// The activation state is independent of the focus state set by
+    /// `set_wnd_focused`. This method maintains the invariant that at most one
+    /// window is active at a time. Activating a window deactivates the
+    /// currently active window. `WndListener::active` is triggered for every
+    /// window whose state has changed.
+    fn simulate_activation(&self, hwnd: &HWnd, active: bool);
+
+    /// Get the activation state of a given window, which is controlled by
+    /// `simulate_activation`.
+    fn is_wnd_active(&self, hwnd: &HWnd) -> bool;
+
+    /// Set the characteristics of the virtual display device a given window is
+    /// on, which will be returned by `Wm::get_wnd_monitor_info`.
+    ///
+    /// The default value is `MonitorInfo { refresh_rate: 60.0, hdr: false,
+    /// max_luminance: None }`.
+    fn set_wnd_monitor_info(&self, hwnd: &HWnd, monitor_info: iface::MonitorInfo);
+
+    /// Set the insets occupied by the virtual system controls of a given
+    /// window, which will be returned by `Wm::get_wnd_content_insets`.
+    ///
+    /// The default value is `Insets::default()` (all zeros).
+    fn set_wnd_content_insets(&self, hwnd: &HWnd, insets: iface::Insets);
+
     /// Render the content of a given window and update `out` with it.
     fn read_wnd_snapshot(&self, hwnd: &HWnd, out: &mut WndSnapshot);
 
@@ -84,6 +126,12 @@ pub trait TestingWm: 'static {
         write: bool,
     ) -> Box<dyn iface::TextInputCtxEdit<crate::Wm>>;
 
+    /// Get the caret rectangle most recently reported by the client through
+    /// `Wm::text_input_ctx_on_layout_change` (which calls
+    /// `TextInputCtxEdit::caret_bounds`). This is where a candidate window
+    /// would be displayed.
+    fn text_input_ctx_caret_bounds(&self, htictx: &HTextInputCtx) -> Option<Box2<f32>>;
+
     /// Translate an input event to an action.
     fn translate_action(&self, hwnd: &HWnd, source: &str, pattern: &str)
         -> Option<iface::ActionId>;
@@ -113,6 +161,18 @@ pub trait TestingWm: 'static {
     ///
     /// It doesn't simulate the pressing and releasing of modifier keys, though.
     fn simulate_key(&self, hwnd: &HWnd, source: &str, pattern: &str);
+
+    /// Set the list of the virtual input sources, which will be returned by
+    /// `Wm::input_sources`. `Wm::set_input_source` updates
+    /// `InputSourceInfo::selected` of the elements.
+    ///
+    /// The default value is an empty list.
+    fn set_input_sources(&self, sources: Vec<iface::InputSourceInfo>);
+
+    /// Set the value returned by `Wm::prefers_reduced_motion`.
+    ///
+    /// The default value is `false`.
+    fn set_prefers_reduced_motion(&self, value: bool);
 }
 
 /// A snapshot of window attributes.
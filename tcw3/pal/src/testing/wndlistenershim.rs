@@ -43,6 +43,14 @@ impl iface::WndListener<native::Wm> for NativeWndListener {
         forward!(self.0, resize, [wm: wm], [hwnd: hwnd])
     }
 
+    fn moved(&self, wm: native::Wm, hwnd: &native::HWnd, new_pos: Point2<f32>) {
+        forward!(self.0, moved, [wm: wm], [hwnd: hwnd], new_pos)
+    }
+
+    fn active(&self, wm: native::Wm, hwnd: &native::HWnd, active: bool) {
+        forward!(self.0, active, [wm: wm], [hwnd: hwnd], active)
+    }
+
     fn dpi_scale_changed(&self, wm: native::Wm, hwnd: &native::HWnd) {
         forward!(self.0, dpi_scale_changed, [wm: wm], [hwnd: hwnd])
     }
@@ -53,6 +53,8 @@ use std::{
 pub struct TimerQueue<T> {
     core: TimerQueueCore<(u64, T)>,
     origin: Instant,
+    /// The amount of time the queue's clock has been advanced by `advance`.
+    skew: Duration,
     next_id: u64,
 }
 
@@ -68,6 +70,7 @@ impl<T> TimerQueue<T> {
         Self {
             core: TimerQueueCore::new(),
             origin: Instant::now(),
+            skew: Duration::from_secs(0),
             next_id: 0,
         }
     }
@@ -82,6 +85,29 @@ impl<T> TimerQueue<T> {
         self.core.len() == 0
     }
 
+    /// Advance the queue's clock by `duration` without waiting. The tasks
+    /// become runnable as if `duration` has elapsed.
+    pub fn advance(&mut self, duration: Duration) {
+        self.skew += duration;
+    }
+
+    /// Get the amount of time the queue's clock has been advanced by
+    /// `advance`.
+    pub fn skew(&self) -> Duration {
+        self.skew
+    }
+
+    /// Get the current time on the queue's clock.
+    fn elapsed(&self) -> Duration {
+        self.origin.elapsed() + self.skew
+    }
+
+    /// Convert a time on the queue's clock to a real `Instant`.
+    fn instant_from_elapsed(&self, time: Duration) -> Instant {
+        let instant = self.origin + time;
+        instant.checked_sub(self.skew).unwrap_or(self.origin)
+    }
+
     pub fn insert(&mut self, delay: Range<Duration>, payload: T) -> Result<HTask, CapacityError> {
         // Allocate a task ID
         let id = self.next_id;
@@ -91,7 +117,7 @@ impl<T> TimerQueue<T> {
             .expect("Task ID exhausted");
         self.next_id = new_next_id;
 
-        let offset = self.origin.elapsed();
+        let offset = self.elapsed();
 
         // Convert `Duration`s to `FixTime`s
         let time: Range<FixTime> = map_range(delay, |dur| (dur + offset).into());
@@ -116,14 +142,14 @@ impl<T> TimerQueue<T> {
 
     pub fn drain_runnable_tasks(&mut self) -> impl Iterator<Item = (HTask, T)> + '_ {
         self.core
-            .drain_runnable_tasks(self.origin.elapsed().into())
+            .drain_runnable_tasks(self.elapsed().into())
             .map(|(htask_core, (id, payload))| (HTask::new(htask_core, id), payload))
     }
 
     pub fn suggest_next_wakeup(&self) -> Option<Instant> {
         let time: Option<Duration> = self.core.suggest_next_wakeup().map(Into::into);
 
-        time.map(|time| self.origin + time)
+        time.map(|time| self.instant_from_elapsed(time))
     }
 
     #[allow(dead_code)]
@@ -131,7 +157,7 @@ impl<T> TimerQueue<T> {
         self.core.iter().map(move |(core, time, payload)| {
             (
                 HTask::new(core, payload.0),
-                map_range(time, |dur| self.origin + Duration::from(dur)),
+                map_range(time, |dur| self.instant_from_elapsed(dur.into())),
                 &payload.1,
             )
         })
@@ -715,4 +741,20 @@ mod tests {
         assert_ne!(htask1, htask2);
         assert!(queue.remove(htask1).is_none());
     }
+
+    #[test]
+    fn advance() {
+        let hour = Duration::from_secs(3600);
+        let mut queue = TimerQueue::new();
+
+        let htask = queue.insert(hour..hour, ()).unwrap();
+        assert_eq!(queue.drain_runnable_tasks().count(), 0);
+
+        queue.advance(hour);
+        assert_eq!(queue.skew(), hour);
+        assert!(queue.suggest_next_wakeup().unwrap() <= Instant::now());
+
+        let tasks: Vec<_> = queue.drain_runnable_tasks().collect();
+        assert_eq!(tasks, [(htask, ())]);
+    }
 }
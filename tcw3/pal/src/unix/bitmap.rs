@@ -205,6 +205,15 @@ impl iface::Canvas for BitmapBuilder {
 
         self.cairo_ctx.fill();
     }
+    fn fill_with_rule(&mut self, rule: iface::FillRule) {
+        use cairo::FillRule;
+        self.cairo_ctx.set_fill_rule(match rule {
+            iface::FillRule::NonZero => FillRule::Winding,
+            iface::FillRule::EvenOdd => FillRule::EvenOdd,
+        });
+        self.fill();
+        self.cairo_ctx.set_fill_rule(FillRule::Winding);
+    }
     fn stroke(&mut self) {
         let col = self.state_top.state.stroke_col;
         self.cairo_ctx
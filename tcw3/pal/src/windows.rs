@@ -10,11 +10,13 @@ use std::{cell::Cell, marker::PhantomData, ops::Range, time::Duration};
 
 mod acceltable;
 mod bitmap;
+mod clipboard;
 mod codecvt;
 mod comp;
 mod drawutils;
 mod eventloop;
 mod frameclock;
+mod inputsource;
 mod surface;
 mod text;
 mod textinput;
@@ -121,6 +123,29 @@ impl iface::Wm for Wm {
         window::is_wnd_focused(self, window)
     }
 
+    fn get_wnd_monitor_info(self, window: &Self::HWnd) -> iface::MonitorInfo {
+        window::get_wnd_monitor_info(self, window)
+    }
+
+    fn get_wnd_content_insets(self, window: &Self::HWnd) -> iface::Insets {
+        window::get_wnd_content_insets(self, window)
+    }
+
+    fn prefers_reduced_motion(self) -> bool {
+        use winapi::{shared::minwindef::BOOL, um::winuser};
+        let mut enabled: BOOL = 1;
+        let ok = unsafe {
+            winuser::SystemParametersInfoW(
+                winuser::SPI_GETCLIENTAREAANIMATION,
+                0,
+                &mut enabled as *mut BOOL as _,
+                0,
+            )
+        };
+        // "Show animations in Windows" is unchecked
+        ok != 0 && enabled == 0
+    }
+
     fn request_update_ready_wnd(self, window: &Self::HWnd) {
         window::request_update_ready_wnd(self, window)
     }
@@ -131,6 +156,12 @@ impl iface::Wm for Wm {
     fn set_layer_attr(self, layer: &Self::HLayer, attrs: LayerAttrs) {
         comp::set_layer_attr(self, layer, attrs)
     }
+    fn set_layer_attrs_batch<'a>(
+        self,
+        updates: impl IntoIterator<Item = (&'a Self::HLayer, LayerAttrs)>,
+    ) {
+        comp::set_layer_attrs_batch(self, updates)
+    }
     fn remove_layer(self, layer: &Self::HLayer) {
         comp::remove_layer(self, layer)
     }
@@ -160,6 +191,22 @@ impl iface::Wm for Wm {
     fn remove_text_input_ctx(self, htictx: &Self::HTextInputCtx) {
         textinput::remove_text_input_ctx(self, htictx);
     }
+
+    fn set_clipboard_text(self, kind: iface::ClipboardKind, text: &str) {
+        clipboard::set_clipboard_text(self, kind, text);
+    }
+
+    fn get_clipboard_text(self, kind: iface::ClipboardKind) -> Option<String> {
+        clipboard::get_clipboard_text(self, kind)
+    }
+
+    fn input_sources(self) -> Vec<iface::InputSourceInfo> {
+        inputsource::input_sources(self)
+    }
+
+    fn set_input_source(self, id: &str) -> bool {
+        inputsource::set_input_source(self, id)
+    }
 }
 
 struct AssertSend<T>(T);
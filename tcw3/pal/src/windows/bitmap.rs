@@ -498,6 +498,18 @@ impl iface::Canvas for BitmapBuilder {
         }
         self.begin_path();
     }
+    fn fill_with_rule(&mut self, rule: iface::FillRule) {
+        let mode = match rule {
+            iface::FillRule::NonZero => gdiplusenums::FillModeWinding,
+            iface::FillRule::EvenOdd => gdiplusenums::FillModeAlternate,
+        };
+
+        unsafe {
+            assert_gp_ok(gp::GdipSetPathFillMode(self.path.gp_path, mode));
+        }
+        // `begin_path` (called by `fill`) resets the fill mode
+        self.fill();
+    }
     fn stroke(&mut self) {
         unsafe {
             assert_gp_ok(gp::GdipDrawPath(
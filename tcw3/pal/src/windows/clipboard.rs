@@ -0,0 +1,91 @@
+//! Clipboard
+use std::{ptr::copy_nonoverlapping, slice};
+use winapi::um::{winbase, winuser};
+
+use super::{
+    codecvt::{str_to_c_wstr, wstr_to_str},
+    eventloop::get_msg_hwnd_with_wm,
+    utils::assert_win32_nonnull,
+    Wm,
+};
+use crate::iface;
+
+/// Implements `Wm::set_clipboard_text`.
+///
+/// Windows doesn't have the primary selection, so `kind` is ignored.
+pub fn set_clipboard_text(wm: Wm, _kind: iface::ClipboardKind, text: &str) {
+    let wstr = str_to_c_wstr(text);
+
+    let _guard = match ClipboardGuard::open(wm) {
+        Some(guard) => guard,
+        None => return,
+    };
+
+    unsafe {
+        winuser::EmptyClipboard();
+
+        let hmem =
+            assert_win32_nonnull(winbase::GlobalAlloc(winbase::GMEM_MOVEABLE, wstr.len() * 2));
+
+        let ptr = assert_win32_nonnull(winbase::GlobalLock(hmem)) as *mut u16;
+        copy_nonoverlapping(wstr.as_ptr(), ptr, wstr.len());
+        winbase::GlobalUnlock(hmem);
+
+        // The system takes the ownership of `hmem` if this succeeds
+        if winuser::SetClipboardData(winuser::CF_UNICODETEXT, hmem as _).is_null() {
+            log::warn!("SetClipboardData failed");
+            winbase::GlobalFree(hmem);
+        }
+    }
+}
+
+/// Implements `Wm::get_clipboard_text`.
+pub fn get_clipboard_text(wm: Wm, _kind: iface::ClipboardKind) -> Option<String> {
+    let _guard = ClipboardGuard::open(wm)?;
+
+    unsafe {
+        let hmem = winuser::GetClipboardData(winuser::CF_UNICODETEXT);
+        if hmem.is_null() {
+            return None;
+        }
+
+        let ptr = winbase::GlobalLock(hmem as _) as *const u16;
+        if ptr.is_null() {
+            return None;
+        }
+
+        // The data is a null-terminated string, but the terminator may be
+        // missing if the data was provided by a misbehaving application
+        let max_len = winbase::GlobalSize(hmem as _) / 2;
+        let wstr = slice::from_raw_parts(ptr, max_len);
+        let len = wstr.iter().position(|&c| c == 0).unwrap_or(max_len);
+        let text = wstr_to_str(&wstr[..len]);
+
+        winbase::GlobalUnlock(hmem as _);
+
+        Some(text.into())
+    }
+}
+
+/// Closes the clipboard when dropped.
+struct ClipboardGuard;
+
+impl ClipboardGuard {
+    fn open(wm: Wm) -> Option<Self> {
+        // This fails if another window has the clipboard open
+        if unsafe { winuser::OpenClipboard(get_msg_hwnd_with_wm(wm)) } == 0 {
+            log::warn!("OpenClipboard failed");
+            None
+        } else {
+            Some(Self)
+        }
+    }
+}
+
+impl Drop for ClipboardGuard {
+    fn drop(&mut self) {
+        unsafe {
+            winuser::CloseClipboard();
+        }
+    }
+}
@@ -781,6 +781,36 @@ pub fn set_layer_attr(wm: Wm, hlayer: &HLayer, attrs: LayerAttrs) {
     state.flags = new_flags;
 }
 
+pub fn set_layer_attrs_batch<'a>(
+    wm: Wm,
+    updates: impl IntoIterator<Item = (&'a HLayer, LayerAttrs)>,
+) {
+    // Defer the updates until all of them are known, and coalesce the ones
+    // to the same layer. This way, the visuals of each layer are updated (and
+    // its sublayer list is reconciled) at most once.
+    //
+    // For each `pending[i]`, `pending[i].0.layer.tmp == i`
+    let mut pending: Vec<(&HLayer, LayerAttrs)> = Vec::new();
+    for (hlayer, attrs) in updates {
+        let i = hlayer.layer.tmp.get();
+        if i == NONE {
+            hlayer.layer.tmp.set(pending.len());
+            pending.push((hlayer, attrs));
+        } else {
+            pending[i].1.override_with(attrs);
+        }
+    }
+
+    // `set_layer_attr` uses `Layer::tmp`, so reset it first
+    for (hlayer, _) in pending.iter() {
+        hlayer.layer.tmp.set(NONE);
+    }
+
+    for (hlayer, attrs) in pending {
+        set_layer_attr(wm, hlayer, attrs);
+    }
+}
+
 fn set_layer_dpi_scale(hlayer: &HLayer, new_dpi_iscale: f32) {
     let layer = &*hlayer.layer;
     if layer.dpi_iscale.get() == new_dpi_iscale {
@@ -97,7 +97,7 @@ fn get_msg_hwnd() -> HWND {
     msg_hwnd
 }
 
-fn get_msg_hwnd_with_wm(_: Wm) -> HWND {
+pub(super) fn get_msg_hwnd_with_wm(_: Wm) -> HWND {
     // Owning `Wm` means a main thread is already initialized, so
     // `MSG_HWND` should already have a valid window handle
     let msg_hwnd = MSG_HWND.load(Ordering::Relaxed) as HWND;
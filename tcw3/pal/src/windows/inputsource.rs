@@ -0,0 +1,82 @@
+//! Keyboard input sources (keyboard layouts)
+use std::ptr::null_mut;
+use winapi::{
+    shared::{minwindef::HKL, ntdef::LCID},
+    um::{winnls, winuser},
+};
+
+use super::{codecvt::wstr_to_str, Wm};
+use crate::iface;
+
+/// Implements `Wm::input_sources`.
+pub fn input_sources(_: Wm) -> Vec<iface::InputSourceInfo> {
+    let current = unsafe { winuser::GetKeyboardLayout(0) };
+
+    keyboard_layouts()
+        .into_iter()
+        .map(|hkl| iface::InputSourceInfo {
+            id: format!("{:08x}", hkl as usize),
+            name: layout_name(hkl),
+            selected: hkl == current,
+        })
+        .collect()
+}
+
+/// Implements `Wm::set_input_source`.
+pub fn set_input_source(_: Wm, id: &str) -> bool {
+    // Only accept the handles returned by `input_sources`
+    let hkl = match usize::from_str_radix(id, 16) {
+        Ok(x) => x as HKL,
+        Err(_) => return false,
+    };
+    if !keyboard_layouts().contains(&hkl) {
+        log::warn!("set_input_source: unknown input source {:?}", id);
+        return false;
+    }
+
+    if unsafe { winuser::ActivateKeyboardLayout(hkl, winuser::KLF_SETFORPROCESS) }.is_null() {
+        log::warn!("ActivateKeyboardLayout failed");
+        false
+    } else {
+        true
+    }
+}
+
+/// Get the list of the keyboard layouts installed for the current user.
+fn keyboard_layouts() -> Vec<HKL> {
+    unsafe {
+        let count = winuser::GetKeyboardLayoutList(0, null_mut());
+        if count <= 0 {
+            return Vec::new();
+        }
+
+        let mut hkls = vec![null_mut(); count as usize];
+        let count = winuser::GetKeyboardLayoutList(count, hkls.as_mut_ptr());
+        hkls.truncate(count.max(0) as usize);
+        hkls
+    }
+}
+
+/// Get the display name of the language associated with a keyboard layout.
+fn layout_name(hkl: HKL) -> String {
+    // The low word of `HKL` is a language identifier. A locale identifier
+    // with the default sort order has the same value.
+    let lcid = (hkl as usize & 0xffff) as LCID;
+
+    let mut buf = [0u16; 256];
+    let len = unsafe {
+        winnls::GetLocaleInfoW(
+            lcid,
+            winnls::LOCALE_SLOCALIZEDDISPLAYNAME,
+            buf.as_mut_ptr(),
+            buf.len() as i32,
+        )
+    };
+
+    if len <= 0 {
+        String::new()
+    } else {
+        // Exclude the null terminator
+        wstr_to_str(&buf[..len as usize - 1]).into()
+    }
+}
@@ -1357,6 +1357,12 @@ unsafe extern "system" fn impl_get_text_ext(
                 })
                 .filter(|bx| bx.is_valid()),
             )
+        } else if range.start == edit.selected_range().end {
+            // The input method is probably asking for the caret position to
+            // place the candidate window
+            let bx = edit.caret_bounds();
+            log::trace!("... caret_bounds() = {:?}", bx);
+            Some(bx).filter(|bx| bx.is_valid())
         } else {
             let (bx, i_next) = edit.slice_bounds(range.clone());
             log::trace!("... slice_bounds({:?}) = {:?}", range.clone(), (bx, i_next));
@@ -102,7 +102,11 @@ unsafe_impl_object! {
     winapi::um::d2d1_1::ID2D1Device,
     winapi::um::d2d1_1::ID2D1DeviceContext,
     winapi::shared::dxgi::IDXGIDevice,
+    winapi::shared::dxgi::IDXGIFactory1,
+    winapi::shared::dxgi::IDXGIAdapter1,
+    winapi::shared::dxgi::IDXGIOutput,
     winapiext::ID3D11Device4,
+    winapiext::IDXGIOutput6,
     winapiext::ICompositorDesktopInterop,
     winapiext::ICompositorInterop,
     winapiext::ICompositionGraphicsDeviceInterop,
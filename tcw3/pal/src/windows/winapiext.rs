@@ -3,10 +3,12 @@
 use std::os::raw::c_int;
 use winapi::{
     shared::{
+        dxgi1_3::{IDXGIOutput3, IDXGIOutput3Vtbl},
+        dxgitype::DXGI_MODE_ROTATION,
         guiddef::{GUID, REFIID},
-        minwindef::{BOOL, DWORD, UINT},
-        ntdef::LPCWSTR,
-        windef::{HWND, POINT, RECT, SIZE},
+        minwindef::{BOOL, DWORD, FLOAT, UINT},
+        ntdef::{LPCWSTR, WCHAR},
+        windef::{HMONITOR, HWND, POINT, RECT, SIZE},
     },
     um::{
         d3d11_2::{ID3D11Device2, ID3D11Device2Vtbl},
@@ -73,6 +75,57 @@ interface ID3D11Device4(ID3D11Device4Vtbl):
     ) -> (),
 }}
 
+RIDL! {#[uuid(0xdc7dca35, 0x2196, 0x414d, 0x9f, 0x53, 0x61, 0x78, 0x84, 0x03, 0x2a, 0x60)]
+interface IDXGIOutput4(IDXGIOutput4Vtbl):
+    IDXGIOutput3(IDXGIOutput3Vtbl) {
+    // We are not interested in the following methods
+    fn CheckOverlayColorSpaceSupport(
+        dummy: Never,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x80a07424, 0xab52, 0x42eb, 0x83, 0x3c, 0x0c, 0x42, 0xfd, 0x28, 0x2d, 0x98)]
+interface IDXGIOutput5(IDXGIOutput5Vtbl):
+    IDXGIOutput4(IDXGIOutput4Vtbl) {
+    // We are not interested in the following methods
+    fn DuplicateOutput1(
+        dummy: Never,
+    ) -> (),
+}}
+
+RIDL! {#[uuid(0x068346e8, 0xaaec, 0x4b84, 0xad, 0xd7, 0x13, 0x7f, 0x51, 0x3f, 0x77, 0xa1)]
+interface IDXGIOutput6(IDXGIOutput6Vtbl):
+    IDXGIOutput5(IDXGIOutput5Vtbl) {
+    fn GetDesc1(
+        pDesc: *mut DXGI_OUTPUT_DESC1,
+    ) -> HRESULT,
+    fn CheckHardwareCompositionSupport(
+        dummy: Never,
+    ) -> (),
+}}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct DXGI_OUTPUT_DESC1 {
+    pub DeviceName: [WCHAR; 32],
+    pub DesktopCoordinates: RECT,
+    pub AttachedToDesktop: BOOL,
+    pub Rotation: DXGI_MODE_ROTATION,
+    pub Monitor: HMONITOR,
+    pub BitsPerColor: UINT,
+    pub ColorSpace: DXGI_COLOR_SPACE_TYPE,
+    pub RedPrimary: [FLOAT; 2],
+    pub GreenPrimary: [FLOAT; 2],
+    pub BluePrimary: [FLOAT; 2],
+    pub WhitePoint: [FLOAT; 2],
+    pub MinLuminance: FLOAT,
+    pub MaxLuminance: FLOAT,
+    pub MaxFullFrameLuminance: FLOAT,
+}
+
+pub type DXGI_COLOR_SPACE_TYPE = UINT;
+pub const DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020: DXGI_COLOR_SPACE_TYPE = 12;
+
 RIDL! {#[uuid(0x25297D5C, 0x3AD4, 0x4C9C, 0xB5, 0xCF, 0xE3, 0x6A, 0x38, 0x51, 0x23, 0x30)]
 interface ICompositorInterop(ICompositorInteropVtbl):
     IUnknown(IUnknownVtbl) {
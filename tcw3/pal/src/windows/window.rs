@@ -12,11 +12,13 @@ use std::{
 use wchar::wch_c;
 use winapi::{
     shared::{
+        dxgi,
         minwindef::{DWORD, HIWORD, LOWORD, LPARAM, LRESULT, UINT, WPARAM},
         ntdef::LONG,
-        windef::{HCURSOR, HICON, HWND, POINT, RECT, SIZE},
+        windef::{HCURSOR, HICON, HMONITOR, HWND, POINT, RECT, SIZE},
     },
-    um::{dwmapi, libloaderapi, uxtheme, winuser},
+    um::{dwmapi, libloaderapi, uxtheme, wingdi, winuser},
+    Interface,
 };
 
 use super::{
@@ -24,13 +26,18 @@ use super::{
     codecvt::str_to_c_wstr,
     comp, frameclock,
     textinput::TextInputWindow,
-    utils::{assert_win32_nonnull, assert_win32_ok},
-    AccelTable, Wm, WndAttrs,
+    utils::{assert_win32_nonnull, assert_win32_ok, result_from_hresult, ComPtr},
+    winapiext, AccelTable, Wm, WndAttrs,
 };
 use crate::{iface, prelude::*};
 
 const WND_CLASS: &[u16] = wch_c!("TcwAppWnd");
 
+/// The window class for borderless windows with a drop shadow. The shadow is
+/// provided by `CS_DROPSHADOW`, which is a class style, so the choice between
+/// this and `WND_CLASS` is made when a window is created.
+const WND_CLASS_SHADOW: &[u16] = wch_c!("TcwAppWndShadow");
+
 /// Mouse buttons
 mod buttons {
     pub const L: u8 = 0;
@@ -141,6 +148,15 @@ pub(super) fn init(_: Wm) {
     };
 
     unsafe { winuser::RegisterClassW(&wnd_class) };
+
+    // Create a window class for borderless windows with a drop shadow
+    let wnd_class = winuser::WNDCLASSW {
+        style: wnd_class.style | winuser::CS_DROPSHADOW,
+        lpszClassName: WND_CLASS_SHADOW.as_ptr(),
+        ..wnd_class
+    };
+
+    unsafe { winuser::RegisterClassW(&wnd_class) };
 }
 
 pub fn new_wnd(wm: Wm, attrs: WndAttrs<'_>) -> HWnd {
@@ -149,7 +165,7 @@ pub fn new_wnd(wm: Wm, attrs: WndAttrs<'_>) -> HWnd {
     let hwnd = assert_win32_nonnull(unsafe {
         winuser::CreateWindowExW(
             winuser::WS_EX_NOREDIRECTIONBITMAP,
-            WND_CLASS.as_ptr(),
+            wnd_class_for_flags(attrs.flags.unwrap_or_default()).as_ptr(),
             null_mut(), // title
             style_for_flags(Default::default()),
             winuser::CW_USEDEFAULT,
@@ -261,6 +277,8 @@ pub fn set_wnd_attr(_: Wm, pal_hwnd: &HWnd, attrs: WndAttrs<'_>) {
 
         if diff.contains(iface::WndFlags::FULL_SIZE_CONTENT) {
             update_wnd_frame(pal_hwnd);
+        } else if diff.contains(iface::WndFlags::NO_SHADOW) {
+            adjust_dwm_frame(pal_hwnd);
         }
     }
 
@@ -372,6 +390,16 @@ pub fn set_wnd_attr(_: Wm, pal_hwnd: &HWnd, attrs: WndAttrs<'_>) {
     }
 }
 
+/// Choose the window class for a window created with the specified flags.
+fn wnd_class_for_flags(flags: iface::WndFlags) -> &'static [u16] {
+    use iface::WndFlags;
+    if flags.contains(WndFlags::BORDERLESS) && !flags.contains(WndFlags::NO_SHADOW) {
+        WND_CLASS_SHADOW
+    } else {
+        WND_CLASS
+    }
+}
+
 fn style_for_flags(flags: iface::WndFlags) -> DWORD {
     use iface::WndFlags;
     let mut out = if flags.contains(WndFlags::BORDERLESS) {
@@ -459,6 +487,132 @@ pub fn is_wnd_focused(_: Wm, pal_hwnd: &HWnd) -> bool {
     hwnd == unsafe { winuser::GetForegroundWindow() }
 }
 
+pub fn get_wnd_monitor_info(_: Wm, pal_hwnd: &HWnd) -> iface::MonitorInfo {
+    let hwnd = pal_hwnd.expect_hwnd();
+
+    let monitor = unsafe { winuser::MonitorFromWindow(hwnd, winuser::MONITOR_DEFAULTTONEAREST) };
+    assert_win32_nonnull(monitor);
+
+    let mut monitor_info: winuser::MONITORINFOEXW = unsafe { std::mem::zeroed() };
+    monitor_info.cbSize = size_of::<winuser::MONITORINFOEXW>() as _;
+    assert_win32_ok(unsafe {
+        winuser::GetMonitorInfoW(monitor, &mut monitor_info as *mut _ as *mut _)
+    });
+
+    let mut dev_mode: wingdi::DEVMODEW = unsafe { std::mem::zeroed() };
+    dev_mode.dmSize = size_of::<wingdi::DEVMODEW>() as _;
+    let ok = unsafe {
+        winuser::EnumDisplaySettingsW(
+            monitor_info.szDevice.as_ptr(),
+            winuser::ENUM_CURRENT_SETTINGS,
+            &mut dev_mode,
+        )
+    };
+
+    // `0` and `1` represent the hardware's default refresh rate
+    let refresh_rate = if ok != 0 && dev_mode.dmDisplayFrequency > 1 {
+        dev_mode.dmDisplayFrequency as f32
+    } else {
+        0.0
+    };
+
+    let output_desc = dxgi_output_desc_for_monitor(monitor);
+
+    iface::MonitorInfo {
+        refresh_rate,
+        // HDR10 output uses the SMPTE ST 2084 transfer function and the
+        // ITU-R BT.2020 primaries
+        hdr: output_desc.map_or(false, |desc| {
+            desc.ColorSpace == winapiext::DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020
+        }),
+        max_luminance: output_desc
+            .map(|desc| desc.MaxLuminance)
+            .filter(|&x| x > 0.0),
+    }
+}
+
+/// Find the DXGI output displaying the specified monitor and get its
+/// description.
+///
+/// Returns `None` if the output couldn't be found or `IDXGIOutput6` isn't
+/// supported by the system (it's only available on Windows 10, version 1703
+/// or later).
+fn dxgi_output_desc_for_monitor(monitor: HMONITOR) -> Option<winapiext::DXGI_OUTPUT_DESC1> {
+    let factory: ComPtr<dxgi::IDXGIFactory1> = unsafe {
+        let mut out = null_mut();
+        result_from_hresult(dxgi::CreateDXGIFactory1(
+            &dxgi::IDXGIFactory1::uuidof(),
+            &mut out,
+        ))
+        .ok()?;
+        ComPtr::from_ptr(out as *mut dxgi::IDXGIFactory1)?
+    };
+
+    // `EnumAdapters1` and `EnumOutputs` fail with `DXGI_ERROR_NOT_FOUND` when
+    // the index is out of range
+    for adapter_i in 0.. {
+        let adapter: ComPtr<dxgi::IDXGIAdapter1> = unsafe {
+            let mut out = null_mut();
+            result_from_hresult(factory.EnumAdapters1(adapter_i, &mut out)).ok()?;
+            ComPtr::from_ptr(out)?
+        };
+
+        for output_i in 0.. {
+            let output: ComPtr<dxgi::IDXGIOutput> = unsafe {
+                let mut out = null_mut();
+                if result_from_hresult(adapter.EnumOutputs(output_i, &mut out)).is_err() {
+                    break;
+                }
+                ComPtr::from_ptr(out)?
+            };
+
+            let output: ComPtr<winapiext::IDXGIOutput6> = output.query_interface()?;
+
+            let desc = unsafe {
+                let mut desc = MaybeUninit::uninit();
+                if result_from_hresult(output.GetDesc1(desc.as_mut_ptr())).is_err() {
+                    continue;
+                }
+                desc.assume_init()
+            };
+
+            if desc.Monitor == monitor {
+                return Some(desc);
+            }
+        }
+    }
+
+    None
+}
+
+/// The width of the sizing border (in logical pixels) of a window with
+/// `FULL_SIZE_CONTENT`, which is hit-tested by `WM_NCHITTEST` inside the client
+/// region.
+///
+/// The idomatic solution would be to use `GetSystemMetricsForDpi`, but if the
+/// user specifies a really large sizing border, it might break the
+/// application's functionality. Thus, we hard-code the width for now.
+const RESIZE_BORDER_WIDTH: u32 = 5;
+
+pub fn get_wnd_content_insets(_: Wm, pal_hwnd: &HWnd) -> iface::Insets {
+    if (pal_hwnd.wnd.flags.get()).contains(iface::WndFlags::FULL_SIZE_CONTENT) {
+        // The window frame is removed, and the sizing border is placed inside
+        // the client region instead. The caption buttons are removed along
+        // with the frame (DWM only draws them in the extended frame, which is
+        // only one pixel wide; see `adjust_dwm_frame`), so they don't need to
+        // be accounted for.
+        let width = RESIZE_BORDER_WIDTH as f32;
+        iface::Insets {
+            top: width,
+            right: width,
+            bottom: width,
+            left: width,
+        }
+    } else {
+        iface::Insets::default()
+    }
+}
+
 static FRAME_CLOCK_MANAGER: frameclock::FrameClockManager<HWnd> =
     frameclock::FrameClockManager::new();
 
@@ -513,12 +667,17 @@ fn update_wnd_frame(pal_hwnd: &HWnd) {
 
 fn adjust_dwm_frame(pal_hwnd: &HWnd) {
     let hwnd = pal_hwnd.expect_hwnd();
+    let margins = dwm_frame_margins(pal_hwnd.wnd.flags.get());
+    unsafe {
+        dwmapi::DwmExtendFrameIntoClientArea(hwnd, &margins);
+    }
+}
 
-    let margins = if pal_hwnd
-        .wnd
-        .flags
-        .get()
-        .contains(iface::WndFlags::FULL_SIZE_CONTENT)
+/// Calculate the amount by which the DWM frame is extended into the client
+/// area of a window with the specified flags.
+fn dwm_frame_margins(flags: iface::WndFlags) -> uxtheme::MARGINS {
+    if flags.contains(iface::WndFlags::FULL_SIZE_CONTENT)
+        && !flags.contains(iface::WndFlags::NO_SHADOW)
     {
         // The margins must be at least 1 pixel for the shadow to appear
         uxtheme::MARGINS {
@@ -534,9 +693,6 @@ fn adjust_dwm_frame(pal_hwnd: &HWnd) {
             cyBottomHeight: 0,
             cyTopHeight: 0,
         }
-    };
-    unsafe {
-        dwmapi::DwmExtendFrameIntoClientArea(hwnd, &margins);
     }
 }
 
@@ -603,6 +759,13 @@ extern "system" fn wnd_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lparam: LPARA
             adjust_dwm_frame(&pal_hwnd);
         } // WM_ACTIVATE
 
+        winuser::WM_NCACTIVATE => {
+            // `wparam` indicates whether the title bar should be drawn as
+            // active. Let `DefWindowProc` handle the rest.
+            let listener = Rc::clone(&pal_hwnd.wnd.listener.borrow());
+            listener.active(wm, &pal_hwnd, wparam != 0);
+        } // WM_NCACTIVATE
+
         winuser::WM_CLOSE => {
             let listener = Rc::clone(&pal_hwnd.wnd.listener.borrow());
             listener.close_requested(wm, &pal_hwnd);
@@ -1026,11 +1189,7 @@ extern "system" fn wnd_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lparam: LPARA
                 let dpi = unsafe { winuser::GetDpiForWindow(hwnd) } as u32;
                 assert_win32_ok(dpi);
 
-                // The idomatic solution would be to use `GetSystemMetricsForDpi`, but
-                // if the user specifies a really large sizing border, it might break
-                // the application's functionality. Thus, we hard-code the width for
-                // now.
-                let width = log_to_phy(5, dpi) as LONG;
+                let width = log_to_phy(RESIZE_BORDER_WIDTH, dpi) as LONG;
 
                 let flags = 0b0001 * (loc[0] < rect.left + width) as u8
                     | 0b0010 * (loc[1] < rect.top + width) as u8
@@ -1122,6 +1281,22 @@ extern "system" fn wnd_proc(hwnd: HWND, msg: UINT, wparam: WPARAM, lparam: LPARA
 
         winuser::WM_MOVE => {
             pal_hwnd.wnd.text_input_wnd.on_move(wm);
+
+            // `lparam` contains the screen coordinates of the client area's
+            // top-left corner
+            let lparam = lparam as DWORD;
+            let pos_phy = [
+                LOWORD(lparam) as i16 as f32, // `GET_X_LPARAM(lparam)`
+                HIWORD(lparam) as i16 as f32, // `GET_Y_LPARAM(lparam)`
+            ];
+
+            let dpi = unsafe { winuser::GetDpiForWindow(hwnd) } as u32;
+            assert_win32_ok(dpi);
+
+            let new_pos = pos_phy.map(|x| phy_to_log_f32(x, dpi)).into();
+
+            let listener = Rc::clone(&pal_hwnd.wnd.listener.borrow());
+            listener.moved(wm, &pal_hwnd, new_pos);
         } // WM_MOVE
 
         _ => {}
@@ -1251,4 +1426,29 @@ mod tests {
         let dpi = dpi as u32 + 96; // assume `dpi >= 96`
         phy_to_log(log_to_phy(x, dpi), dpi) == x
     }
+
+    #[test]
+    fn no_shadow_wnd_class() {
+        use iface::WndFlags;
+        let class = |flags| wnd_class_for_flags(flags).as_ptr();
+
+        assert_eq!(class(WndFlags::default()), WND_CLASS.as_ptr());
+        assert_eq!(class(WndFlags::BORDERLESS), WND_CLASS_SHADOW.as_ptr());
+        assert_eq!(
+            class(WndFlags::BORDERLESS | WndFlags::NO_SHADOW),
+            WND_CLASS.as_ptr()
+        );
+    }
+
+    #[test]
+    fn no_shadow_dwm_frame_margins() {
+        use iface::WndFlags;
+        let has_shadow = |flags| dwm_frame_margins(flags).cyTopHeight > 0;
+
+        assert!(!has_shadow(WndFlags::default()));
+        assert!(has_shadow(WndFlags::FULL_SIZE_CONTENT));
+        assert!(!has_shadow(
+            WndFlags::FULL_SIZE_CONTENT | WndFlags::NO_SHADOW
+        ));
+    }
 }
@@ -2,7 +2,7 @@ use cggeom::{box2, prelude::*, Box2};
 use cgmath::{Deg, Matrix3, Point2, Vector2};
 use log::info;
 use std::{
-    cell::Cell,
+    cell::{Cell, RefCell},
     ops::Range,
     rc::Rc,
     sync::{
@@ -220,6 +220,92 @@ fn invoke_after_cancel() {
     });
 }
 
+/// The interval at which the tests drive animations. This is longer than
+/// the animation driver's frame interval, so each step renders one frame.
+const ANIMATION_STEP: Duration = Duration::from_millis(20);
+
+#[test]
+fn animate() {
+    init_logger();
+    testing::run_test(|twm| {
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let anim = {
+            let values = Rc::clone(&values);
+            twm.wm().animate(
+                Duration::from_millis(100),
+                pal::animation::Easing::EaseInOut,
+                move |_, value| values.borrow_mut().push(value),
+            )
+        };
+
+        // 100ms / 20ms = 5 steps
+        let mut num_steps = 0;
+        while anim.is_running() {
+            assert!(num_steps < 5, "the animation didn't complete in time");
+            twm.advance_time(ANIMATION_STEP);
+            num_steps += 1;
+        }
+
+        let values = values.borrow();
+        info!("values = {:?}", values);
+        assert_eq!(values.len(), num_steps);
+        assert_eq!(values.last(), Some(&1.0));
+        assert!(values.iter().all(|x| (0.0..=1.0).contains(x)));
+        assert!(values.windows(2).all(|w| w[0] <= w[1]));
+    });
+}
+
+#[test]
+fn animate_reduced_motion() {
+    init_logger();
+    testing::run_test(|twm| {
+        twm.set_prefers_reduced_motion(true);
+
+        let values = Rc::new(RefCell::new(Vec::new()));
+        let anim = {
+            let values = Rc::clone(&values);
+            twm.wm().animate(
+                Duration::from_secs(3600),
+                pal::animation::Easing::Linear,
+                move |_, value| values.borrow_mut().push(value),
+            )
+        };
+
+        // The animation jumps to the end on the first frame
+        twm.advance_time(ANIMATION_STEP);
+        assert!(!anim.is_running());
+        assert_eq!(*values.borrow(), [1.0]);
+    });
+}
+
+#[test]
+fn animate_cancel() {
+    init_logger();
+    testing::run_test(|twm| {
+        let count = Rc::new(Cell::new(0));
+        let anim = {
+            let count = Rc::clone(&count);
+            twm.wm().animate(
+                Duration::from_secs(3600),
+                pal::animation::Easing::Linear,
+                move |_, _| count.set(count.get() + 1),
+            )
+        };
+
+        twm.advance_time(ANIMATION_STEP);
+        assert_eq!(count.get(), 1);
+
+        assert!(anim.cancel());
+        assert!(!anim.is_running());
+        assert!(!anim.cancel());
+
+        // The callback function should be dropped without being called
+        assert_eq!(Rc::strong_count(&count), 1);
+        twm.advance_time(ANIMATION_STEP * 5);
+        assert_eq!(count.get(), 1);
+    });
+}
+
 #[test]
 #[should_panic]
 fn panicking() {
@@ -542,6 +628,73 @@ fn wnd_with_layer() {
     });
 }
 
+/// Fill a self-intersecting pentagram using each fill rule and check that
+/// only the non-zero winding number rule fills the inner pentagon.
+#[test]
+fn bitmap_fill_rule() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        const SIZE: [u32; 2] = [100, 100];
+
+        let render = |rule: pal::FillRule| -> wmapi::WndSnapshot {
+            let bmp = {
+                let mut b = pal::BitmapBuilder::new(SIZE);
+                b.set_fill_rgb([0.0, 0.0, 0.0, 1.0].into());
+                b.begin_path();
+                b.move_to(Point2::new(50.0, 0.0));
+                b.line_to(Point2::new(79.4, 90.6));
+                b.line_to(Point2::new(2.5, 34.4));
+                b.line_to(Point2::new(97.5, 34.4));
+                b.line_to(Point2::new(20.6, 90.6));
+                b.close_path();
+                b.fill_with_rule(rule);
+                b.into_bitmap()
+            };
+
+            let hlayer = wm.new_layer(pal::LayerAttrs {
+                contents: Some(Some(bmp)),
+                bounds: Some(box2! { top_left: [0.0, 0.0], size: [100.0, 100.0] }),
+                ..Default::default()
+            });
+
+            let hwnd = wm.new_wnd(pal::WndAttrs {
+                visible: Some(true),
+                size: Some(SIZE),
+                layer: Some(Some(hlayer.clone())),
+                ..Default::default()
+            });
+            wm.update_wnd(&hwnd);
+
+            let mut ss = wmapi::WndSnapshot::new();
+            twm.read_wnd_snapshot(&hwnd, &mut ss);
+
+            wm.remove_wnd(&hwnd);
+            wm.remove_layer(&hlayer);
+
+            ss
+        };
+
+        let alpha_at = |ss: &wmapi::WndSnapshot, [x, y]: [usize; 2]| {
+            let [x, y] = [x * ss.size[0] / 100, y * ss.size[1] / 100];
+            ss.data[y * ss.stride + x * 4 + 3]
+        };
+
+        // A point on one of the spikes and a point in the inner pentagon
+        const SPIKE: [usize; 2] = [50, 15];
+        const PENTAGON: [usize; 2] = [50, 55];
+
+        let ss = render(pal::FillRule::NonZero);
+        assert_eq!(alpha_at(&ss, SPIKE), 255);
+        assert_eq!(alpha_at(&ss, PENTAGON), 255);
+
+        let ss = render(pal::FillRule::EvenOdd);
+        assert_eq!(alpha_at(&ss, SPIKE), 255);
+        assert_eq!(alpha_at(&ss, PENTAGON), 0);
+    });
+}
+
 #[test]
 fn defer_layer_changes_until_update_wnd() {
     init_logger();
@@ -596,6 +749,81 @@ fn defer_layer_changes_until_update_wnd() {
     });
 }
 
+#[test]
+fn batched_layer_changes() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        // Both sublayers are off-screen
+        let sublayers: Vec<_> = (0..2)
+            .map(|_| {
+                wm.new_layer(pal::LayerAttrs {
+                    bg_color: Some([0.2, 0.3, 0.4, 1.0].into()),
+                    bounds: Some(box2! { top_left: [-40.0, -40.0], size: [20.0, 20.0] }),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        let hlayer = wm.new_layer(pal::LayerAttrs {
+            bounds: Some(box2! { top_left: [0.0, 0.0], size: [100.0, 100.0] }),
+            sublayers: Some(sublayers.clone()),
+            ..Default::default()
+        });
+
+        let hwnd = wm.new_wnd(pal::WndAttrs {
+            visible: Some(true),
+            size: Some([100, 100]),
+            layer: Some(Some(hlayer.clone())),
+            ..Default::default()
+        });
+
+        wm.update_wnd(&hwnd);
+
+        let mut ss = wmapi::WndSnapshot::new();
+        twm.read_wnd_snapshot(&hwnd, &mut ss);
+        assert_snapshot_empty(&ss);
+
+        // Move the sublayers to inside the window
+        wm.set_layer_attrs_batch(vec![
+            (
+                &sublayers[0],
+                pal::LayerAttrs {
+                    bounds: Some(box2! { top_left: [10.0, 10.0], size: [20.0, 20.0] }),
+                    ..Default::default()
+                },
+            ),
+            (
+                &sublayers[1],
+                pal::LayerAttrs {
+                    bounds: Some(box2! { top_left: [60.0, 60.0], size: [20.0, 20.0] }),
+                    ..Default::default()
+                },
+            ),
+        ]);
+
+        // None of the changes are visible until `update_wnd`
+        twm.read_wnd_snapshot(&hwnd, &mut ss);
+        assert_snapshot_empty(&ss);
+
+        wm.update_wnd(&hwnd);
+
+        // All of the changes are visible at once
+        twm.read_wnd_snapshot(&hwnd, &mut ss);
+        let alpha_at =
+            |ss: &wmapi::WndSnapshot, [x, y]: [usize; 2]| ss.data[y * ss.stride + x * 4 + 3];
+        assert_ne!(alpha_at(&ss, [20, 20]), 0);
+        assert_ne!(alpha_at(&ss, [70, 70]), 0);
+
+        wm.remove_wnd(&hwnd);
+        wm.remove_layer(&hlayer);
+        for hlayer in sublayers {
+            wm.remove_layer(&hlayer);
+        }
+    });
+}
+
 #[test]
 fn wnd_close_event() {
     init_logger();
@@ -830,6 +1058,39 @@ fn wnd_mouse_events() {
     });
 }
 
+#[test]
+fn wnd_move_event() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        #[derive(Clone)]
+        struct Listener(Rc<Cell<Option<Point2<f32>>>>);
+        impl WndListener<pal::Wm> for Listener {
+            fn moved(&self, _: pal::Wm, _: &pal::HWnd, new_pos: Point2<f32>) {
+                assert!(self.0.get().is_none());
+                self.0.set(Some(new_pos));
+            }
+        }
+
+        let state = Rc::new(Cell::new(None));
+
+        let hwnd = wm.new_wnd(pal::WndAttrs {
+            visible: Some(true),
+            listener: Some(Box::new(Listener(Rc::clone(&state)))),
+            ..Default::default()
+        });
+
+        twm.simulate_move(&hwnd, Point2::new(100.0, 50.0));
+        assert_eq!(state.take(), Some(Point2::new(100.0, 50.0)));
+
+        twm.simulate_move(&hwnd, Point2::new(-20.0, 30.0));
+        assert_eq!(state.take(), Some(Point2::new(-20.0, 30.0)));
+
+        wm.remove_wnd(&hwnd);
+    });
+}
+
 #[test]
 fn wnd_focus_event() {
     init_logger();
@@ -862,6 +1123,225 @@ fn wnd_focus_event() {
     });
 }
 
+#[test]
+fn wnd_active_event() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        struct Listener(usize, Rc<RefCell<Vec<(usize, bool)>>>);
+        impl WndListener<pal::Wm> for Listener {
+            fn focus(&self, _: pal::Wm, _: &pal::HWnd) {
+                panic!("activation must not change the focus state");
+            }
+            fn active(&self, wm: pal::Wm, hwnd: &pal::HWnd, active: bool) {
+                assert_eq!(wmapi::TestingWm::is_wnd_active(&wm, hwnd), active);
+                self.1.borrow_mut().push((self.0, active));
+            }
+        }
+
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let hwnds: Vec<_> = (0..2)
+            .map(|i| {
+                wm.new_wnd(pal::WndAttrs {
+                    visible: Some(true),
+                    listener: Some(Box::new(Listener(i, Rc::clone(&log)))),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        twm.simulate_activation(&hwnds[0], true);
+        assert_eq!(log.replace(Vec::new()), vec![(0, true)]);
+        assert!(twm.is_wnd_active(&hwnds[0]));
+        assert!(!twm.is_wnd_active(&hwnds[1]));
+
+        // Activating another window deactivates the first one
+        twm.simulate_activation(&hwnds[1], true);
+        assert_eq!(log.replace(Vec::new()), vec![(0, false), (1, true)]);
+        assert!(!twm.is_wnd_active(&hwnds[0]));
+        assert!(twm.is_wnd_active(&hwnds[1]));
+
+        // No-op
+        twm.simulate_activation(&hwnds[1], true);
+        twm.simulate_activation(&hwnds[0], false);
+        assert_eq!(log.replace(Vec::new()), vec![]);
+
+        twm.simulate_activation(&hwnds[1], false);
+        assert_eq!(log.replace(Vec::new()), vec![(1, false)]);
+        assert!(!twm.is_wnd_active(&hwnds[0]));
+        assert!(!twm.is_wnd_active(&hwnds[1]));
+
+        for hwnd in hwnds {
+            wm.remove_wnd(&hwnd);
+        }
+    });
+}
+
+#[test]
+fn wnd_monitor_info() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        let hwnd = wm.new_wnd(pal::WndAttrs {
+            visible: Some(true),
+            ..Default::default()
+        });
+
+        let info = wm.get_wnd_monitor_info(&hwnd);
+        assert!(info.refresh_rate > 0.0, "{:?}", info);
+
+        let info = pal::MonitorInfo {
+            refresh_rate: 144.0,
+            hdr: true,
+            max_luminance: Some(1000.0),
+        };
+        twm.set_wnd_monitor_info(&hwnd, info);
+        assert_eq!(wm.get_wnd_monitor_info(&hwnd), info);
+
+        wm.remove_wnd(&hwnd);
+    });
+}
+
+#[test]
+fn wnd_no_shadow() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        let hwnd = wm.new_wnd(pal::WndAttrs {
+            visible: Some(true),
+            flags: Some(pal::WndFlags::BORDERLESS),
+            ..Default::default()
+        });
+
+        let attrs = twm.wnd_attrs(&hwnd).unwrap();
+        assert!(!attrs.flags.contains(pal::WndFlags::NO_SHADOW));
+
+        wm.set_wnd_attr(
+            &hwnd,
+            pal::WndAttrs {
+                flags: Some(pal::WndFlags::BORDERLESS | pal::WndFlags::NO_SHADOW),
+                ..Default::default()
+            },
+        );
+        let attrs = twm.wnd_attrs(&hwnd).unwrap();
+        assert!(attrs.flags.contains(pal::WndFlags::NO_SHADOW));
+
+        wm.set_wnd_attr(
+            &hwnd,
+            pal::WndAttrs {
+                flags: Some(pal::WndFlags::BORDERLESS),
+                ..Default::default()
+            },
+        );
+        let attrs = twm.wnd_attrs(&hwnd).unwrap();
+        assert!(!attrs.flags.contains(pal::WndFlags::NO_SHADOW));
+
+        wm.remove_wnd(&hwnd);
+    });
+}
+
+#[test]
+fn wnd_content_insets() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        let hwnd = wm.new_wnd(pal::WndAttrs {
+            visible: Some(true),
+            flags: Some(pal::WndFlags::BORDERLESS | pal::WndFlags::FULL_SIZE_CONTENT),
+            ..Default::default()
+        });
+
+        assert_eq!(wm.get_wnd_content_insets(&hwnd), pal::Insets::default());
+
+        let insets = pal::Insets {
+            top: 28.0,
+            left: 70.0,
+            ..Default::default()
+        };
+        twm.set_wnd_content_insets(&hwnd, insets);
+        assert_eq!(wm.get_wnd_content_insets(&hwnd), insets);
+
+        wm.remove_wnd(&hwnd);
+    });
+}
+
+#[test]
+fn clipboard_kinds_are_independent() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        assert_eq!(wm.get_clipboard_text(pal::ClipboardKind::Clipboard), None);
+        assert_eq!(wm.get_clipboard_text(pal::ClipboardKind::Primary), None);
+
+        wm.set_clipboard_text(pal::ClipboardKind::Clipboard, "copied");
+        wm.set_clipboard_text(pal::ClipboardKind::Primary, "selected");
+
+        assert_eq!(
+            wm.get_clipboard_text(pal::ClipboardKind::Clipboard)
+                .as_deref(),
+            Some("copied")
+        );
+        assert_eq!(
+            wm.get_clipboard_text(pal::ClipboardKind::Primary)
+                .as_deref(),
+            Some("selected")
+        );
+
+        // Writing to `Primary` doesn't affect `Clipboard`
+        wm.set_clipboard_text(pal::ClipboardKind::Primary, "selected 2");
+        assert_eq!(
+            wm.get_clipboard_text(pal::ClipboardKind::Clipboard)
+                .as_deref(),
+            Some("copied")
+        );
+        assert_eq!(
+            wm.get_clipboard_text(pal::ClipboardKind::Primary)
+                .as_deref(),
+            Some("selected 2")
+        );
+    });
+}
+
+#[test]
+fn input_source_selection() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        assert_eq!(wm.input_sources(), vec![]);
+
+        let source = |id: &str, selected: bool| pal::InputSourceInfo {
+            id: id.to_owned(),
+            name: id.to_uppercase(),
+            selected,
+        };
+        twm.set_input_sources(vec![source("us", true), source("jp", false)]);
+        assert_eq!(
+            wm.input_sources(),
+            vec![source("us", true), source("jp", false)]
+        );
+
+        assert!(wm.set_input_source("jp"));
+        assert_eq!(
+            wm.input_sources(),
+            vec![source("us", false), source("jp", true)]
+        );
+
+        // An unknown ID doesn't change the selection
+        assert!(!wm.set_input_source("fr"));
+        assert_eq!(
+            wm.input_sources(),
+            vec![source("us", false), source("jp", true)]
+        );
+    });
+}
+
 #[test]
 fn text_input_ctx() {
     init_logger();
@@ -950,6 +1430,114 @@ fn text_input_ctx() {
     });
 }
 
+#[test]
+fn text_input_ctx_caret_bounds() {
+    init_logger();
+    testing::run_test(|twm| {
+        let wm = twm.wm();
+
+        #[derive(Default)]
+        struct State {
+            sel: Cell<usize>,
+            comp: Cell<Option<Range<usize>>>,
+        }
+
+        #[derive(Clone)]
+        struct Listener(Rc<State>);
+        impl TextInputCtxListener<pal::Wm> for Listener {
+            fn edit(
+                &self,
+                _wm: pal::Wm,
+                _: &pal::HTextInputCtx,
+                _mutating: bool,
+            ) -> Box<dyn TextInputCtxEdit<pal::Wm> + '_> {
+                Box::new(self.clone())
+            }
+        }
+
+        impl TextInputCtxEdit<pal::Wm> for Listener {
+            fn selected_range(&mut self) -> Range<usize> {
+                self.0.sel.get()..self.0.sel.get()
+            }
+            fn set_selected_range(&mut self, range: Range<usize>) {
+                self.0.sel.set(range.end);
+            }
+            fn set_composition_range(&mut self, range: Option<Range<usize>>) {
+                self.0.comp.set(range);
+            }
+            fn replace(&mut self, _range: Range<usize>, _text: &str) {
+                unreachable!()
+            }
+            fn slice(&mut self, _range: Range<usize>) -> String {
+                unreachable!()
+            }
+            fn floor_index(&mut self, i: usize) -> usize {
+                i
+            }
+            fn ceil_index(&mut self, i: usize) -> usize {
+                i
+            }
+            fn len(&mut self) -> usize {
+                10
+            }
+            fn index_from_point(
+                &mut self,
+                _point: Point2<f32>,
+                _flags: pal::IndexFromPointFlags,
+            ) -> Option<usize> {
+                unreachable!()
+            }
+            fn frame(&mut self) -> Box2<f32> {
+                box2! { min: [0.0, 0.0], max: [100.0, 20.0] }
+            }
+            fn slice_bounds(&mut self, range: Range<usize>) -> (Box2<f32>, usize) {
+                // Each character is 10px wide
+                let x = range.start as f32 * 10.0;
+                (box2! { min: [x, 2.0], max: [x, 18.0] }, range.start)
+            }
+        }
+
+        let state = Rc::new(State::default());
+
+        let hwnd = wm.new_wnd(pal::WndAttrs {
+            visible: Some(true),
+            ..Default::default()
+        });
+
+        let tictx = wm.new_text_input_ctx(&hwnd, Box::new(Listener(Rc::clone(&state))));
+        wm.text_input_ctx_set_active(&tictx, true);
+
+        // Nothing is reported yet
+        assert_eq!(twm.text_input_ctx_caret_bounds(&tictx), None);
+
+        // Start a composition session
+        {
+            let mut edit = twm.raise_edit(&tictx, true);
+            edit.set_composition_range(Some(2..5));
+            edit.set_selected_range(5..5);
+        }
+        assert_eq!(state.comp.take(), Some(2..5));
+
+        // The client reports the new caret position
+        wm.text_input_ctx_on_layout_change(&tictx);
+        assert_eq!(
+            twm.text_input_ctx_caret_bounds(&tictx),
+            Some(box2! { min: [50.0, 2.0], max: [50.0, 18.0] })
+        );
+
+        // The caret moves as the composition is updated
+        state.sel.set(7);
+        wm.text_input_ctx_on_layout_change(&tictx);
+        assert_eq!(
+            twm.text_input_ctx_caret_bounds(&tictx),
+            Some(box2! { min: [70.0, 2.0], max: [70.0, 18.0] })
+        );
+
+        wm.remove_text_input_ctx(&tictx);
+        wm.remove_wnd(&hwnd);
+    });
+}
+
 #[test]
 fn wnd_accel_tables() {
     init_logger();
@@ -19,6 +19,11 @@ fn invoke_on_update_inner(wm: Wm, f: Pin<Box<Node<AssertUnpin<dyn FnOnce(Wm)>>>>
     queue.push_back_node(f);
 }
 
+/// Implements `WmExt::update_pending`.
+pub fn update_pending(wm: Wm) -> bool {
+    !ON_UPDATE_DISPATCHES.get_with_wm(wm).is_empty()
+}
+
 /// Process pending invocations.
 pub fn process_pending_invocations(wm: Wm) {
     loop {
@@ -94,12 +94,36 @@ pub trait WmExt: Sized {
     ///
     /// This is **not** an equivalent of JavaScript's `requestAnimationFrame`!
     fn invoke_on_update(self, f: impl FnOnce(Self) + 'static);
+
+    /// Get a flag indicating whether there are calls enqueued by
+    /// [`invoke_on_update`] that haven't been processed yet.
+    ///
+    /// [`invoke_on_update`]: WmExt::invoke_on_update
+    fn update_pending(self) -> bool;
+
+    /// Process all calls enqueued by [`invoke_on_update`] synchronously,
+    /// including the ones enqueued while processing the queue.
+    ///
+    /// This is useful for tests and tools that want to observe the effects of
+    /// deferred updates (e.g., a committed property value of a Designer
+    /// component) without running the event loop.
+    ///
+    /// [`invoke_on_update`]: WmExt::invoke_on_update
+    fn flush_updates(self);
 }
 
 impl WmExt for Wm {
     fn invoke_on_update(self, f: impl FnOnce(Self) + 'static) {
         invocation::invoke_on_update(self, f);
     }
+
+    fn update_pending(self) -> bool {
+        invocation::update_pending(self)
+    }
+
+    fn flush_updates(self) {
+        invocation::process_pending_invocations(self);
+    }
 }
 
 /// A window handle type.
@@ -1,7 +1,8 @@
 //! The TCW3 binding for StellaVG
 use cggeom::prelude::*;
 use cgmath::Matrix3;
-use stvg_io::{Cmd, CmdDecoder};
+use std::borrow::Cow;
+use stvg_io::{Cmd, CmdDecoder, FillRule};
 use tcw3_images::{himg_from_paint_fn, HImg};
 use tcw3_pal::{iface::Canvas, RGBAF32};
 
@@ -9,6 +10,24 @@ use tcw3_pal::{iface::Canvas, RGBAF32};
 /// StellaVG images.
 pub trait CanvasStvgExt: Canvas {
     /// Draw a StellaVG image.
+    ///
+    /// `bytes` may be compressed by `CmdEncoder::take_bytes_compressed`.
+    /// Compressed data is decompressed on every call, which is considerably
+    /// slower than decoding uncompressed data. Use `stvg_io::decompress` to
+    /// decompress it in advance when drawing the same image repeatedly.
+    /// (`StvgImg` does this automatically.)
+    ///
+    /// The image is drawn in a coordinate space where the fixed-point
+    /// coordinates are divided by `1 << stvg_io::FRAC_BITS`. `Cmd::Transform`
+    /// is applied on the fixed-point coordinate space, i.e., before this root
+    /// scaling. `Cmd::SaveTransform` and `Cmd::RestoreTransform` are mapped to
+    /// `Canvas::save` and `Canvas::restore`, which also save and restore other
+    /// graphics states such as the fill color. The fill rule set by
+    /// `Cmd::SetFillRule` is saved and restored along with them.
+    /// `Cmd::BeginClip` is mapped to `Canvas::save` followed by `Canvas::clip`,
+    /// and `Cmd::EndClip` to `Canvas::restore`. Unbalanced `restore` calls are
+    /// ignored, and the graphics states pushed but not popped are restored at
+    /// the end, so the caller's graphics states are never affected.
     fn draw_stellavg(&mut self, bytes: &[u8], options: &Options<'_>);
 }
 
@@ -40,14 +59,30 @@ impl<'a> Default for Options<'a> {
 
 impl<T: Canvas + ?Sized> CanvasStvgExt for T {
     fn draw_stellavg(&mut self, bytes: &[u8], options: &Options<'_>) {
+        let convert_color = |color: rgb::RGBA8| {
+            (options.color_xform)(RGBAF32::new(
+                color.r as f32 / 255.0,
+                color.g as f32 / 255.0,
+                color.b as f32 / 255.0,
+                color.a as f32 / 255.0,
+            ))
+        };
+
+        let mut fill_rule = tcw3_pal::FillRule::NonZero;
+
+        // The fill rules saved with the graphics states pushed by the image.
+        // The fill rule isn't a part of `Canvas`'s graphics states, so we
+        // have to save it by ourselves.
+        let mut fill_rule_stack = Vec::new();
+
         self.save();
         self.mult_transform(Matrix3::from_scale_2d(
             1.0 / (1 << stvg_io::FRAC_BITS) as f32,
         ));
-        for cmd in CmdDecoder::from_bytes(bytes) {
+        for cmd in CmdDecoder::from_bytes(&stvg_io::decompress(bytes)) {
             match cmd {
                 Cmd::BeginPath => self.begin_path(),
-                Cmd::Fill => self.fill(),
+                Cmd::Fill => self.fill_with_rule(fill_rule),
                 Cmd::MoveTo(p) => self.move_to(p.cast().unwrap()),
                 Cmd::LineTo(p) => self.line_to(p.cast().unwrap()),
                 Cmd::QuadBezierTo(cps) => {
@@ -58,14 +93,37 @@ impl<T: Canvas + ?Sized> CanvasStvgExt for T {
                     cps[1].cast().unwrap(),
                     cps[2].cast().unwrap(),
                 ),
-                Cmd::SetFillRgb(color) => self.set_fill_rgb((options.color_xform)(RGBAF32::new(
-                    color.r as f32 / 255.0,
-                    color.g as f32 / 255.0,
-                    color.b as f32 / 255.0,
-                    color.a as f32 / 255.0,
-                ))),
+                Cmd::SetFillRgb(color) => self.set_fill_rgb(convert_color(color)),
+                Cmd::Stroke => self.stroke(),
+                Cmd::SetStrokeRgb(color) => self.set_stroke_rgb(convert_color(color)),
+                Cmd::SetStrokeWidth(width) => self.set_line_width(width as f32),
+                Cmd::SaveTransform => {
+                    self.save();
+                    fill_rule_stack.push(fill_rule);
+                }
+                Cmd::RestoreTransform | Cmd::EndClip => {
+                    if let Some(saved) = fill_rule_stack.pop() {
+                        self.restore();
+                        fill_rule = saved;
+                    }
+                }
+                Cmd::Transform(m) => self.mult_transform(stvg_io::matrix_from_transform(m)),
+                Cmd::BeginClip => {
+                    self.save();
+                    self.clip();
+                    fill_rule_stack.push(fill_rule);
+                }
+                Cmd::SetFillRule(rule) => {
+                    fill_rule = match rule {
+                        FillRule::NonZero => tcw3_pal::FillRule::NonZero,
+                        FillRule::EvenOdd => tcw3_pal::FillRule::EvenOdd,
+                    };
+                }
             }
         }
+        for _ in 0..fill_rule_stack.len() {
+            self.restore();
+        }
         self.restore();
     }
 }
@@ -130,10 +188,18 @@ where
     /// # inner();
     /// ```
     pub fn into_himg(self) -> HImg {
+        // Decompress the data now, not on every repaint
+        let decompressed = match stvg_io::decompress(self.bytes.borrow()) {
+            Cow::Owned(bytes) => Some(bytes),
+            Cow::Borrowed(_) => None,
+        };
+
         himg_from_paint_fn(
             [self.size[0] * self.scale, self.size[1] * self.scale].into(),
             move |draw_ctx| {
-                let bytes = self.bytes.borrow();
+                let bytes = decompressed
+                    .as_deref()
+                    .unwrap_or_else(|| self.bytes.borrow());
                 let color_xform = &self.color_xform;
 
                 let c = &mut draw_ctx.canvas;